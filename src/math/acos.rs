@@ -4,7 +4,7 @@
 //! around sqrt((1±x)/2) for |x|>0.5. Constants include split pi/2 hi/lo parts
 //! for extra precision.
 
-use super::{PIO2_HI, PIO2_LO, sqrt};
+use super::{PIO2_HI, PIO2_LO, sqrt, with_nearest};
 const PS0: f64 = 1.666_666_666_666_666_574_15e-01;
 const PS1: f64 = -3.255_658_186_224_009_154_05e-01;
 const PS2: f64 = 2.012_125_321_348_629_258_81e-01;
@@ -25,6 +25,11 @@ fn r(z: f64) -> f64 {
 
 #[inline]
 pub fn acos(x: f64) -> f64 {
+    with_nearest(x, acos_nearest)
+}
+
+#[inline]
+fn acos_nearest(x: f64) -> f64 {
     let x1p_120 = f64::from_bits(0x3870_0000_0000_0000);
     let hx = (x.to_bits() >> 32) as u32;
     let ix = hx & 0x7fff_ffff;
@@ -3,10 +3,15 @@
 //! Piecewise algorithm for x>=1: near 1 uses log1p with sqrt(x-1); moderate
 //! uses log(2x) + correction; large values avoid loss of precision.
 
-use super::{fma_internal, ln, log1p, sqrt, two_sum};
+use super::{fma_internal, ln, log1p, sqrt, two_sum, with_nearest};
 
 #[inline(always)]
 pub fn acosh(x: f64) -> f64 {
+    with_nearest(x, acosh_nearest)
+}
+
+#[inline(always)]
+fn acosh_nearest(x: f64) -> f64 {
     let ux = x.to_bits();
     let e = ((ux >> 52) & 0x7ff) as i32;
 
@@ -1,3 +1,5 @@
+use crate::math::RoundingMode;
+
 #[inline(always)]
 pub(crate) unsafe fn fma_hw(a: f64, b: f64, c: f64) -> f64 {
     let out: f64;
@@ -13,3 +15,66 @@ pub(crate) unsafe fn fma_hw(a: f64, b: f64, c: f64) -> f64 {
     }
     out
 }
+
+// frintn rounds to nearest, ties to even, whatever FPCR says.
+#[inline(always)]
+pub(crate) fn roundeven_hw(x: f64) -> f64 {
+    let out: f64;
+    unsafe {
+        core::arch::asm!(
+            "frintn {out:d}, {x:d}",
+            out = out(vreg) out,
+            x = in(vreg) x,
+            options(pure, nomem, nostack)
+        );
+    }
+    out
+}
+
+// FPCR.RMode is bits 22-23: 00 nearest, 01 up, 10 down, 11 toward zero.
+pub(crate) const ROUNDING_BITS: u64 = 3 << 22;
+
+#[inline(always)]
+pub(crate) fn fp_control() -> u64 {
+    let v: u64;
+    unsafe {
+        core::arch::asm!("mrs {v}, fpcr", v = out(reg) v, options(nomem, nostack, preserves_flags));
+    }
+    v
+}
+
+// Writes FPCR. The asm is also handed `v`, so the compiler cannot move the
+// computation of `v` across the switch.
+#[inline(always)]
+pub(crate) unsafe fn set_fp_control<T>(fpcr: u64, v: &mut T) {
+    unsafe {
+        core::arch::asm!(
+            "msr fpcr, {c}",
+            "/* {v} */",
+            c = in(reg) fpcr,
+            v = in(reg) v as *mut T,
+            options(nostack, preserves_flags)
+        );
+    }
+}
+
+#[inline]
+pub(crate) fn get_rounding_mode() -> RoundingMode {
+    match (fp_control() & ROUNDING_BITS) >> 22 {
+        0 => RoundingMode::ToNearest,
+        1 => RoundingMode::Upward,
+        2 => RoundingMode::Downward,
+        _ => RoundingMode::TowardZero,
+    }
+}
+
+#[inline]
+pub(crate) unsafe fn set_rounding_mode(mode: RoundingMode) {
+    let rm = match mode {
+        RoundingMode::ToNearest => 0,
+        RoundingMode::Upward => 1,
+        RoundingMode::Downward => 2,
+        RoundingMode::TowardZero => 3,
+    };
+    unsafe { set_fp_control((fp_control() & !ROUNDING_BITS) | (rm << 22), &mut ()) };
+}
@@ -0,0 +1,101 @@
+// Lane helpers shared by the 4-lane AVX2+FMA kernels.
+
+use core::arch::x86_64::{
+    __m256d, __m256i, _mm256_add_epi64, _mm256_castpd_si256, _mm256_castsi256_pd,
+    _mm256_i64gather_epi64, _mm256_i64gather_pd, _mm256_loadu_pd, _mm256_movemask_pd,
+    _mm256_set1_epi64x, _mm256_set1_pd, _mm256_storeu_pd, _mm256_sub_epi64, _mm256_sub_pd,
+    _mm256_xor_si256,
+};
+
+const MAGIC: f64 = f64::from_bits(0x4338_0000_0000_0000); // 0x1.8p52
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub(crate) fn splat(x: f64) -> __m256d {
+    _mm256_set1_pd(x)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+pub(crate) fn splat_u64(x: u64) -> __m256i {
+    _mm256_set1_epi64x(x as i64)
+}
+
+// table[idx[i]] for each lane; every index must be in bounds.
+#[inline]
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn gather_u64(table: &[u64], idx: __m256i) -> __m256i {
+    unsafe { _mm256_i64gather_epi64::<8>(table.as_ptr() as *const i64, idx) }
+}
+
+// table[idx[i]] for each lane; every index must be in bounds.
+#[inline]
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn gather_f64(table: &[f64], idx: __m256i) -> __m256d {
+    unsafe { _mm256_i64gather_pd::<8>(table.as_ptr(), idx) }
+}
+
+// Converts integers below 2^51 in magnitude to doubles exactly.
+#[inline]
+#[target_feature(enable = "avx2")]
+pub(crate) fn small_i64_to_f64(v: __m256i) -> __m256d {
+    let m = _mm256_add_epi64(v, _mm256_castpd_si256(splat(MAGIC)));
+    _mm256_sub_pd(_mm256_castsi256_pd(m), splat(MAGIC))
+}
+
+// Sign-extends the low 12 bits, as left by a logical shift of the exponent
+// field: the (tmp as i64) >> 52 of the scalar reductions.
+#[inline]
+#[target_feature(enable = "avx2")]
+pub(crate) fn sign_extend_12(v: __m256i) -> __m256i {
+    _mm256_sub_epi64(_mm256_xor_si256(v, splat_u64(0x800)), splat_u64(0x800))
+}
+
+// Replaces the lanes of `v` selected by `slow` with the scalar `f` of `x`.
+#[inline]
+#[target_feature(enable = "avx2")]
+pub(crate) fn fixup1(v: __m256d, slow: __m256d, x: __m256d, f: impl Fn(f64) -> f64) -> __m256d {
+    let mask = _mm256_movemask_pd(slow);
+    if mask == 0 {
+        return v;
+    }
+    let (mut out, mut xs) = ([0.0; 4], [0.0; 4]);
+    unsafe {
+        _mm256_storeu_pd(out.as_mut_ptr(), v);
+        _mm256_storeu_pd(xs.as_mut_ptr(), x);
+    }
+    for i in 0..4 {
+        if mask & (1 << i) != 0 {
+            out[i] = f(xs[i]);
+        }
+    }
+    unsafe { _mm256_loadu_pd(out.as_ptr()) }
+}
+
+// Replaces the lanes of `v` selected by `slow` with the scalar `f` of `x`, `y`.
+#[inline]
+#[target_feature(enable = "avx2")]
+pub(crate) fn fixup2(
+    v: __m256d,
+    slow: __m256d,
+    x: __m256d,
+    y: __m256d,
+    f: impl Fn(f64, f64) -> f64,
+) -> __m256d {
+    let mask = _mm256_movemask_pd(slow);
+    if mask == 0 {
+        return v;
+    }
+    let (mut out, mut xs, mut ys) = ([0.0; 4], [0.0; 4], [0.0; 4]);
+    unsafe {
+        _mm256_storeu_pd(out.as_mut_ptr(), v);
+        _mm256_storeu_pd(xs.as_mut_ptr(), x);
+        _mm256_storeu_pd(ys.as_mut_ptr(), y);
+    }
+    for i in 0..4 {
+        if mask & (1 << i) != 0 {
+            out[i] = f(xs[i], ys[i]);
+        }
+    }
+    unsafe { _mm256_loadu_pd(out.as_ptr()) }
+}
@@ -0,0 +1,137 @@
+// Lane helpers shared by the 8-lane AVX-512F kernels.
+//
+// AVX-512F has no floating-point bitwise operations (those are AVX-512DQ),
+// so sign and mask manipulation goes through the integer domain.
+
+use core::arch::x86_64::{
+    __m512d, __m512i, __mmask8, _mm512_add_epi64, _mm512_and_si512, _mm512_andnot_si512,
+    _mm512_castpd_si512, _mm512_castsi512_pd, _mm512_i64gather_epi64, _mm512_i64gather_pd,
+    _mm512_loadu_pd, _mm512_set1_epi64, _mm512_set1_pd, _mm512_storeu_pd, _mm512_sub_epi64,
+    _mm512_sub_pd, _mm512_xor_si512,
+};
+
+const MAGIC: f64 = f64::from_bits(0x4338_0000_0000_0000); // 0x1.8p52
+const SIGN_BIT: u64 = 0x8000_0000_0000_0000;
+
+#[inline]
+#[target_feature(enable = "avx512f")]
+pub(crate) fn splat(x: f64) -> __m512d {
+    _mm512_set1_pd(x)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f")]
+pub(crate) fn splat_u64(x: u64) -> __m512i {
+    _mm512_set1_epi64(x as i64)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f")]
+pub(crate) fn and(a: __m512d, b: __m512i) -> __m512d {
+    _mm512_castsi512_pd(_mm512_and_si512(_mm512_castpd_si512(a), b))
+}
+
+#[inline]
+#[target_feature(enable = "avx512f")]
+pub(crate) fn xor(a: __m512d, b: __m512i) -> __m512d {
+    _mm512_castsi512_pd(_mm512_xor_si512(_mm512_castpd_si512(a), b))
+}
+
+// The sign bit of each lane, as an integer mask for `xor`.
+#[inline]
+#[target_feature(enable = "avx512f")]
+pub(crate) fn sign(x: __m512d) -> __m512i {
+    _mm512_and_si512(_mm512_castpd_si512(x), splat_u64(SIGN_BIT))
+}
+
+#[inline]
+#[target_feature(enable = "avx512f")]
+pub(crate) fn abs(x: __m512d) -> __m512d {
+    _mm512_castsi512_pd(_mm512_andnot_si512(
+        splat_u64(SIGN_BIT),
+        _mm512_castpd_si512(x),
+    ))
+}
+
+#[inline]
+#[target_feature(enable = "avx512f")]
+pub(crate) fn neg(x: __m512d) -> __m512d {
+    xor(x, splat_u64(SIGN_BIT))
+}
+
+// table[idx[i]] for each lane; every index must be in bounds.
+#[inline]
+#[target_feature(enable = "avx512f")]
+pub(crate) unsafe fn gather_u64(table: &[u64], idx: __m512i) -> __m512i {
+    unsafe { _mm512_i64gather_epi64::<8>(idx, table.as_ptr() as *const i64) }
+}
+
+// table[idx[i]] for each lane; every index must be in bounds.
+#[inline]
+#[target_feature(enable = "avx512f")]
+pub(crate) unsafe fn gather_f64(table: &[f64], idx: __m512i) -> __m512d {
+    unsafe { _mm512_i64gather_pd::<8>(idx, table.as_ptr()) }
+}
+
+// Converts integers below 2^51 in magnitude to doubles exactly.
+#[inline]
+#[target_feature(enable = "avx512f")]
+pub(crate) fn small_i64_to_f64(v: __m512i) -> __m512d {
+    let m = _mm512_add_epi64(v, _mm512_castpd_si512(splat(MAGIC)));
+    _mm512_sub_pd(_mm512_castsi512_pd(m), splat(MAGIC))
+}
+
+// Sign-extends the low 12 bits, as left by a logical shift of the exponent
+// field: the (tmp as i64) >> 52 of the scalar reductions.
+#[inline]
+#[target_feature(enable = "avx512f")]
+pub(crate) fn sign_extend_12(v: __m512i) -> __m512i {
+    _mm512_sub_epi64(_mm512_xor_si512(v, splat_u64(0x800)), splat_u64(0x800))
+}
+
+// Replaces the lanes of `v` selected by `slow` with the scalar `f` of `x`.
+#[inline]
+#[target_feature(enable = "avx512f")]
+pub(crate) fn fixup1(v: __m512d, slow: __mmask8, x: __m512d, f: impl Fn(f64) -> f64) -> __m512d {
+    if slow == 0 {
+        return v;
+    }
+    let (mut out, mut xs) = ([0.0; 8], [0.0; 8]);
+    unsafe {
+        _mm512_storeu_pd(out.as_mut_ptr(), v);
+        _mm512_storeu_pd(xs.as_mut_ptr(), x);
+    }
+    for i in 0..8 {
+        if slow & (1 << i) != 0 {
+            out[i] = f(xs[i]);
+        }
+    }
+    unsafe { _mm512_loadu_pd(out.as_ptr()) }
+}
+
+// Replaces the lanes of `v` selected by `slow` with the scalar `f` of `x`, `y`.
+#[inline]
+#[target_feature(enable = "avx512f")]
+pub(crate) fn fixup2(
+    v: __m512d,
+    slow: __mmask8,
+    x: __m512d,
+    y: __m512d,
+    f: impl Fn(f64, f64) -> f64,
+) -> __m512d {
+    if slow == 0 {
+        return v;
+    }
+    let (mut out, mut xs, mut ys) = ([0.0; 8], [0.0; 8], [0.0; 8]);
+    unsafe {
+        _mm512_storeu_pd(out.as_mut_ptr(), v);
+        _mm512_storeu_pd(xs.as_mut_ptr(), x);
+        _mm512_storeu_pd(ys.as_mut_ptr(), y);
+    }
+    for i in 0..8 {
+        if slow & (1 << i) != 0 {
+            out[i] = f(xs[i], ys[i]);
+        }
+    }
+    unsafe { _mm512_loadu_pd(out.as_ptr()) }
+}
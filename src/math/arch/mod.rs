@@ -2,11 +2,23 @@
 
 #[cfg(target_arch = "aarch64")]
 mod aarch64;
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+pub(crate) mod avx2;
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+pub(crate) mod avx512;
 #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
 mod x86;
 
 #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
 pub(crate) use x86::fma_hw;
+#[cfg(all(target_arch = "x86_64", target_feature = "sse4.1"))]
+pub(crate) use x86::roundeven_hw;
+#[cfg(all(target_arch = "x86_64", feature = "rounding-modes"))]
+pub(crate) use x86::{ROUNDING_BITS, fp_control, set_fp_control};
+#[cfg(target_arch = "x86_64")]
+pub(crate) use x86::{get_rounding_mode, set_rounding_mode};
 
+#[cfg(all(target_arch = "aarch64", feature = "rounding-modes"))]
+pub(crate) use aarch64::{ROUNDING_BITS, fp_control, set_fp_control};
 #[cfg(target_arch = "aarch64")]
-pub(crate) use aarch64::fma_hw;
+pub(crate) use aarch64::{fma_hw, get_rounding_mode, roundeven_hw, set_rounding_mode};
@@ -1,3 +1,6 @@
+#[cfg(target_arch = "x86_64")]
+use crate::math::RoundingMode;
+
 #[cfg(target_arch = "x86_64")]
 #[target_feature(enable = "fma")]
 pub(crate) unsafe fn fma_hw(a: f64, b: f64, c: f64) -> f64 {
@@ -11,3 +14,80 @@ pub(crate) unsafe fn fma_hw(a: f64, b: f64, c: f64) -> f64 {
     use core::arch::x86::{_mm_cvtsd_f64, _mm_fmadd_sd, _mm_set_sd};
     _mm_cvtsd_f64(_mm_fmadd_sd(_mm_set_sd(a), _mm_set_sd(b), _mm_set_sd(c)))
 }
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse4.1"))]
+#[inline(always)]
+pub(crate) fn roundeven_hw(x: f64) -> f64 {
+    use core::arch::x86_64::{
+        _MM_FROUND_NO_EXC, _MM_FROUND_TO_NEAREST_INT, _mm_cvtsd_f64, _mm_round_sd, _mm_set_sd,
+    };
+    // The immediate mode overrides MXCSR. SSE4.1 is enabled at compile time.
+    unsafe {
+        let v = _mm_set_sd(x);
+        _mm_cvtsd_f64(_mm_round_sd::<
+            { _MM_FROUND_TO_NEAREST_INT | _MM_FROUND_NO_EXC },
+        >(v, v))
+    }
+}
+
+// MXCSR.RC is bits 13-14: 00 nearest, 01 down, 10 up, 11 toward zero.
+#[cfg(target_arch = "x86_64")]
+pub(crate) const ROUNDING_BITS: u32 = 3 << 13;
+
+// Reads MXCSR through a slot the asm pushes itself, so it touches no memory
+// the compiler knows of and does not force values out of registers.
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+pub(crate) fn fp_control() -> u32 {
+    let csr: u32;
+    unsafe {
+        core::arch::asm!(
+            "sub rsp, 8",
+            "stmxcsr [rsp]",
+            "mov {c:e}, [rsp]",
+            "add rsp, 8",
+            c = out(reg) csr,
+            options(nomem)
+        );
+    }
+    csr
+}
+
+// Loads MXCSR. The asm is also handed `v`, so the compiler cannot move the
+// computation of `v` across the switch.
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+pub(crate) unsafe fn set_fp_control<T>(csr: u32, v: &mut T) {
+    unsafe {
+        core::arch::asm!(
+            "ldmxcsr [{p}]",
+            "/* {v} */",
+            p = in(reg) &csr,
+            v = in(reg) v as *mut T,
+            options(nostack, preserves_flags)
+        );
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[inline]
+pub(crate) fn get_rounding_mode() -> RoundingMode {
+    match (fp_control() & ROUNDING_BITS) >> 13 {
+        0 => RoundingMode::ToNearest,
+        1 => RoundingMode::Downward,
+        2 => RoundingMode::Upward,
+        _ => RoundingMode::TowardZero,
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[inline]
+pub(crate) unsafe fn set_rounding_mode(mode: RoundingMode) {
+    let rc = match mode {
+        RoundingMode::ToNearest => 0,
+        RoundingMode::Downward => 1,
+        RoundingMode::Upward => 2,
+        RoundingMode::TowardZero => 3,
+    };
+    unsafe { set_fp_control((fp_control() & !ROUNDING_BITS) | (rc << 13), &mut ()) };
+}
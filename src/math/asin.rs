@@ -4,7 +4,7 @@
 //! asin(x)=pi/2-2*asin(sqrt((1-|x|)/2)) for |x|>0.5. Polynomial degrees and
 //! constants are fdlibm-style.
 
-use super::{PIO2_HI, PIO2_LO, hi_word, lo_word, sqrt, with_hi_lo};
+use super::{PIO2_HI, PIO2_LO, hi_word, lo_word, sqrt, with_hi_lo, with_nearest};
 
 const P_S0: f64 = 1.666_666_666_666_666_574_15e-01;
 const P_S1: f64 = -3.255_658_186_224_009_154_05e-01;
@@ -25,7 +25,12 @@ fn comp_r(z: f64) -> f64 {
 }
 
 #[inline]
-pub fn asin(mut x: f64) -> f64 {
+pub fn asin(x: f64) -> f64 {
+    with_nearest(x, asin_nearest)
+}
+
+#[inline]
+fn asin_nearest(mut x: f64) -> f64 {
     let hx = hi_word(x);
     let ix = hx & 0x7fff_ffff;
     if ix >= 0x3ff0_0000 {
@@ -3,10 +3,15 @@
 //! Piecewise algorithm: |x| small -> x; medium -> log1p(x + x^2/(1+sqrt(1+x^2)));
 //! large -> log(2x). Designed to avoid cancellation and overflow.
 
-use super::{fma_internal, ln, log1p, sqrt, two_sum};
+use super::{fma_internal, ln, log1p, sqrt, two_sum, with_nearest};
 
 #[inline(always)]
 pub fn asinh(x: f64) -> f64 {
+    with_nearest(x, asinh_nearest)
+}
+
+#[inline(always)]
+fn asinh_nearest(x: f64) -> f64 {
     let ux = x.to_bits();
     let e = ((ux >> 52) & 0x7ff) as i32;
     let sign = (ux >> 63) != 0;
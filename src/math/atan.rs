@@ -5,6 +5,7 @@
 //! minimax fits.
 
 use super::hi_word;
+use super::with_nearest;
 use core::f64::consts::{FRAC_PI_2, FRAC_PI_4};
 
 const ATANHI: [f64; 4] = [
@@ -42,6 +43,11 @@ fn mul_add_fast(a: f64, b: f64, c: f64) -> f64 {
 
 #[inline]
 pub fn atan(x: f64) -> f64 {
+    with_nearest(x, atan_nearest)
+}
+
+#[inline]
+fn atan_nearest(x: f64) -> f64 {
     let hx = hi_word(x) as i32;
     let ix = hx & 0x7fff_ffff;
 
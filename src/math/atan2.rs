@@ -3,7 +3,7 @@
 //! Handles quadrants, signed zeros, and infinities explicitly, then reduces to
 //! atan(y/x) using the atan polynomial core. Matches glibc sign/edge behavior.
 
-use super::{PIO2_HI, atan, fma_internal};
+use super::{PIO2_HI, atan, fma_internal, with_nearest};
 
 const PI: f64 = core::f64::consts::PI;
 const PI_LO: f64 = 1.224_646_799_147_353_207_2e-16;
@@ -37,6 +37,11 @@ fn atan_with_correction(r: f64, r_lo: f64) -> f64 {
 
 #[inline]
 pub fn atan2(y: f64, x: f64) -> f64 {
+    with_nearest((y, x), |(y, x)| atan2_nearest(y, x))
+}
+
+#[inline]
+fn atan2_nearest(y: f64, x: f64) -> f64 {
     if y.is_nan() || x.is_nan() {
         return f64::NAN;
     }
@@ -5,7 +5,8 @@
 //! <= 1 ULP accuracy with good throughput.
 
 use super::atanh_data::{CH, CL};
-use super::{asdouble, copysign, fasttwosum, fma_internal};
+use super::double::{muldd_acc, muldd_acc2, mulddd3};
+use super::{asdouble, copysign, fasttwosum, fma_internal, with_nearest};
 use super::{log::ln, log1p};
 
 const TINY_BITS: u64 = 0x3e4d_12ed_0af1_a27f; // 0x1.d12ed0af1a27fp-27
@@ -31,35 +32,6 @@ const SMALL_BASE: f64 = f64::from_bits(0x3fd5_5555_5555_5555); // 0x1.5555555555
 const EPS_X4: f64 = f64::from_bits(0x3cad_0000_0000_0000); // 0x1.dp-53
 const EPS_TINY: f64 = f64::from_bits(0x3980_0000_0000_0000); // 0x1p-103
 
-#[inline(always)]
-fn muldd_acc(xh: f64, xl: f64, ch: f64, cl: f64, l: &mut f64) -> f64 {
-    let ahlh = ch * xl;
-    let alhh = cl * xh;
-    let ahhh = ch * xh;
-    let mut ahhl = fma_internal(ch, xh, -ahhh);
-    ahhl += alhh + ahlh;
-    let chh = ahhh + ahhl;
-    *l = (ahhh - chh) + ahhl;
-    chh
-}
-
-#[inline(always)]
-fn muldd_acc2(xh: f64, xl: f64, ch: f64, cl: f64, l: &mut f64) -> f64 {
-    let ahlh = ch * xl;
-    let alhh = cl * xh;
-    let ahhh = ch * xh;
-    let mut ahhl = fma_internal(ch, xh, -ahhh);
-    ahhl += alhh + ahlh;
-    fasttwosum(ahhh, ahhl, l)
-}
-
-#[inline(always)]
-fn mulddd3(xh: f64, xl: f64, ch: f64, l: &mut f64) -> f64 {
-    let hh = xh * ch;
-    *l = fma_internal(ch, xh, -hh) + xl * ch;
-    hh
-}
-
 #[inline(always)]
 fn polydd3(xh: f64, xl: f64, n: usize, c: &[[f64; 2]], l: &mut f64) -> f64 {
     let mut i = n - 1;
@@ -106,6 +78,11 @@ fn as_atanh_zero(x: f64) -> f64 {
 
 #[inline(always)]
 pub fn atanh(x: f64) -> f64 {
+    with_nearest(x, atanh_nearest)
+}
+
+#[inline(always)]
+fn atanh_nearest(x: f64) -> f64 {
     let ax = x.abs();
     let aix = (ax).to_bits();
     if aix == 0 {
@@ -0,0 +1,347 @@
+//! Slice-batched entry points for the unary and binary functions.
+//!
+//! `f_batch` writes `f` of each input element to the matching element of
+//! `out`, and `f_batch_inplace` overwrites its (first) argument slice. When
+//! the crate is built with AVX-512F enabled, functions with an 8-lane kernel
+//! (`exp`, `ln`, `sin`, `cos`, `pow`, `erf`) run it on each full chunk of
+//! eight; otherwise, when AVX2 and FMA are enabled, those with a 4-lane
+//! kernel (`exp`, `exp2`, `ln`, `log2`, `sin`, `cos`, `pow`) run it on each
+//! full chunk of four. With `runtime-dispatch` the same choice is made from
+//! the features detected on the CPU instead. The tail, and every element of
+//! the other functions, goes through the scalar function. The kernels are
+//! bit-identical to the scalar code, so batched results match the scalar
+//! ones whatever the slice length or alignment. Mismatched slice lengths
+//! panic rather than truncating silently.
+
+use super::{
+    acos, acosh, asin, asinh, atan, atan2, atanh, cbrt, ceil, ci, copysign, cos, cosh, ellipe,
+    ellipeinc, ellipk, ellipkinc, ellippi, elliprc, erf, erfc, exp, exp2, exp10, expint_e1,
+    expint_ei, expm1, fabs, fdim, floor, fmax, fmin, fmod, fresnel_c, fresnel_s, hurwitz_zeta,
+    hypot, lambert_w0, lambert_wm1, lgamma, ln, log1p, log2, log10, logb, nearbyint, nextafter,
+    pow, remainder, rint, round, si, sin, sinh, sqrt, tan, tanh, tgamma, trunc, zeta,
+};
+
+#[inline(always)]
+fn map_unary(input: &[f64], out: &mut [f64], f: impl Fn(f64) -> f64) {
+    assert_eq!(
+        input.len(),
+        out.len(),
+        "batch input and output lengths differ"
+    );
+    for (o, &x) in out.iter_mut().zip(input) {
+        *o = f(x);
+    }
+}
+
+#[inline(always)]
+fn map_unary_inplace(data: &mut [f64], f: impl Fn(f64) -> f64) {
+    for x in data.iter_mut() {
+        *x = f(*x);
+    }
+}
+
+#[inline(always)]
+fn map_binary(a: &[f64], b: &[f64], out: &mut [f64], f: impl Fn(f64, f64) -> f64) {
+    assert_eq!(a.len(), b.len(), "batch input lengths differ");
+    assert_eq!(a.len(), out.len(), "batch input and output lengths differ");
+    for ((o, &x), &y) in out.iter_mut().zip(a).zip(b) {
+        *o = f(x, y);
+    }
+}
+
+#[inline(always)]
+fn map_binary_inplace(a: &mut [f64], b: &[f64], f: impl Fn(f64, f64) -> f64) {
+    assert_eq!(a.len(), b.len(), "batch input lengths differ");
+    for (x, &y) in a.iter_mut().zip(b) {
+        *x = f(*x, y);
+    }
+}
+
+// Lane-parallel loops: each full chunk goes through the kernel, the
+// remainder through the scalar function.
+macro_rules! lanes {
+    ($name:ident, $n:literal, $v:ident, $load:ident, $store:ident, $($feature:literal),+) => {
+        #[cfg(all(
+            target_arch = "x86_64",
+            any(all($(target_feature = $feature),+), feature = "runtime-dispatch"),
+            not(feature = "soft-fma")
+        ))]
+        mod $name {
+            use core::arch::x86_64::{$load, $store, $v};
+
+            #[inline]
+            #[target_feature($(enable = $feature),+)]
+            pub(super) unsafe fn map_unary(
+                input: &[f64],
+                out: &mut [f64],
+                vf: unsafe fn($v) -> $v,
+                f: impl Fn(f64) -> f64,
+            ) {
+                assert_eq!(
+                    input.len(),
+                    out.len(),
+                    "batch input and output lengths differ"
+                );
+                let mut oc = out.chunks_exact_mut($n);
+                let mut ic = input.chunks_exact($n);
+                for (o, x) in (&mut oc).zip(&mut ic) {
+                    // SAFETY: both chunks hold exactly one vector, and the
+                    // caller guarantees the kernel's target features.
+                    unsafe { $store(o.as_mut_ptr(), vf($load(x.as_ptr()))) };
+                }
+                for (o, &x) in oc.into_remainder().iter_mut().zip(ic.remainder()) {
+                    *o = f(x);
+                }
+            }
+
+            #[inline]
+            #[target_feature($(enable = $feature),+)]
+            pub(super) unsafe fn map_unary_inplace(
+                data: &mut [f64],
+                vf: unsafe fn($v) -> $v,
+                f: impl Fn(f64) -> f64,
+            ) {
+                let mut dc = data.chunks_exact_mut($n);
+                for d in &mut dc {
+                    // SAFETY: the chunk holds exactly one vector, and the
+                    // caller guarantees the kernel's target features.
+                    unsafe { $store(d.as_mut_ptr(), vf($load(d.as_ptr()))) };
+                }
+                for x in dc.into_remainder() {
+                    *x = f(*x);
+                }
+            }
+
+            #[inline]
+            #[target_feature($(enable = $feature),+)]
+            pub(super) unsafe fn map_binary(
+                a: &[f64],
+                b: &[f64],
+                out: &mut [f64],
+                vf: unsafe fn($v, $v) -> $v,
+                f: impl Fn(f64, f64) -> f64,
+            ) {
+                assert_eq!(a.len(), b.len(), "batch input lengths differ");
+                assert_eq!(a.len(), out.len(), "batch input and output lengths differ");
+                let mut oc = out.chunks_exact_mut($n);
+                let mut ac = a.chunks_exact($n);
+                let mut bc = b.chunks_exact($n);
+                for ((o, x), y) in (&mut oc).zip(&mut ac).zip(&mut bc) {
+                    // SAFETY: all three chunks hold exactly one vector, and
+                    // the caller guarantees the kernel's target features.
+                    unsafe { $store(o.as_mut_ptr(), vf($load(x.as_ptr()), $load(y.as_ptr()))) };
+                }
+                for ((o, &x), &y) in oc
+                    .into_remainder()
+                    .iter_mut()
+                    .zip(ac.remainder())
+                    .zip(bc.remainder())
+                {
+                    *o = f(x, y);
+                }
+            }
+
+            #[inline]
+            #[target_feature($(enable = $feature),+)]
+            pub(super) unsafe fn map_binary_inplace(
+                a: &mut [f64],
+                b: &[f64],
+                vf: unsafe fn($v, $v) -> $v,
+                f: impl Fn(f64, f64) -> f64,
+            ) {
+                assert_eq!(a.len(), b.len(), "batch input lengths differ");
+                let mut ac = a.chunks_exact_mut($n);
+                let mut bc = b.chunks_exact($n);
+                for (x, y) in (&mut ac).zip(&mut bc) {
+                    // SAFETY: both chunks hold exactly one vector, and the
+                    // caller guarantees the kernel's target features.
+                    unsafe { $store(x.as_mut_ptr(), vf($load(x.as_ptr()), $load(y.as_ptr()))) };
+                }
+                for (x, &y) in ac.into_remainder().iter_mut().zip(bc.remainder()) {
+                    *x = f(*x, y);
+                }
+            }
+        }
+    };
+}
+
+lanes!(
+    x4,
+    4,
+    __m256d,
+    _mm256_loadu_pd,
+    _mm256_storeu_pd,
+    "avx2",
+    "fma"
+);
+lanes!(x8, 8, __m512d, _mm512_loadu_pd, _mm512_storeu_pd, "avx512f");
+
+// The widest kernel the build enables (or, with `runtime-dispatch`, the CPU
+// supports), else the scalar loop.
+macro_rules! dispatch {
+    ($map:ident($($arg:expr),*); $f:ident; $($v4:ident)?; $($v8:ident)?) => {{
+        dispatch!(@avx512 $map($($arg),*); $f; $($v8)?);
+        dispatch!(@avx2 $map($($arg),*); $f; $($v4)?);
+        $map($($arg,)* $f)
+    }};
+    (@avx512 $map:ident($($arg:expr),*); $f:ident;) => {};
+    (@avx512 $map:ident($($arg:expr),*); $f:ident; $v8:ident) => {
+        #[cfg(all(
+            target_arch = "x86_64",
+            target_feature = "avx512f",
+            not(feature = "soft-fma")
+        ))]
+        // SAFETY: the build enables AVX-512F.
+        return unsafe { x8::$map($($arg,)* super::$v8, $f) };
+        #[cfg(all(
+            target_arch = "x86_64",
+            not(target_feature = "avx512f"),
+            feature = "runtime-dispatch",
+            not(feature = "soft-fma")
+        ))]
+        if super::dispatch::has_avx512f() {
+            // SAFETY: the CPU supports AVX-512F.
+            return unsafe { x8::$map($($arg,)* super::$v8, $f) };
+        }
+    };
+    (@avx2 $map:ident($($arg:expr),*); $f:ident;) => {};
+    (@avx2 $map:ident($($arg:expr),*); $f:ident; $v4:ident) => {
+        #[cfg(all(
+            target_arch = "x86_64",
+            target_feature = "avx2",
+            target_feature = "fma",
+            not(feature = "soft-fma")
+        ))]
+        // SAFETY: the build enables AVX2 and FMA.
+        return unsafe { x4::$map($($arg,)* super::$v4, $f) };
+        #[cfg(all(
+            target_arch = "x86_64",
+            not(all(target_feature = "avx2", target_feature = "fma")),
+            feature = "runtime-dispatch",
+            not(feature = "soft-fma")
+        ))]
+        if super::dispatch::has_avx2() {
+            // SAFETY: the CPU supports AVX2 and FMA.
+            return unsafe { x4::$map($($arg,)* super::$v4, $f) };
+        }
+    };
+}
+
+macro_rules! unary_batch {
+    ($($f:ident $([avx2 $v4:ident])? $([avx512 $v8:ident])? => $batch:ident, $inplace:ident;)*) => {$(
+        #[doc = concat!("[`", stringify!($f), "`] of each element of `input`, written to `out`.")]
+        ///
+        /// # Panics
+        ///
+        /// If `input` and `out` have different lengths.
+        #[allow(unreachable_code)]
+        pub fn $batch(input: &[f64], out: &mut [f64]) {
+            dispatch!(map_unary(input, out); $f; $($v4)?; $($v8)?)
+        }
+
+        #[doc = concat!("Replaces each element of `data` with its [`", stringify!($f), "`].")]
+        #[allow(unreachable_code)]
+        pub fn $inplace(data: &mut [f64]) {
+            dispatch!(map_unary_inplace(data); $f; $($v4)?; $($v8)?)
+        }
+    )*};
+}
+
+macro_rules! binary_batch {
+    ($($f:ident($a:ident, $b:ident) $([avx2 $v4:ident])? $([avx512 $v8:ident])?
+        => $batch:ident, $inplace:ident;)*) => {$(
+        #[doc = concat!(
+            "[`", stringify!($f), "`]`(", stringify!($a), "[i], ", stringify!($b),
+            "[i])` for each `i`, written to `out[i]`."
+        )]
+        ///
+        /// # Panics
+        ///
+        /// If the three slices do not all have the same length.
+        #[allow(unreachable_code)]
+        pub fn $batch($a: &[f64], $b: &[f64], out: &mut [f64]) {
+            dispatch!(map_binary($a, $b, out); $f; $($v4)?; $($v8)?)
+        }
+
+        #[doc = concat!(
+            "Replaces `", stringify!($a), "[i]` with [`", stringify!($f), "`]`(",
+            stringify!($a), "[i], ", stringify!($b), "[i])` for each `i`."
+        )]
+        ///
+        /// # Panics
+        ///
+        #[doc = concat!(
+            "If `", stringify!($a), "` and `", stringify!($b), "` have different lengths."
+        )]
+        #[allow(unreachable_code)]
+        pub fn $inplace($a: &mut [f64], $b: &[f64]) {
+            dispatch!(map_binary_inplace($a, $b); $f; $($v4)?; $($v8)?)
+        }
+    )*};
+}
+
+unary_batch! {
+    acos => acos_batch, acos_batch_inplace;
+    acosh => acosh_batch, acosh_batch_inplace;
+    asin => asin_batch, asin_batch_inplace;
+    asinh => asinh_batch, asinh_batch_inplace;
+    atan => atan_batch, atan_batch_inplace;
+    atanh => atanh_batch, atanh_batch_inplace;
+    cbrt => cbrt_batch, cbrt_batch_inplace;
+    ceil => ceil_batch, ceil_batch_inplace;
+    ci => ci_batch, ci_batch_inplace;
+    cos [avx2 cos_avx2] [avx512 cos_avx512] => cos_batch, cos_batch_inplace;
+    cosh => cosh_batch, cosh_batch_inplace;
+    ellipe => ellipe_batch, ellipe_batch_inplace;
+    ellipk => ellipk_batch, ellipk_batch_inplace;
+    erf [avx512 erf_avx512] => erf_batch, erf_batch_inplace;
+    erfc => erfc_batch, erfc_batch_inplace;
+    exp [avx2 exp_avx2] [avx512 exp_avx512] => exp_batch, exp_batch_inplace;
+    exp10 => exp10_batch, exp10_batch_inplace;
+    exp2 [avx2 exp2_avx2] => exp2_batch, exp2_batch_inplace;
+    expint_e1 => expint_e1_batch, expint_e1_batch_inplace;
+    expint_ei => expint_ei_batch, expint_ei_batch_inplace;
+    expm1 => expm1_batch, expm1_batch_inplace;
+    fabs => fabs_batch, fabs_batch_inplace;
+    floor => floor_batch, floor_batch_inplace;
+    fresnel_c => fresnel_c_batch, fresnel_c_batch_inplace;
+    fresnel_s => fresnel_s_batch, fresnel_s_batch_inplace;
+    lambert_w0 => lambert_w0_batch, lambert_w0_batch_inplace;
+    lambert_wm1 => lambert_wm1_batch, lambert_wm1_batch_inplace;
+    lgamma => lgamma_batch, lgamma_batch_inplace;
+    ln [avx2 ln_avx2] [avx512 ln_avx512] => ln_batch, ln_batch_inplace;
+    log10 => log10_batch, log10_batch_inplace;
+    log1p => log1p_batch, log1p_batch_inplace;
+    log2 [avx2 log2_avx2] => log2_batch, log2_batch_inplace;
+    logb => logb_batch, logb_batch_inplace;
+    nearbyint => nearbyint_batch, nearbyint_batch_inplace;
+    rint => rint_batch, rint_batch_inplace;
+    round => round_batch, round_batch_inplace;
+    si => si_batch, si_batch_inplace;
+    sin [avx2 sin_avx2] [avx512 sin_avx512] => sin_batch, sin_batch_inplace;
+    sinh => sinh_batch, sinh_batch_inplace;
+    sqrt => sqrt_batch, sqrt_batch_inplace;
+    tan => tan_batch, tan_batch_inplace;
+    tanh => tanh_batch, tanh_batch_inplace;
+    tgamma => tgamma_batch, tgamma_batch_inplace;
+    trunc => trunc_batch, trunc_batch_inplace;
+    zeta => zeta_batch, zeta_batch_inplace;
+}
+
+binary_batch! {
+    atan2(y, x) => atan2_batch, atan2_batch_inplace;
+    copysign(x, y) => copysign_batch, copysign_batch_inplace;
+    ellipeinc(phi, m) => ellipeinc_batch, ellipeinc_batch_inplace;
+    ellipkinc(phi, m) => ellipkinc_batch, ellipkinc_batch_inplace;
+    ellippi(n, m) => ellippi_batch, ellippi_batch_inplace;
+    elliprc(x, y) => elliprc_batch, elliprc_batch_inplace;
+    fdim(x, y) => fdim_batch, fdim_batch_inplace;
+    fmax(x, y) => fmax_batch, fmax_batch_inplace;
+    fmin(x, y) => fmin_batch, fmin_batch_inplace;
+    fmod(x, y) => fmod_batch, fmod_batch_inplace;
+    hurwitz_zeta(s, q) => hurwitz_zeta_batch, hurwitz_zeta_batch_inplace;
+    hypot(x, y) => hypot_batch, hypot_batch_inplace;
+    nextafter(x, y) => nextafter_batch, nextafter_batch_inplace;
+    pow(x, y) [avx2 pow_avx2] [avx512 pow_avx512] => pow_batch, pow_batch_inplace;
+    remainder(x, y) => remainder_batch, remainder_batch_inplace;
+}
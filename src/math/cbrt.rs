@@ -4,6 +4,7 @@
 //! Newton iterations. This mirrors glibc/core-math style for accuracy and speed.
 
 use super::scalbn_internal;
+use super::with_nearest;
 
 const CBRT2: f64 = 1.259_921_049_894_873_164_8; // 2^(1/3)
 const SQR_CBRT2: f64 = 1.587_401_051_968_199_474_8; // 2^(2/3)
@@ -39,6 +40,11 @@ fn div_rem_3(x: i32) -> (i32, i32) {
 
 #[inline]
 pub fn cbrt(x: f64) -> f64 {
+    with_nearest(x, cbrt_nearest)
+}
+
+#[inline]
+fn cbrt_nearest(x: f64) -> f64 {
     if x.is_nan() || x.is_infinite() || x == 0.0 {
         return x;
     }
@@ -64,8 +70,8 @@ pub fn cbrt(x: f64) -> f64 {
     }
 
     let y2 = y * y;
-    if y2 != 0.0 && y2.is_finite() {
-        let y3 = y2 * y;
+    let y3 = y2 * y;
+    if y2 != 0.0 && y3.is_finite() {
         y += (x - y3) / (3.0 * y2);
     }
     y
@@ -0,0 +1,116 @@
+//! Result-returning elementary functions.
+//!
+//! glibc reports `EDOM` and `ERANGE` through `errno`; here each function
+//! returns its plain counterpart's value, or the [`MathError`] glibc would
+//! report for the arguments:
+//!
+//! - [`Domain`](MathError::Domain): outside the domain, e.g. `ln(-1)`,
+//!   `asin(2)`, `sin(inf)`, `tgamma(-2)` or a signaling NaN;
+//! - [`Pole`](MathError::Pole): an exact infinity from finite arguments,
+//!   e.g. `ln(0)`, `atanh(1)`, `pow(0, -1)`, `lgamma(-2)` or `tgamma(0)`;
+//! - [`Overflow`](MathError::Overflow): a finite exact value too large for a
+//!   double, e.g. `exp(1000)` or `lgamma(1e306)`;
+//! - [`Underflow`](MathError::Underflow): a nonzero exact value that rounds
+//!   to zero, e.g. `exp(-1000)`. A subnormal result, exact or not, is
+//!   returned, e.g. `sin(1e-310)` or `exp(-740)`.
+//!
+//! Rather than re-deriving range rules per function, the error is the one
+//! the matching `_with_flags` function's exceptions name: a NaN result is a
+//! domain error, and an infinite or zero one is told apart as a pole, an
+//! overflow or an underflow by whether the value is exact, from the same
+//! per-function checks; the underflow flag on a nonzero subnormal result
+//! is not an error. A normal finite result skips that test. Quiet NaN
+//! arguments are not errors and return NaN, and infinite arguments with a
+//! limit return it, e.g. `exp(-inf)` is `Ok(0.0)`.
+
+use core::fmt;
+
+use super::flags::{self, FpExceptions, raised};
+
+/// Why an elementary function has no finite result.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MathError {
+    /// An argument lies outside the function's domain (`EDOM`).
+    Domain,
+    /// The function has a pole at the argument (`ERANGE`).
+    Pole,
+    /// The result is too large to represent (`ERANGE`).
+    Overflow,
+    /// The result is nonzero but rounds to zero (`ERANGE`).
+    Underflow,
+}
+
+impl fmt::Display for MathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Domain => "argument outside the domain",
+            Self::Pole => "pole",
+            Self::Overflow => "result overflows",
+            Self::Underflow => "result underflows",
+        })
+    }
+}
+
+impl core::error::Error for MathError {}
+
+fn checked(r: f64, e: FpExceptions) -> Result<f64, MathError> {
+    if e.contains(FpExceptions::INVALID) {
+        Err(MathError::Domain)
+    } else if e.contains(FpExceptions::DIVBYZERO) {
+        Err(MathError::Pole)
+    } else if e.contains(FpExceptions::OVERFLOW) {
+        Err(MathError::Overflow)
+    } else if e.contains(FpExceptions::UNDERFLOW) && r == 0.0 {
+        Err(MathError::Underflow)
+    } else {
+        Ok(r)
+    }
+}
+
+macro_rules! checked {
+    ($($f:ident($($a:ident),+) => $exact:ident;)*) => {$(
+        #[doc = concat!("[`", stringify!($f), "`](crate::", stringify!($f), "), or the error it raises.")]
+        pub fn $f($($a: f64),+) -> Result<f64, MathError> {
+            let r = super::$f($($a),+);
+            checked(r, raised(&[$($a),+], r, false, || flags::$exact($($a),+)))
+        }
+    )*};
+}
+
+checked! {
+    exp(x) => exp_is_exact;
+    exp2(x) => exp2_is_exact;
+    exp10(x) => exp10_is_exact;
+    expm1(x) => expm1_is_exact;
+    ln(x) => ln_is_exact;
+    log2(x) => log2_is_exact;
+    log10(x) => log10_is_exact;
+    log1p(x) => log1p_is_exact;
+    sin(x) => zero_is_exact;
+    cos(x) => zero_is_exact;
+    tan(x) => zero_is_exact;
+    asin(x) => zero_is_exact;
+    acos(x) => one_is_exact;
+    atan(x) => zero_is_exact;
+    atan2(y, x) => atan2_is_exact;
+    sinh(x) => zero_is_exact;
+    cosh(x) => zero_is_exact;
+    tanh(x) => tanh_is_exact;
+    asinh(x) => zero_is_exact;
+    acosh(x) => one_is_exact;
+    atanh(x) => atanh_is_exact;
+    cbrt(x) => cbrt_is_exact;
+    sqrt(x) => sqrt_is_exact;
+    hypot(x, y) => hypot_is_exact;
+    pow(x, y) => pow_is_exact;
+    erf(x) => erf_is_exact;
+    erfc(x) => erf_is_exact;
+    lgamma(x) => lgamma_is_exact;
+}
+
+/// [`tgamma`](crate::tgamma), or the error it raises. As in glibc, the
+/// negative integers are a domain error and only zero is a pole.
+pub fn tgamma(x: f64) -> Result<f64, MathError> {
+    let r = super::tgamma(x);
+    checked(r, raised(&[x], r, false, || flags::tgamma_is_exact(x)))
+}
@@ -0,0 +1,438 @@
+//! Complex double type and the C99 Annex G exponential, logarithm, power,
+//! square root and polar functions.
+//!
+//! `Complex64` is `repr(C)` with the real part first, which is the layout of
+//! C `double _Complex`. The functions are built on the real kernels (`exp`,
+//! `log1p`, `sqrt`, `hypot`, `atan2`, `sincos`) and reproduce the special
+//! values of Annex G: signed zeros select the side of a branch cut, and an
+//! infinite part wins over a NaN in the other part wherever the standard says
+//! so. Where Annex G leaves the sign of a zero or infinity unspecified the
+//! choice matches glibc.
+//!
+//! Multiplication and division follow the `_Cmultd` and `_Cdivd` reference
+//! algorithms of G.5.1, so a product or quotient with an infinite operand is
+//! infinite rather than NaN + iNaN.
+
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+use super::atan2::atan2;
+use super::copysign::copysign;
+use super::double::adddd;
+use super::exp::{exp, exp_with_tail};
+use super::gamma::as_logd_accurate;
+use super::hypot::hypot;
+use super::ilogb::ilogb;
+use super::log1p::log1p;
+use super::logb::logb;
+use super::pow::pow;
+use super::sqrt::sqrt;
+use super::trig::sincos;
+use super::{LN2_HI, LN2_LO, fasttwosum, fma_internal, scalbn_internal, two_sum, with_nearest};
+
+// Above this x, exp(x) overflows and cexp scales by 2^-CEXP_SCALE first.
+pub(crate) const CEXP_OVERFLOW: f64 = 709.0;
+pub(crate) const CEXP_SCALE: i32 = 1100;
+// clog takes ln|z| from log1p(|z|^2 - 1) for |z|^2 in this range.
+const CLOG_NEAR_ONE_MIN: f64 = 0.5;
+const CLOG_NEAR_ONE_MAX: f64 = 2.0;
+// Outside [CSQRT_TINY, CSQRT_HUGE) csqrt rescales before `hypot`.
+const CSQRT_HUGE: f64 = f64::from_bits(0x7fda_8279_99fc_ef32); // 0x1.a827999fcef32p+1022
+const CSQRT_TINY: f64 = f64::from_bits(0x0040_0000_0000_0000); // 2^-1019
+
+/// A complex number with `f64` parts, layout-compatible with C
+/// `double _Complex`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Complex64 {
+    /// Real part.
+    pub re: f64,
+    /// Imaginary part.
+    pub im: f64,
+}
+
+impl Complex64 {
+    /// `re + i im`.
+    #[inline]
+    pub const fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+
+    /// Complex conjugate `re - i im`.
+    #[inline]
+    pub const fn conj(self) -> Self {
+        Self {
+            re: self.re,
+            im: -self.im,
+        }
+    }
+}
+
+impl From<f64> for Complex64 {
+    #[inline]
+    fn from(re: f64) -> Self {
+        Self { re, im: 0.0 }
+    }
+}
+
+impl Neg for Complex64 {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self::new(-self.re, -self.im)
+    }
+}
+
+impl Add for Complex64 {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, w: Self) -> Self {
+        Self::new(self.re + w.re, self.im + w.im)
+    }
+}
+
+impl Sub for Complex64 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, w: Self) -> Self {
+        Self::new(self.re - w.re, self.im - w.im)
+    }
+}
+
+// 1 for an infinity and 0 otherwise, carrying the sign of `x` (G.5.1).
+#[inline(always)]
+fn box_inf(x: f64) -> f64 {
+    copysign(if x.is_infinite() { 1.0 } else { 0.0 }, x)
+}
+
+// A NaN part of an operand whose other part is infinite becomes a signed 0.
+#[inline(always)]
+fn nan_to_zero(x: f64) -> f64 {
+    if x.is_nan() { copysign(0.0, x) } else { x }
+}
+
+impl Mul for Complex64 {
+    type Output = Self;
+
+    /// Product following `_Cmultd` of Annex G.5.1.
+    fn mul(self, w: Self) -> Self {
+        let (mut a, mut b, mut c, mut d) = (self.re, self.im, w.re, w.im);
+        let (ac, bd, ad, bc) = (a * c, b * d, a * d, b * c);
+        let x = ac - bd;
+        let y = ad + bc;
+        if !(x.is_nan() && y.is_nan()) {
+            return Self::new(x, y);
+        }
+        let mut recalc = false;
+        if a.is_infinite() || b.is_infinite() {
+            a = box_inf(a);
+            b = box_inf(b);
+            c = nan_to_zero(c);
+            d = nan_to_zero(d);
+            recalc = true;
+        }
+        if c.is_infinite() || d.is_infinite() {
+            c = box_inf(c);
+            d = box_inf(d);
+            a = nan_to_zero(a);
+            b = nan_to_zero(b);
+            recalc = true;
+        }
+        if !recalc && (ac.is_infinite() || bd.is_infinite() || ad.is_infinite() || bc.is_infinite())
+        {
+            // Overflow in a partial product: recover the infinity.
+            a = nan_to_zero(a);
+            b = nan_to_zero(b);
+            c = nan_to_zero(c);
+            d = nan_to_zero(d);
+            recalc = true;
+        }
+        if !recalc {
+            return Self::new(x, y);
+        }
+        Self::new(
+            f64::INFINITY * (a * c - b * d),
+            f64::INFINITY * (a * d + b * c),
+        )
+    }
+}
+
+impl Div for Complex64 {
+    type Output = Self;
+
+    /// Quotient following `_Cdivd` of Annex G.5.1, with the divisor scaled by
+    /// a power of two to avoid spurious overflow and underflow.
+    fn div(self, w: Self) -> Self {
+        let (mut a, mut b, mut c, mut d) = (self.re, self.im, w.re, w.im);
+        let logbw = logb(c.abs().max(d.abs()));
+        let mut ilogbw = 0;
+        if logbw.is_finite() {
+            ilogbw = logbw as i32;
+            c = scalbn_internal(c, -ilogbw);
+            d = scalbn_internal(d, -ilogbw);
+        }
+        let denom = c * c + d * d;
+        let mut x = scalbn_internal((a * c + b * d) / denom, -ilogbw);
+        let mut y = scalbn_internal((b * c - a * d) / denom, -ilogbw);
+        if x.is_nan() && y.is_nan() {
+            if denom == 0.0 && (!a.is_nan() || !b.is_nan()) {
+                x = copysign(f64::INFINITY, c) * a;
+                y = copysign(f64::INFINITY, c) * b;
+            } else if (a.is_infinite() || b.is_infinite()) && c.is_finite() && d.is_finite() {
+                a = box_inf(a);
+                b = box_inf(b);
+                x = f64::INFINITY * (a * c + b * d);
+                y = f64::INFINITY * (b * c - a * d);
+            } else if logbw == f64::INFINITY && a.is_finite() && b.is_finite() {
+                c = box_inf(c);
+                d = box_inf(d);
+                x = 0.0 * (a * c + b * d);
+                y = 0.0 * (b * c - a * d);
+            }
+        }
+        Self::new(x, y)
+    }
+}
+
+/// Complex absolute value |z| = hypot(re, im).
+///
+/// Infinite if either part is infinite, even when the other is NaN.
+#[inline]
+pub fn cabs(z: Complex64) -> f64 {
+    if z.re.is_infinite() || z.im.is_infinite() {
+        return f64::INFINITY;
+    }
+    hypot(z.re, z.im)
+}
+
+/// Argument of `z` in [-pi, pi] = atan2(im, re).
+///
+/// The sign of a zero imaginary part selects pi or -pi on the negative real
+/// axis.
+#[inline]
+pub fn carg(z: Complex64) -> f64 {
+    atan2(z.im, z.re)
+}
+
+/// Projection onto the Riemann sphere.
+///
+/// Every complex infinity, including one with a NaN part, maps to
+/// `inf + i copysign(0, im)`; other values are returned unchanged.
+#[inline]
+pub fn cproj(z: Complex64) -> Complex64 {
+    if z.re.is_infinite() || z.im.is_infinite() {
+        Complex64::new(f64::INFINITY, copysign(0.0, z.im))
+    } else {
+        z
+    }
+}
+
+// e^(x - CEXP_SCALE ln 2) for CEXP_OVERFLOW < x, with x - k ln 2 kept as a
+// double-double; the subtraction of k LN2_HI is exact wherever a result
+// scaled back by 2^CEXP_SCALE can be finite.
+#[inline(always)]
+pub(crate) fn exp_scaled(x: f64) -> f64 {
+    let k = CEXP_SCALE as f64;
+    exp_with_tail(x - k * LN2_HI, -k * LN2_LO)
+}
+
+// m v 2^k for nonzero v, with v normalized first so that m v stays normal.
+#[inline(always)]
+pub(crate) fn scaled_mul(m: f64, v: f64, k: i32) -> f64 {
+    let j = ilogb(v);
+    scalbn_internal(m * scalbn_internal(v, -j), k + j)
+}
+
+/// Complex exponential e^z = e^re (cos im + i sin im).
+///
+/// A zero imaginary part is returned unchanged, so `cexp(x + i0)` is real
+/// for every `x`. For finite `x` an infinite or NaN imaginary part gives
+/// NaN + iNaN; `cexp(-inf + i y)` is a signed zero and `cexp(+inf + i y)` is
+/// inf + iNaN for non-finite `y`.
+pub fn cexp(z: Complex64) -> Complex64 {
+    with_nearest(z, cexp_nearest)
+}
+
+fn cexp_nearest(z: Complex64) -> Complex64 {
+    let (x, y) = (z.re, z.im);
+    if y == 0.0 {
+        return Complex64::new(exp(x), y);
+    }
+    if !y.is_finite() {
+        return if x == f64::INFINITY {
+            Complex64::new(x, f64::NAN)
+        } else if x == f64::NEG_INFINITY {
+            Complex64::new(0.0, copysign(0.0, y))
+        } else {
+            Complex64::new(f64::NAN, f64::NAN)
+        };
+    }
+    if x.is_nan() {
+        return Complex64::new(x, x);
+    }
+    let (s, c) = sincos(y);
+    if x > CEXP_OVERFLOW {
+        // e^x overflows but e^x cos y may not.
+        let m = exp_scaled(x);
+        return Complex64::new(scaled_mul(m, c, CEXP_SCALE), scaled_mul(m, s, CEXP_SCALE));
+    }
+    let e = exp(x);
+    Complex64::new(e * c, e * s)
+}
+
+// |z|^2 = a^2 + b^2 as a double-double.
+#[inline(always)]
+fn norm_dd(a: f64, b: f64, l: &mut f64) -> f64 {
+    let ah = a * a;
+    let bh = b * b;
+    let al = fma_internal(a, a, -ah);
+    let bl = fma_internal(b, b, -bh);
+    let mut e = 0.0;
+    let h = adddd(ah, al, bh, bl, &mut e);
+    fasttwosum(h, e, l)
+}
+
+// a^2 + b^2 - 1 as a double-double. Next to the unit circle the sum cancels,
+// so it is distilled from the exact products with error-free additions.
+pub(crate) fn norm_m1(a: f64, b: f64, l: &mut f64) -> f64 {
+    let ah = a * a;
+    let bh = b * b;
+    let al = fma_internal(a, a, -ah);
+    let bl = fma_internal(b, b, -bh);
+    let (th, e0) = two_sum(ah, -1.0);
+    let (th, e1) = two_sum(th, bh);
+    let (th, e2) = two_sum(th, al);
+    let (th, e3) = two_sum(th, bl);
+    fasttwosum(th, (e0 + e1) + (e2 + e3), l)
+}
+
+// ln|z| for finite nonzero z with max(|re|, |im|) = a >= b = min.
+fn ln_abs(a: f64, b: f64) -> f64 {
+    let mut sl = 0.0;
+    let sh = norm_dd(a, b, &mut sl);
+    if (CLOG_NEAR_ONE_MIN..=CLOG_NEAR_ONE_MAX).contains(&sh) {
+        // ln|z| = log1p(|z|^2 - 1)/2.
+        let mut tl = 0.0;
+        let th = norm_m1(a, b, &mut tl);
+        return 0.5 * (log1p(th) + tl / (1.0 + th));
+    }
+    // Elsewhere |z| = 2^k |z'| with max(|re z'|, |im z'|) in [1, 2), and
+    // ln|z| = k ln 2 + ln(|z'|^2)/2 with the logarithm in double-double.
+    let k = ilogb(a);
+    let sh = norm_dd(scalbn_internal(a, -k), scalbn_internal(b, -k), &mut sl);
+    let (mut ll, mut ll2) = (0.0, 0.0);
+    let lh = as_logd_accurate(sh, &mut ll, &mut ll2);
+    let mut e = 0.0;
+    let lh = adddd(lh, ll + ll2, sl / sh, 0.0, &mut e);
+    let kf = k as f64;
+    let mut rl = 0.0;
+    let rh = adddd(kf * LN2_HI, kf * LN2_LO, 0.5 * lh, 0.5 * e, &mut rl);
+    rh + rl
+}
+
+/// Principal complex logarithm ln|z| + i arg z, with arg z in [-pi, pi].
+///
+/// The branch cut is the negative real axis: `clog(-x ± i0)` is
+/// ln x ± i pi. `clog(±0 + i0)` is -inf + i0 or -inf + i pi, an infinite part
+/// gives +inf in the real part even when the other part is NaN.
+pub fn clog(z: Complex64) -> Complex64 {
+    with_nearest(z, clog_nearest)
+}
+
+fn clog_nearest(z: Complex64) -> Complex64 {
+    let (x, y) = (z.re, z.im);
+    let (ax, ay) = (x.abs(), y.abs());
+    let im = atan2(y, x);
+    if ax.is_infinite() || ay.is_infinite() {
+        return Complex64::new(f64::INFINITY, im);
+    }
+    if ax.is_nan() || ay.is_nan() {
+        return Complex64::new(f64::NAN, f64::NAN);
+    }
+    if ax == 0.0 && ay == 0.0 {
+        return Complex64::new(f64::NEG_INFINITY, im);
+    }
+    let (a, b) = if ax >= ay { (ax, ay) } else { (ay, ax) };
+    Complex64::new(ln_abs(a, b), im)
+}
+
+/// Principal complex square root, with non-negative real part.
+///
+/// The branch cut is the negative real axis: `csqrt(-x ± i0)` is
+/// 0 ± i sqrt(x). `csqrt(x ± i inf)` is +inf ± i inf for every `x`, NaN
+/// included; `csqrt(-inf + i y)` is 0 + i inf and `csqrt(+inf + i y)` is
+/// +inf + i0 for finite `y`.
+pub fn csqrt(z: Complex64) -> Complex64 {
+    with_nearest(z, csqrt_nearest)
+}
+
+fn csqrt_nearest(z: Complex64) -> Complex64 {
+    let (mut a, mut b) = (z.re, z.im);
+    if a == 0.0 && b == 0.0 {
+        return Complex64::new(0.0, b);
+    }
+    if b.is_infinite() {
+        return Complex64::new(f64::INFINITY, b);
+    }
+    if a.is_nan() {
+        return Complex64::new(a, a);
+    }
+    if a.is_infinite() {
+        return if a > 0.0 {
+            Complex64::new(a, if b.is_nan() { b } else { copysign(0.0, b) })
+        } else {
+            Complex64::new(if b.is_nan() { b } else { 0.0 }, copysign(-a, b))
+        };
+    }
+    if b.is_nan() {
+        return Complex64::new(b, b);
+    }
+    // Kahan's form: t = sqrt((|a| + |z|)/2), then the other part is b/(2t);
+    // both are free of cancellation. Huge and tiny inputs are rescaled by an
+    // even power of two so that |a| + |z| neither overflows nor loses bits.
+    let mut k = 0;
+    if a.abs() >= CSQRT_HUGE || b.abs() >= CSQRT_HUGE {
+        a *= 0.25;
+        b *= 0.25;
+        k = 1;
+    } else if a.abs() < CSQRT_TINY && b.abs() < CSQRT_TINY {
+        a = scalbn_internal(a, 108);
+        b = scalbn_internal(b, 108);
+        k = -54;
+    }
+    let t = sqrt(0.5 * (a.abs() + hypot(a, b)));
+    let (re, im) = if a >= 0.0 {
+        (t, b / (2.0 * t))
+    } else {
+        (b.abs() / (2.0 * t), copysign(t, b))
+    };
+    Complex64::new(scalbn_internal(re, k), scalbn_internal(im, k))
+}
+
+/// Complex power z^w = cexp(w clog z) on the principal branch.
+///
+/// Annex G leaves `cpow` to that definition; as for the real `pow`, a zero
+/// exponent gives 1 + i0 for every `z`, and 0^w is +0 + i0 when re w > 0.
+/// A positive real base with a real exponent uses the real `pow`. Otherwise
+/// the error grows with |w ln z|, as the rounding of the exponent is
+/// amplified by `cexp`.
+pub fn cpow(z: Complex64, w: Complex64) -> Complex64 {
+    with_nearest((z, w), |(z, w)| cpow_nearest(z, w))
+}
+
+fn cpow_nearest(z: Complex64, w: Complex64) -> Complex64 {
+    if w.re == 0.0 && w.im == 0.0 {
+        return Complex64::new(1.0, 0.0);
+    }
+    if z.re == 0.0 && z.im == 0.0 && w.re > 0.0 {
+        return Complex64::new(0.0, 0.0);
+    }
+    if w.im == 0.0 && z.im == 0.0 && z.re > 0.0 && z.re.is_finite() {
+        // Positive real base and real exponent: the real `pow` is accurate
+        // and keeps the imaginary part an exact zero.
+        return Complex64::new(pow(z.re, w.re), 0.0);
+    }
+    cexp(w * clog(z))
+}
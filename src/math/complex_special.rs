@@ -0,0 +1,1129 @@
+//! Complex gamma and log-gamma, the complex error functions and the Faddeeva
+//! function w(z) = e^(-z^2) erfc(-iz).
+//!
+//! `clgamma` is the principal branch of ln Gamma used by scipy's `loggamma`:
+//! the analytic continuation of the real ln Gamma(x), x > 0, cut along the
+//! negative real axis, so its imaginary part is continuous off the cut rather
+//! than reduced into (-pi, pi]. The regions are those of Hare ("Computing the
+//! principal branch of log-Gamma", J. Algorithms 25, 1997): the Stirling
+//! series for re z >= 7 or |im z| >= 7, Taylor series at 1 and 2, the
+//! reflection formula for re z < 0.1 and upward recurrence elsewhere, all
+//! evaluated in double-double. The asymptotic tables of the real `lgamma`
+//! are minimax fits along the real axis and do not hold off it, so the
+//! series here uses the exact coefficients B_2k / (2k (2k - 1)). `cgamma` is
+//! the exponential of the double-double log-gamma. Next to the real axis the
+//! imaginary parts of both come from im z psi(re z), with a double-double
+//! digamma: there the general evaluation leaves them to rounding noise and
+//! underflow. Near a zero of psi that first-order term itself cancels, and
+//! the imaginary parts are accurate only relative to |im z|.
+//!
+//! For |z| < 7 and |im z| < 3.5 the Faddeeva function is summed as
+//!   w(z) = e^(-z^2) (1 + 2i/sqrt(pi) sum_n z^(2n+1) / (n! (2n + 1))),
+//! whose terms lose at most 35 bits to cancellation there. The rest of the
+//! upper half-plane uses the Laplace continued fraction
+//!   w(z) = i/sqrt(pi) / (z - (1/2) / (z - 1 / (z - (3/2) / (z - ...)))),
+//! with e^(-z^2) added back next to the real axis, where the truncated
+//! fraction leaves it out; the lower half-plane uses w(z) = 2 e^(-z^2) - w(-z).
+//! `cerf` and `cerfc` sum the Maclaurin series of erf near the origin and the
+//! imaginary axis and otherwise use erfc(z) = e^(-z^2) w(iz); on the real
+//! axis they are the real `erf` and `erfc`.
+
+use super::atan2::atan2;
+use super::complex::{CEXP_OVERFLOW, CEXP_SCALE, Complex64, clog, exp_scaled, scaled_mul};
+use super::copysign::copysign;
+use super::double::{add_dd, adddd, divdd, divddd, muldd_acc, mulddd, twosum};
+use super::erf::{erf, erfc, exp_1};
+use super::exp::exp_with_tail;
+use super::gamma::{as_logd_accurate, lgamma, tgamma};
+use super::ilogb::ilogb;
+use super::sici::{sincos_dd, sincos_taylor_dd};
+use super::sqrt::sqrt;
+use super::trig::sincos;
+use super::{
+    ceil, fasttwosum, floor, fma_internal, roundeven_finite, scalbn_internal, with_nearest,
+};
+
+// pi, ln 2, ln pi, ln(2 pi)/2, 2/sqrt(pi) and 1/sqrt(pi) as double-doubles.
+const PI_HI: f64 = f64::from_bits(0x4009_21fb_5444_2d18);
+const PI_LO: f64 = f64::from_bits(0x3ca1_a626_3314_5c07);
+const LN_2_HI: f64 = f64::from_bits(0x3fe6_2e42_fefa_39ef);
+const LN_2_LO: f64 = f64::from_bits(0x3c7a_bc9e_3b39_803f);
+const LN_PI_HI: f64 = f64::from_bits(0x3ff2_50d0_48e7_a1bd);
+const LN_PI_LO: f64 = f64::from_bits(0x3c67_abf2_ad8d_5088);
+const HALF_LN_2PI_HI: f64 = f64::from_bits(0x3fed_67f1_c864_beb5);
+const HALF_LN_2PI_LO: f64 = f64::from_bits(0xbc86_5b5a_1b7f_f5df);
+const FRAC_2_SQRT_PI_HI: f64 = f64::from_bits(0x3ff2_0dd7_5042_9b6d);
+const FRAC_2_SQRT_PI_LO: f64 = f64::from_bits(0x3c71_ae3a_914f_ed80);
+const FRAC_1_SQRT_PI_HI: f64 = f64::from_bits(0x3fe2_0dd7_5042_9b6d);
+const FRAC_1_SQRT_PI_LO: f64 = f64::from_bits(0x3c61_ae3a_914f_ed80);
+
+// ln Gamma uses the Stirling series from re z or |im z| = 7 on.
+const STIRLING_MIN: f64 = 7.0;
+// Past this max(|re z|, |im z|) only (z - 1/2) ln z - z is significant.
+const STIRLING_HUGE: f64 = f64::from_bits(0x7830_0000_0000_0000); // 2^900
+// Next to the real axis, |im z| <= NEAR_AXIS d with d the distance from
+// re z < 1/2 to the nearest integer (d = 1 from re z = 1/2 on), the
+// imaginary part of ln Gamma(z) is taken from im z psi(re z).
+const NEAR_AXIS: f64 = f64::from_bits(0x3df0_0000_0000_0000); // 2^-32
+// psi(u) uses its asymptotic series from u = 24 on.
+const DIGAMMA_ASYMPTOTIC_MIN: f64 = 24.0;
+// Squared radius of the Taylor discs of ln Gamma at 1 and 2.
+const TAYLOR_R2: f64 = 0.04;
+// Below this re z ln Gamma is reflected.
+const REFLECT_MAX: f64 = 0.1;
+// Below this |pi im z| sinh(pi im z) is taken from its Taylor series.
+const SINH_TAYLOR_MAX: f64 = 0.5;
+// w(z) is summed as a series for |z|^2 < W_SERIES_R2 and |im z| < W_SERIES_IM.
+const W_SERIES_R2: f64 = 49.0;
+const W_SERIES_IM: f64 = 3.5;
+// Below this im z the continued fraction misses e^(-z^2).
+const W_ADD_EXP_IM: f64 = 1.0;
+// From this max(|re z|, |im z|) on, w(z) = i/(sqrt(pi) z) (1 + 1/(2 z^2)).
+const W_ASYMPTOTIC: f64 = 67_108_864.0; // 2^26
+// Below this max(|re z|, |im z|), z^2 is exact as a double-double.
+const SQUARE_MAX: f64 = f64::from_bits(0x5fe0_0000_0000_0000); // 2^511
+// erf is summed as a series for |z|^2 < ERF_SERIES_R2, or for re z < 1 and
+// (im z)^2 < W_SERIES_R2.
+const ERF_SERIES_R2: f64 = 6.25;
+// Series summation stops once a term drops below this fraction of the sum.
+const SERIES_EPS: f64 = f64::from_bits(0x3970_0000_0000_0000); // 2^-104
+// Phase tails below this are applied to first order; larger ones come from
+// phases beyond 2^80 and are reduced separately.
+const CIS_TAIL_MAX: f64 = f64::from_bits(0x3e40_0000_0000_0000); // 2^-27
+
+// B_2k / (2k (2k - 1)) for k = 1..15.
+const STIRLING: [[f64; 2]; 15] = [
+    [
+        f64::from_bits(0x3fb5555555555555),
+        f64::from_bits(0x3c55555555555555),
+    ],
+    [
+        f64::from_bits(0xbf66c16c16c16c17),
+        f64::from_bits(0x3bff49f49f49f49f),
+    ],
+    [
+        f64::from_bits(0x3f4a01a01a01a01a),
+        f64::from_bits(0x3b8a01a01a01a01a),
+    ],
+    [
+        f64::from_bits(0xbf43813813813814),
+        f64::from_bits(0x3befb1fb1fb1fb20),
+    ],
+    [
+        f64::from_bits(0x3f4b951e2b18ff23),
+        f64::from_bits(0x3be5c3a9ce01b952),
+    ],
+    [
+        f64::from_bits(0xbf5f6ab0d9993c7d),
+        f64::from_bits(0x3bff82553c999b0e),
+    ],
+    [
+        f64::from_bits(0x3f7a41a41a41a41a),
+        f64::from_bits(0x3c10690690690690),
+    ],
+    [
+        f64::from_bits(0xbf9e4286cb0f5398),
+        f64::from_bits(0x3c21efcdab896745),
+    ],
+    [
+        f64::from_bits(0x3fc6fe96381e0680),
+        f64::from_bits(0xbc279e2405a71f88),
+    ],
+    [
+        f64::from_bits(0xbff6476701181f3a),
+        f64::from_bits(0x3c724246319da678),
+    ],
+    [
+        f64::from_bits(0x402ace44322ce006),
+        f64::from_bits(0xbcc62c2b1bbcdd32),
+    ],
+    [
+        f64::from_bits(0xc0639b2525cccc1b),
+        f64::from_bits(0x3d052604768a30fc),
+    ],
+    [
+        f64::from_bits(0x40a12234e81b4e82),
+        f64::from_bits(0xbd42c5f92c5f92c6),
+    ],
+    [
+        f64::from_bits(0xc0e1a198ae1c4ab8),
+        f64::from_bits(0x3d64c012227b696e),
+    ],
+    [
+        f64::from_bits(0x41251a2089a6e11a),
+        f64::from_bits(0x3dbc219ee4fdc447),
+    ],
+];
+
+// Taylor coefficients of ln Gamma(1 + t): -gamma, then (-1)^k zeta(k)/k.
+const TAYLOR_1: [[f64; 2]; 27] = [
+    [
+        f64::from_bits(0xbfe2788cfc6fb619),
+        f64::from_bits(0x3c56cb90701fbfab),
+    ],
+    [
+        f64::from_bits(0x3fea51a6625307d3),
+        f64::from_bits(0x3c71873d8912200c),
+    ],
+    [
+        f64::from_bits(0xbfd9a4d55beab2d7),
+        f64::from_bits(0x3c44c26d1b465993),
+    ],
+    [
+        f64::from_bits(0x3fd151322ac7d848),
+        f64::from_bits(0x3c6b5f91211196e5),
+    ],
+    [
+        f64::from_bits(0xbfca8b9c17aa6149),
+        f64::from_bits(0xbc52e826a4fdae1a),
+    ],
+    [
+        f64::from_bits(0x3fc5b40cb100c306),
+        f64::from_bits(0x3c44a79940f15696),
+    ],
+    [
+        f64::from_bits(0xbfc2703a1dcea3ae),
+        f64::from_bits(0xbc66307fd0794ac4),
+    ],
+    [
+        f64::from_bits(0x3fc010b36af86397),
+        f64::from_bits(0xbc4741a635b224a6),
+    ],
+    [
+        f64::from_bits(0xbfbc806706d57db4),
+        f64::from_bits(0xbc556aa806fdd3ee),
+    ],
+    [
+        f64::from_bits(0x3fb9a01e385d5f8f),
+        f64::from_bits(0x3c4813418f3768cd),
+    ],
+    [
+        f64::from_bits(0xbfb748c33114c6d6),
+        f64::from_bits(0xbc2ea57624080720),
+    ],
+    [
+        f64::from_bits(0x3fb556ad63243bc4),
+        f64::from_bits(0x3c15de8580fae81d),
+    ],
+    [
+        f64::from_bits(0xbfb3b1d971fc5985),
+        f64::from_bits(0x3c4e58607e493dfd),
+    ],
+    [
+        f64::from_bits(0x3fb2496df8320c5f),
+        f64::from_bits(0x3c5cf4b4ae040be8),
+    ],
+    [
+        f64::from_bits(0xbfb11133476e7fe0),
+        f64::from_bits(0xbc4dc9a4ff396ee3),
+    ],
+    [
+        f64::from_bits(0x3fb00010064cdeb2),
+        f64::from_bits(0x3c47879d0156affe),
+    ],
+    [
+        f64::from_bits(0xbfae1e2d311e8abd),
+        f64::from_bits(0x3c48d2a110ce956b),
+    ],
+    [
+        f64::from_bits(0x3fac71ce3a20b419),
+        f64::from_bits(0xbc4be9617d035b06),
+    ],
+    [
+        f64::from_bits(0xbfaaf28a1b5688a0),
+        f64::from_bits(0xbc474741e885fefb),
+    ],
+    [
+        f64::from_bits(0x3fa9999b3352d5ba),
+        f64::from_bits(0x3c14951b4c6be56d),
+    ],
+    [
+        f64::from_bits(0xbfa86186db77bfbf),
+        f64::from_bits(0xbc46dedef1f58778),
+    ],
+    [
+        f64::from_bits(0x3fa745d1d1778df9),
+        f64::from_bits(0x3c202b8fe0a898e7),
+    ],
+    [
+        f64::from_bits(0xbfa642c88591b66d),
+        f64::from_bits(0x3c41074551cafc60),
+    ],
+    [
+        f64::from_bits(0x3fa555556aaafdcd),
+        f64::from_bits(0x3c454a05fce04ef6),
+    ],
+    [
+        f64::from_bits(0xbfa47ae151eb9fb7),
+        f64::from_bits(0xbc4d038d4d4653c2),
+    ],
+    [
+        f64::from_bits(0x3fa3b13b189d925e),
+        f64::from_bits(0x3c4f4ad5a89f860c),
+    ],
+    [
+        f64::from_bits(0xbfa2f684c00002bc),
+        f64::from_bits(0xbc4055a3ba5e6a12),
+    ],
+];
+// Taylor coefficients of ln Gamma(2 + t): 1 - gamma, then (-1)^k (zeta(k) - 1)/k.
+const TAYLOR_2: [[f64; 2]; 19] = [
+    [
+        f64::from_bits(0x3fdb0ee6072093ce),
+        f64::from_bits(0x3c56cb90701fbfab),
+    ],
+    [
+        f64::from_bits(0x3fd4a34cc4a60fa6),
+        f64::from_bits(0x3c71873d8912200c),
+    ],
+    [
+        f64::from_bits(0xbfb13e001a557607),
+        f64::from_bits(0x3c5fb68be2f8821f),
+    ],
+    [
+        f64::from_bits(0x3f951322ac7d8483),
+        f64::from_bits(0x3c3afc89088cb729),
+    ],
+    [
+        f64::from_bits(0xbf7e404fc218f5f2),
+        f64::from_bits(0x3c1e4a627cf1eb34),
+    ],
+    [
+        f64::from_bits(0x3f67add6eadb6c30),
+        f64::from_bits(0xbbf5b7828c7fd7f4),
+    ],
+    [
+        f64::from_bits(0xbf538ac5c2bf8e08),
+        f64::from_bits(0x3be8a4c1cfd9cec8),
+    ],
+    [
+        f64::from_bits(0x3f40b36af86396e9),
+        f64::from_bits(0xbbe0698d6c892967),
+    ],
+    [
+        f64::from_bits(0xbf2d3fd4c76d2fc8),
+        f64::from_bits(0x3bbc7c55cfccbb83),
+    ],
+    [
+        f64::from_bits(0x3f1a127b0f17d65a),
+        f64::from_bits(0x3ba9d309aa700268),
+    ],
+    [
+        f64::from_bits(0xbf078de5bd7c81ef),
+        f64::from_bits(0x3b7a20541cde47a6),
+    ],
+    [
+        f64::from_bits(0x3ef580dcee66eb02),
+        f64::from_bits(0x3b8260574b258f72),
+    ],
+    [
+        f64::from_bits(0xbee3cbc963ce2243),
+        f64::from_bits(0x3b8ea56e6c7d5329),
+    ],
+    [
+        f64::from_bits(0x3ed2597a39f34aac),
+        f64::from_bits(0xbb7bf911462a7d81),
+    ],
+    [
+        f64::from_bits(0xbec11b2eb7679541),
+        f64::from_bits(0xbb4c76b0e65ac63a),
+    ],
+    [
+        f64::from_bits(0x3eb0064cdeb22f0f),
+        f64::from_bits(0x3b4d0156affdbc11),
+    ],
+    [
+        f64::from_bits(0xbe9e2600d93cfd2f),
+        f64::from_bits(0x3b3130ac39e5c106),
+    ],
+    [
+        f64::from_bits(0x3e8c76bbb3f07a4d),
+        f64::from_bits(0x3b2d9a2b77769b52),
+    ],
+    [
+        f64::from_bits(0xbe7af5a6cbbf8a97),
+        f64::from_bits(0xbb195f227e96d83e),
+    ],
+];
+
+// A complex double-double (rh + rl) + i (ih + il).
+#[derive(Clone, Copy)]
+struct Cdd {
+    rh: f64,
+    rl: f64,
+    ih: f64,
+    il: f64,
+}
+
+impl Cdd {
+    #[inline(always)]
+    const fn new(rh: f64, rl: f64, ih: f64, il: f64) -> Self {
+        Self { rh, rl, ih, il }
+    }
+
+    #[inline(always)]
+    const fn from_parts(x: f64, y: f64) -> Self {
+        Self::new(x, 0.0, y, 0.0)
+    }
+
+    #[inline(always)]
+    const fn real(h: f64, l: f64) -> Self {
+        Self::new(h, l, 0.0, 0.0)
+    }
+
+    #[inline(always)]
+    const fn imag(h: f64, l: f64) -> Self {
+        Self::new(0.0, 0.0, h, l)
+    }
+
+    #[inline(always)]
+    const fn conj(self) -> Self {
+        Self::new(self.rh, self.rl, -self.ih, -self.il)
+    }
+
+    #[inline(always)]
+    const fn neg(self) -> Self {
+        Self::new(-self.rh, -self.rl, -self.ih, -self.il)
+    }
+
+    #[inline(always)]
+    fn add(self, w: Self) -> Self {
+        let (mut rl, mut il) = (0.0, 0.0);
+        let rh = add_dd(self.rh, self.rl, w.rh, w.rl, &mut rl);
+        let ih = add_dd(self.ih, self.il, w.ih, w.il, &mut il);
+        Self::new(rh, rl, ih, il)
+    }
+
+    #[inline(always)]
+    fn sub(self, w: Self) -> Self {
+        self.add(w.neg())
+    }
+
+    #[inline(always)]
+    fn mul(self, w: Self) -> Self {
+        let (mut pl, mut ql) = (0.0, 0.0);
+        let ph = muldd_acc(self.rh, self.rl, w.rh, w.rl, &mut pl);
+        let qh = muldd_acc(self.ih, self.il, w.ih, w.il, &mut ql);
+        let mut rl = 0.0;
+        let rh = add_dd(ph, pl, -qh, -ql, &mut rl);
+        let ph = muldd_acc(self.rh, self.rl, w.ih, w.il, &mut pl);
+        let qh = muldd_acc(self.ih, self.il, w.rh, w.rl, &mut ql);
+        let mut il = 0.0;
+        let ih = add_dd(ph, pl, qh, ql, &mut il);
+        Self::new(rh, rl, ih, il)
+    }
+
+    // Product with the real double-double h + l.
+    #[inline(always)]
+    fn mul_real(self, h: f64, l: f64) -> Self {
+        let (mut rl, mut il) = (0.0, 0.0);
+        let rh = muldd_acc(self.rh, self.rl, h, l, &mut rl);
+        let ih = muldd_acc(self.ih, self.il, h, l, &mut il);
+        Self::new(rh, rl, ih, il)
+    }
+
+    #[inline(always)]
+    fn mul_f64(self, d: f64) -> Self {
+        let (mut rl, mut il) = (0.0, 0.0);
+        let rh = mulddd(self.rh, self.rl, d, &mut rl);
+        let ih = mulddd(self.ih, self.il, d, &mut il);
+        Self::new(rh, rl, ih, il)
+    }
+
+    #[inline(always)]
+    fn div_f64(self, d: f64) -> Self {
+        let (mut rl, mut il) = (0.0, 0.0);
+        let rh = divddd(self.rh, self.rl, d, &mut rl);
+        let ih = divddd(self.ih, self.il, d, &mut il);
+        Self::new(rh, rl, ih, il)
+    }
+
+    #[inline(always)]
+    fn scalbn(self, k: i32) -> Self {
+        Self::new(
+            scalbn_internal(self.rh, k),
+            scalbn_internal(self.rl, k),
+            scalbn_internal(self.ih, k),
+            scalbn_internal(self.il, k),
+        )
+    }
+
+    // |self|^2 as a double-double.
+    #[inline(always)]
+    fn norm(self, l: &mut f64) -> f64 {
+        let (mut al, mut bl) = (0.0, 0.0);
+        let ah = muldd_acc(self.rh, self.rl, self.rh, self.rl, &mut al);
+        let bh = muldd_acc(self.ih, self.il, self.ih, self.il, &mut bl);
+        add_dd(ah, al, bh, bl, l)
+    }
+
+    // 1/self = conj(self)/|self|^2 for nonzero self, scaled by a power of two
+    // so that the norm neither overflows nor underflows.
+    fn recip(self) -> Self {
+        let k = ilogb(self.rh.abs().max(self.ih.abs()));
+        let s = self.scalbn(-k);
+        let mut nl = 0.0;
+        let nh = s.norm(&mut nl);
+        let (mut rl, mut il) = (0.0, 0.0);
+        let rh = divdd(s.rh, s.rl, nh, nl, &mut rl);
+        let ih = divdd(-s.ih, -s.il, nh, nl, &mut il);
+        Self::new(rh, rl, ih, il).scalbn(-k)
+    }
+
+    #[inline(always)]
+    fn to_complex(self) -> Complex64 {
+        Complex64::new(self.rh + self.rl, self.ih + self.il)
+    }
+}
+
+// Principal ln z for nonzero z: ln|z| from the double-double norm of z
+// scaled by a power of two, arg z from atan2 refined by one Newton step
+// against the double-double sine and cosine of its value.
+fn clog_dd(z: Cdd) -> Cdd {
+    let k = ilogb(z.rh.abs().max(z.ih.abs()));
+    let s = z.scalbn(-k);
+    let mut nl = 0.0;
+    let nh = s.norm(&mut nl);
+    let (mut ll, mut ll2) = (0.0, 0.0);
+    let lh = as_logd_accurate(nh, &mut ll, &mut ll2);
+    let mut e = 0.0;
+    let lh = adddd(lh, ll + ll2, nl / nh, 0.0, &mut e);
+    let mut kl = 0.0;
+    let kh = mulddd(LN_2_HI, LN_2_LO, k as f64, &mut kl);
+    let mut rl = 0.0;
+    let rh = add_dd(kh, kl, 0.5 * lh, 0.5 * e, &mut rl);
+    // arg z = a + (v cos a - u sin a) / (u cos a + v sin a) for z' = u + iv.
+    let a = atan2(s.ih, s.rh);
+    let ((sh, sl), (ch, cl)) = sincos_dd(a);
+    let (mut pl, mut ql) = (0.0, 0.0);
+    let ph = muldd_acc(s.ih, s.il, ch, cl, &mut pl);
+    let qh = muldd_acc(s.rh, s.rl, sh, sl, &mut ql);
+    let mut dl = 0.0;
+    let dh = add_dd(ph, pl, -qh, -ql, &mut dl);
+    let mut il = 0.0;
+    let ih = fasttwosum(a, (dh + dl) / (s.rh * ch + s.ih * sh), &mut il);
+    Cdd::new(rh, rl, ih, il)
+}
+
+// e^z for z as a double-double, with the scaling of `cexp` past the
+// overflow of e^(re z).
+fn cexp_dd(z: Cdd) -> Complex64 {
+    let ((sh, sl), (ch, cl)) = sincos_dd(z.ih);
+    let (c, s) = if z.il.abs() < CIS_TAIL_MAX {
+        // cis(ih + il) = (cos ih - il sin ih) + i (sin ih + il cos ih)
+        (ch + (cl - sh * z.il), sh + (sl + ch * z.il))
+    } else {
+        let (st, ct) = sincos(z.il);
+        (ch * ct - sh * st, sh * ct + ch * st)
+    };
+    exp_cis(z.rh, z.rl, c, s)
+}
+
+// e^(rh + rl) (c + is), scaled like `cexp` past the overflow of e^rh.
+fn exp_cis(rh: f64, rl: f64, c: f64, s: f64) -> Complex64 {
+    if rh > CEXP_OVERFLOW {
+        let m = exp_scaled(rh) * (1.0 + rl);
+        let f = |v: f64| {
+            if v == 0.0 {
+                v
+            } else {
+                scaled_mul(m, v, CEXP_SCALE)
+            }
+        };
+        return Complex64::new(f(c), f(s));
+    }
+    let e = exp_with_tail(rh, rl);
+    Complex64::new(e * c, e * s)
+}
+
+// sum_k c_k t^k with the coefficients c_1, c_2, ... in `c`.
+fn taylor_dd(t: Cdd, c: &[[f64; 2]]) -> Cdd {
+    let n = c.len();
+    let mut s = Cdd::real(c[n - 1][0], c[n - 1][1]);
+    for ck in c[..n - 1].iter().rev() {
+        s = s.mul(t).add(Cdd::real(ck[0], ck[1]));
+    }
+    s.mul(t)
+}
+
+// ln Gamma(z) = (z - 1/2) ln z - z + ln(2 pi)/2 + sum_k c_k z^(1-2k) for
+// |z| >= 7 off the negative real axis.
+fn lgamma_stirling(z: Cdd) -> Cdd {
+    if z.rh.abs().max(z.ih.abs()) >= STIRLING_HUGE {
+        // The other terms are below 2^-890 relative; z ln z may overflow.
+        let zc = z.to_complex();
+        let r = zc * clog(zc) - zc;
+        return Cdd::from_parts(r.re, r.im);
+    }
+    let lz = clog_dd(z);
+    let r = z
+        .add(Cdd::real(-0.5, 0.0))
+        .mul(lz)
+        .sub(z)
+        .add(Cdd::real(HALF_LN_2PI_HI, HALF_LN_2PI_LO));
+    let w = z.recip();
+    let w2 = w.mul(w);
+    let n = STIRLING.len();
+    let mut s = Cdd::real(STIRLING[n - 1][0], STIRLING[n - 1][1]);
+    for c in STIRLING[..n - 1].iter().rev() {
+        s = s.mul(w2).add(Cdd::real(c[0], c[1]));
+    }
+    r.add(s.mul(w))
+}
+
+// ln Gamma(z) for im z > 0 and re z >= 0.1, or im z >= 7.
+fn lgamma_upper(z: Cdd) -> Cdd {
+    if z.rh >= STIRLING_MIN || z.ih >= STIRLING_MIN {
+        return lgamma_stirling(z);
+    }
+    let t = z.add(Cdd::real(-1.0, 0.0));
+    let mut l = 0.0;
+    if t.norm(&mut l) <= TAYLOR_R2 {
+        return taylor_dd(t, &TAYLOR_1);
+    }
+    let t = z.add(Cdd::real(-2.0, 0.0));
+    if t.norm(&mut l) <= TAYLOR_R2 {
+        return taylor_dd(t, &TAYLOR_2);
+    }
+    // ln Gamma(z) = ln Gamma(z + n) - ln(z (z + 1) ... (z + n - 1)). The
+    // argument of the product grows with every factor; each time it passes
+    // pi the principal logarithm drops by 2 pi i.
+    let mut p = z;
+    let mut u = z.add(Cdd::real(1.0, 0.0));
+    let mut flips = 0.0;
+    let mut lower = false;
+    while u.rh < STIRLING_MIN {
+        p = p.mul(u);
+        let now_lower = p.ih.is_sign_negative();
+        if now_lower && !lower {
+            flips += 1.0;
+        }
+        lower = now_lower;
+        u = u.add(Cdd::real(1.0, 0.0));
+    }
+    let mut kl = 0.0;
+    let kh = mulddd(PI_HI, PI_LO, 2.0 * flips, &mut kl);
+    lgamma_stirling(u).sub(clog_dd(p)).sub(Cdd::imag(kh, kl))
+}
+
+// (sin(pi x), cos(pi x)) as double-doubles.
+fn sincospi_dd(x: f64) -> ((f64, f64), (f64, f64)) {
+    if x.abs() >= 9_007_199_254_740_992.0 {
+        // x is an even integer.
+        return ((copysign(0.0, x), 0.0), (1.0, 0.0));
+    }
+    // x = m/2 + r exactly, with |r| <= 1/4.
+    let m = roundeven_finite(2.0 * x);
+    let r = x - 0.5 * m;
+    let mut al = 0.0;
+    let ah = mulddd(PI_HI, PI_LO, r, &mut al);
+    let ((sh, sl), (ch, cl)) = sincos_taylor_dd(ah, al);
+    match (m as i64) & 3 {
+        0 => ((sh, sl), (ch, cl)),
+        1 => ((ch, cl), (-sh, -sl)),
+        2 => ((-sh, -sl), (-ch, -cl)),
+        _ => ((-ch, -cl), (sh, sl)),
+    }
+}
+
+// (sinh(pi y), cosh(pi y)) as double-doubles for 0 < y < 7.
+fn sinhcosh_pi_dd(y: f64) -> ((f64, f64), (f64, f64)) {
+    let mut bl = 0.0;
+    let bh = mulddd(PI_HI, PI_LO, y, &mut bl);
+    let (mut eh, mut el) = (0.0, 0.0);
+    exp_1(&mut eh, &mut el, bh, bl);
+    let mut il = 0.0;
+    let ih = divdd(1.0, 0.0, eh, el, &mut il);
+    let mut cl = 0.0;
+    let ch = add_dd(eh, el, ih, il, &mut cl);
+    let mut sl = 0.0;
+    let sh = if bh < SINH_TAYLOR_MAX {
+        // sinh b = sum_k b^(2k+1) / (2k+1)!, without the cancellation of
+        // (e^b - e^-b)/2.
+        let mut ql = 0.0;
+        let qh = muldd_acc(bh, bl, bh, bl, &mut ql);
+        let (mut th, mut tl) = (bh, bl);
+        let (mut sh, mut sl2) = (bh, bl);
+        let mut k = 1.0;
+        while th.abs() > SERIES_EPS * sh.abs() {
+            th = muldd_acc(th, tl, qh, ql, &mut tl);
+            th = divddd(th, tl, (2.0 * k) * (2.0 * k + 1.0), &mut tl);
+            sh = add_dd(sh, sl2, th, tl, &mut sl2);
+            k += 1.0;
+        }
+        sl = 2.0 * sl2;
+        2.0 * sh
+    } else {
+        add_dd(eh, el, -ih, -il, &mut sl)
+    };
+    ((0.5 * sh, 0.5 * sl), (0.5 * ch, 0.5 * cl))
+}
+
+// ln Gamma(z) for re z < 0.1 and 0 < im z < 7 from the reflection formula
+//   ln Gamma(z) = ln pi - ln sin(pi z) - ln Gamma(1 - z) + 2 pi i k,
+// with k = floor(re z / 2 + 1/4) keeping the branch continuous.
+fn lgamma_reflect(x: f64, y: f64) -> Cdd {
+    let ((sh, sl), (ch, cl)) = sincospi_dd(x);
+    let ((hh, hl), (kh, kl)) = sinhcosh_pi_dd(y);
+    // sin(pi z) = sin(pi x) cosh(pi y) + i cos(pi x) sinh(pi y)
+    let (mut rl, mut il) = (0.0, 0.0);
+    let rh = muldd_acc(sh, sl, kh, kl, &mut rl);
+    let ih = muldd_acc(ch, cl, hh, hl, &mut il);
+    let ls = clog_dd(Cdd::new(rh, rl, ih, il));
+    let mut ol = 0.0;
+    let oh = twosum(1.0, -x, &mut ol);
+    let lg = lgamma_upper(Cdd::new(oh, ol, y, 0.0)).conj();
+    let k = floor(0.5 * x + 0.25);
+    let mut tl = 0.0;
+    let th = mulddd(PI_HI, PI_LO, 2.0 * k, &mut tl);
+    Cdd::real(LN_PI_HI, LN_PI_LO)
+        .sub(ls)
+        .sub(lg)
+        .add(Cdd::imag(th, tl))
+}
+
+// ln Gamma(x + iy) for finite x and finite nonzero y.
+fn lgamma_dd(x: f64, y: f64) -> Cdd {
+    let ay = y.abs();
+    let r = if x < REFLECT_MAX && ay < STIRLING_MIN {
+        lgamma_reflect(x, ay)
+    } else {
+        lgamma_upper(Cdd::from_parts(x, ay))
+    };
+    if y < 0.0 { r.conj() } else { r }
+}
+
+// psi(u) for u >= 0 and u = uh + ul: upward recurrence to u >= 24, then
+//   psi(u) = ln u - 1/(2u) - sum_k B_2k / (2k u^(2k)),
+// whose terms are (2k - 1) times the Stirling coefficients.
+fn digamma_upper(mut uh: f64, mut ul: f64) -> (f64, f64) {
+    let (mut sh, mut sl) = (0.0, 0.0);
+    while uh < DIGAMMA_ASYMPTOTIC_MIN {
+        let mut rl = 0.0;
+        let rh = divdd(1.0, 0.0, uh, ul, &mut rl);
+        sh = add_dd(sh, sl, rh, rl, &mut sl);
+        uh = add_dd(uh, ul, 1.0, 0.0, &mut ul);
+    }
+    let (mut ll, mut ll2) = (0.0, 0.0);
+    let lh = as_logd_accurate(uh, &mut ll, &mut ll2);
+    let mut lnl = 0.0;
+    let lnh = adddd(lh, ll + ll2, ul / uh, 0.0, &mut lnl);
+    let mut wl = 0.0;
+    let wh = divdd(1.0, 0.0, uh, ul, &mut wl);
+    let mut w2l = 0.0;
+    let w2h = muldd_acc(wh, wl, wh, wl, &mut w2l);
+    let n = STIRLING.len();
+    let mut tl = 0.0;
+    let mut th = mulddd(
+        STIRLING[n - 1][0],
+        STIRLING[n - 1][1],
+        (2 * n - 1) as f64,
+        &mut tl,
+    );
+    for (k, c) in STIRLING[..n - 1].iter().enumerate().rev() {
+        let mut cl = 0.0;
+        let ch = mulddd(c[0], c[1], (2 * k + 1) as f64, &mut cl);
+        th = muldd_acc(th, tl, w2h, w2l, &mut tl);
+        th = add_dd(th, tl, ch, cl, &mut tl);
+    }
+    th = muldd_acc(th, tl, w2h, w2l, &mut tl);
+    let mut rl = 0.0;
+    let rh = add_dd(lnh, lnl, -0.5 * wh, -0.5 * wl, &mut rl);
+    let rh = add_dd(rh, rl, -th, -tl, &mut rl);
+    let mut pl = 0.0;
+    let ph = add_dd(rh, rl, -sh, -sl, &mut pl);
+    (ph, pl)
+}
+
+// psi(x) for finite x other than 0, -1, -2, ..., reflected below 0 as
+//   psi(x) = psi(1 - x) - pi cot(pi x).
+fn digamma_dd(x: f64) -> (f64, f64) {
+    if x >= 0.0 {
+        return digamma_upper(x, 0.0);
+    }
+    let mut ol = 0.0;
+    let oh = twosum(1.0, -x, &mut ol);
+    let (mut ph, mut pl) = digamma_upper(oh, ol);
+    let ((sh, sl), (ch, cl)) = sincospi_dd(x);
+    let mut ql = 0.0;
+    let qh = divdd(ch, cl, sh, sl, &mut ql);
+    let mut tl = 0.0;
+    let th = muldd_acc(PI_HI, PI_LO, qh, ql, &mut tl);
+    ph = add_dd(ph, pl, -th, -tl, &mut pl);
+    (ph, pl)
+}
+
+// y psi(x) when x + iy lies next to the real axis, where it is the
+// imaginary part of ln Gamma(x + iy) - ln Gamma(x) up to a relative
+// O(y^2 / d^2). The general evaluation loses it to rounding and underflow
+// there: its terms are much larger than their sum.
+fn near_axis_phase(x: f64, y: f64) -> Option<(f64, f64)> {
+    let d = if x < 0.5 {
+        (x - roundeven_finite(x)).abs()
+    } else {
+        1.0
+    };
+    if y.abs() > NEAR_AXIS * d {
+        return None;
+    }
+    let (ph, pl) = digamma_dd(x);
+    let mut tl = 0.0;
+    let th = mulddd(ph, pl, y, &mut tl);
+    Some((th, tl))
+}
+
+/// Principal branch of the complex log-gamma function ln Gamma(z).
+///
+/// This is the convention of scipy's `loggamma`: the analytic continuation
+/// of the real ln Gamma(x), x > 0, with a branch cut along the negative real
+/// axis. The imaginary part is continuous off the cut and is not reduced into
+/// (-pi, pi], so `cexp(clgamma(z))` is Gamma(z) but `clgamma(z)` generally
+/// differs from `clog(cgamma(z))` by a multiple of 2 pi i. The sign of a zero
+/// imaginary part selects the side of the cut: for x < 0 not an integer,
+/// `clgamma(x ± i0)` is ln|Gamma(x)| ∓ i pi ceil(-x). The poles 0, -1, -2, ...
+/// give +inf + i NaN, and a non-finite argument off the positive real axis
+/// gives NaN + i NaN.
+pub fn clgamma(z: Complex64) -> Complex64 {
+    with_nearest(z, clgamma_nearest)
+}
+
+fn clgamma_nearest(z: Complex64) -> Complex64 {
+    let (x, y) = (z.re, z.im);
+    if x.is_nan() || y.is_nan() {
+        return Complex64::new(f64::NAN, f64::NAN);
+    }
+    if y == 0.0 {
+        if x > 0.0 {
+            return Complex64::new(lgamma(x), y);
+        }
+        if x == floor(x) {
+            return Complex64::new(f64::INFINITY, f64::NAN);
+        }
+        return Complex64::new(lgamma(x), -copysign(PI_HI * ceil(-x), y));
+    }
+    if !x.is_finite() || !y.is_finite() {
+        return Complex64::new(f64::NAN, f64::NAN);
+    }
+    let r = lgamma_dd(x, y);
+    if let Some((th, tl)) = near_axis_phase(x, y) {
+        // The cut puts the same -i pi ceil(-x) sign(y) under the phase as on
+        // the axis.
+        let (mut bh, mut bl) = (0.0, 0.0);
+        if x < 0.0 {
+            bh = mulddd(PI_HI, PI_LO, -copysign(ceil(-x), y), &mut bl);
+        }
+        let mut il = 0.0;
+        let ih = add_dd(bh, bl, th, tl, &mut il);
+        return Complex64::new(r.rh + r.rl, ih + il);
+    }
+    r.to_complex()
+}
+
+/// Complex gamma function Gamma(z) = e^(ln Gamma(z)).
+///
+/// On the real axis this is the real `tgamma` with the zero imaginary part
+/// kept, so the poles give +-inf or NaN as there. Elsewhere a non-finite
+/// argument gives NaN + i NaN.
+pub fn cgamma(z: Complex64) -> Complex64 {
+    with_nearest(z, cgamma_nearest)
+}
+
+fn cgamma_nearest(z: Complex64) -> Complex64 {
+    let (x, y) = (z.re, z.im);
+    if y == 0.0 {
+        return Complex64::new(tgamma(x), y);
+    }
+    if !x.is_finite() || !y.is_finite() {
+        return Complex64::new(f64::NAN, f64::NAN);
+    }
+    let l = lgamma_dd(x, y);
+    if let Some((th, tl)) = near_axis_phase(x, y) {
+        // Gamma(x + iy) = |Gamma(x + iy)| cis(t) with t = y psi(x) and
+        // |t| < 2^-22, times the sign of Gamma(x) for x < 0: that is the
+        // branch offset pi ceil(-x) of the phase, applied exactly rather than
+        // through a double-double phase whose tail cannot hold t.
+        let c = 1.0 - 0.5 * th * th;
+        let s = th + (tl - th * th * th / 6.0);
+        let g = exp_cis(l.rh, l.rl, c, s);
+        if x < 0.0 && ceil(-x) % 2.0 == 1.0 {
+            return Complex64::new(-g.re, -g.im);
+        }
+        return g;
+    }
+    cexp_dd(l)
+}
+
+// sum_n z q^n / (n! (2n + 1)), the series of both erf (q = -z^2) and of
+// e^(z^2) times the Dawson function (q = z^2).
+fn odd_series(z: Cdd, q: Cdd) -> Cdd {
+    let mut p = z;
+    let mut s = z;
+    let mut n = 1.0;
+    loop {
+        p = p.mul(q).div_f64(n);
+        let t = p.div_f64(2.0 * n + 1.0);
+        s = s.add(t);
+        if t.rh.abs() + t.ih.abs() <= SERIES_EPS * (s.rh.abs() + s.ih.abs()) {
+            return s;
+        }
+        n += 1.0;
+    }
+}
+
+// -z^2 as a double-double: y^2 - x^2 and -2xy, exact while the squares are.
+#[inline(always)]
+fn neg_square_dd(x: f64, y: f64) -> Cdd {
+    let x2h = x * x;
+    let x2l = fma_internal(x, x, -x2h);
+    let y2h = y * y;
+    let y2l = fma_internal(y, y, -y2h);
+    let mut rl = 0.0;
+    let rh = add_dd(y2h, y2l, -x2h, -x2l, &mut rl);
+    let ph = -2.0 * x * y;
+    let pl = fma_internal(-2.0 * x, y, -ph);
+    Cdd::new(rh, rl, ph, pl)
+}
+
+// e^(-z^2) as a double-double for |z|^2 < 49.
+fn exp_neg_square_dd(x: f64, y: f64) -> Cdd {
+    let q = neg_square_dd(x, y);
+    let (mut eh, mut el) = (0.0, 0.0);
+    exp_1(&mut eh, &mut el, q.rh, q.rl);
+    let ((sh, sl), (ch, cl)) = sincos_dd(q.ih);
+    // cis(ih + il) = (cos ih - il sin ih) + i (sin ih + il cos ih)
+    let (mut cl2, mut sl2) = (0.0, 0.0);
+    let ch2 = add_dd(ch, cl, -sh * q.il, 0.0, &mut cl2);
+    let sh2 = add_dd(sh, sl, ch * q.il, 0.0, &mut sl2);
+    Cdd::new(ch2, cl2, sh2, sl2).mul_real(eh, el)
+}
+
+// e^(-z^2) = m 2^k, with k = 0 unless e^(y^2 - x^2) overflows.
+fn exp_neg_square(x: f64, y: f64) -> (Complex64, i32) {
+    let (e, cis, k) = exp_neg_square_polar(x, y);
+    (polar(e, cis), k)
+}
+
+// e cis for the parts of exp_neg_square_polar.
+fn polar(e: f64, cis: Complex64) -> Complex64 {
+    if e == 0.0 {
+        // Underflow, possibly with a phase too large to be finite.
+        return Complex64::new(0.0, 0.0);
+    }
+    // The phase is 0 on the axes; keep the zero part exact when e is
+    // infinite.
+    let im = if cis.im == 0.0 { cis.im } else { e * cis.im };
+    Complex64::new(e * cis.re, im)
+}
+
+// e^(-z^2) = e cis 2^k with |cis| = 1, k = 0 unless e^(y^2 - x^2)
+// overflows; e itself is infinite where the scaled value still overflows.
+fn exp_neg_square_polar(x: f64, y: f64) -> (f64, Complex64, i32) {
+    let q = if x.abs().max(y.abs()) < SQUARE_MAX {
+        neg_square_dd(x, y)
+    } else {
+        let (ax, ay) = (x.abs(), y.abs());
+        let p = -2.0 * x * y;
+        let pl = if p.is_finite() {
+            fma_internal(-2.0 * x, y, -p)
+        } else {
+            0.0
+        };
+        Cdd::new((ay - ax) * (ay + ax), 0.0, p, pl)
+    };
+    let (s, c) = sincos(q.ih);
+    let (c, s) = if q.il.abs() < CIS_TAIL_MAX {
+        (c - s * q.il, s + c * q.il)
+    } else {
+        // The tail of a large phase is itself an angle to reduce.
+        let (st, ct) = sincos(q.il);
+        (c * ct - s * st, s * ct + c * st)
+    };
+    let (e, k) = if q.rh > CEXP_OVERFLOW {
+        (exp_scaled(q.rh) * (1.0 + q.rl), CEXP_SCALE)
+    } else {
+        (exp_with_tail(q.rh, q.rl), 0)
+    };
+    (e, Complex64::new(c, s), k)
+}
+
+// w(z) for |z|^2 < 49 and |im z| < 3.5 from
+//   w(z) = e^(-z^2) (1 + 2i/sqrt(pi) sum_n z^(2n+1) / (n! (2n + 1))).
+fn faddeeva_series(x: f64, y: f64) -> Complex64 {
+    let z = Cdd::from_parts(x, y);
+    let s = odd_series(z, z.mul(z)).mul_real(FRAC_2_SQRT_PI_HI, FRAC_2_SQRT_PI_LO);
+    let mut fl = 0.0;
+    let fh = add_dd(1.0, 0.0, -s.ih, -s.il, &mut fl);
+    exp_neg_square_dd(x, y)
+        .mul(Cdd::new(fh, fl, s.rh, s.rl))
+        .to_complex()
+}
+
+// w(z) for im z >= 0 outside the series region, from the Laplace continued
+// fraction evaluated backwards from a depth that keeps the truncation error
+// below 2^-57.
+fn faddeeva_cf(x: f64, y: f64) -> Complex64 {
+    let n = (150.0 / sqrt(x * x + y * y)) as u32 + 4;
+    let z = Cdd::from_parts(x, y);
+    let mut t = z;
+    for k in (1..=n).rev() {
+        // t = z - (k/2) / t
+        t = z.sub(t.recip().mul_f64(0.5 * k as f64));
+    }
+    // i/sqrt(pi) / t
+    let u = t.recip().mul_real(FRAC_1_SQRT_PI_HI, FRAC_1_SQRT_PI_LO);
+    let w = Complex64::new(-(u.ih + u.il), u.rh + u.rl);
+    if y < W_ADD_EXP_IM {
+        // Here x^2 > 48, so e^(-z^2) does not need scaling.
+        return w + exp_neg_square(x, y).0;
+    }
+    w
+}
+
+/// Faddeeva function w(z) = e^(-z^2) erfc(-iz).
+///
+/// On the real axis re w(x) = e^(-x^2) and im w(x) = 2/sqrt(pi) times the
+/// Dawson integral; in the upper half-plane re w is the Voigt profile. The
+/// real part stays accurate relative to itself next to the real axis, where
+/// it is dominated by e^(-x^2). w(z) tends to 0 as |z| grows in the closed
+/// upper half-plane and overflows deep in the lower one; w(x - i inf) is
+/// NaN + i NaN, as is any NaN argument.
+pub fn faddeeva_w(z: Complex64) -> Complex64 {
+    with_nearest(z, faddeeva_w_nearest)
+}
+
+fn faddeeva_w_nearest(z: Complex64) -> Complex64 {
+    let (x, y) = (z.re, z.im);
+    if x.is_nan() || y.is_nan() || y == f64::NEG_INFINITY {
+        return Complex64::new(f64::NAN, f64::NAN);
+    }
+    let (ax, ay) = (x.abs(), y.abs());
+    if y >= 0.0 && ax.max(ay) >= W_ASYMPTOTIC {
+        // i/(sqrt(pi) z) (1 + 1/(2 z^2)); the next term is below 2^-100.
+        let u = Complex64::new(0.0, FRAC_1_SQRT_PI_HI) / z;
+        return u + u * (Complex64::from(0.5) / (z * z));
+    }
+    if ax * ax + ay * ay < W_SERIES_R2 && ay < W_SERIES_IM {
+        return faddeeva_series(x, y);
+    }
+    if y >= 0.0 {
+        return faddeeva_cf(x, y);
+    }
+    // w(z) = 2 e^(-z^2) - w(-z)
+    let v = faddeeva_w(-z);
+    let (m, k) = exp_neg_square(x, y);
+    Complex64::new(
+        scalbn_internal(2.0 * m.re, k) - v.re,
+        scalbn_internal(2.0 * m.im, k) - v.im,
+    )
+}
+
+// Whether erf(z), re z >= 0, is summed from its Maclaurin series.
+#[inline(always)]
+fn erf_by_series(x: f64, y: f64) -> bool {
+    x * x + y * y < ERF_SERIES_R2 || (x < 1.0 && y * y < W_SERIES_R2)
+}
+
+// erf(z) = 2/sqrt(pi) sum_n (-1)^n z^(2n+1) / (n! (2n + 1)).
+fn erf_series(x: f64, y: f64) -> Cdd {
+    let z = Cdd::from_parts(x, y);
+    odd_series(z, z.mul(z).neg()).mul_real(FRAC_2_SQRT_PI_HI, FRAC_2_SQRT_PI_LO)
+}
+
+// erfc(z) = e^(-z^2) w(iz) for re z >= 0, scaled back after the product
+// where e^(-z^2) alone would overflow.
+fn erfc_right(x: f64, y: f64) -> Complex64 {
+    let (e, cis, k) = exp_neg_square_polar(x, y);
+    let w = faddeeva_w(Complex64::new(-y, x));
+    if e.is_infinite() {
+        // Both parts overflow: take their signs from the finite product
+        // cis w, as (e cis) w would give inf - inf.
+        let u = cis * w;
+        let scale = |t: f64| if t == 0.0 { t } else { e * t };
+        return Complex64::new(scale(u.re), scale(u.im));
+    }
+    let p = polar(e, cis) * w;
+    Complex64::new(scalbn_internal(p.re, k), scalbn_internal(p.im, k))
+}
+
+/// Complex error function erf(z) = 2/sqrt(pi) ∫_0^z e^(-t^2) dt.
+///
+/// Odd and symmetric under conjugation. On the real axis this is the real
+/// `erf` with the zero imaginary part kept, and a zero real part is kept
+/// exactly. erf(±inf + iy) = ±1 + i0 for finite y, the zero taking the sign
+/// of y; erf(x ± i inf) is x ± i inf for x = 0 and NaN + i NaN otherwise.
+pub fn cerf(z: Complex64) -> Complex64 {
+    with_nearest(z, cerf_nearest)
+}
+
+fn cerf_nearest(z: Complex64) -> Complex64 {
+    let (x, y) = (z.re, z.im);
+    if y == 0.0 {
+        return Complex64::new(erf(x), y);
+    }
+    if x.is_nan() || y.is_nan() {
+        return Complex64::new(f64::NAN, f64::NAN);
+    }
+    if y.is_infinite() {
+        return if x == 0.0 {
+            z
+        } else {
+            Complex64::new(f64::NAN, f64::NAN)
+        };
+    }
+    if x.is_infinite() {
+        return Complex64::new(copysign(1.0, x), copysign(0.0, y));
+    }
+    let ax = x.abs();
+    let r = if erf_by_series(ax, y) {
+        erf_series(ax, y).to_complex()
+    } else {
+        let c = erfc_right(ax, y);
+        Complex64::new(1.0 - c.re, -c.im)
+    };
+    // erf(-z) = -erf(z) and erf(conj z) = conj erf(z)
+    let re = if x == 0.0 {
+        x
+    } else if x < 0.0 {
+        -r.re
+    } else {
+        r.re
+    };
+    Complex64::new(re, r.im)
+}
+
+/// Complex complementary error function erfc(z) = 1 - erf(z).
+///
+/// On the real axis this is the real `erfc` with the sign of the zero
+/// imaginary part flipped, as erfc decreases there; erfc(iy) has real part
+/// exactly 1. erfc(+inf + iy) = 0 ∓ i0 and erfc(-inf + iy) = 2 ∓ i0 for
+/// finite y; an infinite imaginary part gives NaN + i NaN.
+pub fn cerfc(z: Complex64) -> Complex64 {
+    with_nearest(z, cerfc_nearest)
+}
+
+fn cerfc_nearest(z: Complex64) -> Complex64 {
+    let (x, y) = (z.re, z.im);
+    if y == 0.0 {
+        return Complex64::new(erfc(x), -y);
+    }
+    if x.is_nan() || y.is_nan() || y.is_infinite() {
+        return Complex64::new(f64::NAN, f64::NAN);
+    }
+    if x.is_infinite() {
+        return Complex64::new(if x > 0.0 { 0.0 } else { 2.0 }, copysign(0.0, -y));
+    }
+    let ax = x.abs();
+    let r = if erf_by_series(ax, y) {
+        let s = erf_series(ax, y);
+        let mut l = 0.0;
+        let h = add_dd(1.0, 0.0, -s.rh, -s.rl, &mut l);
+        Complex64::new(if x == 0.0 { 1.0 } else { h + l }, -(s.ih + s.il))
+    } else {
+        erfc_right(ax, y)
+    };
+    if x < 0.0 {
+        // erfc(z) = 2 - erfc(-z) and erfc(conj z) = conj erfc(z)
+        return Complex64::new(2.0 - r.re, r.im);
+    }
+    r
+}
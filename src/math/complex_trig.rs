@@ -0,0 +1,540 @@
+//! Complex trigonometric and hyperbolic functions and their inverses
+//! (C99 Annex G.6.1 and G.6.2).
+//!
+//! The hyperbolic functions are primary and the circular ones follow from
+//! csin(z) = -i csinh(iz), ccos(z) = ccosh(iz), ctan(z) = -i ctanh(iz); the
+//! inverse circular functions swap the parts of the argument and result of
+//! the inverse hyperbolic ones. `casinh` and `cacos` use the algorithm of
+//! Hull, Fairgrieve and Tang ("Implementing the complex arcsine and arccosine
+//! functions using exception handling", ACM TOMS 23, 1997), which keeps both
+//! parts accurate next to the branch points; `catanh` takes its real part
+//! from log1p and its imaginary part from an exact 1 - |z|^2. Special values
+//! follow Annex G; where it leaves the sign of a zero or an infinity
+//! unspecified the choice is that of the FreeBSD msun library.
+
+use super::acos::acos;
+use super::asin::asin;
+use super::atan::atan;
+use super::atan2::atan2;
+use super::atanh::atanh;
+use super::complex::{CEXP_OVERFLOW, CEXP_SCALE, Complex64, clog, exp_scaled, norm_m1, scaled_mul};
+use super::copysign::copysign;
+use super::cosh::cosh;
+use super::double::{adddd, divdd};
+use super::exp::exp;
+use super::hypot::hypot;
+use super::log::ln;
+use super::log1p::log1p;
+use super::sinh::sinh;
+use super::sqrt::sqrt;
+use super::tan::tan;
+use super::tanh::tanh;
+use super::trig::sincos;
+use super::{fasttwosum, fma_internal, with_nearest};
+
+const FRAC_PI_2: f64 = f64::from_bits(0x3ff9_21fb_5444_2d18);
+const FRAC_PI_2_LO: f64 = f64::from_bits(0x3c91_a626_3314_5c07);
+const LN_2: f64 = f64::from_bits(0x3fe6_2e42_fefa_39ef);
+const EPS: f64 = f64::EPSILON;
+
+// From this |x| on, cosh x and sinh x are e^|x|/2 to working precision.
+const HYP_LARGE: f64 = 22.0;
+// Above this max(|x|, |y|) the inverse functions use their expansions at
+// infinity.
+const RECIP_EPSILON: f64 = 4_503_599_627_370_496.0; // 2^52
+// Below these |x|, |y| casinh/cacos and catanh are z (or pi/2 - z).
+const SQRT_6_EPSILON_4: f64 = f64::from_bits(0x3e43_988e_1409_212e); // sqrt(6 eps)/4
+const SQRT_3_EPSILON_2: f64 = f64::from_bits(0x3e4b_b67a_e858_4caa); // sqrt(3 eps)/2
+// Crossovers of Hull et al. between the formulas for A and B.
+const A_CROSSOVER: f64 = 10.0;
+const B_CROSSOVER: f64 = 0.6417;
+const FOUR_SQRT_MIN: f64 = f64::from_bits(0x2020_0000_0000_0000); // 2^-509
+const SQRT_MIN: f64 = f64::from_bits(0x2000_0000_0000_0000); // 2^-511
+// Below this ratio the smaller part drops out of x^2 + y^2.
+const RECIP_CUTOFF: f64 = f64::from_bits(0x3e40_0000_0000_0000); // 2^-27
+
+// (e^ax/2 c, e^ax/2 s) for ax >= 22, scaled past the overflow of e^ax.
+#[inline(always)]
+fn half_exp_cis(ax: f64, s: f64, c: f64) -> (f64, f64) {
+    if ax <= CEXP_OVERFLOW {
+        let h = 0.5 * exp(ax);
+        return (h * c, h * s);
+    }
+    let m = exp_scaled(ax);
+    (
+        scaled_mul(m, c, CEXP_SCALE - 1),
+        scaled_mul(m, s, CEXP_SCALE - 1),
+    )
+}
+
+/// Complex hyperbolic sine.
+///
+/// `csinh(±0 + i inf)` is ±0 + iNaN, `csinh(±inf + i0)` is ±inf + i0, and
+/// an infinite real part with finite nonzero `y` gives ±inf cis(y).
+pub fn csinh(z: Complex64) -> Complex64 {
+    with_nearest(z, csinh_nearest)
+}
+
+fn csinh_nearest(z: Complex64) -> Complex64 {
+    let (x, y) = (z.re, z.im);
+    if x.is_finite() && y.is_finite() {
+        if y == 0.0 {
+            return Complex64::new(sinh(x), y);
+        }
+        let (s, c) = sincos(y);
+        if x.abs() < HYP_LARGE {
+            return Complex64::new(sinh(x) * c, cosh(x) * s);
+        }
+        let (hc, hs) = half_exp_cis(x.abs(), s, c);
+        return Complex64::new(copysign(1.0, x) * hc, hs);
+    }
+    if x == 0.0 {
+        return Complex64::new(x, f64::NAN);
+    }
+    if y == 0.0 {
+        return Complex64::new(x, y);
+    }
+    if x.is_finite() {
+        return Complex64::new(f64::NAN, f64::NAN);
+    }
+    if x.is_infinite() {
+        if !y.is_finite() {
+            return Complex64::new(x, f64::NAN);
+        }
+        let (s, c) = sincos(y);
+        return Complex64::new(x * c, f64::INFINITY * s);
+    }
+    Complex64::new(f64::NAN, f64::NAN)
+}
+
+/// Complex hyperbolic cosine.
+///
+/// `ccosh(±0 + i inf)` is NaN ± i0, `ccosh(±inf + i0)` is +inf ± i0, and an
+/// infinite real part with finite nonzero `y` gives +inf cis(±y).
+pub fn ccosh(z: Complex64) -> Complex64 {
+    with_nearest(z, ccosh_nearest)
+}
+
+fn ccosh_nearest(z: Complex64) -> Complex64 {
+    let (x, y) = (z.re, z.im);
+    if x.is_finite() && y.is_finite() {
+        if y == 0.0 {
+            return Complex64::new(cosh(x), x * y);
+        }
+        let (s, c) = sincos(y);
+        if x.abs() < HYP_LARGE {
+            return Complex64::new(cosh(x) * c, sinh(x) * s);
+        }
+        let (hc, hs) = half_exp_cis(x.abs(), s, c);
+        return Complex64::new(hc, copysign(1.0, x) * hs);
+    }
+    if x == 0.0 {
+        return Complex64::new(f64::NAN, x * copysign(0.0, y));
+    }
+    if y == 0.0 {
+        return Complex64::new(x * x, copysign(0.0, x) * y);
+    }
+    if x.is_finite() {
+        return Complex64::new(f64::NAN, f64::NAN);
+    }
+    if x.is_infinite() {
+        if !y.is_finite() {
+            return Complex64::new(f64::INFINITY, f64::NAN);
+        }
+        let (s, c) = sincos(y);
+        return Complex64::new(f64::INFINITY * c, x * s);
+    }
+    Complex64::new(f64::NAN, f64::NAN)
+}
+
+/// Complex hyperbolic tangent.
+///
+/// `ctanh(±inf + i y)` is ±1 ± i0 for every non-NaN `y`; `ctanh(NaN ± i0)`
+/// is NaN ± i0.
+pub fn ctanh(z: Complex64) -> Complex64 {
+    with_nearest(z, ctanh_nearest)
+}
+
+fn ctanh_nearest(z: Complex64) -> Complex64 {
+    let (x, y) = (z.re, z.im);
+    if x.is_nan() {
+        return Complex64::new(x, if y == 0.0 { y } else { f64::NAN });
+    }
+    if x.is_infinite() {
+        let im = if y.is_finite() {
+            let (s, c) = sincos(y);
+            s * c
+        } else {
+            y
+        };
+        return Complex64::new(copysign(1.0, x), copysign(0.0, im));
+    }
+    if !y.is_finite() {
+        return Complex64::new(if x == 0.0 { x } else { f64::NAN }, f64::NAN);
+    }
+    if y == 0.0 {
+        return Complex64::new(tanh(x), y);
+    }
+    if x == 0.0 {
+        return Complex64::new(x, tan(y));
+    }
+    if x.abs() >= HYP_LARGE {
+        // tanh(x + iy) = ±1 + i 4 sin y cos y e^(-2|x|) to working precision.
+        let (s, c) = sincos(y);
+        let e = exp(-x.abs());
+        return Complex64::new(copysign(1.0, x), 4.0 * s * c * e * e);
+    }
+    // tanh(x + iy) = (sinh x cosh x + i sin y cos y) / (sinh^2 x + cos^2 y),
+    // where the denominator is a sum of squares; the products and the
+    // quotients are carried in double-double.
+    let (sn, cs) = sincos(y);
+    let (sh, ch) = (sinh(x), cosh(x));
+    let mut dl = 0.0;
+    let dh = adddd(
+        sh * sh,
+        fma_internal(sh, sh, -(sh * sh)),
+        cs * cs,
+        fma_internal(cs, cs, -(cs * cs)),
+        &mut dl,
+    );
+    let dh = fasttwosum(dh, dl, &mut dl);
+    let (mut rl, mut il) = (0.0, 0.0);
+    let rh = divdd(sh * ch, fma_internal(sh, ch, -(sh * ch)), dh, dl, &mut rl);
+    let ih = divdd(sn * cs, fma_internal(sn, cs, -(sn * cs)), dh, dl, &mut il);
+    Complex64::new(rh + rl, ih + il)
+}
+
+/// Complex sine, csin(z) = -i csinh(iz).
+#[inline]
+pub fn csin(z: Complex64) -> Complex64 {
+    with_nearest(z, csin_nearest)
+}
+
+#[inline]
+fn csin_nearest(z: Complex64) -> Complex64 {
+    let w = csinh(Complex64::new(-z.im, z.re));
+    Complex64::new(w.im, -w.re)
+}
+
+/// Complex cosine, ccos(z) = ccosh(iz).
+#[inline]
+pub fn ccos(z: Complex64) -> Complex64 {
+    with_nearest(z, ccos_nearest)
+}
+
+#[inline]
+fn ccos_nearest(z: Complex64) -> Complex64 {
+    ccosh(Complex64::new(-z.im, z.re))
+}
+
+/// Complex tangent, ctan(z) = -i ctanh(iz).
+#[inline]
+pub fn ctan(z: Complex64) -> Complex64 {
+    with_nearest(z, ctan_nearest)
+}
+
+#[inline]
+fn ctan_nearest(z: Complex64) -> Complex64 {
+    let w = ctanh(Complex64::new(-z.im, z.re));
+    Complex64::new(w.im, -w.re)
+}
+
+// (hypot(a, b) - b)/2 without cancellation, given h = hypot(a, b).
+#[inline(always)]
+fn half_diff(a: f64, b: f64, h: f64) -> f64 {
+    if b < 0.0 {
+        (h - b) * 0.5
+    } else if b == 0.0 {
+        a * 0.5
+    } else {
+        a * a / (h + b) * 0.5
+    }
+}
+
+// Parts of the Hull-Fairgrieve-Tang arcsine for 0 <= x, y < 2^52 with
+// A = (|z + i| + |z - i|)/2 and B = y/A.
+struct Hft {
+    // Re casinh(x + iy) = ln(A + sqrt(A^2 - 1)).
+    rx: f64,
+    // Im casinh(x + iy) = asin(B) when B is usable, otherwise
+    // atan2(new_y, sqrt_a2my2) with sqrt_a2my2 = sqrt(A^2 - y^2).
+    b: Option<f64>,
+    sqrt_a2my2: f64,
+    new_y: f64,
+}
+
+fn hft(x: f64, y: f64) -> Hft {
+    let r = hypot(x, y + 1.0);
+    let s = hypot(x, y - 1.0);
+    // Mathematically A >= 1; rounding may push it just below.
+    let a = ((r + s) * 0.5).max(1.0);
+
+    let rx = if a < A_CROSSOVER {
+        if y == 1.0 && x < EPS * EPS / 128.0 {
+            // A - 1 = x/2 to working precision.
+            sqrt(x)
+        } else if x >= EPS * (y - 1.0).abs() {
+            let am1 = half_diff(x, 1.0 + y, r) + half_diff(x, 1.0 - y, s);
+            log1p(am1 + sqrt(am1 * (a + 1.0)))
+        } else if y < 1.0 {
+            x / sqrt((1.0 - y) * (1.0 + y))
+        } else {
+            log1p((y - 1.0) + sqrt((y - 1.0) * (y + 1.0)))
+        }
+    } else {
+        ln(a + sqrt(a * a - 1.0))
+    };
+
+    if y < FOUR_SQRT_MIN {
+        // y/A may underflow; hand atan2 rescaled operands instead.
+        return Hft {
+            rx,
+            b: None,
+            sqrt_a2my2: a * (2.0 / EPS),
+            new_y: y * (2.0 / EPS),
+        };
+    }
+    let b = y / a;
+    if b <= B_CROSSOVER {
+        return Hft {
+            rx,
+            b: Some(b),
+            sqrt_a2my2: 0.0,
+            new_y: y,
+        };
+    }
+    // asin(B) is ill-conditioned here; sqrt(A^2 - y^2) from A - y instead.
+    let (sqrt_a2my2, new_y) = if y == 1.0 && x < EPS / 128.0 {
+        (sqrt(x) * sqrt((a + y) * 0.5), y)
+    } else if x >= EPS * (y - 1.0).abs() {
+        let amy = half_diff(x, y + 1.0, r) + half_diff(x, y - 1.0, s);
+        (sqrt(amy * (a + y)), y)
+    } else if y > 1.0 {
+        // Scaled so that the quotient cannot underflow.
+        const SCALE: f64 = 4.0 / EPS / EPS;
+        (x * SCALE * y / sqrt((y + 1.0) * (y - 1.0)), y * SCALE)
+    } else {
+        (sqrt((1.0 - y) * (1.0 + y)), y)
+    };
+    Hft {
+        rx,
+        b: None,
+        sqrt_a2my2,
+        new_y,
+    }
+}
+
+/// Complex inverse hyperbolic sine, with branch cuts on the imaginary axis
+/// outside [-i, i].
+///
+/// `casinh(±inf + i y)` is ±inf + i0 for finite `y`, `casinh(x + i inf)` is
+/// +inf + i pi/2, and `casinh(NaN ± i0)` is NaN ± i0.
+pub fn casinh(z: Complex64) -> Complex64 {
+    with_nearest(z, casinh_nearest)
+}
+
+fn casinh_nearest(z: Complex64) -> Complex64 {
+    let (x, y) = (z.re, z.im);
+    let (ax, ay) = (x.abs(), y.abs());
+    if x.is_nan() || y.is_nan() {
+        if x.is_infinite() {
+            return Complex64::new(x, f64::NAN);
+        }
+        if y.is_infinite() {
+            return Complex64::new(y, f64::NAN);
+        }
+        if y == 0.0 {
+            return Complex64::new(f64::NAN, y);
+        }
+        return Complex64::new(f64::NAN, f64::NAN);
+    }
+    if ax > RECIP_EPSILON || ay > RECIP_EPSILON {
+        // asinh z = sign(x) ln(2 sign(x) z) + O(1/z^2).
+        let w = if x.is_sign_negative() {
+            clog(-z)
+        } else {
+            clog(z)
+        };
+        return Complex64::new(copysign(w.re + LN_2, x), copysign(w.im, y));
+    }
+    if ax < SQRT_6_EPSILON_4 && ay < SQRT_6_EPSILON_4 {
+        return z;
+    }
+    let h = hft(ax, ay);
+    let ry = match h.b {
+        Some(b) => asin(b),
+        None => atan2(h.new_y, h.sqrt_a2my2),
+    };
+    Complex64::new(copysign(h.rx, x), copysign(ry, y))
+}
+
+/// Complex inverse sine, casin(z) = -i casinh(iz), with branch cuts on the
+/// real axis outside [-1, 1].
+#[inline]
+pub fn casin(z: Complex64) -> Complex64 {
+    with_nearest(z, casin_nearest)
+}
+
+#[inline]
+fn casin_nearest(z: Complex64) -> Complex64 {
+    let w = casinh(Complex64::new(z.im, z.re));
+    Complex64::new(w.im, w.re)
+}
+
+/// Complex inverse cosine, with branch cuts on the real axis outside
+/// [-1, 1] and real part in [0, pi].
+///
+/// `cacos(±0 + i0)` is pi/2 - i0, `cacos(-inf + i y)` is pi - i inf and
+/// `cacos(+inf + i y)` is +0 - i inf for finite `y`.
+pub fn cacos(z: Complex64) -> Complex64 {
+    with_nearest(z, cacos_nearest)
+}
+
+fn cacos_nearest(z: Complex64) -> Complex64 {
+    let (x, y) = (z.re, z.im);
+    let (ax, ay) = (x.abs(), y.abs());
+    if x.is_nan() || y.is_nan() {
+        if x.is_infinite() {
+            return Complex64::new(f64::NAN, f64::NEG_INFINITY);
+        }
+        if y.is_infinite() {
+            return Complex64::new(f64::NAN, -y);
+        }
+        if x == 0.0 {
+            return Complex64::new(FRAC_PI_2, f64::NAN);
+        }
+        return Complex64::new(f64::NAN, f64::NAN);
+    }
+    if ax > RECIP_EPSILON || ay > RECIP_EPSILON {
+        // acos z = -i ln(2z) + O(1/z^2) in the upper half-plane.
+        let w = clog(z);
+        let ry = w.re + LN_2;
+        return Complex64::new(w.im.abs(), if y.is_sign_negative() { ry } else { -ry });
+    }
+    if x == 1.0 && y == 0.0 {
+        return Complex64::new(0.0, -y);
+    }
+    if ax < SQRT_6_EPSILON_4 && ay < SQRT_6_EPSILON_4 {
+        return Complex64::new(FRAC_PI_2 - (x - FRAC_PI_2_LO), -y);
+    }
+    // Re cacos(x + iy) = Im casinh(y + ix) reflected about pi/2, and
+    // Im cacos(x + iy) = -Re casinh(y + ix).
+    let h = hft(ay, ax);
+    let rx = match h.b {
+        Some(b) => acos(copysign(b, x)),
+        None => atan2(h.sqrt_a2my2, copysign(h.new_y, x)),
+    };
+    Complex64::new(rx, if y.is_sign_negative() { h.rx } else { -h.rx })
+}
+
+/// Complex inverse hyperbolic cosine, with a branch cut on the real axis
+/// below 1 and non-negative real part.
+///
+/// `cacosh(±0 + i0)` is +0 + i pi/2 and `cacosh(±inf + i NaN)` is
+/// +inf + iNaN.
+pub fn cacosh(z: Complex64) -> Complex64 {
+    with_nearest(z, cacosh_nearest)
+}
+
+fn cacosh_nearest(z: Complex64) -> Complex64 {
+    let w = cacos(z);
+    if w.re.is_nan() && w.im.is_nan() {
+        return w;
+    }
+    if w.re.is_nan() {
+        return Complex64::new(w.im.abs(), w.re);
+    }
+    if w.im.is_nan() {
+        return Complex64::new(w.im, w.im);
+    }
+    Complex64::new(w.im.abs(), copysign(w.re, z.im))
+}
+
+// Re(1/(x + iy)) = x/(x^2 + y^2) without spurious overflow or underflow,
+// for finite x, y with max(|x|, |y|) > 2^52 or an infinite x.
+fn real_recip(x: f64, y: f64) -> f64 {
+    let (ax, ay) = (x.abs(), y.abs());
+    if x.is_infinite() || ay < ax * RECIP_CUTOFF {
+        // y^2 vanishes against x^2.
+        return 1.0 / x;
+    }
+    if ax < ay * RECIP_CUTOFF {
+        return x / y / y;
+    }
+    // Both within 2^27 of each other and above 2^25: scale down by the
+    // exponent of the larger one.
+    let m = ax.max(ay);
+    let k = f64::from_bits(m.to_bits() & 0x7ff0_0000_0000_0000);
+    let (xs, ys) = (x / k, y / k);
+    xs / (xs * xs + ys * ys) / k
+}
+
+/// Complex inverse hyperbolic tangent, with branch cuts on the real axis
+/// outside [-1, 1].
+///
+/// `catanh(±1 + i0)` is ±inf + i0, `catanh(±inf + i y)` is ±0 + i pi/2 for
+/// finite non-negative `y`, and `catanh(NaN ± i inf)` is ±0 ± i pi/2.
+pub fn catanh(z: Complex64) -> Complex64 {
+    with_nearest(z, catanh_nearest)
+}
+
+fn catanh_nearest(z: Complex64) -> Complex64 {
+    let (x, y) = (z.re, z.im);
+    let (ax, ay) = (x.abs(), y.abs());
+    if y == 0.0 && ax <= 1.0 {
+        return Complex64::new(atanh(x), y);
+    }
+    if x == 0.0 {
+        return Complex64::new(x, atan(y));
+    }
+    if x.is_nan() || y.is_nan() {
+        if x.is_infinite() {
+            return Complex64::new(copysign(0.0, x), f64::NAN);
+        }
+        if y.is_infinite() {
+            return Complex64::new(copysign(0.0, x), copysign(FRAC_PI_2, y));
+        }
+        return Complex64::new(f64::NAN, f64::NAN);
+    }
+    if ax > RECIP_EPSILON || ay > RECIP_EPSILON {
+        // atanh z = 1/z ± i pi/2 + O(1/z^3).
+        return Complex64::new(real_recip(x, y), copysign(FRAC_PI_2, y));
+    }
+    if ax < SQRT_3_EPSILON_2 && ay < SQRT_3_EPSILON_2 {
+        return z;
+    }
+    // Re atanh z = log1p(4|x| / ((|x| - 1)^2 + y^2))/4,
+    // Im atanh z = atan2(2|y|, 1 - |z|^2)/2.
+    let rx = if ax == 1.0 && ay < EPS {
+        (LN_2 - ln(ay)) * 0.5
+    } else {
+        let d = ax - 1.0;
+        let n = if ay < SQRT_MIN {
+            d * d
+        } else {
+            d * d + ay * ay
+        };
+        log1p(4.0 * ax / n) * 0.25
+    };
+    let ry = if ax == 1.0 {
+        atan2(2.0, -ay) * 0.5
+    } else {
+        let mut l = 0.0;
+        let h = norm_m1(ax, ay, &mut l);
+        atan2(2.0 * ay, -(h + l)) * 0.5
+    };
+    Complex64::new(copysign(rx, x), copysign(ry, y))
+}
+
+/// Complex inverse tangent, catan(z) = -i catanh(iz), with branch cuts on
+/// the imaginary axis outside [-i, i].
+#[inline]
+pub fn catan(z: Complex64) -> Complex64 {
+    with_nearest(z, catan_nearest)
+}
+
+#[inline]
+fn catan_nearest(z: Complex64) -> Complex64 {
+    let w = catanh(Complex64::new(z.im, z.re));
+    Complex64::new(w.im, w.re)
+}
@@ -3,10 +3,9 @@
 //! Uses expm1 for small |x| and exp-based formulas for medium/large inputs.
 //! Carefully handles overflow thresholds and preserves symmetry cosh(x)=cosh(-x).
 
-use super::exp;
+use super::{exp, sinh, with_nearest};
 
 const TINY: f64 = 3.725_290_298_461_914e-09; // 2^-28
-const EXP_HI: f64 = 709.782_712_893_384;
 const SMALL: f64 = 22.0;
 const TINY_BITS: u64 = TINY.to_bits();
 const SMALL_BITS: u64 = SMALL.to_bits();
@@ -15,6 +14,11 @@ const EXP_MASK: u64 = 0x7ff0_0000_0000_0000u64;
 
 #[inline(always)]
 pub fn cosh(x: f64) -> f64 {
+    with_nearest(x, cosh_nearest)
+}
+
+#[inline(always)]
+fn cosh_nearest(x: f64) -> f64 {
     let ux = x.to_bits() & !SIGN_MASK;
     if ux >= EXP_MASK {
         return if ux > EXP_MASK {
@@ -32,8 +36,7 @@ pub fn cosh(x: f64) -> f64 {
         let inv = 1.0 / e;
         return 0.5 * (e + inv);
     }
-    if ax < EXP_HI {
-        return 0.5 * exp(ax);
-    }
-    f64::INFINITY
+    // Beyond 22, e^-|x| is negligible and cosh(x) = sinh(|x|), whose
+    // near-overflow path also covers the range where e^|x| overflows.
+    sinh(ax)
 }
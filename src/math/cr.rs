@@ -0,0 +1,1317 @@
+//! Correctly rounded elementary functions.
+//!
+//! `cr_f(x)` returns `f(x)` rounded to nearest, ties to even, for every
+//! input; the special cases (NaNs, infinities, zeros and domain errors) are
+//! delegated to `f`, whose results there are exact, and results far outside
+//! the finite range are those of ±2^±4096.
+//!
+//! Finite inputs follow Ziv's strategy. A first phase evaluates in
+//! double-double arithmetic, accurate to between 2^-61 and 2^-96 depending
+//! on the function, and returns if the result rounds the same way at both
+//! edges of its error bound; this settles all but a small fraction of
+//! inputs at a few times the cost of `f`. Otherwise a second phase
+//! evaluates in 128-bit arithmetic, accurate to 2^-96, and a third in
+//! 320-bit arithmetic, accurate to 2^-288, each testing the same way.
+//! The worst cases of the transcendental functions lie no closer than about
+//! 2^-120 to a rounding boundary, and a cube root or square root of a sum
+//! of squares is either exact or much further than 2^-288 from one, so a
+//! third-phase result that still straddles a boundary is an exact midpoint
+//! (of `hypot` or `pow`) and rounds to even. For `pow`, whose worst cases
+//! are not known exhaustively, this assumes as other correctly rounded
+//! libraries do that no inexact result lies within 2^-288 of a midpoint.
+//!
+//! `erf`, `erfc`, `atanh` and `lgamma` are already correctly rounded
+//! core-math ports; `cr_atanh` is provided for completeness.
+//!
+//! `f_rd`, `f_ru` and `f_rz` round the same evaluations down, up and toward
+//! zero, giving the bounds interval arithmetic needs. The double-double
+//! phase runs only while the hardware rounds to nearest, and the later
+//! phases round on integer mantissas, so none of the variants depend on the
+//! hardware rounding mode. Ziv's test cannot place a result that lies
+//! within 2^-288 of a double, which directed rounding must, so those are
+//! settled beforehand: exact results, overflows and underflows, constants
+//! such as `atan(∞)`, and the results at tiny arguments (and `tanh`,
+//! `expm1` and `hypot` near saturation), which lie beside x, 1 or -1 on a
+//! side the Taylor expansion gives. `sqrt` is included; `erf`, `erfc` and
+//! `lgamma` get the neighbours of their correctly rounded results, at most
+//! one ulp looser than the directed roundings. `tgamma`, whose errors reach
+//! beyond one ulp, has no directed variants.
+//!
+//! `f_dd` evaluates the same way in 192-bit arithmetic, accurate to 2^-160,
+//! and returns the result split into a [`DoubleF64`]: the correctly rounded
+//! double and the rest rounded to nearest. A rest below 2^-150 of the
+//! result is taken to be zero: only exact results come that close to a
+//! double (under the same assumption for `pow`).
+
+use super::erf::exp_1;
+use super::flags::{erf_is_exact, lgamma_is_exact};
+use super::gamma::as_logd_accurate;
+use super::mp::{Mp, Round, reduce_pio2};
+use super::sincos_tab::SINCOS_TAB;
+use super::trig::branred;
+use super::{
+    DoubleF64, RoundingMode, acos, acosh, asin, asinh, atan, atan2, atanh, cbrt, cos, cosh, erf,
+    erfc, exp, exp2, exp10, expm1, fegetround, fma_internal, hypot, lgamma, ln, log1p, log2, log10,
+    pow, roundeven_finite, scalbn_internal, sin, sinh, sqrt, tan, tanh,
+};
+
+const PHASE1_ERR: u32 = 128 - 32;
+const PHASE2_ERR: u32 = 320 - 32;
+
+// 2^-100: below it every f(x) here lies within 2^-200 (relative) of x or
+// of 1, on a side its Taylor expansion gives.
+const TINY: f64 = 7.888609052210118e-31;
+
+// A double-double estimate `v` of a value and a bound on its absolute
+// error.
+#[derive(Clone, Copy)]
+struct Approx {
+    v: DoubleF64,
+    err: f64,
+}
+
+impl Approx {
+    // v with an error of at most |v| rel.
+    fn rel(v: DoubleF64, rel: f64) -> Option<Self> {
+        Some(Self {
+            v,
+            err: v.hi.abs() * rel,
+        })
+    }
+
+    // The value rounded in `mode`, if every value within the error bound
+    // rounds the same way. Only results well inside the normal range are
+    // placed, so that the neighbours of `hi` are apart by its ulps.
+    fn round(self, mode: Round) -> Option<f64> {
+        let DoubleF64 { hi: h, lo: l } = self.v;
+        if !(h.abs() > 1e-290 && h.abs() < 1e290) {
+            return None;
+        }
+        if l == 0.0 && self.err == 0.0 {
+            return Some(h);
+        }
+        // The value lies in h + [a, b], the neighbours of h at h + down and
+        // h + up; the differences are exact or keep their signs.
+        let (a, b) = (l - self.err, l + self.err);
+        let (down, up) = (h.next_down() - h, h.next_up() - h);
+        let toward_up = match mode {
+            Round::Nearest => return (a > down / 2.0 && b < up / 2.0).then_some(h),
+            Round::Down => false,
+            Round::Up => true,
+            Round::Zero => h < 0.0,
+        };
+        let above = if a > 0.0 && b < up {
+            true
+        } else if b < 0.0 && a > down {
+            false
+        } else {
+            return None;
+        };
+        Some(match (above, toward_up) {
+            (true, true) => h.next_up(),
+            (false, false) => h.next_down(),
+            _ => h,
+        })
+    }
+}
+
+// The double-double phase's estimate, taken only in round-to-nearest,
+// which its kernels assume; the others leave it to the Mp phases.
+#[inline]
+fn estimate(fast: impl FnOnce() -> Option<Approx>) -> Option<Approx> {
+    if fegetround() == RoundingMode::ToNearest {
+        fast()
+    } else {
+        None
+    }
+}
+
+// Ziv's loop: the double-double phase, then the 128-bit one, then the
+// 320-bit one.
+#[inline]
+fn ziv(
+    mode: Round,
+    fast: impl FnOnce() -> Option<Approx>,
+    phase1: impl FnOnce() -> Mp<2>,
+    phase2: impl FnOnce() -> Mp<5>,
+) -> f64 {
+    if let Some(y) = estimate(fast).and_then(|a| a.round(mode)) {
+        return y;
+    }
+    if let Some(y) = phase1().round_checked(PHASE1_ERR, mode) {
+        return y;
+    }
+    let y = phase2();
+    y.round_checked(PHASE2_ERR, mode)
+        .unwrap_or_else(|| y.round_exact(mode))
+}
+
+// What a `*_round` function returns: its value rounded one way, or, through
+// `Bounds`, rounded down and up together.
+trait Rounding: Copy {
+    type Out;
+
+    // A special-case result, which is exact.
+    fn exact(self, r: f64) -> Self::Out;
+
+    fn round<const L: usize>(self, v: Mp<L>) -> Self::Out;
+
+    fn ziv(
+        self,
+        fast: impl FnOnce() -> Option<Approx>,
+        phase1: impl FnOnce() -> Mp<2>,
+        phase2: impl FnOnce() -> Mp<5>,
+    ) -> Self::Out;
+}
+
+impl Rounding for Round {
+    type Out = f64;
+
+    fn exact(self, r: f64) -> f64 {
+        r
+    }
+
+    fn round<const L: usize>(self, v: Mp<L>) -> f64 {
+        v.to_f64(self)
+    }
+
+    fn ziv(
+        self,
+        fast: impl FnOnce() -> Option<Approx>,
+        phase1: impl FnOnce() -> Mp<2>,
+        phase2: impl FnOnce() -> Mp<5>,
+    ) -> f64 {
+        ziv(self, fast, phase1, phase2)
+    }
+}
+
+// The roundings down and up, which agree exactly when the value is a
+// double. They share one evaluation: an inexact value settles both in the
+// first phases unless it lies within their error of a double, and only
+// that case, exact values included, goes on to the 320-bit phase.
+#[derive(Clone, Copy)]
+struct Bounds;
+
+impl Rounding for Bounds {
+    type Out = (f64, f64);
+
+    fn exact(self, r: f64) -> (f64, f64) {
+        (r, r)
+    }
+
+    fn round<const L: usize>(self, v: Mp<L>) -> (f64, f64) {
+        (v.to_f64(Round::Down), v.to_f64(Round::Up))
+    }
+
+    fn ziv(
+        self,
+        fast: impl FnOnce() -> Option<Approx>,
+        phase1: impl FnOnce() -> Mp<2>,
+        phase2: impl FnOnce() -> Mp<5>,
+    ) -> (f64, f64) {
+        let settled =
+            estimate(fast).and_then(|a| Some((a.round(Round::Down)?, a.round(Round::Up)?)));
+        if let Some(r) = settled {
+            return r;
+        }
+        let y = phase1();
+        if let (Some(lo), Some(hi)) = (
+            y.round_checked(PHASE1_ERR, Round::Down),
+            y.round_checked(PHASE1_ERR, Round::Up),
+        ) {
+            return (lo, hi);
+        }
+        let y = phase2();
+        let round = |mode| {
+            y.round_checked(PHASE2_ERR, mode)
+                .unwrap_or_else(|| y.round_exact(mode))
+        };
+        (round(Round::Down), round(Round::Up))
+    }
+}
+
+fn is_tiny(x: f64) -> bool {
+    x != 0.0 && x.abs() < TINY
+}
+
+fn with_sign<const L: usize>(v: Mp<L>, neg: bool) -> Mp<L> {
+    if neg { v.neg() } else { v }
+}
+
+// A value strictly above (`up`) or below `v` and within 2^-200 of it,
+// rounded: Ziv's test cannot tell such values from `v`, but a directed
+// rounding depends on the side.
+fn beside<M: Rounding>(v: Mp<5>, up: bool, mode: M) -> M::Out {
+    let eps = v.abs().ldexp(-200);
+    let w = if up { v.add(eps) } else { v.sub(eps) };
+    mode.round(w)
+}
+
+// ±2^±4096 rounded: the result of an overflow or total underflow.
+fn out_of_range<M: Rounding>(neg: bool, big: bool, mode: M) -> M::Out {
+    let v = Mp::<2>::one().ldexp(if big { 4096 } else { -4096 });
+    mode.round(with_sign(v, neg))
+}
+
+fn exp_mp<const L: usize>(x: f64) -> Mp<L> {
+    Mp::from_f64(x).exp()
+}
+
+fn exp2_mp<const L: usize>(x: f64) -> Mp<L> {
+    Mp::from_f64(x).mul(Mp::ln2()).exp()
+}
+
+fn exp10_mp<const L: usize>(x: f64) -> Mp<L> {
+    Mp::from_f64(x).mul(Mp::ln10()).exp()
+}
+
+fn expm1_mp<const L: usize>(x: f64) -> Mp<L> {
+    Mp::from_f64(x).expm1()
+}
+
+fn ln_mp<const L: usize>(x: f64) -> Mp<L> {
+    Mp::from_f64(x).ln()
+}
+
+fn log2_mp<const L: usize>(x: f64) -> Mp<L> {
+    Mp::from_f64(x).ln().div(Mp::ln2())
+}
+
+fn log10_mp<const L: usize>(x: f64) -> Mp<L> {
+    Mp::from_f64(x).ln().div(Mp::ln10())
+}
+
+fn log1p_mp<const L: usize>(x: f64) -> Mp<L> {
+    Mp::from_f64(x).ln1p()
+}
+
+fn sin_mp<const L: usize>(x: f64) -> Mp<L> {
+    let (k, r) = reduce_pio2::<L>(x);
+    match k {
+        0 => Mp::sin_reduced(r),
+        1 => Mp::cos_reduced(r),
+        2 => Mp::sin_reduced(r).neg(),
+        _ => Mp::cos_reduced(r).neg(),
+    }
+}
+
+fn cos_mp<const L: usize>(x: f64) -> Mp<L> {
+    let (k, r) = reduce_pio2::<L>(x);
+    match k {
+        0 => Mp::cos_reduced(r),
+        1 => Mp::sin_reduced(r).neg(),
+        2 => Mp::cos_reduced(r).neg(),
+        _ => Mp::sin_reduced(r),
+    }
+}
+
+fn tan_mp<const L: usize>(x: f64) -> Mp<L> {
+    let (k, r) = reduce_pio2::<L>(x);
+    let (s, c) = (Mp::sin_reduced(r), Mp::cos_reduced(r));
+    if k & 1 == 0 { s.div(c) } else { c.div(s).neg() }
+}
+
+fn atan2_mp<const L: usize>(y: f64, x: f64) -> Mp<L> {
+    let a = Mp::from_f64(y).div(Mp::from_f64(x)).atan();
+    if x > 0.0 {
+        a
+    } else if y > 0.0 {
+        a.add(Mp::pi_2().ldexp(1))
+    } else {
+        a.sub(Mp::pi_2().ldexp(1))
+    }
+}
+
+fn asin_mp<const L: usize>(x: f64) -> Mp<L> {
+    let (one, x) = (Mp::one(), Mp::from_f64(x));
+    x.div(one.sub(x).mul(one.add(x)).sqrt()).atan()
+}
+
+fn acos_mp<const L: usize>(x: f64) -> Mp<L> {
+    let (one, x) = (Mp::one(), Mp::from_f64(x));
+    one.sub(x).div(one.add(x)).sqrt().atan().ldexp(1)
+}
+
+fn sinh_mp<const L: usize>(x: f64) -> Mp<L> {
+    // (e - 1/e) / 2 = E (E + 2) / (2 (E + 1)) with E = e^|x| - 1.
+    let e = Mp::from_f64(x.abs()).expm1();
+    let one = Mp::one();
+    let s = e.mul(e.add(one.ldexp(1))).div(e.add(one)).ldexp(-1);
+    if x < 0.0 { s.neg() } else { s }
+}
+
+fn cosh_mp<const L: usize>(x: f64) -> Mp<L> {
+    let e = Mp::from_f64(x.abs()).exp();
+    e.add(e.recip()).ldexp(-1)
+}
+
+fn tanh_mp<const L: usize>(x: f64) -> Mp<L> {
+    // E / (E + 2) with E = e^(2|x|) - 1.
+    let e = Mp::from_f64(x.abs()).ldexp(1).expm1();
+    let t = e.div(e.add(Mp::one().ldexp(1)));
+    if x < 0.0 { t.neg() } else { t }
+}
+
+fn asinh_mp<const L: usize>(x: f64) -> Mp<L> {
+    // ln1p(a + a² / (1 + √(1 + a²))) with a = |x|.
+    let a = Mp::from_f64(x.abs());
+    let one = Mp::one();
+    let a2 = a.sqr();
+    let t = a.add(a2.div(one.add(one.add(a2).sqrt()))).ln1p();
+    if x < 0.0 { t.neg() } else { t }
+}
+
+fn acosh_mp<const L: usize>(x: f64) -> Mp<L> {
+    // ln1p(t + √(t (x + 1))) with t = x - 1, exact.
+    let one = Mp::one();
+    let x = Mp::from_f64(x);
+    let t = x.sub(one);
+    t.add(t.mul(x.add(one)).sqrt()).ln1p()
+}
+
+fn atanh_mp<const L: usize>(x: f64) -> Mp<L> {
+    // ln1p(2x / (1 - x)) / 2.
+    let x = Mp::from_f64(x);
+    x.ldexp(1).div(Mp::one().sub(x)).ln1p().ldexp(-1)
+}
+
+fn pow_mp<const L: usize>(x: f64, y: f64) -> Mp<L> {
+    let p = Mp::from_f64(y).mul(Mp::from_f64(x.abs()).ln()).exp();
+    with_sign(p, x < 0.0 && is_odd_integer(y))
+}
+
+fn is_odd_integer(y: f64) -> bool {
+    y.abs() < 9007199254740992.0 && y == super::trunc(y) && (y as i64) & 1 != 0
+}
+
+// === the double-double phase ===
+//
+// Each `*_fast` estimate is built on the double-double kernels of the
+// other functions, its error bounded by the kernels' relative errors below
+// (measured against the 320-bit evaluations and given a margin of at least
+// 2^8) and a few 2^-104 per double-double operation, both taken in
+// `*_ERR`. An estimate returns `None` where the kernels do not apply.
+
+const LN2: DoubleF64 = dd(0x3fe62e42fefa39ef, 0x3c7abc9e3b39803f);
+const LN10: DoubleF64 = dd(0x40026bb1bbb55516, 0xbcaf48ad494ea3e9);
+const INV_LN2: DoubleF64 = dd(0x3ff71547652b82fe, 0x3c7777d0ffda0d24);
+const INV_LN10: DoubleF64 = dd(0x3fdbcb7b1526e50e, 0x3c695355baaafad3);
+const PI: DoubleF64 = dd(0x400921fb54442d18, 0x3ca1a62633145c07);
+
+// 2^-61: `exp_1`, measured within 2^-69, and `expm1_est`, relative.
+const EXP_ERR: f64 = 4.336808689942018e-19;
+// 2^-92: `log_est` and `log1p_est`, measured within 2^-100, relative.
+const LOG_ERR: f64 = 2.0194839173657902e-28;
+// 2^-80: `sincos_est`, measured within 2^-88 relative to its results, and
+// 2^-84: `branred`'s argument reduction, within 2^-92 absolute.
+const SINCOS_ERR: f64 = 8.271806125530277e-25;
+const REDUCTION_ERR: f64 = 5.169878828456423e-26;
+// 2^-80: `atan_est`, measured within 2^-88, relative.
+const ATAN_ERR: f64 = 8.271806125530277e-25;
+// 2^-96: the square and cube roots, measured within 2^-104, relative.
+const ROOT_ERR: f64 = 1.262177448353619e-29;
+
+const fn dd(hi: u64, lo: u64) -> DoubleF64 {
+    DoubleF64 {
+        hi: f64::from_bits(hi),
+        lo: f64::from_bits(lo),
+    }
+}
+
+// e^(xh + xl) for |xh| <= 708, a normal result.
+fn exp_est(xh: f64, xl: f64) -> Option<DoubleF64> {
+    if xh.abs() > 708.0 {
+        return None;
+    }
+    let (mut h, mut l) = (0.0, 0.0);
+    exp_1(&mut h, &mut l, xh, xl);
+    Some(DoubleF64::new(h, l))
+}
+
+// e^x - 1 with an error of at most EXP_ERR relative: the Taylor series
+// below 1/16, whose terms from x^10/10! on are summed in double precision,
+// and e^x - 1 above, where the subtraction loses less than 2^5.
+fn expm1_est(x: f64) -> Option<DoubleF64> {
+    if x.abs() < 0.0625 {
+        // x (1 + x/2 (1 + x/3 (1 + ...)))
+        let mut p = 1.0;
+        for k in (10..=17).rev() {
+            p = 1.0 + x * p / (k + 1) as f64;
+        }
+        let mut p = DoubleF64::from(p);
+        for k in (1..=9).rev() {
+            p = p * x / (k + 1) as f64 + 1.0;
+        }
+        return Some(p * x);
+    }
+    Some(exp_est(x, 0.0)? - 1.0)
+}
+
+// ln(1 + d) with an error of at most LOG_ERR relative: 2 atanh(d / (2 + d))
+// from its series below 1/32, where the terms from s^9/9 on are summed in
+// double precision, and `as_logd_accurate` above.
+fn log1p_est(d: DoubleF64) -> DoubleF64 {
+    if d.hi.abs() < 0.03125 {
+        let s = d / (d + 2.0);
+        let s2 = s * s;
+        let mut p = 0.0;
+        for k in (4..=9).rev() {
+            p = 1.0 / (2 * k + 1) as f64 + s2.hi * p;
+        }
+        let mut p = DoubleF64::from(p);
+        for k in (1..=3).rev() {
+            p = p * s2 + DoubleF64::ONE / (2 * k + 1) as f64;
+        }
+        let t = (p * s2 + 1.0) * s;
+        return t + t;
+    }
+    // ln(h + l) = ln h + l/h - (l/h)^2 / 2 + ..., the square below 2^-106.
+    let u = d + 1.0;
+    let (mut l, mut l2) = (0.0, 0.0);
+    let h = as_logd_accurate(u.hi, &mut l, &mut l2);
+    DoubleF64::new(h, l) + (l2 + u.lo / u.hi)
+}
+
+// ln x, x > 0.
+fn log_est(x: f64) -> DoubleF64 {
+    if (x - 1.0).abs() < 0.03125 {
+        return log1p_est(DoubleF64::from(x - 1.0));
+    }
+    let (mut l, mut l2) = (0.0, 0.0);
+    let h = as_logd_accurate(x, &mut l, &mut l2);
+    DoubleF64::new(h, l) + l2
+}
+
+// π/2 as P1 + P2 + P3 + P4, the first two of 33 bits so that k P1 and
+// k P2 are exact for |k| < 2^20.
+const P1: f64 = f64::from_bits(0x3ff921fb54400000);
+const P2: f64 = f64::from_bits(0x3dd0b4611a600000);
+const P3: f64 = f64::from_bits(0x3ba3198a2e037073);
+const P4: f64 = f64::from_bits(0x384129024e088a68);
+const FRAC_2_PI: f64 = f64::from_bits(0x3fe45f306dc9c883);
+
+// -1/3!, as a double-double.
+const INV_FACT3: DoubleF64 = dd(0xbfc5555555555555, 0xbc65555555555555);
+
+// (sin a, cos a) for 0 <= a <= π/4: a = i/128 + u, with sin(i/128) and
+// cos(i/128) from `SINCOS_TAB` and the Taylor series of u, |u| <= 2^-8,
+// whose terms after the first two are summed in double precision.
+fn sincos_reduced(a: DoubleF64) -> (DoubleF64, DoubleF64) {
+    let i = roundeven_finite(a.hi * 128.0);
+    let u = a - i * 0.0078125;
+    let q = u * u;
+    let qh = q.hi;
+    // sin u = u + u q (-1/3! + q/5! - q^2/7! + q^3/9!)
+    let ps = INV_FACT3
+        + qh * (0.008333333333333333 + qh * (-0.0001984126984126984 + qh * 2.755731922398589e-6));
+    let su = u * q * ps + u;
+    // cos u = 1 + q (-1/2! + q/4! - q^2/6! + q^3/8!)
+    let pc = DoubleF64::from_sum(
+        -0.5,
+        qh * (0.041666666666666664 + qh * (-0.001388888888888889 + qh * 2.48015873015873e-5)),
+    );
+    let cu = q * pc + 1.0;
+    if i == 0.0 {
+        return (su, cu);
+    }
+    let k = 4 * i as usize;
+    let sn = DoubleF64::new(SINCOS_TAB[k], SINCOS_TAB[k + 1]);
+    let cs = DoubleF64::new(SINCOS_TAB[k + 2], SINCOS_TAB[k + 3]);
+    (sn * cu + cs * su, cs * cu - sn * su)
+}
+
+// (sin x, cos x) and their absolute errors: x = k π/2 + a, with π/2 in
+// four parts below 2^20 π/2 and with `sincos_dd`'s reduction above, and
+// the Taylor series of a.
+fn sincos_est(x: f64) -> ((DoubleF64, f64), (DoubleF64, f64)) {
+    let (n, a, reduction) = if x.abs() < 1647099.0 {
+        let k = roundeven_finite(x * FRAC_2_PI);
+        let r = DoubleF64::from_sum(x - k * P1, -k * P2);
+        let a = r - DoubleF64::from_product(k, P3) - k * P4;
+        (k as i64, a, 0.0)
+    } else {
+        let (n, ah, al) = branred(x);
+        (n as i64, DoubleF64::new(ah, al), REDUCTION_ERR)
+    };
+    let (s, c) = sincos_reduced(a.abs());
+    let s = if a.hi < 0.0 { -s } else { s };
+    let (s, c) = match n & 3 {
+        0 => (s, c),
+        1 => (c, -s),
+        2 => (-s, -c),
+        _ => (-c, s),
+    };
+    let err = |v: DoubleF64| v.hi.abs() * SINCOS_ERR + reduction;
+    ((s, err(s)), (c, err(c)))
+}
+
+// atan q: a = atan(q.hi), within an ulp, corrected by
+// atan((q - tan a) / (1 + q tan a)), of which the cube is below 2^-150 a.
+fn atan_est(q: DoubleF64) -> DoubleF64 {
+    let a = atan(q.hi);
+    let ((s, _), (c, _)) = sincos_est(a);
+    DoubleF64::from(a) + (q * c - s) / (c + q * s)
+}
+
+fn exp_fast(x: f64) -> Option<Approx> {
+    Approx::rel(exp_est(x, 0.0)?, EXP_ERR)
+}
+
+fn exp2_fast(x: f64) -> Option<Approx> {
+    let t = LN2 * x;
+    Approx::rel(exp_est(t.hi, t.lo)?, EXP_ERR)
+}
+
+fn exp10_fast(x: f64) -> Option<Approx> {
+    let t = LN10 * x;
+    Approx::rel(exp_est(t.hi, t.lo)?, EXP_ERR)
+}
+
+fn expm1_fast(x: f64) -> Option<Approx> {
+    Approx::rel(expm1_est(x)?, EXP_ERR)
+}
+
+fn ln_fast(x: f64) -> Option<Approx> {
+    Approx::rel(log_est(x), LOG_ERR)
+}
+
+fn log2_fast(x: f64) -> Option<Approx> {
+    Approx::rel(log_est(x) * INV_LN2, LOG_ERR)
+}
+
+fn log10_fast(x: f64) -> Option<Approx> {
+    Approx::rel(log_est(x) * INV_LN10, LOG_ERR)
+}
+
+fn log1p_fast(x: f64) -> Option<Approx> {
+    Approx::rel(log1p_est(DoubleF64::from(x)), LOG_ERR)
+}
+
+fn sin_fast(x: f64) -> Option<Approx> {
+    let ((v, err), _) = sincos_est(x);
+    Some(Approx { v, err })
+}
+
+fn cos_fast(x: f64) -> Option<Approx> {
+    let (_, (v, err)) = sincos_est(x);
+    Some(Approx { v, err })
+}
+
+fn tan_fast(x: f64) -> Option<Approx> {
+    let ((s, es), (c, ec)) = sincos_est(x);
+    let t = s / c;
+    // |t| (es/|s| + ec/|c|), with room for the division.
+    let err = (t.hi.abs() * (es / s.hi.abs() + ec / c.hi.abs())) * 1.0001;
+    Some(Approx { v: t, err })
+}
+
+fn atan_fast(x: f64) -> Option<Approx> {
+    Approx::rel(atan_est(DoubleF64::from(x)), ATAN_ERR)
+}
+
+fn atan2_fast(y: f64, x: f64) -> Option<Approx> {
+    let q = DoubleF64::from(y) / x;
+    if !(q.hi.abs() > 1e-290 && q.hi.abs() < 1e290) {
+        return None;
+    }
+    let a = atan_est(q);
+    let v = if x > 0.0 {
+        a
+    } else if y > 0.0 {
+        a + PI
+    } else {
+        a - PI
+    };
+    Approx::rel(v, ATAN_ERR)
+}
+
+// x / √(1 - x²), or √(1 - x²) / x when `inverse`.
+fn asin_quotient(x: f64, inverse: bool) -> DoubleF64 {
+    let one_minus = DoubleF64::from_sum(1.0, -x) * DoubleF64::from_sum(1.0, x);
+    let r = one_minus.sqrt();
+    if inverse {
+        r / x
+    } else {
+        DoubleF64::from(x) / r
+    }
+}
+
+fn asin_fast(x: f64) -> Option<Approx> {
+    Approx::rel(atan_est(asin_quotient(x, false)), ATAN_ERR)
+}
+
+fn acos_fast(x: f64) -> Option<Approx> {
+    // π/2 - asin x = atan(√(1 - x²) / x), plus π below zero.
+    let a = atan_est(asin_quotient(x, true));
+    Approx::rel(if x < 0.0 { a + PI } else { a }, ATAN_ERR)
+}
+
+fn sinh_fast(x: f64) -> Option<Approx> {
+    // (e - 1/e) / 2, as E (E + 2) / (2 (E + 1)) with E = e^|x| - 1 below 1.
+    let s = if x.abs() < 1.0 {
+        let e = expm1_est(x.abs())?;
+        e * (e + 2.0) / (e + 1.0) * 0.5
+    } else {
+        let e = exp_est(x.abs(), 0.0)?;
+        (e - DoubleF64::ONE / e) * 0.5
+    };
+    Approx::rel(if x < 0.0 { -s } else { s }, EXP_ERR)
+}
+
+fn cosh_fast(x: f64) -> Option<Approx> {
+    let e = exp_est(x.abs(), 0.0)?;
+    Approx::rel((e + DoubleF64::ONE / e) * 0.5, EXP_ERR)
+}
+
+fn tanh_fast(x: f64) -> Option<Approx> {
+    let e = expm1_est(2.0 * x.abs())?;
+    let t = e / (e + 2.0);
+    Approx::rel(if x < 0.0 { -t } else { t }, EXP_ERR)
+}
+
+// ln(2a) + 1/(4a²) for a >= 2^28, where asinh a and acosh a are within
+// 2^-110 of it, the last term added for asinh and subtracted for acosh.
+fn log_twice(a: f64, sign: f64) -> DoubleF64 {
+    log_est(a) + LN2 + sign * 0.25 / (a * a)
+}
+
+fn asinh_fast(x: f64) -> Option<Approx> {
+    let a = x.abs();
+    let t = if a >= 268435456.0 {
+        log_twice(a, 1.0)
+    } else {
+        // ln1p(a + a² / (1 + √(1 + a²))).
+        let a2 = DoubleF64::from_product(a, a);
+        log1p_est(a2 / ((a2 + 1.0).sqrt() + 1.0) + a)
+    };
+    Approx::rel(if x < 0.0 { -t } else { t }, LOG_ERR)
+}
+
+fn acosh_fast(x: f64) -> Option<Approx> {
+    if x >= 268435456.0 {
+        return Approx::rel(log_twice(x, -1.0), LOG_ERR);
+    }
+    // ln1p(t + √(t (x + 1))) with t = x - 1.
+    let t = DoubleF64::from_sum(x, -1.0);
+    Approx::rel(
+        log1p_est(t + (t * (DoubleF64::from(x) + 1.0)).sqrt()),
+        LOG_ERR,
+    )
+}
+
+fn atanh_fast(x: f64) -> Option<Approx> {
+    // ln1p(2a / (1 - a)) / 2 with a = |x|.
+    let a = x.abs();
+    let t = log1p_est(DoubleF64::from(2.0 * a) / DoubleF64::from_sum(1.0, -a)) * 0.5;
+    Approx::rel(if x < 0.0 { -t } else { t }, LOG_ERR)
+}
+
+fn cbrt_fast(x: f64) -> Option<Approx> {
+    // One Newton step from c = cbrt(x): c - (c³ - x) / (3c²).
+    if x.abs() < 1e-280 {
+        return None;
+    }
+    let c = cbrt(x);
+    let d = DoubleF64::from_product(c, c) * c - x;
+    Approx::rel(DoubleF64::new(c, -d.hi / (3.0 * c * c)), ROOT_ERR)
+}
+
+fn sqrt_fast(x: f64) -> Option<Approx> {
+    // r + (x - r²) / (2r), the residual exact unless it underflows.
+    if x < 1e-280 {
+        return None;
+    }
+    let r = sqrt(x);
+    let e = fma_internal(-r, r, x);
+    if e == 0.0 {
+        return Some(Approx {
+            v: DoubleF64::from(r),
+            err: 0.0,
+        });
+    }
+    Approx::rel(DoubleF64::new(r, e / (2.0 * r)), ROOT_ERR)
+}
+
+fn hypot_fast(x: f64, y: f64) -> Option<Approx> {
+    // Scaled by 2^-k to put the larger in [1, 2), the smaller staying
+    // normal for a ratio above TINY.
+    let (a, b) = (x.abs().max(y.abs()), x.abs().min(y.abs()));
+    let k = super::ilogb(a);
+    let (a, b) = (scalbn_internal(a, -k), scalbn_internal(b, -k));
+    let s = DoubleF64::from_product(a, a) + DoubleF64::from_product(b, b);
+    let r = s.sqrt();
+    let v = DoubleF64::new(scalbn_internal(r.hi, k), scalbn_internal(r.lo, k));
+    Approx::rel(v, ROOT_ERR)
+}
+
+fn pow_fast(x: f64, y: f64) -> Option<Approx> {
+    // e^(y ln|x|), the exponent's error |y ln|x|| LOG_ERR below 2^-89.
+    let t = log_est(x.abs()) * y;
+    let p = exp_est(t.hi, t.lo)?;
+    let v = if x < 0.0 && is_odd_integer(y) { -p } else { p };
+    Approx::rel(v, EXP_ERR + t.hi.abs() * LOG_ERR)
+}
+
+fn exp_round<M: Rounding>(x: f64, mode: M) -> M::Out {
+    if !x.is_finite() {
+        return mode.exact(exp(x));
+    }
+    if x.abs() > 750.0 {
+        return out_of_range(false, x > 0.0, mode);
+    }
+    if is_tiny(x) {
+        return beside(Mp::one(), x > 0.0, mode);
+    }
+    mode.ziv(|| exp_fast(x), || exp_mp(x), || exp_mp(x))
+}
+
+fn exp2_round<M: Rounding>(x: f64, mode: M) -> M::Out {
+    if !x.is_finite() {
+        return mode.exact(exp2(x));
+    }
+    if x.abs() > 1100.0 {
+        return out_of_range(false, x > 0.0, mode);
+    }
+    if is_tiny(x) {
+        return beside(Mp::one(), x > 0.0, mode);
+    }
+    mode.ziv(|| exp2_fast(x), || exp2_mp(x), || exp2_mp(x))
+}
+
+fn exp10_round<M: Rounding>(x: f64, mode: M) -> M::Out {
+    if !x.is_finite() {
+        return mode.exact(exp10(x));
+    }
+    if x.abs() > 330.0 {
+        return out_of_range(false, x > 0.0, mode);
+    }
+    if is_tiny(x) {
+        return beside(Mp::one(), x > 0.0, mode);
+    }
+    mode.ziv(|| exp10_fast(x), || exp10_mp(x), || exp10_mp(x))
+}
+
+fn expm1_round<M: Rounding>(x: f64, mode: M) -> M::Out {
+    if !x.is_finite() || x == 0.0 {
+        return mode.exact(expm1(x));
+    }
+    if x > 750.0 {
+        return out_of_range(false, true, mode);
+    }
+    // Below -40, e^x - 1 is within 2^-57 of -1.
+    if x < -40.0 {
+        return beside(Mp::one().neg(), true, mode);
+    }
+    if is_tiny(x) {
+        return beside(Mp::from_f64(x), true, mode);
+    }
+    mode.ziv(|| expm1_fast(x), || expm1_mp(x), || expm1_mp(x))
+}
+
+fn ln_round<M: Rounding>(x: f64, mode: M) -> M::Out {
+    if !x.is_finite() || x <= 0.0 {
+        return mode.exact(ln(x));
+    }
+    mode.ziv(|| ln_fast(x), || ln_mp(x), || ln_mp(x))
+}
+
+fn log2_round<M: Rounding>(x: f64, mode: M) -> M::Out {
+    if !x.is_finite() || x <= 0.0 {
+        return mode.exact(log2(x));
+    }
+    mode.ziv(|| log2_fast(x), || log2_mp(x), || log2_mp(x))
+}
+
+fn log10_round<M: Rounding>(x: f64, mode: M) -> M::Out {
+    if !x.is_finite() || x <= 0.0 {
+        return mode.exact(log10(x));
+    }
+    mode.ziv(|| log10_fast(x), || log10_mp(x), || log10_mp(x))
+}
+
+fn log1p_round<M: Rounding>(x: f64, mode: M) -> M::Out {
+    if !x.is_finite() || x <= -1.0 || x == 0.0 {
+        return mode.exact(log1p(x));
+    }
+    if is_tiny(x) {
+        return beside(Mp::from_f64(x), false, mode);
+    }
+    mode.ziv(|| log1p_fast(x), || log1p_mp(x), || log1p_mp(x))
+}
+
+fn sin_round<M: Rounding>(x: f64, mode: M) -> M::Out {
+    if !x.is_finite() || x == 0.0 {
+        return mode.exact(sin(x));
+    }
+    if is_tiny(x) {
+        return beside(Mp::from_f64(x), x < 0.0, mode);
+    }
+    mode.ziv(|| sin_fast(x), || sin_mp(x), || sin_mp(x))
+}
+
+fn cos_round<M: Rounding>(x: f64, mode: M) -> M::Out {
+    if !x.is_finite() || x == 0.0 {
+        return mode.exact(cos(x));
+    }
+    if is_tiny(x) {
+        return beside(Mp::one(), false, mode);
+    }
+    mode.ziv(|| cos_fast(x), || cos_mp(x), || cos_mp(x))
+}
+
+fn tan_round<M: Rounding>(x: f64, mode: M) -> M::Out {
+    if !x.is_finite() || x == 0.0 {
+        return mode.exact(tan(x));
+    }
+    if is_tiny(x) {
+        return beside(Mp::from_f64(x), x > 0.0, mode);
+    }
+    mode.ziv(|| tan_fast(x), || tan_mp(x), || tan_mp(x))
+}
+
+fn asin_round<M: Rounding>(x: f64, mode: M) -> M::Out {
+    if x.is_nan() || x.abs() > 1.0 || x == 0.0 {
+        return mode.exact(asin(x));
+    }
+    if x.abs() == 1.0 {
+        return mode.round(with_sign(Mp::<2>::pi_2(), x < 0.0));
+    }
+    if is_tiny(x) {
+        return beside(Mp::from_f64(x), x > 0.0, mode);
+    }
+    mode.ziv(|| asin_fast(x), || asin_mp(x), || asin_mp(x))
+}
+
+fn acos_round<M: Rounding>(x: f64, mode: M) -> M::Out {
+    if x.is_nan() || x.abs() > 1.0 || x == 1.0 {
+        return mode.exact(acos(x));
+    }
+    if x == -1.0 {
+        return mode.round(Mp::<2>::pi_2().ldexp(1));
+    }
+    mode.ziv(|| acos_fast(x), || acos_mp(x), || acos_mp(x))
+}
+
+fn atan_round<M: Rounding>(x: f64, mode: M) -> M::Out {
+    if x.is_nan() || x == 0.0 {
+        return mode.exact(atan(x));
+    }
+    if x.is_infinite() {
+        return mode.round(with_sign(Mp::<2>::pi_2(), x < 0.0));
+    }
+    if is_tiny(x) {
+        return beside(Mp::from_f64(x), x < 0.0, mode);
+    }
+    mode.ziv(
+        || atan_fast(x),
+        || Mp::from_f64(x).atan(),
+        || Mp::from_f64(x).atan(),
+    )
+}
+
+fn atan2_round<M: Rounding>(y: f64, x: f64, mode: M) -> M::Out {
+    if !x.is_finite() || !y.is_finite() || x == 0.0 || y == 0.0 {
+        // The special results are zeros, NaNs and multiples of π/4.
+        let r = atan2(y, x);
+        if r == 0.0 || r.is_nan() {
+            return mode.exact(r);
+        }
+        let k = (r.abs() / core::f64::consts::FRAC_PI_4 + 0.5) as u64;
+        return mode.round(with_sign(Mp::<2>::pi_2().mul_u64(k).ldexp(-1), r < 0.0));
+    }
+    // atan(q) for a tiny quotient q; an inexact q is much further than
+    // 2^-200 from a double.
+    if x > 0.0 && (y / x).abs() < TINY {
+        let q = Mp::from_f64(y).div(Mp::from_f64(x));
+        return beside(q, y < 0.0, mode);
+    }
+    mode.ziv(|| atan2_fast(y, x), || atan2_mp(y, x), || atan2_mp(y, x))
+}
+
+fn sinh_round<M: Rounding>(x: f64, mode: M) -> M::Out {
+    if !x.is_finite() || x == 0.0 {
+        return mode.exact(sinh(x));
+    }
+    if x.abs() > 750.0 {
+        return out_of_range(x < 0.0, true, mode);
+    }
+    if is_tiny(x) {
+        return beside(Mp::from_f64(x), x > 0.0, mode);
+    }
+    mode.ziv(|| sinh_fast(x), || sinh_mp(x), || sinh_mp(x))
+}
+
+fn cosh_round<M: Rounding>(x: f64, mode: M) -> M::Out {
+    if !x.is_finite() {
+        return mode.exact(cosh(x));
+    }
+    if x.abs() > 750.0 {
+        return out_of_range(false, true, mode);
+    }
+    if is_tiny(x) {
+        return beside(Mp::one(), true, mode);
+    }
+    mode.ziv(|| cosh_fast(x), || cosh_mp(x), || cosh_mp(x))
+}
+
+fn tanh_round<M: Rounding>(x: f64, mode: M) -> M::Out {
+    if !x.is_finite() || x == 0.0 {
+        return mode.exact(tanh(x));
+    }
+    // Beyond 22, tanh is within 2^-60 of ±1.
+    if x.abs() > 22.0 {
+        return beside(with_sign(Mp::one(), x < 0.0), x < 0.0, mode);
+    }
+    if is_tiny(x) {
+        return beside(Mp::from_f64(x), x < 0.0, mode);
+    }
+    mode.ziv(|| tanh_fast(x), || tanh_mp(x), || tanh_mp(x))
+}
+
+fn asinh_round<M: Rounding>(x: f64, mode: M) -> M::Out {
+    if !x.is_finite() || x == 0.0 {
+        return mode.exact(asinh(x));
+    }
+    if is_tiny(x) {
+        return beside(Mp::from_f64(x), x < 0.0, mode);
+    }
+    mode.ziv(|| asinh_fast(x), || asinh_mp(x), || asinh_mp(x))
+}
+
+fn acosh_round<M: Rounding>(x: f64, mode: M) -> M::Out {
+    if !x.is_finite() || x <= 1.0 {
+        return mode.exact(acosh(x));
+    }
+    mode.ziv(|| acosh_fast(x), || acosh_mp(x), || acosh_mp(x))
+}
+
+fn atanh_round<M: Rounding>(x: f64, mode: M) -> M::Out {
+    if x.is_nan() || x.abs() >= 1.0 || x == 0.0 {
+        return mode.exact(atanh(x));
+    }
+    if is_tiny(x) {
+        return beside(Mp::from_f64(x), x > 0.0, mode);
+    }
+    mode.ziv(|| atanh_fast(x), || atanh_mp(x), || atanh_mp(x))
+}
+
+fn cbrt_round<M: Rounding>(x: f64, mode: M) -> M::Out {
+    if !x.is_finite() || x == 0.0 {
+        return mode.exact(cbrt(x));
+    }
+    mode.ziv(
+        || cbrt_fast(x),
+        || Mp::from_f64(x).cbrt(),
+        || Mp::from_f64(x).cbrt(),
+    )
+}
+
+fn sqrt_round<M: Rounding>(x: f64, mode: M) -> M::Out {
+    if !x.is_finite() || x <= 0.0 {
+        return mode.exact(sqrt(x));
+    }
+    mode.ziv(
+        || sqrt_fast(x),
+        || Mp::from_f64(x).sqrt(),
+        || Mp::from_f64(x).sqrt(),
+    )
+}
+
+fn hypot_round<M: Rounding>(x: f64, y: f64, mode: M) -> M::Out {
+    if !x.is_finite() || !y.is_finite() || x == 0.0 || y == 0.0 {
+        return mode.exact(hypot(x, y));
+    }
+    // a √(1 + (b/a)²) is just above a for a tiny ratio.
+    let (a, b) = (x.abs().max(y.abs()), x.abs().min(y.abs()));
+    if b / a < TINY {
+        return beside(Mp::from_f64(a), true, mode);
+    }
+    fn hypot_mp<const L: usize>(x: f64, y: f64) -> Mp<L> {
+        Mp::from_f64(x).sqr().add(Mp::from_f64(y).sqr()).sqrt()
+    }
+    mode.ziv(|| hypot_fast(x, y), || hypot_mp(x, y), || hypot_mp(x, y))
+}
+
+fn pow_round<M: Rounding>(x: f64, y: f64, mode: M) -> M::Out {
+    if !x.is_finite() || !y.is_finite() || x == 0.0 || y == 0.0 || x == 1.0 {
+        return mode.exact(pow(x, y));
+    }
+    if x < 0.0 && y != super::trunc(y) {
+        return mode.exact(pow(x, y));
+    }
+    // y ln|x| in double precision, within 2^-50 of it relative in
+    // round-to-nearest, leaves it to the checks below only outside
+    // (2^-89, 700).
+    let t0 = if fegetround() == RoundingMode::ToNearest {
+        (y * ln(x.abs())).abs()
+    } else {
+        f64::NAN
+    };
+    if !(t0 > 1e-27 && t0 < 700.0) {
+        let t = Mp::<2>::from_f64(y).mul(Mp::from_f64(x.abs()).ln());
+        // |y ln|x|| >= 1024 overflows or underflows whatever the rounding.
+        if t.exponent() > 10 {
+            return out_of_range(x < 0.0 && is_odd_integer(y), !t.is_neg(), mode);
+        }
+        // Only a positive x has |y ln x| < 2^-54, y being then no integer.
+        if !t.is_zero() && t.exponent() < -100 {
+            return beside(Mp::one(), !t.is_neg(), mode);
+        }
+    }
+    mode.ziv(|| pow_fast(x, y), || pow_mp(x, y), || pow_mp(x, y))
+}
+
+// v as hi + lo, each rounded to nearest; see the module docs for the rest
+// below 2^-150.
+fn to_dd(v: Mp<3>) -> DoubleF64 {
+    let hi = v.to_f64(Round::Nearest);
+    if hi == 0.0 || !hi.is_finite() {
+        return DoubleF64::from(hi);
+    }
+    let rest = v.sub(Mp::from_f64(hi));
+    if rest.is_zero() || rest.exponent() < v.exponent() - 150 {
+        return DoubleF64::from(hi);
+    }
+    DoubleF64 {
+        hi,
+        lo: rest.to_f64(Round::Nearest),
+    }
+}
+
+fn exp_double(x: f64) -> DoubleF64 {
+    if !x.is_finite() || x.abs() > 750.0 {
+        return DoubleF64::from(exp(x));
+    }
+    to_dd(exp_mp(x))
+}
+
+fn ln_double(x: f64) -> DoubleF64 {
+    if !x.is_finite() || x <= 0.0 {
+        return DoubleF64::from(ln(x));
+    }
+    to_dd(ln_mp(x))
+}
+
+fn log2_double(x: f64) -> DoubleF64 {
+    if !x.is_finite() || x <= 0.0 {
+        return DoubleF64::from(log2(x));
+    }
+    to_dd(log2_mp(x))
+}
+
+fn sin_double(x: f64) -> DoubleF64 {
+    if !x.is_finite() || x == 0.0 {
+        return DoubleF64::from(sin(x));
+    }
+    to_dd(sin_mp(x))
+}
+
+fn cos_double(x: f64) -> DoubleF64 {
+    if !x.is_finite() || x == 0.0 {
+        return DoubleF64::from(cos(x));
+    }
+    to_dd(cos_mp(x))
+}
+
+fn tan_double(x: f64) -> DoubleF64 {
+    if !x.is_finite() || x == 0.0 {
+        return DoubleF64::from(tan(x));
+    }
+    to_dd(tan_mp(x))
+}
+
+fn atan_double(x: f64) -> DoubleF64 {
+    if x.is_nan() || x == 0.0 {
+        return DoubleF64::from(atan(x));
+    }
+    if x.is_infinite() {
+        return to_dd(with_sign(Mp::pi_2(), x < 0.0));
+    }
+    to_dd(Mp::from_f64(x).atan())
+}
+
+fn sqrt_double(x: f64) -> DoubleF64 {
+    if !x.is_finite() || x <= 0.0 {
+        return DoubleF64::from(sqrt(x));
+    }
+    to_dd(Mp::from_f64(x).sqrt())
+}
+
+fn pow_double(x: f64, y: f64) -> DoubleF64 {
+    if !x.is_finite() || !y.is_finite() || x == 0.0 || y == 0.0 || x == 1.0 {
+        return DoubleF64::from(pow(x, y));
+    }
+    if x < 0.0 && y != super::trunc(y) {
+        return DoubleF64::from(pow(x, y));
+    }
+    // |y ln|x|| >= 1024 overflows or underflows.
+    let t = Mp::<2>::from_f64(y).mul(Mp::from_f64(x.abs()).ln());
+    if t.exponent() > 10 {
+        return DoubleF64::from(pow(x, y));
+    }
+    to_dd(pow_mp(x, y))
+}
+
+macro_rules! double_double {
+    ($($double:ident($($a:ident),+) => $f:literal: $dd:ident;)*) => {$(
+        #[doc = concat!("[`", $f, "`](crate::", $f, ") as a double-double: `hi` is the correctly rounded result and `lo` the rest, rounded to nearest.")]
+        ///
+        /// The relative error is below 2^-105 unless `lo` underflows.
+        /// Special and overflowing results are those of the double function
+        /// with a zero `lo`.
+        pub fn $dd($($a: f64),+) -> DoubleF64 {
+            $double($($a),+)
+        }
+    )*};
+}
+
+double_double! {
+    exp_double(x) => "exp": exp_dd;
+    ln_double(x) => "ln": ln_dd;
+    log2_double(x) => "log2": log2_dd;
+    sin_double(x) => "sin": sin_dd;
+    cos_double(x) => "cos": cos_dd;
+    tan_double(x) => "tan": tan_dd;
+    atan_double(x) => "atan": atan_dd;
+    sqrt_double(x) => "sqrt": sqrt_dd;
+    pow_double(x, y) => "pow": pow_dd;
+}
+
+// The neighbour of a correctly rounded nearest result `r` in the direction
+// of `mode`, which bounds the exact value unless `r` is exact; a zero `r`
+// has the sign of the value it was rounded from.
+fn faithful(r: f64, exact: bool, mode: Round) -> f64 {
+    if exact || r.is_nan() {
+        return r;
+    }
+    let up = match mode {
+        Round::Nearest => return r,
+        Round::Down => false,
+        Round::Up => true,
+        Round::Zero => r.is_sign_negative(),
+    };
+    if up {
+        if r == 0.0 && r.is_sign_negative() {
+            r
+        } else {
+            r.next_up()
+        }
+    } else if r == 0.0 && r.is_sign_positive() {
+        r
+    } else {
+        r.next_down()
+    }
+}
+
+fn erf_bound(x: f64, mode: Round) -> f64 {
+    faithful(erf(x), erf_is_exact(x), mode).clamp(-1.0, 1.0)
+}
+
+fn erfc_bound(x: f64, mode: Round) -> f64 {
+    faithful(erfc(x), erf_is_exact(x), mode).clamp(0.0, 2.0)
+}
+
+fn lgamma_bound(x: f64, mode: Round) -> f64 {
+    faithful(lgamma(x), lgamma_is_exact(x), mode)
+}
+
+macro_rules! directed {
+    ($($round:ident($($a:ident),+) => $f:literal: $cr:ident, $rd:ident, $ru:ident, $rz:ident;)*) => {$(
+        #[doc = concat!("Correctly rounded [`", $f, "`](crate::", $f, ").")]
+        pub fn $cr($($a: f64),+) -> f64 {
+            $round($($a,)+ Round::Nearest)
+        }
+
+        #[doc = concat!("[`", $f, "`](crate::", $f, ") rounded down, toward -∞.")]
+        pub fn $rd($($a: f64),+) -> f64 {
+            $round($($a,)+ Round::Down)
+        }
+
+        #[doc = concat!("[`", $f, "`](crate::", $f, ") rounded up, toward +∞.")]
+        pub fn $ru($($a: f64),+) -> f64 {
+            $round($($a,)+ Round::Up)
+        }
+
+        #[doc = concat!("[`", $f, "`](crate::", $f, ") rounded toward zero.")]
+        pub fn $rz($($a: f64),+) -> f64 {
+            $round($($a,)+ Round::Zero)
+        }
+    )*};
+}
+
+directed! {
+    exp_round(x) => "exp": cr_exp, exp_rd, exp_ru, exp_rz;
+    exp2_round(x) => "exp2": cr_exp2, exp2_rd, exp2_ru, exp2_rz;
+    exp10_round(x) => "exp10": cr_exp10, exp10_rd, exp10_ru, exp10_rz;
+    expm1_round(x) => "expm1": cr_expm1, expm1_rd, expm1_ru, expm1_rz;
+    ln_round(x) => "ln": cr_ln, ln_rd, ln_ru, ln_rz;
+    log2_round(x) => "log2": cr_log2, log2_rd, log2_ru, log2_rz;
+    log10_round(x) => "log10": cr_log10, log10_rd, log10_ru, log10_rz;
+    log1p_round(x) => "log1p": cr_log1p, log1p_rd, log1p_ru, log1p_rz;
+    sin_round(x) => "sin": cr_sin, sin_rd, sin_ru, sin_rz;
+    cos_round(x) => "cos": cr_cos, cos_rd, cos_ru, cos_rz;
+    tan_round(x) => "tan": cr_tan, tan_rd, tan_ru, tan_rz;
+    asin_round(x) => "asin": cr_asin, asin_rd, asin_ru, asin_rz;
+    acos_round(x) => "acos": cr_acos, acos_rd, acos_ru, acos_rz;
+    atan_round(x) => "atan": cr_atan, atan_rd, atan_ru, atan_rz;
+    atan2_round(y, x) => "atan2": cr_atan2, atan2_rd, atan2_ru, atan2_rz;
+    sinh_round(x) => "sinh": cr_sinh, sinh_rd, sinh_ru, sinh_rz;
+    cosh_round(x) => "cosh": cr_cosh, cosh_rd, cosh_ru, cosh_rz;
+    tanh_round(x) => "tanh": cr_tanh, tanh_rd, tanh_ru, tanh_rz;
+    asinh_round(x) => "asinh": cr_asinh, asinh_rd, asinh_ru, asinh_rz;
+    acosh_round(x) => "acosh": cr_acosh, acosh_rd, acosh_ru, acosh_rz;
+    atanh_round(x) => "atanh": cr_atanh, atanh_rd, atanh_ru, atanh_rz;
+    cbrt_round(x) => "cbrt": cr_cbrt, cbrt_rd, cbrt_ru, cbrt_rz;
+    hypot_round(x, y) => "hypot": cr_hypot, hypot_rd, hypot_ru, hypot_rz;
+    pow_round(x, y) => "pow": cr_pow, pow_rd, pow_ru, pow_rz;
+}
+
+/// [`sqrt`](crate::sqrt) rounded down, toward -∞.
+pub fn sqrt_rd(x: f64) -> f64 {
+    sqrt_round(x, Round::Down)
+}
+
+/// [`sqrt`](crate::sqrt) rounded up, toward +∞.
+pub fn sqrt_ru(x: f64) -> f64 {
+    sqrt_round(x, Round::Up)
+}
+
+/// [`sqrt`](crate::sqrt) rounded toward zero.
+pub fn sqrt_rz(x: f64) -> f64 {
+    sqrt_round(x, Round::Zero)
+}
+
+// Rounded down and up from one evaluation, for the exactness of the
+// results `flags` cannot settle from the arguments.
+pub(crate) fn hypot_bounds(x: f64, y: f64) -> (f64, f64) {
+    hypot_round(x, y, Bounds)
+}
+
+pub(crate) fn pow_bounds(x: f64, y: f64) -> (f64, f64) {
+    pow_round(x, y, Bounds)
+}
+
+macro_rules! bounded {
+    ($($bound:ident => $f:literal: $rd:ident, $ru:ident, $rz:ident;)*) => {$(
+        #[doc = concat!("A lower bound on [`", $f, "`](crate::", $f, "), at most one ulp below the exact value rounded down.")]
+        pub fn $rd(x: f64) -> f64 {
+            $bound(x, Round::Down)
+        }
+
+        #[doc = concat!("An upper bound on [`", $f, "`](crate::", $f, "), at most one ulp above the exact value rounded up.")]
+        pub fn $ru(x: f64) -> f64 {
+            $bound(x, Round::Up)
+        }
+
+        #[doc = concat!("A bound on [`", $f, "`](crate::", $f, ") no further from zero than the exact value, at most one ulp closer to zero than it rounded toward zero.")]
+        pub fn $rz(x: f64) -> f64 {
+            $bound(x, Round::Zero)
+        }
+    )*};
+}
+
+bounded! {
+    erf_bound => "erf": erf_rd, erf_ru, erf_rz;
+    erfc_bound => "erfc": erfc_rd, erfc_ru, erfc_rz;
+    lgamma_bound => "lgamma": lgamma_rd, lgamma_ru, lgamma_rz;
+}
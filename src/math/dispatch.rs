@@ -0,0 +1,88 @@
+//! Runtime CPU feature detection for the `runtime-dispatch` feature.
+//!
+//! By default the crate assumes FMA on every x86 and x86_64 target (unless
+//! `soft-fma` is enabled) and picks the AVX2 and AVX-512 kernels from the
+//! build's target features, so a binary built for one CPU can fault with
+//! SIGILL on an older one. With `runtime-dispatch`, FMA, AVX2 and AVX-512F
+//! are detected once, on first use, with `is_x86_feature_detected!`. The
+//! scalar functions with `_fma`/`_generic` pairs (`exp`, `exp2`, `expm1`,
+//! `sin`, `cos`, `sincos`, `tan`, `pow`) then call through a function pointer
+//! chosen and cached on their first call, the helpers built on
+//! `fma_internal` test the detected flag, and the batched functions run the
+//! widest kernel the CPU supports.
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+const FMA: u8 = 1 << 0;
+const AVX2: u8 = 1 << 1;
+const AVX512F: u8 = 1 << 2;
+const DETECTED: u8 = 1 << 7;
+
+static FEATURES: AtomicU8 = AtomicU8::new(0);
+
+#[cold]
+fn detect() -> u8 {
+    let mut f = DETECTED;
+    if std::is_x86_feature_detected!("fma") {
+        f |= FMA;
+    }
+    if std::is_x86_feature_detected!("avx2") {
+        f |= AVX2;
+    }
+    if std::is_x86_feature_detected!("avx512f") {
+        f |= AVX512F;
+    }
+    FEATURES.store(f, Ordering::Relaxed);
+    f
+}
+
+#[inline(always)]
+fn features() -> u8 {
+    let f = FEATURES.load(Ordering::Relaxed);
+    if f & DETECTED != 0 { f } else { detect() }
+}
+
+/// Whether the `_fma` scalar paths may run: the CPU has FMA and `soft-fma`
+/// is off.
+#[inline(always)]
+pub(crate) fn has_fma() -> bool {
+    !cfg!(feature = "soft-fma") && features() & FMA != 0
+}
+
+/// Whether the AVX2+FMA kernels may run.
+#[inline(always)]
+pub(crate) fn has_avx2() -> bool {
+    has_fma() && features() & AVX2 != 0
+}
+
+/// Whether the AVX-512F kernels may run.
+#[inline(always)]
+pub(crate) fn has_avx512f() -> bool {
+    has_fma() && features() & AVX512F != 0
+}
+
+// The `_fma` function if the CPU has FMA, else the generic one, chosen on
+// the first call and cached as a function pointer.
+macro_rules! select {
+    ($fma:path, $generic:path; fn($($a:ident: $t:ty),*) -> $r:ty) => {{
+        use core::sync::atomic::{AtomicPtr, Ordering};
+        type F = fn($($t),*) -> $r;
+        static CACHE: AtomicPtr<()> = AtomicPtr::new(core::ptr::null_mut());
+        let p = CACHE.load(Ordering::Relaxed);
+        if p.is_null() {
+            let f: F = if $crate::math::dispatch::has_fma() {
+                // SAFETY: the CPU supports FMA.
+                |$($a),*| unsafe { $fma($($a),*) }
+            } else {
+                $generic
+            };
+            CACHE.store(f as *mut (), Ordering::Relaxed);
+            f
+        } else {
+            // SAFETY: CACHE only ever holds an `F`.
+            unsafe { core::mem::transmute::<*mut (), F>(p) }
+        }
+    }};
+}
+
+pub(crate) use select;
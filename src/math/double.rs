@@ -0,0 +1,388 @@
+//! Double-double arithmetic.
+//!
+//! A [`DoubleF64`] is the unevaluated sum `hi + lo` of two doubles with
+//! `|lo| <= ulp(hi) / 2`, about 106 bits of precision with the exponent
+//! range of `f64`. The operations return normalized values with relative
+//! errors of a few 2^-106, barring underflow of the low part; a non-finite
+//! result, or one whose low part overflows, is the plain double operation's
+//! result with a zero low part.
+//!
+//! The module also holds the kernels the functions are built on: the
+//! error-free `two_sum` and `fasttwosum` and core-math's ddcoremath helpers
+//! (`adddd`, `muldd_acc`, `divdd`, ...). These take the parts separately,
+//! return the high part and store the low one through `l`, and some leave
+//! their result unnormalized, so the ported code keeps its exact evaluation
+//! order and error bounds; `DoubleF64` is written on the same kernels.
+
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+use super::fma_internal;
+use super::sqrt::sqrt;
+
+/// A double-double number `hi + lo`.
+///
+/// Values built by the operations are normalized, `hi` being `hi + lo`
+/// rounded to nearest, so the derived comparison, which orders by `hi` and
+/// then `lo`, is the comparison of the sums.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct DoubleF64 {
+    /// High part.
+    pub hi: f64,
+    /// Low part.
+    pub lo: f64,
+}
+
+impl DoubleF64 {
+    pub const ZERO: Self = Self { hi: 0.0, lo: 0.0 };
+    pub const ONE: Self = Self { hi: 1.0, lo: 0.0 };
+
+    /// `hi + lo` exactly, normalized.
+    #[inline]
+    pub fn new(hi: f64, lo: f64) -> Self {
+        let (h, l) = two_sum(hi, lo);
+        Self::checked(h, l, || hi + lo)
+    }
+
+    /// `a + b` exactly.
+    #[inline]
+    pub fn from_sum(a: f64, b: f64) -> Self {
+        Self::new(a, b)
+    }
+
+    /// `a * b`, exact unless the product or its error underflows.
+    #[inline]
+    pub fn from_product(a: f64, b: f64) -> Self {
+        let mut l = 0.0;
+        let h = twoprod(a, b, &mut l);
+        Self::checked(h, l, || a * b)
+    }
+
+    /// `hi + lo` rounded to nearest.
+    #[inline]
+    pub fn to_f64(self) -> f64 {
+        self.hi + self.lo
+    }
+
+    #[inline]
+    pub fn abs(self) -> Self {
+        if self.hi.is_sign_negative() {
+            -self
+        } else {
+            self
+        }
+    }
+
+    /// The square root, NaN below zero.
+    pub fn sqrt(self) -> Self {
+        let mut l = 0.0;
+        let h = sqrt_dd(self.hi, self.lo, &mut l);
+        Self::checked(h, l, || sqrt(self.hi))
+    }
+
+    // (h, l), or the plain result when either part is not finite.
+    #[inline(always)]
+    fn checked(h: f64, l: f64, plain: impl FnOnce() -> f64) -> Self {
+        if h.is_finite() && l.is_finite() {
+            Self { hi: h, lo: l }
+        } else {
+            Self::from(plain())
+        }
+    }
+}
+
+impl From<f64> for DoubleF64 {
+    #[inline]
+    fn from(x: f64) -> Self {
+        Self { hi: x, lo: 0.0 }
+    }
+}
+
+impl Neg for DoubleF64 {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self {
+            hi: -self.hi,
+            lo: -self.lo,
+        }
+    }
+}
+
+impl Add for DoubleF64 {
+    type Output = Self;
+
+    // Joldes, Muller and Popescu's AccurateDWPlusDW: the low parts are summed
+    // with their own error term, so the bound holds under cancellation.
+    #[inline]
+    fn add(self, o: Self) -> Self {
+        let (sh, sl) = two_sum(self.hi, o.hi);
+        let (th, tl) = two_sum(self.lo, o.lo);
+        let mut vl = 0.0;
+        let vh = fasttwosum(sh, sl + th, &mut vl);
+        let mut l = 0.0;
+        let h = fasttwosum(vh, tl + vl, &mut l);
+        Self::checked(h, l, || self.hi + o.hi)
+    }
+}
+
+impl Add<f64> for DoubleF64 {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, o: f64) -> Self {
+        let (sh, sl) = two_sum(self.hi, o);
+        let mut l = 0.0;
+        let h = fasttwosum(sh, self.lo + sl, &mut l);
+        Self::checked(h, l, || self.hi + o)
+    }
+}
+
+impl Sub for DoubleF64 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, o: Self) -> Self {
+        self + -o
+    }
+}
+
+impl Sub<f64> for DoubleF64 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, o: f64) -> Self {
+        self + -o
+    }
+}
+
+impl Mul for DoubleF64 {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, o: Self) -> Self {
+        let p = self.hi * o.hi;
+        let e =
+            fma_internal(self.hi, o.hi, -p) + (self.hi * o.lo + self.lo * o.hi) + self.lo * o.lo;
+        let mut l = 0.0;
+        let h = fasttwosum(p, e, &mut l);
+        Self::checked(h, l, || p)
+    }
+}
+
+impl Mul<f64> for DoubleF64 {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, o: f64) -> Self {
+        let mut l = 0.0;
+        let h = mulddd(self.hi, self.lo, o, &mut l);
+        Self::checked(h, l, || self.hi * o)
+    }
+}
+
+impl Div for DoubleF64 {
+    type Output = Self;
+
+    #[inline]
+    fn div(self, o: Self) -> Self {
+        let mut l = 0.0;
+        let h = divdd(self.hi, self.lo, o.hi, o.lo, &mut l);
+        Self::checked(h, l, || self.hi / o.hi)
+    }
+}
+
+impl Div<f64> for DoubleF64 {
+    type Output = Self;
+
+    #[inline]
+    fn div(self, o: f64) -> Self {
+        let mut l = 0.0;
+        let h = divddd(self.hi, self.lo, o, &mut l);
+        Self::checked(h, l, || self.hi / o)
+    }
+}
+
+// === error-free transformations ===
+
+#[inline(always)]
+pub(crate) fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    let bb = s - a;
+    let err = (a - (s - bb)) + (b - bb);
+    (s, err)
+}
+
+// x + y exactly when |x| >= |y| (or x = 0).
+#[inline(always)]
+pub(crate) fn fasttwosum(x: f64, y: f64, e: &mut f64) -> f64 {
+    let s = x + y;
+    let z = s - x;
+    *e = y - z;
+    s
+}
+
+// x * y exactly, barring underflow.
+#[inline(always)]
+pub(crate) fn twoprod(x: f64, y: f64, l: &mut f64) -> f64 {
+    let h = x * y;
+    *l = fma_internal(x, y, -h);
+    h
+}
+
+// === ddcoremath helpers (ported) ===
+
+#[inline(always)]
+pub(crate) fn fasttwosub(x: f64, y: f64, e: &mut f64) -> f64 {
+    let s = x - y;
+    let z = x - s;
+    *e = z - y;
+    s
+}
+
+#[inline(always)]
+pub(crate) fn twosum(x: f64, y: f64, e: &mut f64) -> f64 {
+    if x.abs() > y.abs() {
+        fasttwosum(x, y, e)
+    } else {
+        fasttwosum(y, x, e)
+    }
+}
+
+#[inline(always)]
+pub(crate) fn fastsum(xh: f64, xl: f64, yh: f64, yl: f64, e: &mut f64) -> f64 {
+    let mut sl = 0.0;
+    let sh = fasttwosum(xh, yh, &mut sl);
+    *e = (xl + yl) + sl;
+    sh
+}
+
+#[inline(always)]
+pub(crate) fn sumdd(xh: f64, xl: f64, yh: f64, yl: f64, e: &mut f64) -> f64 {
+    let mut sl = 0.0;
+    let sh = if xh.abs() > yh.abs() {
+        fasttwosum(xh, yh, &mut sl)
+    } else {
+        fasttwosum(yh, xh, &mut sl)
+    };
+    *e = (xl + yl) + sl;
+    sh
+}
+
+#[inline(always)]
+pub(crate) fn adddd(xh: f64, xl: f64, ch: f64, cl: f64, l: &mut f64) -> f64 {
+    let s = xh + ch;
+    let d = s - xh;
+    *l = ((ch - d) + (xh + (d - s))) + (xl + cl);
+    s
+}
+
+#[inline(always)]
+pub(crate) fn muldd_acc(xh: f64, xl: f64, ch: f64, cl: f64, l: &mut f64) -> f64 {
+    let ahlh = ch * xl;
+    let alhh = cl * xh;
+    let ahhh = ch * xh;
+    let mut ahhl = fma_internal(ch, xh, -ahhh);
+    ahhl += alhh + ahlh;
+    let chh = ahhh + ahhl;
+    *l = (ahhh - chh) + ahhl;
+    chh
+}
+
+#[inline(always)]
+pub(crate) fn muldd_acc2(xh: f64, xl: f64, ch: f64, cl: f64, l: &mut f64) -> f64 {
+    let ahlh = ch * xl;
+    let alhh = cl * xh;
+    let ahhh = ch * xh;
+    let mut ahhl = fma_internal(ch, xh, -ahhh);
+    ahhl += alhh + ahlh;
+    fasttwosum(ahhh, ahhl, l)
+}
+
+#[inline(always)]
+pub(crate) fn muldd2(xh: f64, xl: f64, ch: f64, cl: f64, l: &mut f64) -> f64 {
+    let ahhh = ch * xh;
+    *l = (ch * xl + cl * xh) + fma_internal(ch, xh, -ahhh);
+    ahhh
+}
+
+#[inline(always)]
+pub(crate) fn muldd3(xh: f64, xl: f64, yh: f64, yl: f64, l: &mut f64) -> f64 {
+    let ch = xh * yh;
+    let cl1 = fma_internal(xh, yh, -ch);
+    let tl0 = xl * yl;
+    let tl1 = tl0 + xh * yl;
+    let cl2 = tl1 + xl * yh;
+    let cl3 = cl1 + cl2;
+    fasttwosum(ch, cl3, l)
+}
+
+#[inline(always)]
+pub(crate) fn mulddd(xh: f64, xl: f64, ch: f64, l: &mut f64) -> f64 {
+    let ahlh = ch * xl;
+    let ahhh = ch * xh;
+    let mut ahhl = fma_internal(ch, xh, -ahhh);
+    ahhl += ahlh;
+    let chh = ahhh + ahhl;
+    *l = (ahhh - chh) + ahhl;
+    chh
+}
+
+#[inline(always)]
+pub(crate) fn mulddd2(x: f64, ch: f64, cl: f64, l: &mut f64) -> f64 {
+    let ahhh = ch * x;
+    *l = cl * x + fma_internal(ch, x, -ahhh);
+    ahhh
+}
+
+#[inline(always)]
+pub(crate) fn mulddd3(xh: f64, xl: f64, ch: f64, l: &mut f64) -> f64 {
+    let hh = xh * ch;
+    *l = fma_internal(ch, xh, -hh) + xl * ch;
+    hh
+}
+
+// (xh + xl) / d as a normalized double-double.
+#[inline(always)]
+pub(crate) fn divddd(xh: f64, xl: f64, d: f64, l: &mut f64) -> f64 {
+    let qh = xh / d;
+    let r = fma_internal(-qh, d, xh) + xl;
+    fasttwosum(qh, r / d, l)
+}
+
+// (xh + xl) / (dh + dl) as a normalized double-double.
+#[inline(always)]
+pub(crate) fn divdd(xh: f64, xl: f64, dh: f64, dl: f64, l: &mut f64) -> f64 {
+    let qh = xh / dh;
+    let r = fma_internal(-qh, dh, xh) + xl - qh * dl;
+    fasttwosum(qh, r / dh, l)
+}
+
+// (xh + xl) + (yh + yl) as a normalized double-double.
+#[inline(always)]
+pub(crate) fn add_dd(xh: f64, xl: f64, yh: f64, yl: f64, l: &mut f64) -> f64 {
+    let mut e = 0.0;
+    let h = adddd(xh, xl, yh, yl, &mut e);
+    fasttwosum(h, e, l)
+}
+
+// sqrt(xh + xl) as a double-double.
+#[inline(always)]
+pub(crate) fn sqrt_dd(xh: f64, xl: f64, l: &mut f64) -> f64 {
+    let s = sqrt(xh);
+    if s == 0.0 {
+        *l = 0.0;
+        return s;
+    }
+    let r = fma_internal(-s, s, xh) + xl;
+    fasttwosum(s, r / (2.0 * s), l)
+}
+
+// |t|^2 for t = (rh + rl) + i (ih + il).
+#[inline(always)]
+pub(crate) fn norm_dd(rh: f64, rl: f64, ih: f64, il: f64, l: &mut f64) -> f64 {
+    let (mut al, mut bl) = (0.0, 0.0);
+    let ah = muldd_acc(rh, rl, rh, rl, &mut al);
+    let bh = muldd_acc(ih, il, ih, il, &mut bl);
+    add_dd(ah, al, bh, bl, l)
+}
@@ -0,0 +1,1016 @@
+//! Carlson symmetric elliptic integrals R_F, R_D, R_J, R_C and the Legendre
+//! forms K, E, Π (complete) and F, E, Π (incomplete) built on them.
+//!
+//! The duplication theorem runs in double-double until every variable is
+//! within 2^-8 of the mean, then a degree-7 Taylor expansion (Carlson 1995,
+//! DLMF 19.36) finishes; its truncation stays below 2^-64. Arguments are
+//! rescaled by powers of four away from the overflow and subnormal ranges.
+//! Legendre forms use the cancellation-free combinations of DLMF 19.25 where
+//! they exist; incomplete amplitudes are reduced modulo pi first.
+
+use super::double::{add_dd, divdd, divddd, muldd_acc, mulddd, sqrt_dd, twosum};
+use super::fasttwosum;
+use super::sqrt::sqrt;
+use super::trig::sincos;
+use super::{fma_internal, roundeven_finite, scalbn_internal, with_nearest};
+
+// pi as a double-double.
+const PI_HI: f64 = f64::from_bits(0x4009_21fb_5444_2d18);
+const PI_LO: f64 = f64::from_bits(0x3ca1_a626_3314_5c07);
+const FRAC_PI_2: f64 = f64::from_bits(0x3ff9_21fb_5444_2d18);
+const TWO54: f64 = f64::from_bits(0x4350_0000_0000_0000);
+
+// Duplication stops once every |A - x_i| <= DUP_TOL A.
+const DUP_TOL: f64 = 0.003_906_25; // 2^-8
+// Below this |e|, R_C(1, 1 + e) uses its power series.
+const RC_SERIES: f64 = 0.125;
+
+// Arguments whose largest component lies outside [TINY, HUGE] are scaled by
+// a power of four bringing it near 1; R(4^k x) = 2^-k R(x) for degree -1/2.
+// The cores form products of order A^3, so the safe range stays well inside
+// the exponent range.
+const TINY: f64 = f64::from_bits(0x2d30_0000_0000_0000); // 2^-300
+const HUGE: f64 = f64::from_bits(0x52b0_0000_0000_0000); // 2^300
+
+// (xh + xl)^-1/2 as a double-double.
+#[inline(always)]
+fn rsqrt_dd(xh: f64, xl: f64, l: &mut f64) -> f64 {
+    let mut sl = 0.0;
+    let sh = sqrt_dd(xh, xl, &mut sl);
+    divdd(1.0, 0.0, sh, sl, l)
+}
+
+// Power-of-four exponent k with 4^k m in [1, 4) when m = max |argument| lies
+// outside [TINY, HUGE], else 0.
+#[inline(always)]
+fn scale_exp(m: f64) -> i32 {
+    if (TINY..=HUGE).contains(&m) {
+        return 0;
+    }
+    let mut e = ((m.to_bits() >> 52) & 0x7ff) as i32;
+    if e == 0 {
+        e = (((m * TWO54).to_bits() >> 52) & 0x7ff) as i32 - 54;
+    }
+    -((e - 1023) >> 1)
+}
+
+// 2^n (h + l) with the tail cleared once the head overflows.
+#[inline(always)]
+fn unscale(h: f64, n: i32, l: &mut f64) -> f64 {
+    let h = scalbn_internal(h, n);
+    *l = if h.is_finite() {
+        scalbn_internal(*l, n)
+    } else {
+        0.0
+    };
+    h
+}
+
+#[inline(always)]
+fn scale4(x: f64, k: i32) -> f64 {
+    if k == 0 { x } else { scalbn_internal(x, 2 * k) }
+}
+
+// One duplication step on a double-double variable: v = (v + lambda) / 4.
+#[inline(always)]
+fn dup_step(vh: &mut f64, vl: &mut f64, lh: f64, ll: f64) {
+    let mut l = 0.0;
+    let h = add_dd(*vh, *vl, lh, ll, &mut l);
+    *vh = 0.25 * h;
+    *vl = 0.25 * l;
+}
+
+// lambda = sqrt(x) sqrt(y) + sqrt(x) sqrt(z) + sqrt(y) sqrt(z).
+#[inline(always)]
+fn dup_lambda(s: [(f64, f64); 3], l: &mut f64) -> f64 {
+    let (mut l0, mut l1, mut l2) = (0.0, 0.0, 0.0);
+    let h0 = muldd_acc(s[0].0, s[0].1, s[1].0, s[1].1, &mut l0);
+    let h1 = muldd_acc(s[0].0, s[0].1, s[2].0, s[2].1, &mut l1);
+    let h2 = muldd_acc(s[1].0, s[1].1, s[2].0, s[2].1, &mut l2);
+    let mut t = 0.0;
+    let h = add_dd(h0, l0, h1, l1, &mut t);
+    add_dd(h, t, h2, l2, l)
+}
+
+// R_F(x, y, z) for x, y, z >= 0 in the safe range, at most one zero.
+fn rf_core(x: f64, y: f64, z: f64, l: &mut f64) -> f64 {
+    let mut v = [(x, 0.0), (y, 0.0), (z, 0.0)];
+    let mut t = 0.0;
+    let s = twosum(x, y, &mut t);
+    let mut al = 0.0;
+    let ah = add_dd(s, t, z, 0.0, &mut al);
+    let mut ah = divddd(ah, al, 3.0, &mut al);
+    let dx = (ah - x) + al;
+    let dy = (ah - y) + al;
+    let q = dx.abs().max(dy.abs()).max((dx + dy).abs());
+    let mut fac = 1.0;
+    while fac * q > DUP_TOL * ah {
+        let mut sq = [(0.0, 0.0); 3];
+        for (si, vi) in sq.iter_mut().zip(v.iter()) {
+            si.0 = sqrt_dd(vi.0, vi.1, &mut si.1);
+        }
+        let mut lam_l = 0.0;
+        let lam_h = dup_lambda(sq, &mut lam_l);
+        for vi in v.iter_mut() {
+            dup_step(&mut vi.0, &mut vi.1, lam_h, lam_l);
+        }
+        dup_step(&mut ah, &mut al, lam_h, lam_l);
+        fac *= 0.25;
+    }
+    let inv = fac / ah;
+    let xx = dx * inv;
+    let yy = dy * inv;
+    let zz = -(xx + yy);
+    let e2 = xx * yy - zz * zz;
+    let e3 = xx * yy * zz;
+    // 1 - E2/10 + E3/14 + E2^2/24 - 3 E2 E3/44 - 5 E2^3/208 + 3 E3^2/104
+    //   + E2^2 E3/16
+    let p = e2 * (-0.1 + e2 * (1.0 / 24.0 - e2 * (5.0 / 208.0)))
+        + e3 * (1.0 / 14.0 - e2 * (3.0 / 44.0) + e3 * (3.0 / 104.0) + e2 * e2 * 0.0625);
+    let mut rl = 0.0;
+    let rh = rsqrt_dd(ah, al, &mut rl);
+    fasttwosum(rh, rl + rh * p, l)
+}
+
+// Degree-7 expansion shared by R_D and R_J:
+//   1 - 3 E2/14 + E3/6 + 9 E2^2/88 - 3 E4/22 - 9 E2 E3/52 + 3 E5/26
+//   - E2^3/16 + 3 E3^2/40 + 3 E2 E4/20 + 45 E2^2 E3/272 - 9 (E3 E4 + E2 E5)/68
+// minus the leading 1.
+#[inline(always)]
+fn rd_series(e2: f64, e3: f64, e4: f64, e5: f64) -> f64 {
+    e2 * (-3.0 / 14.0 + e2 * (9.0 / 88.0 - e2 * 0.0625) + e4 * 0.15 + e2 * e3 * (45.0 / 272.0))
+        + e3 * (1.0 / 6.0 - e2 * (9.0 / 52.0) + e3 * 0.075)
+        - e4 * (3.0 / 22.0)
+        + e5 * (3.0 / 26.0)
+        - (e3 * e4 + e2 * e5) * (9.0 / 68.0)
+}
+
+// fac A^-3/2 (1 + p) as a double-double.
+#[inline(always)]
+fn rd_tail(ah: f64, al: f64, fac: f64, p: f64, l: &mut f64) -> f64 {
+    let mut rl = 0.0;
+    let rh = rsqrt_dd(ah, al, &mut rl);
+    let mut r3l = 0.0;
+    let r3h = divdd(rh, rl, ah, al, &mut r3l);
+    fasttwosum(fac * r3h, fac * (r3l + r3h * p), l)
+}
+
+// R_D(x, y, z) for x, y >= 0 (at most one zero), z > 0, in the safe range.
+fn rd_core(x: f64, y: f64, z: f64, l: &mut f64) -> f64 {
+    let mut v = [(x, 0.0), (y, 0.0), (z, 0.0)];
+    // A0 = (x + y + 3 z) / 5
+    let mut t = 0.0;
+    let s = twosum(x, y, &mut t);
+    let mut al = 0.0;
+    let ah = add_dd(s, t, 3.0 * z, fma_internal(3.0, z, -3.0 * z), &mut al);
+    let mut ah = divddd(ah, al, 5.0, &mut al);
+    let dx = (ah - x) + al;
+    let dy = (ah - y) + al;
+    let q = dx.abs().max(dy.abs()).max(((dx + dy) / 3.0).abs());
+    let mut fac = 1.0;
+    let (mut sum_h, mut sum_l) = (0.0, 0.0);
+    while fac * q > DUP_TOL * ah {
+        let mut sq = [(0.0, 0.0); 3];
+        for (si, vi) in sq.iter_mut().zip(v.iter()) {
+            si.0 = sqrt_dd(vi.0, vi.1, &mut si.1);
+        }
+        let mut lam_l = 0.0;
+        let lam_h = dup_lambda(sq, &mut lam_l);
+        // sum += fac / (sqrt(z) (z + lambda))
+        let mut zl = 0.0;
+        let zh = add_dd(v[2].0, v[2].1, lam_h, lam_l, &mut zl);
+        let mut dl = 0.0;
+        let dh = muldd_acc(sq[2].0, sq[2].1, zh, zl, &mut dl);
+        let mut tl = 0.0;
+        let th = divdd(fac, 0.0, dh, dl, &mut tl);
+        sum_h = add_dd(sum_h, sum_l, th, tl, &mut sum_l);
+        for vi in v.iter_mut() {
+            dup_step(&mut vi.0, &mut vi.1, lam_h, lam_l);
+        }
+        dup_step(&mut ah, &mut al, lam_h, lam_l);
+        fac *= 0.25;
+    }
+    let inv = fac / ah;
+    let xx = dx * inv;
+    let yy = dy * inv;
+    let zz = -(xx + yy) / 3.0;
+    let xy = xx * yy;
+    let z2 = zz * zz;
+    let e2 = xy - 6.0 * z2;
+    let e3 = (3.0 * xy - 8.0 * z2) * zz;
+    let e4 = 3.0 * (xy - z2) * z2;
+    let e5 = xy * z2 * zz;
+    let p = rd_series(e2, e3, e4, e5);
+    let mut tl = 0.0;
+    let th = rd_tail(ah, al, fac, p, &mut tl);
+    let mut s3l = 0.0;
+    let s3h = mulddd(sum_h, sum_l, 3.0, &mut s3l);
+    add_dd(s3h, s3l, th, tl, l)
+}
+
+// R_C(1, 1 + e) for e = eh + el > -1.
+#[inline(always)]
+fn rc_one(eh: f64, el: f64, l: &mut f64) -> f64 {
+    if eh.abs() < RC_SERIES {
+        // sum_k (-e)^k / (2k + 1); the tail past -e/3 only needs doubles.
+        let mut s = 0.0;
+        let mut p = -eh;
+        for k in 2..28 {
+            p *= -eh;
+            s += p / (2 * k + 1) as f64;
+        }
+        return fasttwosum(1.0, (-eh - el) / 3.0 + s, l);
+    }
+    let mut yl = 0.0;
+    let yh = twosum(1.0, eh, &mut yl);
+    rc_core((1.0, 0.0), (yh, yl + el), l)
+}
+
+// R_J(x, y, z, p) for x, y, z >= 0 (at most one zero), p > 0, safe range.
+// A wide spread takes a duplication step per halving of the ratio, so the
+// variables would shrink into the subnormals; whenever A falls below 2^200
+// they are scaled back up by 2^100. A small variable keeps at least its
+// ratio to A, so spreads up to 2^1274 stay clear of underflow. `fac` is 4^-m
+// times the accumulated scale and `w`, which weights the R_C terms and the
+// tail, 4^-m times its 3/2 power. For the same reason e is formed as a
+// product of ratios r_i in (-1, 1] rather than from delta / d^2, whose parts
+// underflow.
+fn rj_core(x: f64, y: f64, z: f64, p: f64, l: &mut f64) -> f64 {
+    let mut v = [(x, 0.0), (y, 0.0), (z, 0.0)];
+    let (mut ph, mut pl) = (p, 0.0);
+    // A0 = (x + y + z + 2 p) / 5
+    let mut t = 0.0;
+    let s = twosum(x, y, &mut t);
+    let mut al = 0.0;
+    let ah = add_dd(s, t, z, 0.0, &mut al);
+    let ah = add_dd(ah, al, 2.0 * p, 0.0, &mut al);
+    let mut ah = divddd(ah, al, 5.0, &mut al);
+    let dx = (ah - x) + al;
+    let dy = (ah - y) + al;
+    let dz = (ah - z) + al;
+    let dp = -(dx + dy + dz) / 2.0;
+    let q = dx.abs().max(dy.abs()).max(dz.abs()).max(dp.abs());
+    // p - x_i, exactly, carrying the 4^-m factor.
+    let mut dif = [(0.0, 0.0); 3];
+    for (di, xi) in dif.iter_mut().zip([x, y, z]) {
+        di.0 = twosum(p, -xi, &mut di.1);
+    }
+    let (mut fac, mut w) = (1.0, 1.0);
+    let (mut sum_h, mut sum_l) = (0.0, 0.0);
+    while fac * q > DUP_TOL * ah {
+        let mut sq = [(0.0, 0.0); 3];
+        for (si, vi) in sq.iter_mut().zip(v.iter()) {
+            si.0 = sqrt_dd(vi.0, vi.1, &mut si.1);
+        }
+        let mut spl = 0.0;
+        let sph = sqrt_dd(ph, pl, &mut spl);
+        let mut lam_l = 0.0;
+        let lam_h = dup_lambda(sq, &mut lam_l);
+        // Only past the spreads above, with two variables flushed to zero.
+        if lam_h == 0.0 {
+            break;
+        }
+        // d = (sqrt(p) + sqrt(x)) (sqrt(p) + sqrt(y)) (sqrt(p) + sqrt(z)) = f0 f1 f2
+        // and e = delta / d^2 = r0 r1 r2 with r_i = (p - x_i) / f_i^2.
+        let mut f = [(0.0, 0.0); 3];
+        let mut r = [(0.0, 0.0); 3];
+        let (mut dh, mut dl) = (1.0, 0.0);
+        let (mut eh, mut el) = (1.0, 0.0);
+        for i in 0..3 {
+            f[i].0 = add_dd(sph, spl, sq[i].0, sq[i].1, &mut f[i].1);
+            dh = muldd_acc(dh, dl, f[i].0, f[i].1, &mut dl);
+            let mut f2l = 0.0;
+            let f2h = muldd_acc(f[i].0, f[i].1, f[i].0, f[i].1, &mut f2l);
+            r[i].0 = divdd(dif[i].0, dif[i].1, f2h, f2l, &mut r[i].1);
+            eh = muldd_acc(eh, el, r[i].0, r[i].1, &mut el);
+        }
+        let mut rl = 0.0;
+        let rh = if eh < -0.5 {
+            // p far below the x_i leaves 1 + e below the resolution of e.
+            // With e < 0, 1 + e = 1 - |r0 r1 r2| sums the non-negative
+            // 1 - |r_i| = 2 min(sqrt(p), sqrt(x_i)) / f_i:
+            //   1 + e = t0 + |r0| t1 + |r0 r1| t2.
+            let (mut yh, mut yl) = (0.0, 0.0);
+            let (mut gh, mut gl) = (1.0, 0.0);
+            for i in 0..3 {
+                let (mh, ml) = if sph < sq[i].0 { (sph, spl) } else { sq[i] };
+                let mut tl = 0.0;
+                let th = divdd(2.0 * mh, 2.0 * ml, f[i].0, f[i].1, &mut tl);
+                let th = muldd_acc(gh, gl, th, tl, &mut tl);
+                yh = add_dd(yh, yl, th, tl, &mut yl);
+                let (ah, al) = if r[i].0 < 0.0 {
+                    (-r[i].0, -r[i].1)
+                } else {
+                    r[i]
+                };
+                gh = muldd_acc(gh, gl, ah, al, &mut gl);
+            }
+            rc_core((1.0, 0.0), (yh, yl), &mut rl)
+        } else {
+            rc_one(eh, el, &mut rl)
+        };
+        // sum += fac R_C(1, 1 + e) / d
+        let mut tl = 0.0;
+        let th = divdd(w * rh, w * rl, dh, dl, &mut tl);
+        sum_h = add_dd(sum_h, sum_l, th, tl, &mut sum_l);
+        for vi in v.iter_mut() {
+            dup_step(&mut vi.0, &mut vi.1, lam_h, lam_l);
+        }
+        dup_step(&mut ph, &mut pl, lam_h, lam_l);
+        dup_step(&mut ah, &mut al, lam_h, lam_l);
+        fac *= 0.25;
+        w *= 0.25;
+        for di in dif.iter_mut() {
+            di.0 *= 0.25;
+            di.1 *= 0.25;
+        }
+        if ah < f64::from_bits(0x4c70_0000_0000_0000) {
+            // 2^100, and its 3/2 power for w.
+            let s = f64::from_bits(0x4630_0000_0000_0000);
+            for (vi, di) in v.iter_mut().zip(dif.iter_mut()) {
+                vi.0 *= s;
+                vi.1 *= s;
+                di.0 *= s;
+                di.1 *= s;
+            }
+            ph *= s;
+            pl *= s;
+            ah *= s;
+            al *= s;
+            fac *= s;
+            w *= f64::from_bits(0x4950_0000_0000_0000);
+        }
+    }
+    let inv = fac / ah;
+    let xx = dx * inv;
+    let yy = dy * inv;
+    let zz = dz * inv;
+    let pp = dp * inv;
+    let xyz = xx * yy * zz;
+    let p2 = pp * pp;
+    let e2 = xx * yy + xx * zz + yy * zz - 3.0 * p2;
+    let e3 = xyz + 2.0 * e2 * pp + 4.0 * p2 * pp;
+    let e4 = (2.0 * xyz + e2 * pp + 3.0 * p2 * pp) * pp;
+    let e5 = xyz * p2;
+    let p = rd_series(e2, e3, e4, e5);
+    let mut tl = 0.0;
+    let th = rd_tail(ah, al, w, p, &mut tl);
+    let mut s6l = 0.0;
+    let s6h = mulddd(sum_h, sum_l, 6.0, &mut s6l);
+    add_dd(s6h, s6l, th, tl, l)
+}
+
+// R_C(x, y) for x = xh + xl >= 0, y = yh + yl > 0 in the safe range.
+fn rc_core(x: (f64, f64), y: (f64, f64), l: &mut f64) -> f64 {
+    let (mut xh, mut xl) = x;
+    let (mut yh, mut yl) = y;
+    // A0 = (x + 2 y) / 3, d0 = y - A0 = (y - x) / 3
+    let mut t = 0.0;
+    let s = add_dd(xh, xl, 2.0 * yh, 2.0 * yl, &mut t);
+    let mut al = 0.0;
+    let mut ah = divddd(s, t, 3.0, &mut al);
+    let mut dl = 0.0;
+    let dh = add_dd(yh, yl, -xh, -xl, &mut dl);
+    let d0 = (dh + dl) / 3.0;
+    let mut fac = 1.0;
+    while fac * d0.abs() > DUP_TOL * ah {
+        let mut sxl = 0.0;
+        let sxh = sqrt_dd(xh, xl, &mut sxl);
+        let mut syl = 0.0;
+        let syh = sqrt_dd(yh, yl, &mut syl);
+        // lambda = 2 sqrt(x) sqrt(y) + y
+        let mut ml = 0.0;
+        let mh = muldd_acc(sxh, sxl, syh, syl, &mut ml);
+        let mut lam_l = 0.0;
+        let lam_h = add_dd(2.0 * mh, 2.0 * ml, yh, yl, &mut lam_l);
+        dup_step(&mut xh, &mut xl, lam_h, lam_l);
+        dup_step(&mut yh, &mut yl, lam_h, lam_l);
+        dup_step(&mut ah, &mut al, lam_h, lam_l);
+        fac *= 0.25;
+    }
+    let s = d0 * fac / ah;
+    // 1 + 3 s^2/10 + s^3/7 + 3 s^4/8 + 9 s^5/22 + 159 s^6/208 + 9 s^7/8
+    let p = s
+        * s
+        * (0.3
+            + s * (1.0 / 7.0 + s * (0.375 + s * (9.0 / 22.0 + s * (159.0 / 208.0 + s * 1.125)))));
+    let mut rl = 0.0;
+    let rh = rsqrt_dd(ah, al, &mut rl);
+    fasttwosum(rh, rl + rh * p, l)
+}
+
+// R_F(x, y, z) for finite arguments already checked against the domain.
+#[inline(always)]
+fn rf_dd(x: f64, y: f64, z: f64, l: &mut f64) -> f64 {
+    let k = scale_exp(x.max(y).max(z));
+    if k == 0 {
+        return rf_core(x, y, z, l);
+    }
+    let h = rf_core(scale4(x, k), scale4(y, k), scale4(z, k), l);
+    unscale(h, k, l)
+}
+
+#[inline(always)]
+fn rd_dd(x: f64, y: f64, z: f64, l: &mut f64) -> f64 {
+    let k = scale_exp(x.max(y).max(z));
+    if k == 0 {
+        return rd_core(x, y, z, l);
+    }
+    let h = rd_core(scale4(x, k), scale4(y, k), scale4(z, k), l);
+    unscale(h, 3 * k, l)
+}
+
+#[inline(always)]
+fn rc_dd(x: f64, y: f64, l: &mut f64) -> f64 {
+    let k = scale_exp(x.max(y));
+    if k == 0 {
+        return rc_core((x, 0.0), (y, 0.0), l);
+    }
+    let h = rc_core((scale4(x, k), 0.0), (scale4(y, k), 0.0), l);
+    unscale(h, k, l)
+}
+
+#[inline(always)]
+fn rj_dd(x: f64, y: f64, z: f64, p: f64, l: &mut f64) -> f64 {
+    let k = scale_exp(x.max(y).max(z).max(p));
+    if k == 0 {
+        return rj_core(x, y, z, p, l);
+    }
+    // Large arguments come down to about 2^300 rather than 1, which keeps
+    // spreads up to 2^1374 clear of underflow. Past that an argument that
+    // flushes to zero stands in as the least subnormal: with two zeros the
+    // duplication would never converge.
+    let k = if k < 0 { k + 150 } else { k };
+    let s = |v: f64| {
+        let t = scale4(v, k);
+        if t == 0.0 && v != 0.0 {
+            f64::from_bits(1)
+        } else {
+            t
+        }
+    };
+    let h = rj_core(s(x), s(y), s(z), s(p), l);
+    unscale(h, 3 * k, l)
+}
+
+// Number of zeros among the arguments.
+#[inline(always)]
+fn zeros(a: &[f64]) -> usize {
+    a.iter().filter(|&&v| v == 0.0).count()
+}
+
+/// Carlson's symmetric elliptic integral of the first kind,
+/// R_F(x, y, z) = 1/2 ∫_0^∞ ((t + x)(t + y)(t + z))^-1/2 dt.
+///
+/// Requires x, y, z >= 0 with at most one zero; two zeros give +inf and
+/// negative arguments NaN.
+#[inline]
+pub fn elliprf(x: f64, y: f64, z: f64) -> f64 {
+    with_nearest((x, y, z), |(x, y, z)| elliprf_nearest(x, y, z))
+}
+
+#[inline]
+fn elliprf_nearest(x: f64, y: f64, z: f64) -> f64 {
+    if x.is_nan() || y.is_nan() || z.is_nan() {
+        return x + y + z;
+    }
+    if x < 0.0 || y < 0.0 || z < 0.0 {
+        return f64::NAN;
+    }
+    if x == f64::INFINITY || y == f64::INFINITY || z == f64::INFINITY {
+        return 0.0;
+    }
+    if zeros(&[x, y, z]) > 1 {
+        return f64::INFINITY;
+    }
+    let mut l = 0.0;
+    let h = rf_dd(x, y, z, &mut l);
+    h + l
+}
+
+/// Carlson's symmetric elliptic integral of the second kind,
+/// R_D(x, y, z) = 3/2 ∫_0^∞ ((t + x)(t + y))^-1/2 (t + z)^-3/2 dt.
+///
+/// Requires x, y >= 0 with at most one zero and z > 0; otherwise +inf at the
+/// singular points and NaN outside the domain.
+#[inline]
+pub fn elliprd(x: f64, y: f64, z: f64) -> f64 {
+    with_nearest((x, y, z), |(x, y, z)| elliprd_nearest(x, y, z))
+}
+
+#[inline]
+fn elliprd_nearest(x: f64, y: f64, z: f64) -> f64 {
+    if x.is_nan() || y.is_nan() || z.is_nan() {
+        return x + y + z;
+    }
+    if x < 0.0 || y < 0.0 || z < 0.0 {
+        return f64::NAN;
+    }
+    if x == f64::INFINITY || y == f64::INFINITY || z == f64::INFINITY {
+        return 0.0;
+    }
+    if z == 0.0 || (x == 0.0 && y == 0.0) {
+        return f64::INFINITY;
+    }
+    let mut l = 0.0;
+    let h = rd_dd(x, y, z, &mut l);
+    h + l
+}
+
+/// Carlson's degenerate integral R_C(x, y) = R_F(x, y, y).
+///
+/// Requires x >= 0 and y != 0; y < 0 gives the Cauchy principal value.
+#[inline]
+pub fn elliprc(x: f64, y: f64) -> f64 {
+    with_nearest((x, y), |(x, y)| elliprc_nearest(x, y))
+}
+
+#[inline]
+fn elliprc_nearest(x: f64, y: f64) -> f64 {
+    if x.is_nan() || y.is_nan() {
+        return x + y;
+    }
+    if x < 0.0 {
+        return f64::NAN;
+    }
+    if y == 0.0 {
+        return f64::INFINITY;
+    }
+    if x == f64::INFINITY || y.is_infinite() {
+        return 0.0;
+    }
+    let mut l = 0.0;
+    if y > 0.0 {
+        let h = rc_dd(x, y, &mut l);
+        return h + l;
+    }
+    // R_C(x, y) = sqrt(x / (x - y)) R_C(x - y, -y)
+    if x == 0.0 {
+        return 0.0;
+    }
+    let k = scale_exp(x.max(-y));
+    let (x, y) = (scale4(x, k), scale4(y, k));
+    let mut dl = 0.0;
+    let dh = twosum(x, -y, &mut dl);
+    let h = rc_core((dh, dl), (-y, 0.0), &mut l);
+    let mut ql = 0.0;
+    let qh = divdd(x, 0.0, dh, dl, &mut ql);
+    let mut sl = 0.0;
+    let sh = sqrt_dd(qh, ql, &mut sl);
+    let mut rl = 0.0;
+    let rh = muldd_acc(h, l, sh, sl, &mut rl);
+    scalbn_internal(rh + rl, k)
+}
+
+// R_J for validated arguments, including the principal value for p < 0.
+fn rj_any(x: f64, y: f64, z: f64, p: f64, l: &mut f64) -> f64 {
+    if p > 0.0 {
+        return rj_dd(x, y, z, p, l);
+    }
+    // Carlson (1995) eq. 4.6 with x <= y <= z and q = -p:
+    //   (z + q) R_J(x, y, z, p) = (r - z) R_J(x, y, z, r) - 3 R_F(x, y, z)
+    //     + 3 sqrt(x y z / (x y + r q)) R_C(x y + r q, r q),
+    //   r = (z (x + y + q) - x y) / (z + q).
+    let k = scale_exp(x.max(y).max(z).max(-p));
+    let mut a = [scale4(x, k), scale4(y, k), scale4(z, k)];
+    a.sort_unstable_by(f64::total_cmp);
+    let [x, y, z] = a;
+    let q = -scale4(p, k);
+    let r = (z * (x + y + q) - x * y) / (z + q);
+    let mut jl = 0.0;
+    let jh = rj_dd(x, y, z, r, &mut jl);
+    let mut fl = 0.0;
+    let fh = rf_dd(x, y, z, &mut fl);
+    let xy = x * y;
+    let rq = r * q;
+    let mut cl = 0.0;
+    let ch = rc_dd(xy + rq, rq, &mut cl);
+    let c = 3.0 * sqrt(x * y * z / (xy + rq));
+    let mut vl = 0.0;
+    let vh = mulddd(jh, jl, r - z, &mut vl);
+    let vh = add_dd(vh, vl, -3.0 * fh, -3.0 * fl, &mut vl);
+    let mut wl = 0.0;
+    let wh = mulddd(ch, cl, c, &mut wl);
+    let vh = add_dd(vh, vl, wh, wl, &mut vl);
+    let h = divddd(vh, vl, z + q, l);
+    unscale(h, 3 * k, l)
+}
+
+/// Carlson's symmetric elliptic integral of the third kind,
+/// R_J(x, y, z, p) = 3/2 ∫_0^∞ ((t + x)(t + y)(t + z))^-1/2 (t + p)^-1 dt.
+///
+/// Requires x, y, z >= 0 with at most one zero and p != 0; p < 0 gives the
+/// Cauchy principal value, which is assembled from R_F, R_C and R_J with
+/// positive p and can lose a few bits to cancellation.
+#[inline]
+pub fn elliprj(x: f64, y: f64, z: f64, p: f64) -> f64 {
+    with_nearest((x, y, z, p), |(x, y, z, p)| elliprj_nearest(x, y, z, p))
+}
+
+#[inline]
+fn elliprj_nearest(x: f64, y: f64, z: f64, p: f64) -> f64 {
+    if x.is_nan() || y.is_nan() || z.is_nan() || p.is_nan() {
+        return x + y + z + p;
+    }
+    if x < 0.0 || y < 0.0 || z < 0.0 {
+        return f64::NAN;
+    }
+    if p == 0.0 || zeros(&[x, y, z]) > 1 {
+        return f64::INFINITY;
+    }
+    if x == f64::INFINITY || y == f64::INFINITY || z == f64::INFINITY || p.is_infinite() {
+        return 0.0;
+    }
+    let mut l = 0.0;
+    let h = rj_any(x, y, z, p, &mut l);
+    h + l
+}
+
+// K(m) = R_F(0, 1 - m, 1) as a double-double, for m < 1.
+#[inline(always)]
+fn ellipk_dd(m: f64, l: &mut f64) -> f64 {
+    rf_dd(0.0, 1.0 - m, 1.0, l)
+}
+
+// E(m) as a double-double for m <= 1, from the positive combinations of
+// DLMF 19.25.1 (y = 1 - m):
+//   m >= 0: E = y R_F(0, 1, y) + m y R_D(0, 1, y) / 3
+//   m < 0:  E = R_F(0, y, 1) - m R_D(0, y, 1) / 3
+#[inline(always)]
+fn ellipe_dd(m: f64, l: &mut f64) -> f64 {
+    let y = 1.0 - m;
+    let (mut fl, mut dl) = (0.0, 0.0);
+    let (fh, dh) = if m >= 0.0 {
+        let fh = rf_dd(0.0, 1.0, y, &mut fl);
+        let dh = rd_dd(0.0, 1.0, y, &mut dl);
+        (
+            mulddd(fh, fl, y, &mut fl),
+            mulddd(dh, dl, m * y / 3.0, &mut dl),
+        )
+    } else {
+        let fh = rf_dd(0.0, y, 1.0, &mut fl);
+        let dh = rd_dd(0.0, y, 1.0, &mut dl);
+        (fh, mulddd(dh, dl, -m / 3.0, &mut dl))
+    };
+    add_dd(fh, fl, dh, dl, l)
+}
+
+// Pi(n, m) = R_F(0, y, 1) + n R_J(0, y, 1, 1 - n) / 3 as a double-double.
+// The principal value for n > 1 comes from Pi(n, m) = K(m) - Pi(m / n, m),
+// which avoids the cancellation inside the R_J principal value.
+fn ellippi_dd(n: f64, m: f64, l: &mut f64) -> f64 {
+    let y = 1.0 - m;
+    if n < 0.0 {
+        return ellippi_neg_dd(n, y, l);
+    }
+    let mut fl = 0.0;
+    let fh = rf_dd(0.0, y, 1.0, &mut fl);
+    if n == 0.0 {
+        *l = fl;
+        return fh;
+    }
+    if n > 1.0 {
+        let mut pl = 0.0;
+        let ph = ellippi_dd(m / n, m, &mut pl);
+        return add_dd(fh, fl, -ph, -pl, l);
+    }
+    let mut jl = 0.0;
+    let jh = rj_any(0.0, y, 1.0, 1.0 - n, &mut jl);
+    let jh = mulddd(jh, jl, n / 3.0, &mut jl);
+    add_dd(fh, fl, jh, jl, l)
+}
+
+// Π(n, m) for n < 0, with y = 1 - m. There R_F + n R_J / 3 cancels, down to
+// the absolute error of R_F once -n is large. With p = 1 - n and q = y / p,
+// p R_J(0, y, 1, p) + q R_J(0, y, 1, q) = 3 R_F(0, y, 1), so
+//   Π(n, m) = (R_J(0, y, 1, p) + q R_J(0, y, 1, q)) / 3,
+// a sum of positive terms. Past p = 2^160 max(y, 1), before q can
+// underflow, Π = π / (2 sqrt(p)) (1 + 2 R_F(0, y, 1) / (π sqrt(p))) and the
+// correction is below 2^-70.
+#[inline(always)]
+fn ellippi_neg_dd(n: f64, y: f64, l: &mut f64) -> f64 {
+    let p = 1.0 - n;
+    if p > f64::from_bits(0x49f0_0000_0000_0000) * y.max(1.0) {
+        let mut rl = 0.0;
+        let rh = sqrt_dd(p, 0.0, &mut rl);
+        return divdd(PI_HI, PI_LO, 2.0 * rh, 2.0 * rl, l);
+    }
+    let q = y / p;
+    let mut al = 0.0;
+    let ah = rj_any(0.0, y, 1.0, p, &mut al);
+    let mut bl = 0.0;
+    let bh = if q > f64::from_bits(0x5ff0_0000_0000_0000) {
+        // R_J(0, y, 1, q) would underflow once y passes about 2^680; by
+        // homogeneity q R_J(0, y, 1, q) = R_J(0, p, 1 / q, 1) / sqrt(q), where
+        // the rounding of 1 / q moves the result by a factor of ln(q) less.
+        let bh = rj_any(0.0, p, 1.0 / q, 1.0, &mut bl);
+        let mut sl = 0.0;
+        let sh = sqrt_dd(q, 0.0, &mut sl);
+        divdd(bh, bl, sh, sl, &mut bl)
+    } else {
+        let bh = rj_any(0.0, y, 1.0, q, &mut bl);
+        mulddd(bh, bl, q, &mut bl)
+    };
+    let mut sl = 0.0;
+    let sh = add_dd(ah, al, bh, bl, &mut sl);
+    divddd(sh, sl, 3.0, l)
+}
+
+/// Complete elliptic integral of the first kind K(m) = F(pi/2, m), with
+/// parameter m = k^2.
+///
+/// K(1) = +inf and m > 1 returns NaN.
+#[inline]
+pub fn ellipk(m: f64) -> f64 {
+    with_nearest(m, ellipk_nearest)
+}
+
+#[inline]
+fn ellipk_nearest(m: f64) -> f64 {
+    if m.is_nan() {
+        return m;
+    }
+    if m > 1.0 {
+        return f64::NAN;
+    }
+    if m == 1.0 {
+        return f64::INFINITY;
+    }
+    if m == f64::NEG_INFINITY {
+        return 0.0;
+    }
+    let mut l = 0.0;
+    let h = ellipk_dd(m, &mut l);
+    h + l
+}
+
+/// Complete elliptic integral of the second kind E(m) = E(pi/2, m), with
+/// parameter m = k^2.
+///
+/// E(1) = 1 and m > 1 returns NaN.
+#[inline]
+pub fn ellipe(m: f64) -> f64 {
+    with_nearest(m, ellipe_nearest)
+}
+
+#[inline]
+fn ellipe_nearest(m: f64) -> f64 {
+    if m.is_nan() {
+        return m;
+    }
+    if m > 1.0 {
+        return f64::NAN;
+    }
+    if m == 1.0 {
+        return 1.0;
+    }
+    if m == f64::NEG_INFINITY {
+        return f64::INFINITY;
+    }
+    let mut l = 0.0;
+    let h = ellipe_dd(m, &mut l);
+    h + l
+}
+
+/// Complete elliptic integral of the third kind
+/// Π(n, m) = ∫_0^{pi/2} (1 - n sin^2 t)^-1 (1 - m sin^2 t)^-1/2 dt.
+///
+/// n > 1 gives the Cauchy principal value; n = 1 or m = 1 return +inf and
+/// m > 1 returns NaN.
+#[inline]
+pub fn ellippi(n: f64, m: f64) -> f64 {
+    with_nearest((n, m), |(n, m)| ellippi_nearest(n, m))
+}
+
+#[inline]
+fn ellippi_nearest(n: f64, m: f64) -> f64 {
+    if n.is_nan() || m.is_nan() {
+        return n + m;
+    }
+    if m > 1.0 {
+        return f64::NAN;
+    }
+    if n == 1.0 || m == 1.0 {
+        return f64::INFINITY;
+    }
+    if m == f64::NEG_INFINITY || n.is_infinite() {
+        return 0.0;
+    }
+    let mut l = 0.0;
+    let h = ellippi_dd(n, m, &mut l);
+    h + l
+}
+
+// Amplitude reduction phi = k pi + r with |r| <= pi/2.
+#[inline(always)]
+fn reduce_phi(phi: f64) -> (f64, f64) {
+    if phi.abs() <= FRAC_PI_2 {
+        return (0.0, phi);
+    }
+    let k = roundeven_finite(phi / PI_HI);
+    (k, fma_internal(-k, PI_HI, phi) - k * PI_LO)
+}
+
+// sin(r), cos(r)^2 and 1 - m sin(r)^2 for the reduced amplitude, or None
+// when 1 - m sin^2 < 0.
+#[inline(always)]
+fn amplitude(r: f64, m: f64) -> Option<(f64, f64, f64)> {
+    let (s, c) = sincos(r);
+    let c2 = c * c;
+    let s2 = s * s;
+    let delta = if m <= 1.0 {
+        c2 + (1.0 - m) * s2
+    } else {
+        fma_internal(-m * s, s, 1.0)
+    };
+    if delta < 0.0 {
+        return None;
+    }
+    Some((s, c2, delta))
+}
+
+// (h + l) + 2 k (ch + cl), rounded.
+#[inline(always)]
+fn add_periods(h: f64, l: f64, k: f64, ch: f64, cl: f64) -> f64 {
+    if k == 0.0 {
+        return h + l;
+    }
+    let mut pl = 0.0;
+    let ph = mulddd(ch, cl, 2.0 * k, &mut pl);
+    let mut rl = 0.0;
+    let rh = add_dd(h, l, ph, pl, &mut rl);
+    rh + rl
+}
+
+/// Incomplete elliptic integral of the first kind
+/// F(phi, m) = ∫_0^phi (1 - m sin^2 t)^-1/2 dt.
+///
+/// For m > 1 the amplitude must satisfy m sin^2 phi <= 1; other arguments
+/// return NaN. F(phi + k pi, m) = F(phi, m) + 2 k K(m).
+#[inline]
+pub fn ellipkinc(phi: f64, m: f64) -> f64 {
+    with_nearest((phi, m), |(phi, m)| ellipkinc_nearest(phi, m))
+}
+
+#[inline]
+fn ellipkinc_nearest(phi: f64, m: f64) -> f64 {
+    if phi.is_nan() || m.is_nan() {
+        return phi + m;
+    }
+    if phi == 0.0 {
+        return phi;
+    }
+    if m > 1.0 && phi.abs() > FRAC_PI_2 {
+        return f64::NAN;
+    }
+    if phi.is_infinite() {
+        return if m <= 1.0 { phi } else { f64::NAN };
+    }
+    if m == f64::NEG_INFINITY {
+        return 0.0;
+    }
+    let (k, r) = reduce_phi(phi);
+    let Some((s, c2, delta)) = amplitude(r, m) else {
+        return f64::NAN;
+    };
+    let mut fl = 0.0;
+    let fh = rf_dd(c2, delta, 1.0, &mut fl);
+    let fh = mulddd(fh, fl, s, &mut fl);
+    if k == 0.0 {
+        return fh + fl;
+    }
+    let mut kl = 0.0;
+    let kh = if m == 1.0 {
+        f64::INFINITY
+    } else {
+        ellipk_dd(m, &mut kl)
+    };
+    add_periods(fh, fl, k, kh, kl)
+}
+
+/// Incomplete elliptic integral of the second kind
+/// E(phi, m) = ∫_0^phi (1 - m sin^2 t)^1/2 dt.
+///
+/// For m > 1 the amplitude must satisfy m sin^2 phi <= 1; other arguments
+/// return NaN. E(phi + k pi, m) = E(phi, m) + 2 k E(m).
+#[inline]
+pub fn ellipeinc(phi: f64, m: f64) -> f64 {
+    with_nearest((phi, m), |(phi, m)| ellipeinc_nearest(phi, m))
+}
+
+#[inline]
+fn ellipeinc_nearest(phi: f64, m: f64) -> f64 {
+    if phi.is_nan() || m.is_nan() {
+        return phi + m;
+    }
+    if phi == 0.0 {
+        return phi;
+    }
+    if m > 1.0 && phi.abs() > FRAC_PI_2 {
+        return f64::NAN;
+    }
+    if phi.is_infinite() {
+        return if m <= 1.0 { phi } else { f64::NAN };
+    }
+    if m == f64::NEG_INFINITY {
+        return if phi > 0.0 {
+            f64::INFINITY
+        } else {
+            f64::NEG_INFINITY
+        };
+    }
+    let (k, r) = reduce_phi(phi);
+    let Some((s, c2, delta)) = amplitude(r, m) else {
+        return f64::NAN;
+    };
+    let (mut hl, mut el) = (0.0, 0.0);
+    let hh = if m == 1.0 {
+        s
+    } else if (0.0..1.0).contains(&m) {
+        // (1 - m) s R_F(c^2, delta, 1) + m (1 - m) s^3 R_D(c^2, 1, delta) / 3
+        //   + m s cos(phi) / sqrt(delta)      (DLMF 19.25.9)
+        let y = 1.0 - m;
+        let mut fl = 0.0;
+        let fh = rf_dd(c2, delta, 1.0, &mut fl);
+        let fh = mulddd(fh, fl, y * s, &mut fl);
+        let mut dl = 0.0;
+        let dh = rd_dd(c2, 1.0, delta, &mut dl);
+        let dh = mulddd(dh, dl, m * y * s * s * s / 3.0, &mut dl);
+        let t = m * s * sqrt(c2 / delta);
+        let h = add_dd(fh, fl, dh, dl, &mut hl);
+        add_dd(h, hl, t, 0.0, &mut hl)
+    } else {
+        // s R_F(c^2, delta, 1) - m s^3 R_D(c^2, delta, 1) / 3
+        let mut fl = 0.0;
+        let fh = rf_dd(c2, delta, 1.0, &mut fl);
+        let fh = mulddd(fh, fl, s, &mut fl);
+        let mut dl = 0.0;
+        let dh = rd_dd(c2, delta, 1.0, &mut dl);
+        let dh = mulddd(dh, dl, -m * s * s * s / 3.0, &mut dl);
+        add_dd(fh, fl, dh, dl, &mut hl)
+    };
+    if k == 0.0 {
+        return hh + hl;
+    }
+    let eh = if m == 1.0 { 1.0 } else { ellipe_dd(m, &mut el) };
+    add_periods(hh, hl, k, eh, el)
+}
+
+/// Incomplete elliptic integral of the third kind
+/// Π(n, phi, m) = ∫_0^phi (1 - n sin^2 t)^-1 (1 - m sin^2 t)^-1/2 dt.
+///
+/// Amplitudes past the singularity n sin^2 phi = 1 give the Cauchy principal
+/// value; m sin^2 phi > 1 returns NaN.
+#[inline]
+pub fn ellippiinc(n: f64, phi: f64, m: f64) -> f64 {
+    with_nearest((n, phi, m), |(n, phi, m)| ellippiinc_nearest(n, phi, m))
+}
+
+#[inline]
+fn ellippiinc_nearest(n: f64, phi: f64, m: f64) -> f64 {
+    if n.is_nan() || phi.is_nan() || m.is_nan() {
+        return n + phi + m;
+    }
+    if phi == 0.0 {
+        return phi;
+    }
+    if (m > 1.0 && phi.abs() > FRAC_PI_2) || phi.is_infinite() || n.is_infinite() {
+        return f64::NAN;
+    }
+    let (k, r) = reduce_phi(phi);
+    let Some((s, c2, delta)) = amplitude(r, m) else {
+        return f64::NAN;
+    };
+    // s R_F(c^2, delta, 1) + n s^3 R_J(c^2, delta, 1, 1 - n s^2) / 3
+    let mut fl = 0.0;
+    let fh = rf_dd(c2, delta, 1.0, &mut fl);
+    let fh = mulddd(fh, fl, s, &mut fl);
+    let (mut hh, mut hl) = (fh, fl);
+    if n != 0.0 {
+        let p = fma_internal(-n * s, s, 1.0);
+        if p == 0.0 {
+            return if s > 0.0 {
+                f64::INFINITY
+            } else {
+                f64::NEG_INFINITY
+            };
+        }
+        let mut jl = 0.0;
+        let jh = rj_any(c2, delta, 1.0, p, &mut jl);
+        let jh = mulddd(jh, jl, n * s * s * s / 3.0, &mut jl);
+        hh = add_dd(hh, hl, jh, jl, &mut hl);
+    }
+    if k == 0.0 {
+        return hh + hl;
+    }
+    if n == 1.0 || m == 1.0 {
+        return if k > 0.0 {
+            f64::INFINITY
+        } else {
+            f64::NEG_INFINITY
+        };
+    }
+    let mut cl = 0.0;
+    let ch = ellippi_dd(n, m, &mut cl);
+    add_periods(hh, hl, k, ch, cl)
+}
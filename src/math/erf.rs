@@ -4,6 +4,8 @@
 //! double-double arithmetic for correctly rounded results (<= 1 ULP).
 //! Constants and tables are sourced from glibc/core-math (see glibc/).
 
+use super::double::{fastsum, twoprod};
+use super::with_nearest;
 use super::{
     asdouble, copysign, fasttwosum, floor, fma_internal, ldexp, roundeven_finite, two_sum,
 };
@@ -11,57 +13,41 @@ use super::{erf_data, erfc_data};
 
 const SIGN_MASK: u64 = 0x8000_0000_0000_0000;
 
-// Multiply exactly a and b, such that *hi + *lo = a * b.
-#[inline(always)]
-fn a_mul(hi: &mut f64, lo: &mut f64, a: f64, b: f64) {
-    *hi = a * b;
-    *lo = fma_internal(a, b, -*hi);
-}
-
 // Multiply a double with a double-double: a * (bh + bl).
 #[inline(always)]
 fn s_mul(hi: &mut f64, lo: &mut f64, a: f64, bh: f64, bl: f64) {
-    a_mul(hi, lo, a, bh);
+    *hi = twoprod(a, bh, lo);
     *lo = fma_internal(a, bl, *lo);
 }
 
 // Returns (ah + al) * (bh + bl) - (al * bl).
 #[inline(always)]
 fn d_mul(hi: &mut f64, lo: &mut f64, ah: f64, al: f64, bh: f64, bl: f64) {
-    a_mul(hi, lo, ah, bh);
+    *hi = twoprod(ah, bh, lo);
     *lo = fma_internal(ah, bl, *lo);
     *lo = fma_internal(al, bh, *lo);
 }
 
-// Add a + (bh + bl), assuming |a| >= |bh|.
-#[inline(always)]
-fn fast_sum(hi: &mut f64, lo: &mut f64, a: f64, bh: f64, bl: f64) {
-    *hi = fasttwosum(a, bh, lo);
-    *lo += bl;
-}
-
 // Assuming 0 <= z <= f64::from_bits(0x4017afb48dc96626), put in h+l an approximation of erf(z).
 // Return err: |(h + l)/erf(z) - 1| < err*|h+l|.
 fn cr_erf_fast(h: &mut f64, l: &mut f64, mut z: f64) -> f64 {
     if z < 0.0625 {
-        let mut z2h = 0.0;
         let mut z2l = 0.0;
-        a_mul(&mut z2h, &mut z2l, z, z);
+        let z2h = twoprod(z, z, &mut z2l);
         let z4 = z2h * z2h;
         let c9 = fma_internal(erf_data::C0[7], z2h, erf_data::C0[6]);
         let c5 = fma_internal(erf_data::C0[5], z2h, erf_data::C0[4]);
         let c5 = fma_internal(c9, z4, c5);
-        let mut th = 0.0;
         let mut tl = 0.0;
-        a_mul(&mut th, &mut tl, z2h, c5);
+        let mut th = twoprod(z2h, c5, &mut tl);
         *h = fasttwosum(erf_data::C0[2], th, l);
         *l += tl + erf_data::C0[3];
         let h_copy = *h;
-        a_mul(&mut th, &mut tl, z2h, *h);
+        th = twoprod(z2h, *h, &mut tl);
         tl += fma_internal(z2h, *l, erf_data::C0[1]);
         *h = fasttwosum(erf_data::C0[0], th, l);
         *l += fma_internal(z2l, h_copy, tl);
-        a_mul(h, &mut tl, *h, z);
+        *h = twoprod(*h, z, &mut tl);
         *l = fma_internal(*l, z, tl);
         return f64::from_bits(0x3ba7800000000000);
     }
@@ -85,16 +71,15 @@ fn cr_erf_fast(h: &mut f64, l: &mut f64, mut z: f64) -> f64 {
     let c3h1 = c3h;
     c3h = fasttwosum(c3h1, c7 * z4, &mut tl);
     c3l += tl;
-    let mut th = 0.0;
     let mut tl2 = 0.0;
-    a_mul(&mut th, &mut tl2, z, c3h);
+    let mut th = twoprod(z, c3h, &mut tl2);
     let mut c2l = 0.0;
     let c2h = fasttwosum(c[4], th, &mut c2l);
     c2l += fma_internal(z, c3l, tl2);
-    a_mul(&mut th, &mut tl2, z, c2h);
+    th = twoprod(z, c2h, &mut tl2);
     *h = fasttwosum(c[2], th, l);
     *l += tl2 + fma_internal(z, c2l, c[3]);
-    a_mul(&mut th, &mut tl2, z, *h);
+    th = twoprod(z, *h, &mut tl2);
     tl2 = fma_internal(z, *l, tl2);
     *h = fasttwosum(c[0], th, l);
     *l += tl2 + c[1];
@@ -122,7 +107,6 @@ fn cr_erf_accurate_tiny(h: &mut f64, l: &mut f64, z: f64, exceptions: bool) {
     }
 
     let z2 = z * z;
-    let mut th = 0.0;
     let mut tl = 0.0;
     *h = erf_data::P[21 / 2 + 4];
     for a in (13..=19).rev().step_by(2) {
@@ -130,22 +114,22 @@ fn cr_erf_accurate_tiny(h: &mut f64, l: &mut f64, z: f64, exceptions: bool) {
     }
     *l = 0.0;
     for a in (9..=11).rev().step_by(2) {
-        a_mul(&mut th, &mut tl, *h, z);
+        let th = twoprod(*h, z, &mut tl);
         tl = fma_internal(*l, z, tl);
-        a_mul(h, l, th, z);
+        *h = twoprod(th, z, l);
         *l = fma_internal(tl, z, *l);
         *h = fasttwosum(erf_data::P[a / 2 + 4], *h, &mut tl);
         *l += tl;
     }
     for a in (1..=7).rev().step_by(2) {
-        a_mul(&mut th, &mut tl, *h, z);
+        let th = twoprod(*h, z, &mut tl);
         tl = fma_internal(*l, z, tl);
-        a_mul(h, l, th, z);
+        *h = twoprod(th, z, l);
         *l = fma_internal(tl, z, *l);
         *h = fasttwosum(erf_data::P[a - 1], *h, &mut tl);
         *l += erf_data::P[a] + tl;
     }
-    a_mul(h, &mut tl, *h, z);
+    *h = twoprod(*h, z, &mut tl);
     *l = fma_internal(*l, z, tl);
 }
 
@@ -173,10 +157,9 @@ fn cr_erf_accurate(h: &mut f64, l: &mut f64, z: f64) {
         *h = fma_internal(*h, zz, p[8 + j]);
     }
     *l = 0.0;
-    let mut th = 0.0;
     let mut tl = 0.0;
     for j in (8..=10).rev() {
-        a_mul(&mut th, &mut tl, *h, zz);
+        let th = twoprod(*h, zz, &mut tl);
         tl = fma_internal(*l, zz, tl);
         let (sum, err) = two_sum(p[8 + j], th);
         *h = sum;
@@ -184,7 +167,7 @@ fn cr_erf_accurate(h: &mut f64, l: &mut f64, z: f64) {
         *l += tl;
     }
     for j in (0..=7).rev() {
-        a_mul(&mut th, &mut tl, *h, zz);
+        let th = twoprod(*h, zz, &mut tl);
         tl = fma_internal(*l, zz, tl);
         let (sum, err) = two_sum(p[2 * j], th);
         *h = sum;
@@ -195,6 +178,11 @@ fn cr_erf_accurate(h: &mut f64, l: &mut f64, z: f64) {
 
 #[inline(always)]
 pub fn erf(x: f64) -> f64 {
+    with_nearest(x, erf_nearest)
+}
+
+#[inline(always)]
+fn erf_nearest(x: f64) -> f64 {
     let z = x.abs();
     let ux = (z).to_bits();
     if ux > 0x4017_afb4_8dc9_6626u64 {
@@ -213,9 +201,8 @@ pub fn erf(x: f64) -> f64 {
         }
         let y = erf_data::C0[0] * x;
         let sx = x * f64::from_bits(0x4690000000000000);
-        let mut h = 0.0;
         let mut l = 0.0;
-        a_mul(&mut h, &mut l, erf_data::C0[0], sx);
+        let h = twoprod(erf_data::C0[0], sx, &mut l);
         l = fma_internal(erf_data::C0[1], sx, l);
         l += h - y * f64::from_bits(0x4690000000000000);
         return fma_internal(l, f64::from_bits(0x3950000000000000), y);
@@ -240,6 +227,151 @@ pub fn erf(x: f64) -> f64 {
     if x >= 0.0 { h + l } else { (-h) + (-l) }
 }
 
+/// erf(x) in each lane of `x`, bit-identical to [`erf`] lane by lane.
+///
+/// Both branches of the fast double-double approximation run in every lane
+/// (the table coefficients are gathered) and are blended together with the
+/// tiny-argument, saturated, infinite and NaN results under AVX-512 masks.
+/// Lanes whose fast result fails the rounding test are handed to [`erf`],
+/// which then takes its accurate path.
+///
+/// # Safety
+///
+/// The CPU must support AVX-512F.
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[target_feature(enable = "avx512f")]
+pub unsafe fn erf_avx512(x: core::arch::x86_64::__m512d) -> core::arch::x86_64::__m512d {
+    with_nearest(x, |x| unsafe { erf_avx512_nearest(x) })
+}
+
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[target_feature(enable = "avx512f")]
+unsafe fn erf_avx512_nearest(x: core::arch::x86_64::__m512d) -> core::arch::x86_64::__m512d {
+    use super::arch::avx512::{abs, fixup1, neg, sign, splat, splat_u64, xor};
+    use core::arch::x86_64::*;
+
+    let fma = |a, b, c| _mm512_fmadd_pd(a, b, c);
+    let add = |a, b| _mm512_add_pd(a, b);
+    let mul = |a, b| _mm512_mul_pd(a, b);
+    // fasttwosum: (x + y, the error term).
+    let fast2 = |x: __m512d, y: __m512d| {
+        let s = _mm512_add_pd(x, y);
+        (s, _mm512_sub_pd(y, _mm512_sub_pd(s, x)))
+    };
+    // twoprod: (a b, the error term).
+    let amul = |a: __m512d, b: __m512d| {
+        let p = _mm512_mul_pd(a, b);
+        (p, _mm512_fmsub_pd(a, b, p))
+    };
+    let c0 = |j: usize| splat(erf_data::C0[j]);
+
+    let z = abs(x);
+    let sx = sign(x);
+
+    // cr_erf_fast, z < 0.0625.
+    let (z2h, z2l) = amul(z, z);
+    let z4 = mul(z2h, z2h);
+    let c9 = fma(c0(7), z2h, c0(6));
+    let c5 = fma(c0(5), z2h, c0(4));
+    let c5 = fma(c9, z4, c5);
+    let (th, tl) = amul(z2h, c5);
+    let (h, l) = fast2(c0(2), th);
+    let l = add(l, add(tl, c0(3)));
+    let (th, tl) = amul(z2h, h);
+    let tl = add(tl, fma(z2h, l, c0(1)));
+    let (h2, l2) = fast2(c0(0), th);
+    let l2 = add(l2, fma(z2l, h, tl));
+    let (h2, tl) = amul(h2, z);
+    let l2 = fma(l2, z, tl);
+    let (h_small, l_small) = (h2, l2);
+
+    // cr_erf_fast, 0.0625 <= z <= 0x1.7afb48dc96626p+2, on the 13 gathered
+    // coefficients of C[i - 1]; other lanes use i = 1.
+    let in_table = _mm512_cmp_pd_mask::<_CMP_GE_OQ>(z, splat(0.0625))
+        & _mm512_cmp_pd_mask::<_CMP_LE_OQ>(z, splat(f64::from_bits(0x4017_afb4_8dc9_6626)));
+    let zt = _mm512_mask_blend_pd(in_table, splat(0.0625), z);
+    let sz = mul(splat(16.0), zt);
+    let v = _mm512_roundscale_pd::<{ _MM_FROUND_TO_NEG_INF | _MM_FROUND_NO_EXC }>(sz);
+    let i = _mm512_cvttpd_epi32(sz);
+    let base = _mm256_mullo_epi32(
+        _mm256_sub_epi32(i, _mm256_set1_epi32(1)),
+        _mm256_set1_epi32(13),
+    );
+    let table = erf_data::C.as_flattened();
+    // SAFETY: 1 <= i <= 94, so base + 12 indexes inside C.
+    let c = |j: usize| unsafe { _mm512_i32gather_pd::<8>(base, table[j..].as_ptr()) };
+    let zt = _mm512_sub_pd(_mm512_sub_pd(zt, splat(0.03125)), mul(splat(0.0625), v));
+    let z2 = mul(zt, zt);
+    let z4 = mul(z2, z2);
+    let c9 = fma(c(12), zt, c(11));
+    let c7 = fma(c(10), zt, c(9));
+    let c5 = fma(c(8), zt, c(7));
+    let (c3h, c3l) = fast2(c(5), mul(zt, c(6)));
+    let c7 = fma(c9, z2, c7);
+    let (c3h, tl) = fast2(c3h, mul(c5, z2));
+    let c3l = add(c3l, tl);
+    let (c3h, tl) = fast2(c3h, mul(c7, z4));
+    let c3l = add(c3l, tl);
+    let (th, tl2) = amul(zt, c3h);
+    let (c2h, c2l) = fast2(c(4), th);
+    let c2l = add(c2l, fma(zt, c3l, tl2));
+    let (th, tl2) = amul(zt, c2h);
+    let (h, l) = fast2(c(2), th);
+    let l = add(l, add(tl2, fma(zt, c2l, c(3))));
+    let (th, tl2) = amul(zt, h);
+    let tl2 = fma(zt, l, tl2);
+    let (h, l) = fast2(c(0), th);
+    let l = add(l, add(tl2, c(1)));
+
+    let small = _mm512_cmp_pd_mask::<_CMP_LT_OQ>(z, splat(0.0625));
+    let h = _mm512_mask_blend_pd(small, h, h_small);
+    let l = _mm512_mask_blend_pd(small, l, l_small);
+    let err = _mm512_mask_blend_pd(
+        small,
+        splat(f64::from_bits(0x3ba1100000000000)),
+        splat(f64::from_bits(0x3ba7800000000000)),
+    );
+    let uf = xor(h, sx);
+    let vf = xor(l, sx);
+    let left = add(uf, fma(err, neg(uf), vf));
+    let right = add(uf, fma(err, uf, vf));
+    let fast = _mm512_cmp_pd_mask::<_CMP_EQ_OQ>(left, right);
+
+    // z < 2^-61: C0[0] x with the product's error scaled out of the
+    // subnormal range.
+    let y = mul(c0(0), x);
+    let sxx = mul(x, splat(f64::from_bits(0x4690000000000000)));
+    let (th, tl) = amul(c0(0), sxx);
+    let tl = fma(c0(1), sxx, tl);
+    let tl = add(
+        tl,
+        _mm512_sub_pd(th, mul(y, splat(f64::from_bits(0x4690000000000000)))),
+    );
+    let tiny = fma(tl, splat(f64::from_bits(0x3950000000000000)), y);
+    let tiny = _mm512_mask_blend_pd(
+        _mm512_cmp_pd_mask::<_CMP_EQ_OQ>(x, _mm512_setzero_pd()),
+        tiny,
+        x,
+    );
+
+    // |x| beyond the threshold: +-1 rounded from below, +-1, or x + x.
+    let os = _mm512_castsi512_pd(_mm512_or_si512(sx, _mm512_castpd_si512(splat(1.0))));
+    let sat = _mm512_sub_pd(os, mul(splat(f64::from_bits(0x3c90000000000000)), os));
+    let sat = _mm512_mask_blend_pd(
+        _mm512_cmp_pd_mask::<_CMP_EQ_OQ>(z, splat(f64::INFINITY)),
+        sat,
+        os,
+    );
+    let sat = _mm512_mask_blend_pd(_mm512_cmp_pd_mask::<_CMP_UNORD_Q>(x, x), sat, add(x, x));
+
+    let uz = _mm512_castpd_si512(z);
+    let big = _mm512_cmpgt_epu64_mask(uz, splat_u64(0x4017_afb4_8dc9_6626));
+    let is_tiny = _mm512_cmplt_epu64_mask(uz, splat_u64(0x3c20_0000_0000_0000));
+    let res = _mm512_mask_blend_pd(is_tiny, left, tiny);
+    let res = _mm512_mask_blend_pd(big, res, sat);
+    fixup1(res, !(fast | big | is_tiny), x, erf)
+}
+
 // Approximation for exp(x) with x = xh + xl, small magnitude.
 #[inline(always)]
 fn q_1(hi: &mut f64, lo: &mut f64, zh: f64, zl: f64) {
@@ -248,12 +380,12 @@ fn q_1(hi: &mut f64, lo: &mut f64, zh: f64, zl: f64) {
     q = fma_internal(q, z, erf_data::Q_1[2]);
     *hi = fasttwosum(erf_data::Q_1[1], q * z, lo);
     d_mul(hi, lo, zh, zl, *hi, *lo);
-    fast_sum(hi, lo, erf_data::Q_1[0], *hi, *lo);
+    *hi = fastsum(erf_data::Q_1[0], 0.0, *hi, *lo, lo);
 }
 
 // Approximation of exp(x) where x = xh + xl.
 #[inline(always)]
-fn exp_1(hi: &mut f64, lo: &mut f64, xh: f64, xl: f64) {
+pub(crate) fn exp_1(hi: &mut f64, lo: &mut f64, xh: f64, xl: f64) {
     const INVLOG2: f64 = f64::from_bits(0x40b71547652b82fe);
     let k = roundeven_finite(xh * INVLOG2);
     const LOG2H: f64 = f64::from_bits(0x3f262e42fefa39ef);
@@ -301,19 +433,19 @@ fn exp_accurate(h: &mut f64, l: &mut f64, e: &mut i32, xh: f64, xl: f64) {
     for i in (16..=18).rev() {
         *h = fma_internal(*h, yh, erfc_data::E2[i + 8]);
     }
-    a_mul(&mut th, &mut tl, *h, yh);
+    th = twoprod(*h, yh, &mut tl);
     tl = fma_internal(*h, yl, tl);
     *h = fasttwosum(erfc_data::E2[15 + 8], th, l);
     *l += tl;
     for i in (8..=14).rev() {
-        a_mul(&mut th, &mut tl, *h, yh);
+        th = twoprod(*h, yh, &mut tl);
         tl = fma_internal(*h, yl, tl);
         tl = fma_internal(*l, yh, tl);
         *h = fasttwosum(erfc_data::E2[i + 8], th, l);
         *l += tl;
     }
     for i in (0..=7).rev() {
-        a_mul(&mut th, &mut tl, *h, yh);
+        th = twoprod(*h, yh, &mut tl);
         tl = fma_internal(*h, yl, tl);
         tl = fma_internal(*l, yh, tl);
         *h = fasttwosum(erfc_data::E2[2 * i], th, l);
@@ -332,9 +464,8 @@ fn erfc_asympt_fast(h: &mut f64, l: &mut f64, x: f64) -> f64 {
     }
     let mut eh = 0.0;
     let mut el = 0.0;
-    let mut uh = 0.0;
     let mut ul = 0.0;
-    a_mul(&mut uh, &mut ul, x, x);
+    let mut uh = twoprod(x, x, &mut ul);
     exp_1(&mut eh, &mut el, -uh, -ul);
     let yh = 1.0 / x;
     let yl = yh * fma_internal(-x, yh, 1.0);
@@ -351,7 +482,7 @@ fn erfc_asympt_fast(h: &mut f64, l: &mut f64, x: f64) -> f64 {
         i += 1;
     }
     let p = &erfc_data::T[i];
-    a_mul(&mut uh, &mut ul, yh, yh);
+    uh = twoprod(yh, yh, &mut ul);
     ul = fma_internal(2.0 * yh, yl, ul);
     let mut zh = p[12];
     zh = fma_internal(zh, uh, p[11]);
@@ -417,13 +548,11 @@ fn erfc_asympt_accurate(x: f64) -> f64 {
             f64::from_bits(0x000667bd620fd95b),
         );
     }
-    let mut h = 0.0;
     let mut l = 0.0;
     let mut eh = 0.0;
     let mut el = 0.0;
-    let mut uh = 0.0;
     let mut ul = 0.0;
-    a_mul(&mut uh, &mut ul, x, x);
+    let mut uh = twoprod(x, x, &mut ul);
     let mut e = 0i32;
     exp_accurate(&mut eh, &mut el, &mut e, -uh, -ul);
     let yh = 1.0 / x;
@@ -445,16 +574,15 @@ fn erfc_asympt_accurate(x: f64) -> f64 {
         i += 1;
     }
     let p = &erfc_data::TACC[i];
-    a_mul(&mut uh, &mut ul, yh, yh);
+    uh = twoprod(yh, yh, &mut ul);
     ul = fma_internal(2.0 * yh, yl, ul);
     let mut zh = p[14 + 6 + i];
     let mut zl = 0.0;
-    let mut th = 0.0;
     let mut tl = 0.0;
     let top = 27 + 2 * i;
     let mut j = top as i32;
     while j >= 13 {
-        a_mul(&mut th, &mut tl, zh, uh);
+        let th = twoprod(zh, uh, &mut tl);
         tl = fma_internal(zh, ul, tl);
         tl = fma_internal(zl, uh, tl);
         let (sum, err) = two_sum(p[((j - 1) / 2) as usize + 6], th);
@@ -465,7 +593,7 @@ fn erfc_asympt_accurate(x: f64) -> f64 {
     }
     let mut j2 = 11;
     while j2 >= 1 {
-        a_mul(&mut th, &mut tl, zh, uh);
+        let th = twoprod(zh, uh, &mut tl);
         tl = fma_internal(zh, ul, tl);
         tl = fma_internal(zl, uh, tl);
         let (sum, err) = two_sum(p[(j2 - 1) as usize], th);
@@ -474,13 +602,13 @@ fn erfc_asympt_accurate(x: f64) -> f64 {
         zl += tl + p[j2 as usize];
         j2 -= 2;
     }
-    a_mul(&mut uh, &mut ul, zh, yh);
+    uh = twoprod(zh, yh, &mut ul);
     ul = fma_internal(zh, yl, ul);
     ul = fma_internal(zl, yh, ul);
     let uh0 = uh;
     let ul0 = ul;
     uh = fasttwosum(uh0, ul0, &mut ul);
-    a_mul(&mut h, &mut l, uh, eh);
+    let h = twoprod(uh, eh, &mut l);
     l = fma_internal(uh, el, l);
     l = fma_internal(ul, eh, l);
     let mut res = ldexp(h + l, e);
@@ -527,6 +655,11 @@ fn cr_erfc_accurate(x: f64) -> f64 {
 
 #[inline(always)]
 pub fn erfc(x: f64) -> f64 {
+    with_nearest(x, erfc_nearest)
+}
+
+#[inline(always)]
+fn erfc_nearest(x: f64) -> f64 {
     let t = (x).to_bits();
     let at = t & 0x7fff_ffff_ffff_ffffu64;
     if t >= 0x8000_0000_0000_0000u64 {
@@ -4,7 +4,7 @@
 //! The small remainder r is approximated with a minimax polynomial (degree 5).
 //! Constants and tables are derived from glibc/core-math sources.
 
-use super::{f64_from_bits, f64_to_bits, fma_internal, is_inf_bits, is_nan_bits};
+use super::{f64_from_bits, f64_to_bits, fma_internal, is_inf_bits, is_nan_bits, with_nearest};
 
 // ========= glibc-derived exp table (N=128) =========
 
@@ -533,8 +533,251 @@ pub(crate) unsafe fn exp_with_tail_fma(x: f64, xtail: f64) -> f64 {
     unsafe { fma_f64(scale, tmp, scale) }
 }
 
+/// e^x in each lane of `x`, bit-identical to [`exp`] lane by lane.
+///
+/// Table lookups are gathers; lanes outside the range where the scalar
+/// reaches its final FMA (overflow, underflow, NaN, infinities) are handed to
+/// [`exp`].
+///
+/// # Safety
+///
+/// The CPU must support AVX2 and FMA.
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[target_feature(enable = "avx2,fma")]
+pub unsafe fn exp_avx2(x: core::arch::x86_64::__m256d) -> core::arch::x86_64::__m256d {
+    with_nearest(x, |x| unsafe { exp_avx2_nearest(x) })
+}
+
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[target_feature(enable = "avx2,fma")]
+unsafe fn exp_avx2_nearest(x: core::arch::x86_64::__m256d) -> core::arch::x86_64::__m256d {
+    use super::arch::avx2::{fixup1, gather_u64, splat, splat_u64};
+    use core::arch::x86_64::*;
+
+    let z = _mm256_mul_pd(x, splat(INV_LN2_N));
+    let kd = _mm256_add_pd(z, splat(SHIFT));
+    let ki = _mm256_castpd_si256(kd);
+    let kd = _mm256_sub_pd(kd, splat(SHIFT));
+    let r = _mm256_fmadd_pd(kd, splat(NEG_LN2_HI_N), x);
+    let r = _mm256_fmadd_pd(kd, splat(NEG_LN2_LO_N), r);
+
+    let idx = _mm256_slli_epi64::<1>(_mm256_and_si256(ki, splat_u64(N - 1)));
+    let top = _mm256_slli_epi64::<{ 52 - EXP_TABLE_BITS as i32 }>(ki);
+    // idx is masked by N and doubled, so idx+1 is in-bounds.
+    let tail = _mm256_castsi256_pd(unsafe { gather_u64(&EXP_TAB_U64, idx) });
+    let sbits = unsafe { gather_u64(&EXP_TAB_U64[1..], idx) };
+    let scale = _mm256_castsi256_pd(_mm256_add_epi64(sbits, top));
+
+    let r2 = _mm256_mul_pd(r, r);
+    let p1 = _mm256_fmadd_pd(r, splat(EXP_C3), splat(EXP_C2));
+    let p2 = _mm256_fmadd_pd(r, splat(EXP_C5), splat(EXP_C4));
+    let tmp = _mm256_fmadd_pd(r2, p1, _mm256_add_pd(tail, r));
+    let tmp = _mm256_fmadd_pd(_mm256_mul_pd(r2, r2), p2, tmp);
+    let y = _mm256_fmadd_pd(scale, tmp, scale);
+
+    // The scalar k_adj test, (k + 1023 N) outside [N, 2047 N), on kd.
+    let fast = _mm256_and_pd(
+        _mm256_and_pd(
+            _mm256_cmp_pd::<_CMP_LE_OQ>(x, splat(EXP_HI)),
+            _mm256_cmp_pd::<_CMP_GE_OQ>(x, splat(EXP_LO)),
+        ),
+        _mm256_and_pd(
+            _mm256_cmp_pd::<_CMP_GE_OQ>(kd, splat((N as f64) * (1.0 - 1023.0))),
+            _mm256_cmp_pd::<_CMP_LT_OQ>(kd, splat((N as f64) * 1024.0)),
+        ),
+    );
+    let slow = _mm256_xor_pd(fast, _mm256_castsi256_pd(_mm256_set1_epi64x(-1)));
+    fixup1(y, slow, x, exp)
+}
+
+// exp_with_tail_fma lane by lane, with the mask of lanes that reach its
+// final FMA; the other lanes of the value are unspecified.
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[inline]
+#[target_feature(enable = "avx2,fma")]
+pub(crate) fn exp_with_tail_avx2(
+    x: core::arch::x86_64::__m256d,
+    xtail: core::arch::x86_64::__m256d,
+) -> (core::arch::x86_64::__m256d, core::arch::x86_64::__m256d) {
+    use super::arch::avx2::{gather_u64, splat, splat_u64};
+    use core::arch::x86_64::*;
+
+    let z = _mm256_mul_pd(x, splat(INV_LN2_N));
+    let kd = _mm256_add_pd(z, splat(SHIFT));
+    let ki = _mm256_castpd_si256(kd);
+    let kd = _mm256_sub_pd(kd, splat(SHIFT));
+    let r = _mm256_fmadd_pd(kd, splat(NEG_LN2_HI_N), x);
+    let r = _mm256_fmadd_pd(kd, splat(NEG_LN2_LO_N), r);
+    let r = _mm256_add_pd(r, xtail);
+
+    let idx = _mm256_slli_epi64::<1>(_mm256_and_si256(ki, splat_u64(N - 1)));
+    let top = _mm256_slli_epi64::<{ 52 - EXP_TABLE_BITS as i32 }>(ki);
+    // idx is masked by N and doubled, so idx+1 is in-bounds.
+    let tail = _mm256_castsi256_pd(unsafe { gather_u64(&EXP_TAB_U64, idx) });
+    let sbits = unsafe { gather_u64(&EXP_TAB_U64[1..], idx) };
+    let scale = _mm256_castsi256_pd(_mm256_add_epi64(sbits, top));
+
+    let r2 = _mm256_mul_pd(r, r);
+    let p1 = _mm256_fmadd_pd(r, splat(EXP_C3), splat(EXP_C2));
+    let p2 = _mm256_fmadd_pd(r, splat(EXP_C5), splat(EXP_C4));
+    let tmp = _mm256_fmadd_pd(r2, p1, _mm256_add_pd(tail, r));
+    let tmp = _mm256_fmadd_pd(_mm256_mul_pd(r2, r2), p2, tmp);
+    let y = _mm256_fmadd_pd(scale, tmp, scale);
+
+    // As in exp_avx2, plus a NaN tail sending the lane to the scalar.
+    let fast = _mm256_and_pd(
+        _mm256_and_pd(
+            _mm256_cmp_pd::<_CMP_LE_OQ>(x, splat(EXP_HI)),
+            _mm256_cmp_pd::<_CMP_GE_OQ>(x, splat(EXP_LO)),
+        ),
+        _mm256_and_pd(
+            _mm256_cmp_pd::<_CMP_GE_OQ>(kd, splat((N as f64) * (1.0 - 1023.0))),
+            _mm256_cmp_pd::<_CMP_LT_OQ>(kd, splat((N as f64) * 1024.0)),
+        ),
+    );
+    let fast = _mm256_and_pd(fast, _mm256_cmp_pd::<_CMP_ORD_Q>(xtail, xtail));
+    (y, fast)
+}
+
+// exp_fma / exp_with_tail_fma for x in [EXP_LO, EXP_HI], including the
+// specialcase_fma scaling near the overflow and underflow thresholds.
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[inline]
+#[target_feature(enable = "avx512f")]
+fn exp_core_avx512(
+    x: core::arch::x86_64::__m512d,
+    xtail: Option<core::arch::x86_64::__m512d>,
+) -> core::arch::x86_64::__m512d {
+    use super::arch::avx512::{gather_u64, splat, splat_u64};
+    use core::arch::x86_64::*;
+
+    let z = _mm512_mul_pd(x, splat(INV_LN2_N));
+    let kd = _mm512_add_pd(z, splat(SHIFT));
+    let ki = _mm512_castpd_si512(kd);
+    let kd = _mm512_sub_pd(kd, splat(SHIFT));
+    let r = _mm512_fmadd_pd(kd, splat(NEG_LN2_HI_N), x);
+    let mut r = _mm512_fmadd_pd(kd, splat(NEG_LN2_LO_N), r);
+    if let Some(xtail) = xtail {
+        r = _mm512_add_pd(r, xtail);
+    }
+
+    let idx = _mm512_slli_epi64::<1>(_mm512_and_si512(ki, splat_u64(N - 1)));
+    let top = _mm512_slli_epi64::<{ 52 - EXP_TABLE_BITS }>(ki);
+    // idx is masked by N and doubled, so idx+1 is in-bounds.
+    let tail = _mm512_castsi512_pd(unsafe { gather_u64(&EXP_TAB_U64, idx) });
+    let sbits = _mm512_add_epi64(unsafe { gather_u64(&EXP_TAB_U64[1..], idx) }, top);
+    let scale = _mm512_castsi512_pd(sbits);
+
+    let r2 = _mm512_mul_pd(r, r);
+    let p1 = _mm512_fmadd_pd(r, splat(EXP_C3), splat(EXP_C2));
+    let p2 = _mm512_fmadd_pd(r, splat(EXP_C5), splat(EXP_C4));
+    let tmp = _mm512_fmadd_pd(r2, p1, _mm512_add_pd(tail, r));
+    let tmp = _mm512_fmadd_pd(_mm512_mul_pd(r2, r2), p2, tmp);
+    let y = _mm512_fmadd_pd(scale, tmp, scale);
+
+    // specialcase_fma, both signs of k.
+    let s = _mm512_castsi512_pd(_mm512_sub_epi64(sbits, splat_u64(1009u64 << 52)));
+    let big = _mm512_mul_pd(
+        splat(f64::from_bits(0x7f00_0000_0000_0000)),
+        _mm512_fmadd_pd(s, tmp, s),
+    );
+    let s = _mm512_castsi512_pd(_mm512_add_epi64(sbits, splat_u64(1022u64 << 52)));
+    let t = _mm512_fmadd_pd(s, tmp, s);
+    let lo = _mm512_fmadd_pd(s, tmp, _mm512_sub_pd(s, t));
+    let hi = _mm512_add_pd(splat(1.0), t);
+    let lo = _mm512_add_pd(_mm512_add_pd(_mm512_sub_pd(splat(1.0), hi), t), lo);
+    let u = _mm512_sub_pd(_mm512_add_pd(hi, lo), splat(1.0));
+    // A zero from the rounded sum is +0.
+    let u = _mm512_mask_blend_pd(
+        _mm512_cmp_pd_mask::<_CMP_EQ_OQ>(u, _mm512_setzero_pd()),
+        u,
+        _mm512_setzero_pd(),
+    );
+    let t = _mm512_mask_blend_pd(_mm512_cmp_pd_mask::<_CMP_LT_OQ>(t, splat(1.0)), t, u);
+    let small = _mm512_mul_pd(splat(f64::from_bits(0x0010_0000_0000_0000)), t);
+    let special = _mm512_mask_blend_pd(
+        _mm512_cmp_pd_mask::<_CMP_GT_OQ>(kd, _mm512_setzero_pd()),
+        small,
+        big,
+    );
+
+    // The scalar k_adj test, (k + 1023 N) outside [N, 2047 N), on kd.
+    let inside = _mm512_cmp_pd_mask::<_CMP_GE_OQ>(kd, splat((N as f64) * (1.0 - 1023.0)))
+        & _mm512_cmp_pd_mask::<_CMP_LT_OQ>(kd, splat((N as f64) * 1024.0));
+    _mm512_mask_blend_pd(inside, special, y)
+}
+
+// exp_with_tail_fma lane by lane.
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[inline]
+#[target_feature(enable = "avx512f")]
+pub(crate) fn exp_with_tail_avx512(
+    x: core::arch::x86_64::__m512d,
+    xtail: core::arch::x86_64::__m512d,
+) -> core::arch::x86_64::__m512d {
+    use super::arch::avx512::splat;
+    use core::arch::x86_64::*;
+
+    let y = exp_core_avx512(x, Some(xtail));
+    let y = _mm512_mask_blend_pd(
+        _mm512_cmp_pd_mask::<_CMP_LT_OQ>(x, splat(EXP_LO)),
+        y,
+        _mm512_setzero_pd(),
+    );
+    let y = _mm512_mask_blend_pd(
+        _mm512_cmp_pd_mask::<_CMP_GT_OQ>(x, splat(EXP_HI)),
+        y,
+        splat(f64::INFINITY),
+    );
+    let nan = _mm512_cmp_pd_mask::<_CMP_UNORD_Q>(x, xtail);
+    _mm512_mask_blend_pd(nan, y, splat(f64::NAN))
+}
+
+/// e^x in each lane of `x`, bit-identical to [`exp`] lane by lane.
+///
+/// Entirely branch-free: overflow, underflow, the subnormal range, NaNs and
+/// infinities are computed alongside the main path and blended in under
+/// AVX-512 masks.
+///
+/// # Safety
+///
+/// The CPU must support AVX-512F.
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[target_feature(enable = "avx512f")]
+pub unsafe fn exp_avx512(x: core::arch::x86_64::__m512d) -> core::arch::x86_64::__m512d {
+    with_nearest(x, |x| unsafe { exp_avx512_nearest(x) })
+}
+
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[target_feature(enable = "avx512f")]
+unsafe fn exp_avx512_nearest(x: core::arch::x86_64::__m512d) -> core::arch::x86_64::__m512d {
+    use super::arch::avx512::splat;
+    use core::arch::x86_64::*;
+
+    let y = exp_core_avx512(x, None);
+    let y = _mm512_mask_blend_pd(
+        _mm512_cmp_pd_mask::<_CMP_LT_OQ>(x, splat(EXP_LO)),
+        y,
+        _mm512_setzero_pd(),
+    );
+    let y = _mm512_mask_blend_pd(
+        _mm512_cmp_pd_mask::<_CMP_GT_OQ>(x, splat(EXP_HI)),
+        y,
+        splat(f64::INFINITY),
+    );
+    _mm512_mask_blend_pd(_mm512_cmp_pd_mask::<_CMP_UNORD_Q>(x, x), y, x)
+}
+
 #[inline(always)]
 pub fn exp(x: f64) -> f64 {
+    with_nearest(x, exp_nearest)
+}
+
+#[inline(always)]
+#[allow(unreachable_code)]
+fn exp_nearest(x: f64) -> f64 {
+    #[cfg(all(target_arch = "x86_64", feature = "runtime-dispatch"))]
+    return super::dispatch::select!(exp_fma, exp_generic; fn(x: f64) -> f64)(x);
     #[cfg(target_arch = "x86_64")]
     if super::fma_available() {
         // SAFETY: guarded by compile-time FMA availability.
@@ -544,7 +787,12 @@ pub fn exp(x: f64) -> f64 {
 }
 
 #[inline(always)]
+#[allow(unreachable_code)]
 pub(crate) fn exp_with_tail(x: f64, xtail: f64) -> f64 {
+    #[cfg(all(target_arch = "x86_64", feature = "runtime-dispatch"))]
+    return super::dispatch::select!(exp_with_tail_fma, exp_with_tail_generic; fn(x: f64, xtail: f64) -> f64)(
+        x, xtail,
+    );
     #[cfg(target_arch = "x86_64")]
     if super::fma_available() {
         // SAFETY: guarded by compile-time FMA availability.
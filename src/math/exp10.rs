@@ -4,7 +4,7 @@
 //! to limit error; reuses exp2 polynomial/table core for speed and accuracy.
 
 use super::exp::EXP_TAB_U64;
-use super::{f64_from_bits, f64_to_bits};
+use super::{f64_from_bits, f64_to_bits, with_nearest};
 
 const EXP_TABLE_BITS: u32 = 7;
 const N: u64 = 1u64 << EXP_TABLE_BITS;
@@ -50,6 +50,11 @@ fn specialcase(tmp: f64, sbits: u64, k: i64) -> f64 {
 
 #[inline(always)]
 pub fn exp10(x: f64) -> f64 {
+    with_nearest(x, exp10_nearest)
+}
+
+#[inline(always)]
+fn exp10_nearest(x: f64) -> f64 {
     if !x.is_finite() {
         return if x.is_nan() {
             f64::NAN
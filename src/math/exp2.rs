@@ -4,7 +4,7 @@
 //! and a polynomial for 2^r on a small interval. Handles overflow/underflow
 //! boundaries explicitly; constants sourced from glibc/core-math.
 
-use super::{exp::EXP_TAB_U64, f64_from_bits, f64_to_bits};
+use super::{exp::EXP_TAB_U64, f64_from_bits, f64_to_bits, with_nearest};
 
 const EXP_TABLE_BITS: u32 = 7;
 const N: u64 = 1u64 << EXP_TABLE_BITS;
@@ -228,8 +228,66 @@ unsafe fn exp2_with_tail_fma(x: f64, xtail: f64) -> f64 {
     unsafe { fma_f64(scale, tmp, scale) }
 }
 
+/// 2^x in each lane of `x`, bit-identical to [`exp2`] lane by lane.
+///
+/// Lanes whose result leaves the normal range, NaNs and infinities are handed
+/// to [`exp2`].
+///
+/// # Safety
+///
+/// The CPU must support AVX2 and FMA.
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[target_feature(enable = "avx2,fma")]
+pub unsafe fn exp2_avx2(x: core::arch::x86_64::__m256d) -> core::arch::x86_64::__m256d {
+    with_nearest(x, |x| unsafe { exp2_avx2_nearest(x) })
+}
+
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[target_feature(enable = "avx2,fma")]
+unsafe fn exp2_avx2_nearest(x: core::arch::x86_64::__m256d) -> core::arch::x86_64::__m256d {
+    use super::arch::avx2::{fixup1, gather_u64, splat, splat_u64};
+    use core::arch::x86_64::*;
+
+    let kd = _mm256_add_pd(x, splat(EXP2_SHIFT));
+    let ki = _mm256_castpd_si256(kd);
+    let kd = _mm256_sub_pd(kd, splat(EXP2_SHIFT));
+    let r = _mm256_sub_pd(x, kd);
+
+    let idx = _mm256_slli_epi64::<1>(_mm256_and_si256(ki, splat_u64(N - 1)));
+    let top = _mm256_slli_epi64::<{ 52 - EXP_TABLE_BITS as i32 }>(ki);
+    let tail = _mm256_castsi256_pd(unsafe { gather_u64(&EXP_TAB_U64, idx) });
+    let sbits = unsafe { gather_u64(&EXP_TAB_U64[1..], idx) };
+    let scale = _mm256_castsi256_pd(_mm256_add_epi64(sbits, top));
+
+    let r2 = _mm256_mul_pd(r, r);
+    let t1 = _mm256_fmadd_pd(r, splat(C1), tail);
+    let t2 = _mm256_fmadd_pd(r, splat(C3), splat(C2));
+    let t3 = _mm256_fmadd_pd(r, splat(C5), splat(C4));
+    let tmp = _mm256_fmadd_pd(r2, t2, t1);
+    let tmp = _mm256_fmadd_pd(_mm256_mul_pd(r2, r2), t3, tmp);
+    let y = _mm256_fmadd_pd(scale, tmp, scale);
+
+    // The scalar k_adj test on k = kd N; it also rejects NaN, the
+    // infinities and |x| beyond the early returns.
+    let k = _mm256_mul_pd(kd, splat(N as f64));
+    let fast = _mm256_and_pd(
+        _mm256_cmp_pd::<_CMP_GE_OQ>(k, splat((N as f64) * (1.0 - 1023.0))),
+        _mm256_cmp_pd::<_CMP_LT_OQ>(k, splat((N as f64) * 1024.0)),
+    );
+    let slow = _mm256_xor_pd(fast, _mm256_castsi256_pd(_mm256_set1_epi64x(-1)));
+    fixup1(y, slow, x, exp2)
+}
+
 #[inline]
 pub fn exp2(x: f64) -> f64 {
+    with_nearest(x, exp2_nearest)
+}
+
+#[inline]
+#[allow(unreachable_code)]
+fn exp2_nearest(x: f64) -> f64 {
+    #[cfg(all(target_arch = "x86_64", feature = "runtime-dispatch"))]
+    return super::dispatch::select!(exp2_fma, exp2_generic; fn(x: f64) -> f64)(x);
     #[cfg(target_arch = "x86_64")]
     if super::fma_available() {
         // SAFETY: guarded by compile-time FMA availability.
@@ -239,7 +297,12 @@ pub fn exp2(x: f64) -> f64 {
 }
 
 #[inline]
+#[allow(unreachable_code)]
 pub(crate) fn exp2_with_tail(x: f64, xtail: f64) -> f64 {
+    #[cfg(all(target_arch = "x86_64", feature = "runtime-dispatch"))]
+    return super::dispatch::select!(exp2_with_tail_fma, exp2_with_tail_generic; fn(x: f64, xtail: f64) -> f64)(
+        x, xtail,
+    );
     #[cfg(target_arch = "x86_64")]
     if super::fma_available() {
         // SAFETY: guarded by compile-time FMA availability.
@@ -0,0 +1,343 @@
+//! Exponential integrals E1(x), Ei(x) and En(x).
+//!
+//! Small arguments use the convergent power series in double-double, larger
+//! E_n arguments a backward-evaluated continued fraction, and large Ei
+//! arguments the asymptotic expansion. Ei is expanded around its positive zero
+//! so relative accuracy holds there. The final exponential is applied as
+//! exp(h + l) in a single rounding step; double-double helpers come from the
+//! gamma.rs (ddcoremath) port.
+
+use super::double::{adddd, divdd, divddd, muldd_acc, mulddd, twosum};
+use super::exp::exp_with_tail;
+use super::fasttwosum;
+use super::log::ln_dd;
+use super::with_nearest;
+
+// Euler-Mascheroni constant as a double-double.
+const EULER_HI: f64 = f64::from_bits(0x3fe2_788c_fc6f_b619);
+const EULER_LO: f64 = f64::from_bits(0xbc56_cb90_701f_bfab);
+
+// Positive zero of Ei: x0 = 0x1.7d72952b4b5fcp-2 + 0x1.e4c986021c6f2p-57.
+const EI_ZERO_HI: f64 = f64::from_bits(0x3fd7_d729_52b4_b5fc);
+const EI_ZERO_LO: f64 = f64::from_bits(0x3c6e_4c98_6021_c6f2);
+const EI_ZERO_WIDTH: f64 = 0.031_25;
+
+// Taylor coefficients of Ei around x0: c1 and c2 as double-doubles, then
+// c3..c20 as doubles (|x - x0| <= 2^-5 keeps the truncation below 2^-60).
+const EI_ZERO_C1: [f64; 2] = [
+    f64::from_bits(0x400f_2b73_279a_55db),
+    f64::from_bits(0x3c92_e482_5482_cff3),
+];
+const EI_ZERO_C2: [f64; 2] = [
+    f64::from_bits(0xc00a_40bb_a003_d345),
+    f64::from_bits(0xbca8_6563_87ab_2dae),
+];
+const EI_ZERO_C: [f64; 18] = [
+    f64::from_bits(0x401a_16e9_c5b3_7b9f),
+    f64::from_bits(0xc029_f07c_2cea_9cba),
+    f64::from_bits(0x403b_e2e4_6c5d_58f7),
+    f64::from_bits(0xc04f_307c_9189_2eea),
+    f64::from_bits(0x4061_f11e_4cf7_52d6),
+    f64::from_bits(0xc075_127e_44a3_c8bc),
+    f64::from_bits(0x4089_2441_3552_0fe9),
+    f64::from_bits(0xc09e_5f2f_c3e5_83b4),
+    f64::from_bits(0x40b2_87c4_01aa_2e9c),
+    f64::from_bits(0xc0c6_ccbb_6410_044d),
+    f64::from_bits(0x40dc_3fbd_23b9_68bd),
+    f64::from_bits(0xc0f1_9abf_2246_dfa5),
+    f64::from_bits(0x4106_0dec_77fd_b713),
+    f64::from_bits(0xc11b_c0a0_e01b_62a2),
+    f64::from_bits(0x4131_87a2_8bfc_3f69),
+    f64::from_bits(0xc146_38e8_d4f9_1072),
+    f64::from_bits(0x415c_4213_3d4f_e1b2),
+    f64::from_bits(0xc172_043c_f001_9011),
+];
+
+// Below this the power series is used for E_n; above it the continued fraction.
+const SERIES_LIMIT: f64 = 2.0;
+// Above this Ei switches from the power series to the asymptotic expansion.
+const EI_ASYM_LIMIT: f64 = 60.0;
+// Relative size at which a series term no longer affects a double-double sum.
+const SERIES_EPS: f64 = f64::from_bits(0x3910_0000_0000_0000); // 2^-110
+
+// exp(-x) / (dh + dl), evaluated as exp(-x - ln(d)) so that results in the
+// subnormal range are rounded only once.
+#[inline(always)]
+fn exp_neg_over(x: f64, dh: f64, dl: f64) -> f64 {
+    let (lh, ll) = ln_dd(dh);
+    let mut e = 0.0;
+    let h = twosum(-x, -lh, &mut e);
+    let mut l = 0.0;
+    let h = fasttwosum(h, e - ll - dl / dh, &mut l);
+    exp_with_tail(h, l)
+}
+
+// sum_{k>=1} s^(k+1) x^k / (k k!) for s = +-1, in double-double.
+#[inline(always)]
+fn ei_series_sum(x: f64, alternating: bool, l: &mut f64) -> f64 {
+    let mut th = 1.0;
+    let mut tl = 0.0;
+    let mut sh = 0.0;
+    let mut sl = 0.0;
+    let mut k = 1.0;
+    loop {
+        th = mulddd(th, tl, x, &mut tl);
+        th = divddd(th, tl, k, &mut tl);
+        let mut ul = 0.0;
+        let mut uh = divddd(th, tl, k, &mut ul);
+        if alternating && (k as u32) & 1 == 0 {
+            uh = -uh;
+            ul = -ul;
+        }
+        let mut e = 0.0;
+        sh = adddd(sh, sl, uh, ul, &mut e);
+        sh = fasttwosum(sh, e, &mut sl);
+        if uh.abs() <= SERIES_EPS * sh.abs() {
+            break;
+        }
+        k += 1.0;
+    }
+    *l = sl;
+    sh
+}
+
+// E1(x) = -gamma - ln(x) - sum_{k>=1} (-x)^k / (k k!) for 0 < x <= 2.
+#[inline(always)]
+fn e1_series(x: f64) -> f64 {
+    let mut sl = 0.0;
+    let sh = ei_series_sum(x, true, &mut sl);
+    let (lh, ll) = ln_dd(x);
+    let mut rl = 0.0;
+    let rh = adddd(sh, sl, -EULER_HI, -EULER_LO, &mut rl);
+    let mut e = 0.0;
+    let rh = adddd(rh, rl, -lh, -ll, &mut e);
+    rh + e
+}
+
+// Ei(x) = gamma + ln(x) + sum_{k>=1} x^k / (k k!) for 0 < x <= 60.
+#[inline(always)]
+fn ei_series(x: f64) -> f64 {
+    let mut sl = 0.0;
+    let sh = ei_series_sum(x, false, &mut sl);
+    let (lh, ll) = ln_dd(x);
+    let mut rl = 0.0;
+    let rh = adddd(sh, sl, EULER_HI, EULER_LO, &mut rl);
+    let mut e = 0.0;
+    let rh = adddd(rh, rl, lh, ll, &mut e);
+    rh + e
+}
+
+// Ei(x) near its zero x0 from the Taylor expansion in d = x - x0.
+#[inline(always)]
+fn ei_near_zero(x: f64) -> f64 {
+    let mut dl = 0.0;
+    // x - x0_hi is exact (Sterbenz); the low part of x0 is folded into dl.
+    let dh = twosum(x - EI_ZERO_HI, -EI_ZERO_LO, &mut dl);
+    let mut p = EI_ZERO_C[EI_ZERO_C.len() - 1];
+    for &c in EI_ZERO_C.iter().rev().skip(1) {
+        p = super::fma_internal(p, dh, c);
+    }
+    let q = super::fma_internal(p, dh, EI_ZERO_C2[0]) + EI_ZERO_C2[1];
+    let mh = dh * q;
+    let ml = super::fma_internal(dh, q, -mh) + dl * q;
+    let mut tl = 0.0;
+    let th = adddd(EI_ZERO_C1[0], EI_ZERO_C1[1], mh, ml, &mut tl);
+    let mut rl = 0.0;
+    let rh = muldd_acc(dh, dl, th, tl, &mut rl);
+    rh + rl
+}
+
+// Ei(x) ~ e^x / x * sum_{k>=0} k! / x^k for x > 60, truncated at the first
+// term below 2^-110 (the smallest term at x = 60 is about 2^-82).
+#[inline(always)]
+fn ei_asymptotic(x: f64) -> f64 {
+    let mut th = 1.0;
+    let mut tl = 0.0;
+    let mut sh = 1.0;
+    let mut sl = 0.0;
+    let mut k = 1.0;
+    loop {
+        th = mulddd(th, tl, k, &mut tl);
+        th = divddd(th, tl, x, &mut tl);
+        let mut e = 0.0;
+        sh = adddd(sh, sl, th, tl, &mut e);
+        sh = fasttwosum(sh, e, &mut sl);
+        if th <= SERIES_EPS * sh || k > x {
+            break;
+        }
+        k += 1.0;
+    }
+    // exp(x - ln(x) + ln(s))
+    let (xh, xl) = ln_dd(x);
+    let (s_h, s_l) = ln_dd(sh);
+    let mut e = 0.0;
+    let h = twosum(x, -xh, &mut e);
+    let mut rl = 0.0;
+    let h = adddd(h, e - xl, s_h, s_l + sl / sh, &mut rl);
+    let mut l = 0.0;
+    let h = fasttwosum(h, rl, &mut l);
+    exp_with_tail(h, l)
+}
+
+// E_n(x) = e^-x / (x + n - 1 n / (x + n + 2 - 2 (n + 1) / (x + n + 4 - ...)))
+// evaluated backwards in double-double. The depth keeps the truncation error
+// below 2^-66 for x >= 2 and every n >= 1.
+#[inline(always)]
+fn en_cf(n: f64, x: f64) -> f64 {
+    let terms = 12 + (200.0 / x) as u32;
+    let mut dl = 0.0;
+    let mut dh = twosum(x, n + 2.0 * terms as f64, &mut dl);
+    for k in (0..terms).rev() {
+        let kf = k as f64;
+        let mut ql = 0.0;
+        let qh = divdd((kf + 1.0) * (n + kf), 0.0, dh, dl, &mut ql);
+        let mut al = 0.0;
+        let ah = twosum(x, n + 2.0 * kf, &mut al);
+        let mut e = 0.0;
+        dh = adddd(ah, al, -qh, -ql, &mut e);
+        dh = fasttwosum(dh, e, &mut dl);
+    }
+    exp_neg_over(x, dh, dl)
+}
+
+// E_n(x) for n >= 2 and 0 < x <= 2:
+//   (-x)^(n-1) / (n-1)! (psi(n) - ln x) - sum_{m != n-1} (-x)^m / ((m-n+1) m!)
+#[inline(always)]
+fn en_series(n: u32, x: f64) -> f64 {
+    let (lh, ll) = ln_dd(x);
+    // Bound on |psi(n) - ln x| used for the stopping test.
+    let log_bound = 24.0 + lh.abs();
+    let mut th = 1.0;
+    let mut tl = 0.0;
+    let mut sh = 0.0;
+    let mut sl = 0.0;
+    let mut m = 0u32;
+    loop {
+        let mut ul = 0.0;
+        let uh = if m == n - 1 {
+            // psi(n) = -gamma + sum_{k=1}^{n-1} 1/k
+            let mut ph = -EULER_HI;
+            let mut pl = -EULER_LO;
+            for k in 1..n {
+                let mut rl = 0.0;
+                let rh = divddd(1.0, 0.0, k as f64, &mut rl);
+                let mut e = 0.0;
+                ph = adddd(ph, pl, rh, rl, &mut e);
+                ph = fasttwosum(ph, e, &mut pl);
+            }
+            let mut e = 0.0;
+            ph = adddd(ph, pl, -lh, -ll, &mut e);
+            ph = fasttwosum(ph, e, &mut pl);
+            muldd_acc(th, tl, ph, pl, &mut ul)
+        } else {
+            divddd(th, tl, n as f64 - 1.0 - m as f64, &mut ul)
+        };
+        let mut e = 0.0;
+        sh = adddd(sh, sl, uh, ul, &mut e);
+        sh = fasttwosum(sh, e, &mut sl);
+        m += 1;
+        th = mulddd(th, tl, -x, &mut tl);
+        th = divddd(th, tl, m as f64, &mut tl);
+        if m > 1 && th.abs() * log_bound <= SERIES_EPS * sh.abs() {
+            break;
+        }
+    }
+    sh + sl
+}
+
+/// Exponential integral E1(x) = ∫_x^∞ e^-t / t dt.
+///
+/// Returns +inf at zero and NaN for negative arguments (use `expint_ei` for
+/// the principal value on the negative axis).
+#[inline]
+pub fn expint_e1(x: f64) -> f64 {
+    with_nearest(x, expint_e1_nearest)
+}
+
+#[inline]
+fn expint_e1_nearest(x: f64) -> f64 {
+    if x.is_nan() {
+        return x;
+    }
+    if x == 0.0 {
+        return f64::INFINITY;
+    }
+    if x < 0.0 {
+        return f64::NAN;
+    }
+    if x == f64::INFINITY {
+        return 0.0;
+    }
+    if x <= SERIES_LIMIT {
+        return e1_series(x);
+    }
+    en_cf(1.0, x)
+}
+
+/// Exponential integral Ei(x), the principal value of ∫_-∞^x e^t / t dt.
+///
+/// Ei(x) = -E1(-x) for x < 0; Ei(0) = -inf and the result overflows for
+/// x > 716.35.
+#[inline]
+pub fn expint_ei(x: f64) -> f64 {
+    with_nearest(x, expint_ei_nearest)
+}
+
+#[inline]
+fn expint_ei_nearest(x: f64) -> f64 {
+    if x.is_nan() {
+        return x;
+    }
+    if x == 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if x < 0.0 {
+        return -expint_e1(-x);
+    }
+    if x == f64::INFINITY {
+        return x;
+    }
+    if (x - EI_ZERO_HI).abs() <= EI_ZERO_WIDTH {
+        return ei_near_zero(x);
+    }
+    if x <= EI_ASYM_LIMIT {
+        return ei_series(x);
+    }
+    ei_asymptotic(x)
+}
+
+/// Generalized exponential integral E_n(x) = ∫_1^∞ e^-xt / t^n dt.
+///
+/// Defined for n >= 0 and x >= 0; E_n(0) = 1 / (n - 1) for n >= 2 and +inf
+/// for n <= 1. Negative n or x return NaN.
+#[inline]
+pub fn expint_en(n: i32, x: f64) -> f64 {
+    with_nearest((n, x), |(n, x)| expint_en_nearest(n, x))
+}
+
+#[inline]
+fn expint_en_nearest(n: i32, x: f64) -> f64 {
+    if x.is_nan() {
+        return x;
+    }
+    if n < 0 || x < 0.0 {
+        return f64::NAN;
+    }
+    if x == 0.0 {
+        return if n <= 1 {
+            f64::INFINITY
+        } else {
+            1.0 / (n - 1) as f64
+        };
+    }
+    if x == f64::INFINITY {
+        return 0.0;
+    }
+    match n {
+        // E_0(x) = e^-x / x
+        0 => exp_neg_over(x, x, 0.0),
+        1 => expint_e1(x),
+        _ if x <= SERIES_LIMIT => en_series(n as u32, x),
+        _ => en_cf(n as f64, x),
+    }
+}
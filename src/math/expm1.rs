@@ -4,7 +4,7 @@
 //! switches to exp(x)-1 for larger magnitudes. Polynomial degree and constants
 //! match fdlibm/glibc style minimax fits.
 
-use super::{LN2_HI, LN2_LO, hi_word, lo_word, with_hi_lo};
+use super::{LN2_HI, LN2_LO, hi_word, lo_word, with_hi_lo, with_nearest};
 
 const ONE: f64 = 1.0;
 const HUGE: f64 = 1.0e300;
@@ -234,6 +234,14 @@ unsafe fn expm1_fma(mut x: f64) -> f64 {
 
 #[inline(always)]
 pub fn expm1(x: f64) -> f64 {
+    with_nearest(x, expm1_nearest)
+}
+
+#[inline(always)]
+#[allow(unreachable_code)]
+fn expm1_nearest(x: f64) -> f64 {
+    #[cfg(all(target_arch = "x86_64", feature = "runtime-dispatch"))]
+    return super::dispatch::select!(expm1_fma, expm1_generic; fn(x: f64) -> f64)(x);
     #[cfg(target_arch = "x86_64")]
     if super::fma_available() {
         // SAFETY: guarded by compile-time FMA availability.
@@ -0,0 +1,785 @@
+//! IEEE 754 binary128 in software.
+//!
+//! An [`F128`] holds the bits of a binary128 number: a sign, a 15-bit
+//! exponent biased by 16383 and a 112-bit fraction, giving 113 bits of
+//! precision from 2^-16494 to just below 2^16384. Rust's `f128` is not
+//! stable yet; this type has the same layout, so `to_bits` and `from_bits`
+//! convert between the two.
+//!
+//! `+`, `-`, `*`, `/`, `sqrt` and `mul_add` compute the exact result in
+//! integers and round it once, to nearest with ties to even, whatever the
+//! hardware rounding mode. `exp`, `ln`, `sin`, `cos`, `tan`, `atan2` and
+//! `pow` are correctly rounded the way the `cr_*` functions are: Ziv's
+//! strategy over the 192-bit and then 320-bit evaluations, a result still
+//! straddling a midpoint being an exact one (as for `pow`, under the same
+//! assumption that no inexact result lies within 2^-288 of a midpoint).
+//! Special cases follow glibc's `*f128` functions; a NaN operand is
+//! returned quieted and invalid operations return [`F128::NAN`].
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+use super::DoubleF64;
+use super::mp::{Mp, Round, reduce_pio2_parts};
+
+const SIGN: u128 = 1 << 127;
+const QUIET: u128 = 1 << 111;
+const FRAC: u128 = (1 << 112) - 1;
+const IMPLICIT: u128 = 1 << 112;
+// The exponent of the last mantissa bit of the subnormals and of the
+// smallest normals.
+const E_MIN: i32 = -16494;
+
+const PHASE1_ERR: i32 = 192 - 32;
+const PHASE2_ERR: i32 = 320 - 32;
+
+/// An IEEE 754 binary128 number.
+#[derive(Clone, Copy, Default)]
+pub struct F128(u128);
+
+impl F128 {
+    pub const ZERO: Self = Self(0);
+    pub const ONE: Self = Self(0x3fff << 112);
+    pub const INFINITY: Self = Self(0x7fff << 112);
+    pub const NEG_INFINITY: Self = Self(SIGN | 0x7fff << 112);
+    pub const NAN: Self = Self(0x7fff << 112 | QUIET);
+    /// The largest finite value, (2 - 2^-112) × 2^16383.
+    pub const MAX: Self = Self(0x7ffe_ffff_ffff_ffff_ffff_ffff_ffff_ffff);
+    /// The smallest positive normal value, 2^-16382.
+    pub const MIN_POSITIVE: Self = Self(IMPLICIT);
+    /// 2^-112, the difference between 1 and the next value.
+    pub const EPSILON: Self = Self(0x3f8f << 112);
+    /// π rounded to nearest.
+    pub const PI: Self = Self(0x4000_921f_b544_42d1_8469_898c_c517_01b8);
+
+    #[inline]
+    pub const fn from_bits(bits: u128) -> Self {
+        Self(bits)
+    }
+
+    #[inline]
+    pub const fn to_bits(self) -> u128 {
+        self.0
+    }
+
+    #[inline]
+    pub const fn is_nan(self) -> bool {
+        self.0 & !SIGN > Self::INFINITY.0
+    }
+
+    #[inline]
+    pub const fn is_infinite(self) -> bool {
+        self.0 & !SIGN == Self::INFINITY.0
+    }
+
+    #[inline]
+    pub const fn is_finite(self) -> bool {
+        self.0 & !SIGN < Self::INFINITY.0
+    }
+
+    #[inline]
+    pub const fn is_sign_negative(self) -> bool {
+        self.0 & SIGN != 0
+    }
+
+    #[inline]
+    pub const fn abs(self) -> Self {
+        Self(self.0 & !SIGN)
+    }
+
+    /// The nearest `f64`, ties to even.
+    pub fn to_f64(self) -> f64 {
+        if self.is_nan() {
+            return if self.is_sign_negative() {
+                -f64::NAN
+            } else {
+                f64::NAN
+            };
+        }
+        if self.is_infinite() {
+            return if self.is_sign_negative() {
+                f64::NEG_INFINITY
+            } else {
+                f64::INFINITY
+            };
+        }
+        let (neg, m, e) = self.parts();
+        if m == 0 {
+            return if neg { -0.0 } else { 0.0 };
+        }
+        Mp::<2>::from_u128(neg, m, e).to_f64(Round::Nearest)
+    }
+
+    /// The square root, NaN below zero.
+    pub fn sqrt(self) -> Self {
+        if self.is_nan() {
+            return self.quieted();
+        }
+        if self.is_zero() || self == Self::INFINITY {
+            return self;
+        }
+        if self.is_sign_negative() {
+            return Self::NAN;
+        }
+        let (_, m, e) = self.parts();
+        let (mut m, mut e) = normalize(m, e);
+        if e & 1 != 0 {
+            m <<= 1;
+            e -= 1;
+        }
+        // m < 2^114: one root bit per pair of bits of m × 4^63, 120 bits.
+        let (mut root, mut rem) = (0u128, 0u128);
+        for i in 0..120 {
+            let pair = if i < 57 { (m >> (112 - 2 * i)) & 3 } else { 0 };
+            rem = (rem << 2) | pair;
+            let trial = (root << 2) | 1;
+            root <<= 1;
+            if rem >= trial {
+                rem -= trial;
+                root |= 1;
+            }
+        }
+        round(false, root, e / 2 - 63, rem != 0)
+    }
+
+    /// `self * a + b` with a single rounding.
+    pub fn mul_add(self, a: Self, b: Self) -> Self {
+        if self.is_nan() || a.is_nan() || b.is_nan() {
+            let n = [self, a, b].into_iter().find(|v| v.is_nan());
+            return n.unwrap_or(Self::NAN).quieted();
+        }
+        let neg = self.is_sign_negative() != a.is_sign_negative();
+        if self.is_infinite() || a.is_infinite() {
+            if self.is_zero() || a.is_zero() || (b.is_infinite() && b.is_sign_negative() != neg) {
+                return Self::NAN;
+            }
+            return Self::INFINITY.with_sign(neg);
+        }
+        if b.is_infinite() {
+            return b;
+        }
+        let (_, am, ae) = self.parts();
+        let (_, bm, be) = a.parts();
+        let (cneg, cm, ce) = b.parts();
+        if am == 0 || bm == 0 {
+            if cm == 0 {
+                return Self::ZERO.with_sign(neg && cneg);
+            }
+            return b;
+        }
+        let (ph, pl) = mul_wide(am, bm);
+        if cm == 0 {
+            return round_wide(neg, ph, pl, ae + be);
+        }
+        // Both terms with their top bit at 2^254, the smaller one shifted
+        // right and jammed: no set bit is lost unless the exponents differ
+        // by more than one, when at most one bit cancels.
+        let sp = lz256(ph, pl) - 1;
+        let (ph, pl) = shl256(ph, pl, sp);
+        let pe = ae + be - sp as i32;
+        let sc = lz256(0, cm) - 1;
+        let (ch, cl) = shl256(0, cm, sc);
+        let ce = ce - sc as i32;
+        let ((xneg, xh, xl, xe), (yneg, yh, yl, ye)) = if (pe, ph, pl) >= (ce, ch, cl) {
+            ((neg, ph, pl, pe), (cneg, ch, cl, ce))
+        } else {
+            ((cneg, ch, cl, ce), (neg, ph, pl, pe))
+        };
+        let (yh, yl) = shr_jam256(yh, yl, (xe - ye) as u32);
+        let (h, l) = if xneg == yneg {
+            let (l, c) = xl.overflowing_add(yl);
+            (xh + yh + c as u128, l)
+        } else {
+            let (l, b) = xl.overflowing_sub(yl);
+            (xh - yh - b as u128, l)
+        };
+        if h == 0 && l == 0 {
+            return Self::ZERO;
+        }
+        round_wide(xneg, h, l, xe)
+    }
+
+    /// e^self.
+    pub fn exp(self) -> Self {
+        if self.is_nan() {
+            return self.quieted();
+        }
+        let x = self.to_f64();
+        // e^11357 overflows and e^-11500 is below half the smallest
+        // subnormal.
+        if x > 11400.0 {
+            return Self::INFINITY;
+        }
+        if x < -11500.0 {
+            return Self::ZERO;
+        }
+        ziv(|| self.mp().exp(), || self.mp().exp())
+    }
+
+    /// The natural logarithm.
+    pub fn ln(self) -> Self {
+        if self.is_nan() {
+            return self.quieted();
+        }
+        if self.is_zero() {
+            return Self::NEG_INFINITY;
+        }
+        if self.is_sign_negative() {
+            return Self::NAN;
+        }
+        if self.is_infinite() {
+            return self;
+        }
+        if self == Self::ONE {
+            return Self::ZERO;
+        }
+        ziv(|| self.mp().ln(), || self.mp().ln())
+    }
+
+    pub fn sin(self) -> Self {
+        if !self.is_finite() {
+            return self.invalid();
+        }
+        if self.is_zero() {
+            return self;
+        }
+        ziv(|| self.sin_mp(), || self.sin_mp())
+    }
+
+    pub fn cos(self) -> Self {
+        if !self.is_finite() {
+            return self.invalid();
+        }
+        if self.is_zero() {
+            return Self::ONE;
+        }
+        ziv(|| self.cos_mp(), || self.cos_mp())
+    }
+
+    pub fn tan(self) -> Self {
+        if !self.is_finite() {
+            return self.invalid();
+        }
+        if self.is_zero() {
+            return self;
+        }
+        ziv(|| self.tan_mp(), || self.tan_mp())
+    }
+
+    /// The angle of the point `(x, self)`, in [-π, π].
+    pub fn atan2(self, x: Self) -> Self {
+        let y = self;
+        if y.is_nan() || x.is_nan() {
+            return if y.is_nan() { y } else { x }.quieted();
+        }
+        // k π/4 with the sign of y.
+        let quarters =
+            |k: u64| round_mp(Mp::<3>::pi_2().mul_u64(k).ldexp(-1)).with_sign(y.is_sign_negative());
+        if y.is_infinite() {
+            let k = match (x.is_infinite(), x.is_sign_negative()) {
+                (false, _) => 2,
+                (true, false) => 1,
+                (true, true) => 3,
+            };
+            return quarters(k);
+        }
+        if y.is_zero() || x.is_infinite() {
+            return if x.is_sign_negative() {
+                quarters(4)
+            } else {
+                Self::ZERO.with_sign(y.is_sign_negative())
+            };
+        }
+        if x.is_zero() {
+            return quarters(2);
+        }
+        fn atan2_mp<const L: usize>(y: F128, x: F128) -> Mp<L> {
+            let a = y.mp::<L>().div(x.mp()).atan();
+            if !x.is_sign_negative() {
+                a
+            } else if !y.is_sign_negative() {
+                a.add(Mp::pi_2().ldexp(1))
+            } else {
+                a.sub(Mp::pi_2().ldexp(1))
+            }
+        }
+        ziv(|| atan2_mp(y, x), || atan2_mp(y, x))
+    }
+
+    /// `self` raised to the power `y`.
+    pub fn pow(self, y: Self) -> Self {
+        let x = self;
+        if y.is_zero() || x == Self::ONE {
+            return Self::ONE;
+        }
+        if x.is_nan() || y.is_nan() {
+            return if x.is_nan() { x } else { y }.quieted();
+        }
+        let (integer, odd) = y.integer_parity();
+        let yneg = y.is_sign_negative();
+        if x.is_zero() {
+            return match (yneg, odd) {
+                (true, true) => Self::INFINITY.with_sign(x.is_sign_negative()),
+                (true, false) => Self::INFINITY,
+                (false, true) => x,
+                (false, false) => Self::ZERO,
+            };
+        }
+        if y.is_infinite() {
+            if x == -Self::ONE {
+                return Self::ONE;
+            }
+            return if (x.abs() < Self::ONE) == yneg {
+                Self::INFINITY
+            } else {
+                Self::ZERO
+            };
+        }
+        let neg = x.is_sign_negative() && odd;
+        if x.is_infinite() {
+            let r = if yneg { Self::ZERO } else { Self::INFINITY };
+            return r.with_sign(neg);
+        }
+        if x.is_sign_negative() && !integer {
+            return Self::NAN;
+        }
+        // |y ln|x|| >= 2^14 overflows or underflows.
+        let t = y.mp::<2>().mul(x.abs().mp::<2>().ln());
+        if t.exponent() > 14 {
+            let r = if t.is_neg() {
+                Self::ZERO
+            } else {
+                Self::INFINITY
+            };
+            return r.with_sign(neg);
+        }
+        fn pow_mp<const L: usize>(x: F128, y: F128) -> Mp<L> {
+            y.mp::<L>().mul(x.abs().mp::<L>().ln()).exp()
+        }
+        ziv(|| pow_mp(x, y), || pow_mp(x, y)).with_sign(neg)
+    }
+
+    // (neg, m, e) with |self| = m × 2^e, for finite self.
+    #[inline]
+    fn parts(self) -> (bool, u128, i32) {
+        let b = ((self.0 >> 112) & 0x7fff) as i32;
+        let f = self.0 & FRAC;
+        if b == 0 {
+            (self.is_sign_negative(), f, E_MIN)
+        } else {
+            (self.is_sign_negative(), f | IMPLICIT, b - 1 + E_MIN)
+        }
+    }
+
+    #[inline]
+    fn is_zero(self) -> bool {
+        self.0 & !SIGN == 0
+    }
+
+    #[inline]
+    fn quieted(self) -> Self {
+        Self(self.0 | QUIET)
+    }
+
+    // The NaN of an infinite argument to sin, cos or tan.
+    #[inline]
+    fn invalid(self) -> Self {
+        if self.is_nan() {
+            self.quieted()
+        } else {
+            Self::NAN
+        }
+    }
+
+    #[inline]
+    fn with_sign(self, neg: bool) -> Self {
+        Self((self.0 & !SIGN) | if neg { SIGN } else { 0 })
+    }
+
+    // (integer, odd) for a finite or infinite self; infinities are even
+    // integers.
+    fn integer_parity(self) -> (bool, bool) {
+        if !self.is_finite() {
+            return (true, false);
+        }
+        let (_, m, e) = self.parts();
+        if e >= 0 {
+            return (true, e == 0 && m & 1 != 0);
+        }
+        if e <= -113 {
+            return (m == 0, false);
+        }
+        let s = -e as u32;
+        (m & ((1 << s) - 1) == 0, (m >> s) & 1 != 0)
+    }
+
+    // self exactly, for finite self.
+    #[inline]
+    fn mp<const L: usize>(self) -> Mp<L> {
+        let (neg, m, e) = self.parts();
+        Mp::from_u128(neg, m, e)
+    }
+
+    // (k, r) with self = k π/2 + r, k mod 4 and |r| <= π/4.
+    fn reduced<const L: usize>(self) -> (u32, Mp<L>) {
+        if self.abs().to_f64() < 0.78 {
+            return (0, self.mp());
+        }
+        let (neg, m, e) = self.parts();
+        reduce_pio2_parts(neg, m, e, 9)
+    }
+
+    fn sin_mp<const L: usize>(self) -> Mp<L> {
+        let (k, r) = self.reduced::<L>();
+        match k {
+            0 => Mp::sin_reduced(r),
+            1 => Mp::cos_reduced(r),
+            2 => Mp::sin_reduced(r).neg(),
+            _ => Mp::cos_reduced(r).neg(),
+        }
+    }
+
+    fn cos_mp<const L: usize>(self) -> Mp<L> {
+        let (k, r) = self.reduced::<L>();
+        match k {
+            0 => Mp::cos_reduced(r),
+            1 => Mp::sin_reduced(r).neg(),
+            2 => Mp::cos_reduced(r).neg(),
+            _ => Mp::sin_reduced(r),
+        }
+    }
+
+    fn tan_mp<const L: usize>(self) -> Mp<L> {
+        let (k, r) = self.reduced::<L>();
+        let (s, c) = (Mp::sin_reduced(r), Mp::cos_reduced(r));
+        if k & 1 == 0 { s.div(c) } else { c.div(s).neg() }
+    }
+}
+
+// The key ordering finite and infinite values as the numbers they are.
+#[inline]
+fn key(x: F128) -> i128 {
+    let m = (x.0 & !SIGN) as i128;
+    if x.is_sign_negative() { -m } else { m }
+}
+
+impl PartialEq for F128 {
+    #[inline]
+    fn eq(&self, o: &Self) -> bool {
+        !self.is_nan() && !o.is_nan() && key(*self) == key(*o)
+    }
+}
+
+impl PartialOrd for F128 {
+    #[inline]
+    fn partial_cmp(&self, o: &Self) -> Option<Ordering> {
+        if self.is_nan() || o.is_nan() {
+            return None;
+        }
+        Some(key(*self).cmp(&key(*o)))
+    }
+}
+
+impl fmt::Debug for F128 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "F128({:#034x} ≈ {:e})", self.0, self.to_f64())
+    }
+}
+
+impl From<f64> for F128 {
+    /// `x` exactly; a NaN keeps its sign and payload.
+    fn from(x: f64) -> Self {
+        let b = x.to_bits();
+        let sign = ((b >> 63) as u128) << 127;
+        let f = (b & ((1 << 52) - 1)) as u128;
+        match (b >> 52) & 0x7ff {
+            0x7ff => Self(sign | Self::INFINITY.0 | (f << 60)),
+            0 if f == 0 => Self(sign),
+            0 => round(x < 0.0, f, -1074, false),
+            e => round(x < 0.0, f | (1 << 52), e as i32 - 1075, false),
+        }
+    }
+}
+
+impl From<i64> for F128 {
+    fn from(n: i64) -> Self {
+        round(n < 0, n.unsigned_abs() as u128, 0, false)
+    }
+}
+
+impl From<DoubleF64> for F128 {
+    /// `hi + lo` rounded to nearest.
+    fn from(d: DoubleF64) -> Self {
+        F128::from(d.hi) + F128::from(d.lo)
+    }
+}
+
+impl Neg for F128 {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self(self.0 ^ SIGN)
+    }
+}
+
+impl Add for F128 {
+    type Output = Self;
+
+    fn add(self, o: Self) -> Self {
+        if self.is_nan() || o.is_nan() {
+            return if self.is_nan() { self } else { o }.quieted();
+        }
+        if self.is_infinite() || o.is_infinite() {
+            if self.is_infinite() && o.is_infinite() && self.0 != o.0 {
+                return Self::NAN;
+            }
+            return if self.is_infinite() { self } else { o };
+        }
+        if o.is_zero() {
+            return if self.is_zero() {
+                Self::ZERO.with_sign(self.is_sign_negative() && o.is_sign_negative())
+            } else {
+                self
+            };
+        }
+        if self.is_zero() {
+            return o;
+        }
+        let (a, b) = if self.abs().0 >= o.abs().0 {
+            (self, o)
+        } else {
+            (o, self)
+        };
+        // 14 guard bits below the larger mantissa; the smaller one is
+        // aligned to them and jammed.
+        let (aneg, am, ae) = a.parts();
+        let (bneg, bm, be) = b.parts();
+        let bm = shr_jam(bm << 14, (ae - be) as u32);
+        let m = if aneg == bneg {
+            (am << 14) + bm
+        } else {
+            (am << 14) - bm
+        };
+        if m == 0 {
+            return Self::ZERO;
+        }
+        round(aneg, m, ae - 14, false)
+    }
+}
+
+impl Sub for F128 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, o: Self) -> Self {
+        self + -o
+    }
+}
+
+impl Mul for F128 {
+    type Output = Self;
+
+    fn mul(self, o: Self) -> Self {
+        if self.is_nan() || o.is_nan() {
+            return if self.is_nan() { self } else { o }.quieted();
+        }
+        let neg = self.is_sign_negative() != o.is_sign_negative();
+        if self.is_infinite() || o.is_infinite() {
+            if self.is_zero() || o.is_zero() {
+                return Self::NAN;
+            }
+            return Self::INFINITY.with_sign(neg);
+        }
+        let (_, am, ae) = self.parts();
+        let (_, bm, be) = o.parts();
+        if am == 0 || bm == 0 {
+            return Self::ZERO.with_sign(neg);
+        }
+        let (h, l) = mul_wide(am, bm);
+        round_wide(neg, h, l, ae + be)
+    }
+}
+
+impl Div for F128 {
+    type Output = Self;
+
+    fn div(self, o: Self) -> Self {
+        if self.is_nan() || o.is_nan() {
+            return if self.is_nan() { self } else { o }.quieted();
+        }
+        let neg = self.is_sign_negative() != o.is_sign_negative();
+        if self.is_infinite() {
+            if o.is_infinite() {
+                return Self::NAN;
+            }
+            return Self::INFINITY.with_sign(neg);
+        }
+        if o.is_infinite() {
+            return Self::ZERO.with_sign(neg);
+        }
+        if o.is_zero() {
+            if self.is_zero() {
+                return Self::NAN;
+            }
+            return Self::INFINITY.with_sign(neg);
+        }
+        if self.is_zero() {
+            return Self::ZERO.with_sign(neg);
+        }
+        let (_, am, ae) = self.parts();
+        let (_, bm, be) = o.parts();
+        let (am, ae) = normalize(am, ae);
+        let (bm, be) = normalize(bm, be);
+        // floor(am 2^126 / bm), 14 bits at a time: the remainder stays
+        // below bm < 2^113.
+        let (mut q, mut r) = (0u128, am);
+        for _ in 0..9 {
+            r <<= 14;
+            q = (q << 14) | (r / bm);
+            r %= bm;
+        }
+        round(neg, q, ae - be - 126, r != 0)
+    }
+}
+
+// m × 2^e with m moved up to 113 bits.
+#[inline]
+fn normalize(m: u128, e: i32) -> (u128, i32) {
+    let s = m.leading_zeros() - 15;
+    (m << s, e - s as i32)
+}
+
+// m >> s, with the bits shifted out ORed into the last bit.
+#[inline]
+fn shr_jam(m: u128, s: u32) -> u128 {
+    if s == 0 {
+        m
+    } else if s >= 128 {
+        (m != 0) as u128
+    } else {
+        (m >> s) | (m << (128 - s) != 0) as u128
+    }
+}
+
+// a × b as (high, low) words.
+#[inline]
+fn mul_wide(a: u128, b: u128) -> (u128, u128) {
+    let (a0, a1) = (a as u64 as u128, a >> 64);
+    let (b0, b1) = (b as u64 as u128, b >> 64);
+    let (ll, lh, hl, hh) = (a0 * b0, a0 * b1, a1 * b0, a1 * b1);
+    let mid = (ll >> 64) + (lh as u64 as u128) + (hl as u64 as u128);
+    let lo = (ll as u64 as u128) | (mid << 64);
+    let hi = hh + (lh >> 64) + (hl >> 64) + (mid >> 64);
+    (hi, lo)
+}
+
+#[inline]
+fn lz256(h: u128, l: u128) -> u32 {
+    if h != 0 {
+        h.leading_zeros()
+    } else {
+        128 + l.leading_zeros()
+    }
+}
+
+#[inline]
+fn shl256(h: u128, l: u128, s: u32) -> (u128, u128) {
+    match s {
+        0 => (h, l),
+        1..128 => ((h << s) | (l >> (128 - s)), l << s),
+        _ => (l << (s - 128), 0),
+    }
+}
+
+// (h, l) >> s with the bits shifted out ORed into the last bit.
+#[inline]
+fn shr_jam256(h: u128, l: u128, s: u32) -> (u128, u128) {
+    match s {
+        0 => (h, l),
+        1..128 => (
+            h >> s,
+            (l >> s) | (h << (128 - s)) | (l << (128 - s) != 0) as u128,
+        ),
+        128..256 => {
+            let t = s - 128;
+            let lost = l != 0 || (t > 0 && h << (128 - t) != 0);
+            (0, (h >> t) | lost as u128)
+        }
+        _ => (0, (h != 0 || l != 0) as u128),
+    }
+}
+
+// ±(m + δ) × 2^e rounded to nearest, ties to even, where δ in [0, 1) is
+// nonzero iff `sticky`, which needs m to have more than 113 bits.
+fn round(neg: bool, m: u128, e: i32, sticky: bool) -> F128 {
+    if m == 0 {
+        return F128::ZERO.with_sign(neg);
+    }
+    // Keep 113 bits, fewer below the normal range.
+    let len = 128 - m.leading_zeros() as i32;
+    let shift = (len - 113).max(E_MIN - e);
+    let (mut q, mut e) = if shift <= 0 {
+        (m << -shift, e + shift)
+    } else {
+        let s = shift as u32;
+        let (q, half, rest) = match s {
+            1..128 => (
+                m >> s,
+                (m >> (s - 1)) & 1 != 0,
+                s > 1 && m << (129 - s) != 0,
+            ),
+            128 => (0, m >> 127 != 0, m << 1 != 0),
+            _ => (0, false, true),
+        };
+        let q = q + (half && (rest || sticky || q & 1 != 0)) as u128;
+        (q, e + shift)
+    };
+    if q == IMPLICIT << 1 {
+        q >>= 1;
+        e += 1;
+    }
+    // Normal q carries the implicit bit into the exponent field.
+    if q >= IMPLICIT && e - E_MIN + 1 >= 0x7fff {
+        return F128::INFINITY.with_sign(neg);
+    }
+    F128((((e - E_MIN) as u128) << 112) + q).with_sign(neg)
+}
+
+// ±(h 2^128 + l) × 2^e rounded to nearest.
+fn round_wide(neg: bool, h: u128, l: u128, e: i32) -> F128 {
+    if h == 0 {
+        return round(neg, l, e, false);
+    }
+    let s = 128 - h.leading_zeros();
+    let (m, sticky) = if s == 128 {
+        (h, l != 0)
+    } else {
+        ((h << (128 - s)) | (l >> s), l << (128 - s) != 0)
+    };
+    round(neg, m, e + s as i32, sticky)
+}
+
+#[inline]
+fn round_mp<const L: usize>(v: Mp<L>) -> F128 {
+    let (neg, m, e, sticky) = v.to_u128();
+    round(neg, m, e, sticky)
+}
+
+// v rounded at the edges of its relative error bound 2^-err.
+fn bounds<const L: usize>(v: Mp<L>, err: i32) -> (F128, F128) {
+    let eps = v.abs().ldexp(-err);
+    (round_mp(v.sub(eps)), round_mp(v.add(eps)))
+}
+
+// Ziv's loop: the 192-bit phase, then the 320-bit one, whose bounds can
+// only straddle an exact midpoint, which rounds to even.
+fn ziv(phase1: impl FnOnce() -> Mp<3>, phase2: impl FnOnce() -> Mp<5>) -> F128 {
+    let (a, b) = bounds(phase1(), PHASE1_ERR);
+    if a.0 == b.0 {
+        return a;
+    }
+    let (a, b) = bounds(phase2(), PHASE2_ERR);
+    if a.0 & 1 == 0 { a } else { b }
+}
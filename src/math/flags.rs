@@ -0,0 +1,334 @@
+//! IEEE-754 exception flags for the elementary functions.
+//!
+//! `no_std` has no `fenv`, and the fast paths raise whatever their
+//! arithmetic happens to raise, so `<name>_with_flags` returns the function's
+//! result together with the exceptions glibc's version signals for it:
+//!
+//! - invalid: a NaN from non-NaN arguments, or a signaling NaN argument;
+//! - divide-by-zero: an exact infinity from finite arguments, i.e. a pole;
+//! - overflow: an infinity from finite arguments that is not a pole, with
+//!   inexact;
+//! - underflow: an inexact result below the smallest normal, with inexact;
+//! - inexact: a result that differs from the exact value.
+//!
+//! Quiet NaN arguments propagate without exceptions, including in results
+//! that do not depend on them such as `pow(NaN, 0)`. Exactness, which also
+//! tells a pole from an overflow, is checked per function from the
+//! arguments: by Lindemann–Weierstrass the exponentials, logarithms and
+//! trigonometric and hyperbolic functions and their inverses are exact only
+//! where the value is 0, ±1 or a power of 2 or 10, and a root is exact when
+//! the argument's odd mantissa is a square or cube. `hypot` and `pow` with a
+//! non-integer exponent rule out most arguments the same way and round the
+//! rest down and up: the value is exact when both agree.
+
+use core::ops::{BitAnd, BitOr, BitOrAssign};
+
+use super::cr::{hypot_bounds, pow_bounds};
+use super::{
+    acos, acosh, asin, asinh, atan, atan2, atanh, cbrt, cos, cosh, erf, erfc, exp, exp2, exp10,
+    expm1, hypot, lgamma, ln, log1p, log2, log10, pow, sin, sinh, sqrt, tan, tanh, tgamma, trunc,
+};
+
+/// A set of IEEE-754 exception flags.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FpExceptions(u8);
+
+impl FpExceptions {
+    pub const NONE: Self = Self(0);
+    pub const INVALID: Self = Self(1);
+    pub const DIVBYZERO: Self = Self(1 << 1);
+    pub const OVERFLOW: Self = Self(1 << 2);
+    pub const UNDERFLOW: Self = Self(1 << 3);
+    pub const INEXACT: Self = Self(1 << 4);
+    pub const ALL: Self = Self(0x1f);
+
+    #[inline]
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Whether every flag of `o` is set.
+    #[inline]
+    pub const fn contains(self, o: Self) -> bool {
+        self.0 & o.0 == o.0
+    }
+}
+
+impl BitOr for FpExceptions {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, o: Self) -> Self {
+        Self(self.0 | o.0)
+    }
+}
+
+impl BitOrAssign for FpExceptions {
+    #[inline]
+    fn bitor_assign(&mut self, o: Self) {
+        self.0 |= o.0;
+    }
+}
+
+impl BitAnd for FpExceptions {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(self, o: Self) -> Self {
+        Self(self.0 & o.0)
+    }
+}
+
+#[inline]
+fn is_signaling(x: f64) -> bool {
+    x.is_nan() && x.to_bits() & (1 << 51) == 0
+}
+
+// Whether the rounding down and up of a value agree, i.e. it is exact.
+#[inline]
+fn is_exact((lo, hi): (f64, f64)) -> bool {
+    lo == hi
+}
+
+// The exceptions for result `r` of `args`; `exact` tells whether the exact
+// value of the function is a double (or an exact infinity). Without
+// `inexact`, normal results skip that test and report no exception.
+pub(crate) fn raised(
+    args: &[f64],
+    r: f64,
+    inexact: bool,
+    exact: impl FnOnce() -> bool,
+) -> FpExceptions {
+    if args.iter().any(|&a| is_signaling(a)) {
+        return FpExceptions::INVALID;
+    }
+    if args.iter().any(|a| a.is_nan()) {
+        return FpExceptions::NONE;
+    }
+    if r.is_nan() {
+        return FpExceptions::INVALID;
+    }
+    if r.is_infinite() {
+        if args.iter().any(|a| a.is_infinite()) {
+            return FpExceptions::NONE;
+        }
+        return if exact() {
+            FpExceptions::DIVBYZERO
+        } else {
+            FpExceptions::OVERFLOW | FpExceptions::INEXACT
+        };
+    }
+    if (!inexact && r.abs() >= f64::MIN_POSITIVE) || exact() {
+        FpExceptions::NONE
+    } else if r.abs() < f64::MIN_POSITIVE {
+        FpExceptions::UNDERFLOW | FpExceptions::INEXACT
+    } else {
+        FpExceptions::INEXACT
+    }
+}
+
+macro_rules! with_flags {
+    ($($name:ident($($a:ident),+) => $f:ident, $exact:ident;)*) => {$(
+        #[doc = concat!("[`", stringify!($f), "`](crate::", stringify!($f), ") and the exceptions it raises.")]
+        pub fn $name($($a: f64),+) -> (f64, FpExceptions) {
+            let r = $f($($a),+);
+            (r, raised(&[$($a),+], r, true, || $exact($($a),+)))
+        }
+    )*};
+}
+
+with_flags! {
+    exp_with_flags(x) => exp, exp_is_exact;
+    exp2_with_flags(x) => exp2, exp2_is_exact;
+    exp10_with_flags(x) => exp10, exp10_is_exact;
+    expm1_with_flags(x) => expm1, expm1_is_exact;
+    ln_with_flags(x) => ln, ln_is_exact;
+    log2_with_flags(x) => log2, log2_is_exact;
+    log10_with_flags(x) => log10, log10_is_exact;
+    log1p_with_flags(x) => log1p, log1p_is_exact;
+    sin_with_flags(x) => sin, zero_is_exact;
+    cos_with_flags(x) => cos, zero_is_exact;
+    tan_with_flags(x) => tan, zero_is_exact;
+    asin_with_flags(x) => asin, zero_is_exact;
+    acos_with_flags(x) => acos, one_is_exact;
+    atan_with_flags(x) => atan, zero_is_exact;
+    atan2_with_flags(y, x) => atan2, atan2_is_exact;
+    sinh_with_flags(x) => sinh, zero_is_exact;
+    cosh_with_flags(x) => cosh, zero_is_exact;
+    tanh_with_flags(x) => tanh, tanh_is_exact;
+    asinh_with_flags(x) => asinh, zero_is_exact;
+    acosh_with_flags(x) => acosh, one_is_exact;
+    atanh_with_flags(x) => atanh, atanh_is_exact;
+    cbrt_with_flags(x) => cbrt, cbrt_is_exact;
+    sqrt_with_flags(x) => sqrt, sqrt_is_exact;
+    hypot_with_flags(x, y) => hypot, hypot_is_exact;
+    pow_with_flags(x, y) => pow, pow_is_exact;
+    erf_with_flags(x) => erf, erf_is_exact;
+    erfc_with_flags(x) => erfc, erf_is_exact;
+    lgamma_with_flags(x) => lgamma, lgamma_is_exact;
+}
+
+/// [`tgamma`](crate::tgamma) and the exceptions it raises.
+pub fn tgamma_with_flags(x: f64) -> (f64, FpExceptions) {
+    let r = tgamma(x);
+    (r, raised(&[x], r, true, || tgamma_is_exact(x)))
+}
+
+// Whether the exact value of the function is a double or an exact infinity.
+// Each is asked only for arguments that are not NaN and whose result is not
+// NaN, nor infinite from infinite arguments.
+
+// x = m 2^e with m odd, for finite nonzero x.
+fn odd_parts(x: f64) -> (u64, i64) {
+    let bits = x.to_bits() & !(1 << 63);
+    let (m, e) = match bits >> 52 {
+        0 => (bits, -1074),
+        b => (bits & ((1 << 52) - 1) | 1 << 52, b as i64 - 1075),
+    };
+    (m >> m.trailing_zeros(), e + m.trailing_zeros() as i64)
+}
+
+// Whether the n-th root of finite nonzero x, for n = 2 or 3, is a double,
+// c 2^(e/n) with c odd: n divides e and m = c^n, where c < 2^27.
+fn has_exact_root(x: f64, n: u32, root: fn(f64) -> f64) -> bool {
+    let (m, e) = odd_parts(x);
+    let c = (root(m as f64) + 0.5) as u64;
+    e % n as i64 == 0 && c.pow(n) == m
+}
+
+// Functions whose only finite exact value, by Lindemann–Weierstrass, is
+// their value at zero, or at one.
+pub(crate) fn zero_is_exact(x: f64) -> bool {
+    x == 0.0
+}
+
+pub(crate) fn one_is_exact(x: f64) -> bool {
+    x == 1.0
+}
+
+pub(crate) fn exp_is_exact(x: f64) -> bool {
+    x == 0.0 || x == f64::NEG_INFINITY
+}
+
+pub(crate) fn exp2_is_exact(x: f64) -> bool {
+    x == f64::NEG_INFINITY || ((-1074.0..=1023.0).contains(&x) && x == trunc(x))
+}
+
+// 10^22 is the largest power of ten with at most 53 significant bits.
+pub(crate) fn exp10_is_exact(x: f64) -> bool {
+    x == f64::NEG_INFINITY || ((0.0..=22.0).contains(&x) && x == trunc(x))
+}
+
+pub(crate) fn expm1_is_exact(x: f64) -> bool {
+    exp_is_exact(x)
+}
+
+// The logarithms are exact at their pole at zero too.
+pub(crate) fn ln_is_exact(x: f64) -> bool {
+    x == 0.0 || x == 1.0
+}
+
+pub(crate) fn log2_is_exact(x: f64) -> bool {
+    x == 0.0 || (x > 0.0 && x.is_finite() && odd_parts(x).0 == 1)
+}
+
+pub(crate) fn log10_is_exact(x: f64) -> bool {
+    if x == 0.0 {
+        return true;
+    }
+    let mut p = 1.0;
+    while p < x && p < 1e22 {
+        p *= 10.0;
+    }
+    p == x
+}
+
+pub(crate) fn log1p_is_exact(x: f64) -> bool {
+    x == 0.0 || x == -1.0
+}
+
+// atan2(±0, x) is ±0 for x >= +0, and atan2(y, +∞) for finite y; the
+// other exact-looking results are ±π/2, ±π/4, ±3π/4 and ±π.
+pub(crate) fn atan2_is_exact(y: f64, x: f64) -> bool {
+    x.is_sign_positive() && (y == 0.0 || (x == f64::INFINITY && y.is_finite()))
+}
+
+pub(crate) fn tanh_is_exact(x: f64) -> bool {
+    x == 0.0 || x.is_infinite()
+}
+
+pub(crate) fn atanh_is_exact(x: f64) -> bool {
+    x == 0.0 || x.abs() == 1.0
+}
+
+pub(crate) fn cbrt_is_exact(x: f64) -> bool {
+    x == 0.0 || has_exact_root(x, 3, cbrt)
+}
+
+pub(crate) fn sqrt_is_exact(x: f64) -> bool {
+    x == 0.0 || (x > 0.0 && has_exact_root(x, 2, sqrt))
+}
+
+// With m_x and m_y the odd mantissas, hypot(x, y)^2 is (m_x^2 + m_y^2)
+// 2^(2e) if x and y share the exponent e, and twice an odd number is no
+// square; y below 2^-27 x leaves hypot between |x| and its successor.
+pub(crate) fn hypot_is_exact(x: f64, y: f64) -> bool {
+    let (x, y) = (x.abs().max(y.abs()), x.abs().min(y.abs()));
+    if y == 0.0 {
+        return true;
+    }
+    if y < x * 7.450580596923828e-9 || odd_parts(x).1 == odd_parts(y).1 {
+        return false;
+    }
+    is_exact(hypot_bounds(x, y))
+}
+
+// The finite results of infinite, zero or unit arguments are 0 and ±1, and
+// the poles are exact. x^y = m^y 2^(e y) for integer y is a double when m^y
+// fits in 53 bits, so m = 1 if y < 0, and its bits lie in the exponent
+// range. A non-integer y is p / 2^k with p odd and k > 0, and x^y is then
+// rational only if the square root of x is.
+pub(crate) fn pow_is_exact(x: f64, y: f64) -> bool {
+    if x.is_infinite() || y.is_infinite() || x == 0.0 || y == 0.0 || x.abs() == 1.0 {
+        return true;
+    }
+    if y != trunc(y) {
+        return has_exact_root(x, 2, sqrt) && is_exact(pow_bounds(x, y));
+    }
+    let (m, e) = odd_parts(x);
+    if m == 1 {
+        return (-1074.0..=1023.0).contains(&(e as f64 * y));
+    }
+    if !(1.0..=52.0).contains(&y) {
+        return false;
+    }
+    let mut p: u64 = 1;
+    for _ in 0..y as u32 {
+        p = match p.checked_mul(m) {
+            Some(p) if p < 1 << 53 => p,
+            _ => return false,
+        };
+    }
+    let k = e * y as i64;
+    k >= -1074 && k + 64 - p.leading_zeros() as i64 <= 1024
+}
+
+pub(crate) fn erf_is_exact(x: f64) -> bool {
+    x == 0.0 || x.is_infinite()
+}
+
+// lgamma is zero at 1 and 2, and has poles at the non-positive integers.
+pub(crate) fn lgamma_is_exact(x: f64) -> bool {
+    x == 1.0 || x == 2.0 || x.is_infinite() || (x <= 0.0 && x == trunc(x))
+}
+
+// Γ is exact at its pole at zero and at 1 to 23, whose factorials fit in 53
+// bits.
+pub(crate) fn tgamma_is_exact(x: f64) -> bool {
+    x == 0.0 || ((1.0..=23.0).contains(&x) && x == trunc(x))
+}
@@ -1,27 +1,9 @@
 //! Fused multiply-add helper.
 //!
 //! Uses compile-time FMA selection to select hardware FMA where available.
-//! Falls back to a software-fused path using Dekker splitting (2^27+1) to
-//! track the product error and preserve correct rounding without libm.
-
-use super::{SPLIT, two_sum};
-
-#[inline(always)]
-fn split(a: f64) -> (f64, f64) {
-    let t = SPLIT * a;
-    let hi = t - (t - a);
-    let lo = a - hi;
-    (hi, lo)
-}
-
-#[inline(always)]
-fn two_prod(a: f64, b: f64) -> (f64, f64) {
-    let p = a * b;
-    let (ah, al) = split(a);
-    let (bh, bl) = split(b);
-    let err = ((ah * bh - p) + ah * bl + al * bh) + al * bl;
-    (p, err)
-}
+//! Falls back to a software-fused path, built from Dekker splitting (2^27+1)
+//! and integer arithmetic where that cannot be exact, that rounds once like
+//! the hardware instruction, without libm.
 
 #[cfg(target_arch = "x86_64")]
 #[target_feature(enable = "fma")]
@@ -37,13 +19,5 @@ pub fn fma(a: f64, b: f64, c: f64) -> f64 {
         // SAFETY: guarded by compile-time FMA availability.
         return unsafe { fma_f64(a, b, c) };
     }
-    if a.is_infinite() || b.is_infinite() {
-        return a * b + c;
-    }
-    if c.is_infinite() {
-        return c;
-    }
-    let (p, pe) = two_prod(a, b);
-    let (s, se) = two_sum(p, c);
-    (pe + se) + s
+    super::fma_soft(a, b, c)
 }
@@ -0,0 +1,218 @@
+//! Fresnel integrals S(x) = ∫_0^x sin(pi t^2/2) dt and
+//! C(x) = ∫_0^x cos(pi t^2/2) dt.
+//!
+//! Up to |x| = 2.25 (u = pi x^2/2 <= 8) the power series in u are summed in
+//! double-double. Larger arguments use the auxiliary functions of DLMF 7.5,
+//!   C(x) = 1/2 + f sin u - g cos u,  S(x) = 1/2 - f cos u - g sin u,
+//! with g + i f from the continued fraction of erfc on arg z = -pi/4, the
+//! convergent form of f ~ 1/(pi x), g ~ 1/(pi^2 x^3); past 2^26 those leading
+//! terms suffice. The phase u is reduced exactly through x^2 mod 4 before
+//! `sincos`.
+
+use super::double::{add_dd, divdd, divddd, muldd_acc, mulddd, norm_dd, twosum};
+use super::trig::sincos;
+use super::{fma_internal, roundeven_finite, scalbn_internal, with_nearest};
+
+// pi/2, pi/6 and 1/pi as double-doubles, 1/pi^2 as a double.
+const FRAC_PI_2_HI: f64 = f64::from_bits(0x3ff9_21fb_5444_2d18);
+const FRAC_PI_2_LO: f64 = f64::from_bits(0x3c91_a626_3314_5c07);
+const FRAC_PI_6_HI: f64 = f64::from_bits(0x3fe0_c152_382d_7366);
+const FRAC_PI_6_LO: f64 = f64::from_bits(0xbc8e_e691_3347_c2a6);
+const FRAC_1_PI_HI: f64 = f64::from_bits(0x3fd4_5f30_6dc9_c883);
+const FRAC_1_PI_LO: f64 = f64::from_bits(0xbc76_b01e_c541_7056);
+const FRAC_1_PI2: f64 = f64::from_bits(0x3fb9_f02f_6222_c720);
+
+// Up to this |x| the power series is summed directly.
+const SERIES_MAX: f64 = 2.25;
+// From this x on, f and g are the leading terms of their expansions.
+const ASYMPTOTIC_MIN: f64 = 67_108_864.0; // 2^26
+// From this x on, S(x) and C(x) round to 1/2.
+const HALF_MIN: f64 = 18_014_398_509_481_984.0; // 2^54
+// Below this |x|, C(x) = x and S(x) = pi x^3/6 to working precision.
+const TINY: f64 = f64::from_bits(0x3f10_0000_0000_0000); // 2^-14
+// Below this |x|, x^3 is computed on a scaled argument.
+const CUBE_TINY: f64 = f64::from_bits(0x2d30_0000_0000_0000); // 2^-300
+// Series summation stops once a term drops below this fraction of the sum.
+const SERIES_EPS: f64 = f64::from_bits(0x3970_0000_0000_0000); // 2^-104
+
+// v - 4 roundeven(v/4), which is exact.
+#[inline(always)]
+fn rem4(v: f64) -> f64 {
+    v - 4.0 * roundeven_finite(0.25 * v)
+}
+
+// (C(x), S(x)) as double-doubles for 0 < x <= 2.25 from
+//   C = x sum_k (-1)^k u^(2k) / ((2k)! (4k + 1)),
+//   S = x sum_k (-1)^k u^(2k+1) / ((2k + 1)! (4k + 3)),
+// summed together over the terms p_j = u^j / j!.
+fn series(x: f64, u: (f64, f64)) -> ((f64, f64), (f64, f64)) {
+    let (mut ph, mut pl) = (1.0, 0.0);
+    let (mut ch, mut cl) = (1.0, 0.0);
+    let (mut sh, mut sl) = (0.0, 0.0);
+    let mut j = 1u32;
+    loop {
+        ph = muldd_acc(ph, pl, u.0, u.1, &mut pl);
+        ph = divddd(ph, pl, j as f64, &mut pl);
+        let mut tl = 0.0;
+        let mut th = divddd(ph, pl, (2 * j + 1) as f64, &mut tl);
+        if j & 2 != 0 {
+            th = -th;
+            tl = -tl;
+        }
+        if j & 1 == 0 {
+            ch = add_dd(ch, cl, th, tl, &mut cl);
+        } else {
+            sh = add_dd(sh, sl, th, tl, &mut sl);
+        }
+        if j > 1 && th.abs() <= SERIES_EPS * sh.abs() {
+            break;
+        }
+        j += 1;
+    }
+    ch = mulddd(ch, cl, x, &mut cl);
+    sh = mulddd(sh, sl, x, &mut sl);
+    ((ch, cl), (sh, sl))
+}
+
+// Auxiliary functions (f, g) as double-doubles for x > 2.25.
+//
+// g + i f = x/2 / (b_0 - a_1 / (b_1 - a_2 / (b_2 - ...))) with
+// b_k = (4k + 1)/2 - iu and a_k = k (2k - 1)/2, evaluated backwards from a
+// depth that keeps the truncation error below 2^-70.
+fn aux_fg(x: f64, u: (f64, f64)) -> ((f64, f64), (f64, f64)) {
+    if x >= ASYMPTOTIC_MIN {
+        // The next terms are below 2^-100 relative.
+        let mut fl = 0.0;
+        let fh = divddd(FRAC_1_PI_HI, FRAC_1_PI_LO, x, &mut fl);
+        return ((fh, fl), (((FRAC_1_PI2 / x) / x) / x, 0.0));
+    }
+    let n = (320.0 / u.0) as u32 + 4;
+    let (mut rh, mut rl) = ((4 * n + 1) as f64 * 0.5, 0.0);
+    let (mut ih, mut il) = (-u.0, -u.1);
+    for k in (1..=n).rev() {
+        // t = (4k - 3)/2 - iu - a_k / t
+        let mut nl = 0.0;
+        let nh = norm_dd(rh, rl, ih, il, &mut nl);
+        let mut wl = 0.0;
+        let wh = divdd((k * (2 * k - 1)) as f64 * 0.5, 0.0, nh, nl, &mut wl);
+        let mut al = 0.0;
+        let ah = muldd_acc(wh, wl, rh, rl, &mut al);
+        rh = add_dd((4 * k - 3) as f64 * 0.5, 0.0, -ah, -al, &mut rl);
+        let ah = muldd_acc(wh, wl, ih, il, &mut al);
+        ih = add_dd(-u.0, -u.1, ah, al, &mut il);
+    }
+    // x/2 / t = x/2 (re t - i im t) / |t|^2
+    let mut nl = 0.0;
+    let nh = norm_dd(rh, rl, ih, il, &mut nl);
+    let (mut fl, mut gl) = (0.0, 0.0);
+    let fh = mulddd(ih, il, -0.5 * x, &mut fl);
+    let fh = divdd(fh, fl, nh, nl, &mut fl);
+    let gh = mulddd(rh, rl, 0.5 * x, &mut gl);
+    let gh = divdd(gh, gl, nh, nl, &mut gl);
+    ((fh, fl), (gh, gl))
+}
+
+// (C(x), S(x)) as double-doubles for 2^-14 <= x < 2^54.
+fn fresnel_cs(x: f64) -> ((f64, f64), (f64, f64)) {
+    let x2h = x * x;
+    let x2l = fma_internal(x, x, -x2h);
+    let mut ul = 0.0;
+    let uh = muldd_acc(FRAC_PI_2_HI, FRAC_PI_2_LO, x2h, x2l, &mut ul);
+    if x <= SERIES_MAX {
+        return series(x, (uh, ul));
+    }
+    let (f, g) = aux_fg(x, (uh, ul));
+    // u mod 2 pi = pi/2 (x^2 mod 4), with |x^2 mod 4| <= 2.
+    let mut rl = 0.0;
+    let rh = twosum(rem4(x2h), rem4(x2l), &mut rl);
+    let rh = rem4(rh);
+    let mut pl = 0.0;
+    let ph = muldd_acc(FRAC_PI_2_HI, FRAC_PI_2_LO, rh, rl, &mut pl);
+    let (s, c) = sincos(ph);
+    let (mut sin_l, mut cos_l) = (0.0, 0.0);
+    let sin_h = twosum(s, c * pl, &mut sin_l);
+    let cos_h = twosum(c, -s * pl, &mut cos_l);
+    // C = 1/2 + f sin u - g cos u, S = 1/2 - f cos u - g sin u
+    let (mut al, mut bl) = (0.0, 0.0);
+    let ah = muldd_acc(f.0, f.1, sin_h, sin_l, &mut al);
+    let bh = muldd_acc(g.0, g.1, cos_h, cos_l, &mut bl);
+    let mut tl = 0.0;
+    let th = add_dd(ah, al, -bh, -bl, &mut tl);
+    let mut cl = 0.0;
+    let ch = add_dd(0.5, 0.0, th, tl, &mut cl);
+    let ah = muldd_acc(f.0, f.1, cos_h, cos_l, &mut al);
+    let bh = muldd_acc(g.0, g.1, sin_h, sin_l, &mut bl);
+    let th = add_dd(ah, al, bh, bl, &mut tl);
+    let mut sl = 0.0;
+    let sh = add_dd(0.5, 0.0, -th, -tl, &mut sl);
+    ((ch, cl), (sh, sl))
+}
+
+// pi x^3 / 6 for |x| < 2^-14.
+#[inline(always)]
+fn s_tiny(x: f64) -> f64 {
+    let (y, k) = if x.abs() < CUBE_TINY {
+        (scalbn_internal(x, 400), -1200)
+    } else {
+        (x, 0)
+    };
+    let y2h = y * y;
+    let y2l = fma_internal(y, y, -y2h);
+    let mut y3l = 0.0;
+    let y3h = mulddd(y2h, y2l, y, &mut y3l);
+    let mut rl = 0.0;
+    let rh = muldd_acc(FRAC_PI_6_HI, FRAC_PI_6_LO, y3h, y3l, &mut rl);
+    scalbn_internal(rh + rl, k)
+}
+
+/// Fresnel sine integral S(x) = ∫_0^x sin(pi t^2/2) dt.
+///
+/// Odd, with S(±inf) = ±1/2.
+#[inline]
+pub fn fresnel_s(x: f64) -> f64 {
+    with_nearest(x, fresnel_s_nearest)
+}
+
+#[inline]
+fn fresnel_s_nearest(x: f64) -> f64 {
+    if x.is_nan() || x == 0.0 {
+        return x;
+    }
+    let ax = x.abs();
+    if ax < TINY {
+        return s_tiny(x);
+    }
+    let r = if ax >= HALF_MIN {
+        0.5
+    } else {
+        let (_, (h, l)) = fresnel_cs(ax);
+        h + l
+    };
+    if x < 0.0 { -r } else { r }
+}
+
+/// Fresnel cosine integral C(x) = ∫_0^x cos(pi t^2/2) dt.
+///
+/// Odd, with C(±inf) = ±1/2.
+#[inline]
+pub fn fresnel_c(x: f64) -> f64 {
+    with_nearest(x, fresnel_c_nearest)
+}
+
+#[inline]
+fn fresnel_c_nearest(x: f64) -> f64 {
+    if x.is_nan() {
+        return x;
+    }
+    let ax = x.abs();
+    if ax < TINY {
+        return x;
+    }
+    let r = if ax >= HALF_MIN {
+        0.5
+    } else {
+        let ((h, l), _) = fresnel_cs(ax);
+        h + l
+    };
+    if x < 0.0 { -r } else { r }
+}
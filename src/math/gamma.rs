@@ -5,120 +5,9 @@
 //! error below 1 ULP in difficult regions. Constants and tables are sourced from
 //! glibc/core-math (see glibc/ directory).
 
-use super::{asdouble, exp, expm1, fasttwosum, fma_internal, roundeven_finite};
-use super::{floor, rint};
-
-// === ddcoremath helpers (ported) ===
-
-#[inline(always)]
-fn fasttwosub(x: f64, y: f64, e: &mut f64) -> f64 {
-    let s = x - y;
-    let z = x - s;
-    *e = z - y;
-    s
-}
-
-#[inline(always)]
-fn twosum(x: f64, y: f64, e: &mut f64) -> f64 {
-    if x.abs() > y.abs() {
-        fasttwosum(x, y, e)
-    } else {
-        fasttwosum(y, x, e)
-    }
-}
-
-#[inline(always)]
-fn fastsum(xh: f64, xl: f64, yh: f64, yl: f64, e: &mut f64) -> f64 {
-    let mut sl = 0.0;
-    let sh = fasttwosum(xh, yh, &mut sl);
-    *e = (xl + yl) + sl;
-    sh
-}
-
-#[inline(always)]
-fn sumdd(xh: f64, xl: f64, yh: f64, yl: f64, e: &mut f64) -> f64 {
-    let mut sl = 0.0;
-    let sh = if xh.abs() > yh.abs() {
-        fasttwosum(xh, yh, &mut sl)
-    } else {
-        fasttwosum(yh, xh, &mut sl)
-    };
-    *e = (xl + yl) + sl;
-    sh
-}
-
-#[inline(always)]
-fn adddd(xh: f64, xl: f64, ch: f64, cl: f64, l: &mut f64) -> f64 {
-    let s = xh + ch;
-    let d = s - xh;
-    *l = ((ch - d) + (xh + (d - s))) + (xl + cl);
-    s
-}
-
-#[inline(always)]
-fn muldd_acc(xh: f64, xl: f64, ch: f64, cl: f64, l: &mut f64) -> f64 {
-    let ahlh = ch * xl;
-    let alhh = cl * xh;
-    let ahhh = ch * xh;
-    let mut ahhl = fma_internal(ch, xh, -ahhh);
-    ahhl += alhh + ahlh;
-    let chh = ahhh + ahhl;
-    *l = (ahhh - chh) + ahhl;
-    chh
-}
-
-#[inline(always)]
-fn muldd_acc2(xh: f64, xl: f64, ch: f64, cl: f64, l: &mut f64) -> f64 {
-    let ahlh = ch * xl;
-    let alhh = cl * xh;
-    let ahhh = ch * xh;
-    let mut ahhl = fma_internal(ch, xh, -ahhh);
-    ahhl += alhh + ahlh;
-    fasttwosum(ahhh, ahhl, l)
-}
-
-#[inline(always)]
-fn muldd2(xh: f64, xl: f64, ch: f64, cl: f64, l: &mut f64) -> f64 {
-    let ahhh = ch * xh;
-    *l = (ch * xl + cl * xh) + fma_internal(ch, xh, -ahhh);
-    ahhh
-}
-
-#[inline(always)]
-fn muldd3(xh: f64, xl: f64, yh: f64, yl: f64, l: &mut f64) -> f64 {
-    let ch = xh * yh;
-    let cl1 = fma_internal(xh, yh, -ch);
-    let tl0 = xl * yl;
-    let tl1 = tl0 + xh * yl;
-    let cl2 = tl1 + xl * yh;
-    let cl3 = cl1 + cl2;
-    fasttwosum(ch, cl3, l)
-}
-
-#[inline(always)]
-fn mulddd(xh: f64, xl: f64, ch: f64, l: &mut f64) -> f64 {
-    let ahlh = ch * xl;
-    let ahhh = ch * xh;
-    let mut ahhl = fma_internal(ch, xh, -ahhh);
-    ahhl += ahlh;
-    let chh = ahhh + ahhl;
-    *l = (ahhh - chh) + ahhl;
-    chh
-}
-
-#[inline(always)]
-fn mulddd2(x: f64, ch: f64, cl: f64, l: &mut f64) -> f64 {
-    let ahhh = ch * x;
-    *l = cl * x + fma_internal(ch, x, -ahhh);
-    ahhh
-}
-
-#[inline(always)]
-fn mulddd3(xh: f64, xl: f64, ch: f64, l: &mut f64) -> f64 {
-    let hh = xh * ch;
-    *l = fma_internal(ch, xh, -hh) + xl * ch;
-    hh
-}
+use super::double::{fastsum, muldd_acc, muldd_acc2, muldd2, mulddd2, sumdd, twosum};
+use super::floor;
+use super::{asdouble, exp, expm1, fasttwosum, fma_internal, roundeven_finite, with_nearest};
 
 #[inline(always)]
 fn polydd(xh: f64, xl: f64, n: usize, c: &[[f64; 2]], l: &mut f64) -> f64 {
@@ -4527,7 +4416,7 @@ fn as_logd(x: f64, l: &mut f64) -> f64 {
     lh
 }
 
-fn as_logd_accurate(x: f64, l: &mut f64, l2: &mut f64) -> f64 {
+pub(crate) fn as_logd_accurate(x: f64, l: &mut f64, l2: &mut f64) -> f64 {
     let mut t = (x).to_bits();
     let mut ex = (t >> 52) as i32;
     if ex == 0 {
@@ -5179,6 +5068,9 @@ fn tgamma_fast(x: f64) -> Option<f64> {
         let mut cl = x * c0;
         let ch = polyddd(x, TGAMMA_SMALL_CC.len(), &TGAMMA_SMALL_CC, &mut cl);
         let mut fh = 1.0 / x;
+        if fh.is_infinite() {
+            return Some(fh);
+        }
         let dh = fma_internal(fh, -x, 1.0);
         let mut fl = dh * fh;
         let mut fll = fma_internal(fl, -x, dh) * fh;
@@ -5310,7 +5202,7 @@ fn tgamma_fast(x: f64) -> Option<f64> {
 }
 
 #[inline(always)]
-fn sinpi_parts(x: f64, sl: &mut f64) -> f64 {
+pub(crate) fn sinpi_parts(x: f64, sl: &mut f64) -> f64 {
     let k = floor(x);
     let phi = x - k;
     let sh = as_sinpipid_accurate(phi, sl);
@@ -5323,7 +5215,7 @@ fn sinpi_parts(x: f64, sl: &mut f64) -> f64 {
 
 #[inline(always)]
 fn tgamma_half(x: f64) -> f64 {
-    let n = rint(x - 0.5) as i32;
+    let n = roundeven_finite(x - 0.5) as i32;
     let mut y = f64::from_bits(0x3ffc5bf891b4ef6a); // sqrt(pi)
     if n >= 0 {
         for i in 0..n {
@@ -5357,7 +5249,7 @@ fn tgamma_quarter_dd(x: f64, frac: f64) -> (f64, f64) {
             f64::from_bits(0x3c43d7a9256698c6),
         )
     };
-    let n = rint(x - base) as i32;
+    let n = roundeven_finite(x - base) as i32;
     if n >= 0 {
         for i in 0..n {
             let mut l = 0.0;
@@ -5400,7 +5292,7 @@ fn eval_lgamma_acc_block(
 
 #[inline(never)]
 #[allow(unused_assignments)]
-fn as_lgamma_accurate_dd(mut x: f64) -> (f64, f64) {
+pub(crate) fn as_lgamma_accurate_dd(mut x: f64) -> (f64, f64) {
     let sx = x;
     let mut fh = 0.0;
     let mut fl = 0.0;
@@ -5444,7 +5336,10 @@ fn as_lgamma_accurate_dd(mut x: f64) -> (f64, f64) {
             fl *= 1.0 + copysign(f64::from_bits(0x3cb0000000000000), fl) * copysign(1.0, fll);
         }
     } else {
-        if (x - 0.5).abs() < f64::from_bits(0x3fd0000000000000) {
+        // The intervals are closed: a quarter-integer such as 1.75, which the
+        // reflection in zeta passes here, would otherwise fall through to the
+        // asymptotic expansion.
+        if (x - 0.5).abs() <= f64::from_bits(0x3fd0000000000000) {
             // 0x1p-2
             fh = polydddfst(x - 0.5, LGAMMA_B.len(), &LGAMMA_B, &mut fl);
             if sx > 0.0 {
@@ -5456,7 +5351,7 @@ fn as_lgamma_accurate_dd(mut x: f64) -> (f64, f64) {
                 fh = twosum(fh, -lh, &mut lh_err);
                 fl = sumdd(fl, fll, lh_err, 0.0, &mut fll);
             }
-        } else if (x - 2.5).abs() < f64::from_bits(0x3fd0000000000000) {
+        } else if (x - 2.5).abs() <= f64::from_bits(0x3fd0000000000000) {
             fh = polydddfst(x - 2.5, LGAMMA_B.len(), &LGAMMA_B, &mut fl);
             let mut lll = 0.0;
             let mut ll = 0.0;
@@ -5472,7 +5367,7 @@ fn as_lgamma_accurate_dd(mut x: f64) -> (f64, f64) {
                 fh = twosum(fh, lh2, &mut lh_err2);
                 fl = sumdd(fl, fll, lh_err2, 0.0, &mut fll);
             }
-        } else if (x - 3.5).abs() < f64::from_bits(0x3fd0000000000000) {
+        } else if (x - 3.5).abs() <= f64::from_bits(0x3fd0000000000000) {
             let mut l2ll = 0.0;
             let mut l2l = 0.0;
             let l2h = as_logd_accurate(x - 2.0, &mut l2l, &mut l2ll);
@@ -5497,7 +5392,7 @@ fn as_lgamma_accurate_dd(mut x: f64) -> (f64, f64) {
                 fh = twosum(fh, lh, &mut lh_err);
                 fl = sumdd(fl, fll, lh_err, 0.0, &mut fll);
             }
-        } else if (x - 1.0).abs() < f64::from_bits(0x3fd0000000000000) {
+        } else if (x - 1.0).abs() <= f64::from_bits(0x3fd0000000000000) {
             fh = polydddfst(x - 1.0, LGAMMA_C0.len(), &LGAMMA_C0, &mut fl);
             fh = mulddd2(x - 1.0, fh, fl, &mut fl);
             if sx < 0.0 {
@@ -5509,7 +5404,7 @@ fn as_lgamma_accurate_dd(mut x: f64) -> (f64, f64) {
                 fh = twosum(fh, lh, &mut lh_err);
                 fl = sumdd(fl, fll, lh_err, 0.0, &mut fll);
             }
-        } else if (x - 1.5).abs() < f64::from_bits(0x3fd0000000000000) {
+        } else if (x - 1.5).abs() <= f64::from_bits(0x3fd0000000000000) {
             fh = polydddfst(x - 1.5, LGAMMA_B.len(), &LGAMMA_B, &mut fl);
             if sx < 0.0 {
                 let mut lll = 0.0;
@@ -5520,7 +5415,7 @@ fn as_lgamma_accurate_dd(mut x: f64) -> (f64, f64) {
                 fh = twosum(fh, lh, &mut lh_err);
                 fl = sumdd(fl, fll, lh_err, 0.0, &mut fll);
             }
-        } else if (x - 2.0).abs() < f64::from_bits(0x3fd0000000000000) {
+        } else if (x - 2.0).abs() <= f64::from_bits(0x3fd0000000000000) {
             let mut lll = 0.0;
             let mut ll = 0.0;
             let lh = as_logd_accurate(x - 1.0, &mut ll, &mut lll);
@@ -5537,7 +5432,7 @@ fn as_lgamma_accurate_dd(mut x: f64) -> (f64, f64) {
                 fh = twosum(fh, lh2, &mut lh_err2);
                 fl = sumdd(fl, fll, lh_err2, 0.0, &mut fll);
             }
-        } else if (x - 3.0).abs() < f64::from_bits(0x3fd0000000000000) {
+        } else if (x - 3.0).abs() <= f64::from_bits(0x3fd0000000000000) {
             let mut l2ll = 0.0;
             let mut l2l = 0.0;
             let l2h = as_logd_accurate(x - 2.0, &mut l2l, &mut l2ll);
@@ -5985,6 +5880,11 @@ fn ieee754_lgamma_r(x: f64, signgamp: &mut i32) -> f64 {
 }
 #[inline(always)]
 pub fn lgamma(x: f64) -> f64 {
+    with_nearest(x, lgamma_nearest)
+}
+
+#[inline(always)]
+fn lgamma_nearest(x: f64) -> f64 {
     let mut sign = 1;
     ieee754_lgamma_r(x, &mut sign)
 }
@@ -6021,7 +5921,8 @@ fn tgamma_slow(x: f64) -> f64 {
     }
     if x < 0.0 {
         let frac = x - floor(x);
-        if frac == 0.25 || frac == 0.5 || frac == 0.75 {
+        // Γ(1 - x) overflows below -170.6.
+        if x > -170.0 && (frac == 0.25 || frac == 0.5 || frac == 0.75) {
             let mut sl = 0.0;
             let sh = sinpi_parts(x, &mut sl);
             let (yh, yl) = tgamma_pos_dd(1.0 - x);
@@ -6047,6 +5948,11 @@ fn tgamma_slow(x: f64) -> f64 {
 
 #[inline(always)]
 pub fn tgamma(x: f64) -> f64 {
+    with_nearest(x, tgamma_nearest)
+}
+
+#[inline(always)]
+fn tgamma_nearest(x: f64) -> f64 {
     if x.is_nan() {
         return x;
     }
@@ -0,0 +1,283 @@
+//! Half-precision storage types.
+//!
+//! [`F16`] is IEEE 754 binary16 (5 exponent bits, 10 fraction bits) and
+//! [`BF16`] is bfloat16 (8 exponent bits, 7 fraction bits: the top half of
+//! an `f32`). Both are storage formats: values widen exactly to `f32` and
+//! `f64`, and narrow from them with a single rounding to nearest, ties to
+//! even, through the subnormals and to infinity on overflow. A NaN keeps
+//! its sign and as much of its payload as fits, and is quieted.
+//!
+//! The functions evaluate in `f64` and round once to the 16-bit format.
+//! The `f64` results are within a few ulps, and with 42 or more extra bits
+//! that error cannot move any of the 65,536 results across a rounding
+//! boundary (exhaustively checked against MPFR), so they are correctly
+//! rounded. Like the conversions they round to nearest whatever the
+//! hardware rounding mode.
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops::Neg;
+
+use super::{erf, exp, fabs, ln, sqrt, tanh};
+
+const SIGN: u16 = 0x8000;
+
+// `x` rounded to nearest-even in the format with `w` exponent bits and `p`
+// fraction bits.
+fn narrow(x: f64, w: u32, p: u32) -> u16 {
+    let b = x.to_bits();
+    let sign = (b >> 48) as u16 & SIGN;
+    let inf = ((1u16 << w) - 1) << p;
+    let a = b & !(1 << 63);
+    if a > 0x7ff0 << 48 {
+        let payload = (a >> (52 - p)) as u16 & ((1 << p) - 1);
+        return sign | inf | 1 << (p - 1) | payload;
+    }
+    if a == 0x7ff0 << 48 {
+        return sign | inf;
+    }
+    if a == 0 {
+        return sign;
+    }
+    let (m, e) = match a >> 52 {
+        0 => (a, -1074),
+        be => (a & ((1 << 52) - 1) | 1 << 52, be as i32 - 1075),
+    };
+    let bias = (1 << (w - 1)) - 1;
+    // The exponent of the result's leading bit, at least the normals' one;
+    // m × 2^e becomes q × 2^(lead - p) plus the dropped bits.
+    let lead = (63 - m.leading_zeros() as i32 + e).max(1 - bias);
+    let s = lead - p as i32 - e;
+    if s > 63 {
+        return sign;
+    }
+    let (q, rest, half) = (m >> s, m & ((1 << s) - 1), 1 << (s - 1));
+    let q = q + (rest > half || rest == half && q & 1 == 1) as u64;
+    // A subnormal q has no leading bit and a carry out of the fraction
+    // bumps the exponent, so the biased exponent less one plus q is right.
+    let bits = ((lead + bias - 1) as u64) << p;
+    sign | (bits + q).min(inf as u64) as u16
+}
+
+// The value of `h`, exactly.
+fn widen(h: u16, w: u32, p: u32) -> f64 {
+    let sign = ((h & SIGN) as u64) << 48;
+    let top = (1 << w) - 1;
+    let (be, f) = ((h & !SIGN) >> p, (h & ((1 << p) - 1)) as u64);
+    if be == top {
+        let quiet = if f == 0 { 0 } else { 1 << 51 };
+        return f64::from_bits(sign | 0x7ff << 52 | quiet | f << (52 - p));
+    }
+    if be == 0 && f == 0 {
+        return f64::from_bits(sign);
+    }
+    let bias = (1 << (w - 1)) - 1;
+    let (m, e) = if be == 0 {
+        (f, 1 - bias - p as i32)
+    } else {
+        (f | 1 << p, be as i32 - bias - p as i32)
+    };
+    let v = m as f64 * f64::from_bits(((e + 1023) as u64) << 52);
+    f64::from_bits(sign | v.to_bits())
+}
+
+macro_rules! half_type {
+    ($(#[$doc:meta])* $name:ident, $w:expr, $p:expr, $max:expr, $tag:expr) => {
+        $(#[$doc])*
+        #[derive(Clone, Copy, Default)]
+        pub struct $name(u16);
+
+        impl $name {
+            pub const ZERO: Self = Self(0);
+            pub const ONE: Self = Self((((1 << ($w - 1)) - 1) as u16) << $p);
+            pub const INFINITY: Self = Self((((1 << $w) - 1) as u16) << $p);
+            pub const NEG_INFINITY: Self = Self(SIGN | Self::INFINITY.0);
+            pub const NAN: Self = Self(Self::INFINITY.0 | 1 << ($p - 1));
+            /// The largest finite value.
+            pub const MAX: Self = Self($max);
+            /// The smallest positive normal value.
+            pub const MIN_POSITIVE: Self = Self(1 << $p);
+            /// The difference between 1 and the next value.
+            pub const EPSILON: Self = Self(Self::ONE.0 - ($p << $p));
+
+            #[inline]
+            pub const fn from_bits(bits: u16) -> Self {
+                Self(bits)
+            }
+
+            #[inline]
+            pub const fn to_bits(self) -> u16 {
+                self.0
+            }
+
+            /// `x` rounded to nearest, ties to even.
+            #[inline]
+            pub fn from_f32(x: f32) -> Self {
+                // `x as f64` may quiet a signaling NaN and drop its sign, so
+                // a NaN is narrowed from its own bits.
+                if x.is_nan() {
+                    let b = x.to_bits();
+                    let sign = (b >> 16) as u16 & SIGN;
+                    let f = (b >> (23 - $p)) as u16 & ((1 << $p) - 1);
+                    Self(sign | Self::NAN.0 | f)
+                } else {
+                    Self::from_f64(x as f64)
+                }
+            }
+
+            /// `x` rounded to nearest, ties to even.
+            #[inline]
+            pub fn from_f64(x: f64) -> Self {
+                Self(narrow(x, $w, $p))
+            }
+
+            /// The value as an `f32`, exactly; a NaN keeps its payload.
+            #[inline]
+            pub fn to_f32(self) -> f32 {
+                if self.is_nan() {
+                    let sign = ((self.0 & SIGN) as u32) << 16;
+                    let f = (self.0 & ((1 << $p) - 1)) as u32;
+                    f32::from_bits(sign | 0x7fc0_0000 | f << (23 - $p))
+                } else {
+                    self.to_f64() as f32
+                }
+            }
+
+            /// The value as an `f64`, exactly; a NaN keeps its payload.
+            #[inline]
+            pub fn to_f64(self) -> f64 {
+                widen(self.0, $w, $p)
+            }
+
+            #[inline]
+            pub const fn is_nan(self) -> bool {
+                self.0 & !SIGN > Self::INFINITY.0
+            }
+
+            #[inline]
+            pub const fn is_infinite(self) -> bool {
+                self.0 & !SIGN == Self::INFINITY.0
+            }
+
+            #[inline]
+            pub const fn is_finite(self) -> bool {
+                self.0 & !SIGN < Self::INFINITY.0
+            }
+
+            #[inline]
+            pub const fn is_sign_negative(self) -> bool {
+                self.0 & SIGN != 0
+            }
+
+            #[inline]
+            pub const fn abs(self) -> Self {
+                Self(self.0 & !SIGN)
+            }
+
+            // f at the value, rounded; a NaN comes back quieted, payload kept.
+            #[inline]
+            fn eval(self, f: impl FnOnce(f64) -> f64) -> Self {
+                if self.is_nan() {
+                    return Self(self.0 | 1 << ($p - 1));
+                }
+                Self::from_f64(f(self.to_f64()))
+            }
+
+            /// e^self.
+            pub fn exp(self) -> Self {
+                self.eval(|x| exp(x))
+            }
+
+            /// The natural logarithm.
+            pub fn ln(self) -> Self {
+                self.eval(|x| ln(x))
+            }
+
+            pub fn tanh(self) -> Self {
+                self.eval(|x| tanh(x))
+            }
+
+            pub fn erf(self) -> Self {
+                self.eval(|x| erf(x))
+            }
+
+            /// The logistic function 1 / (1 + e^-self).
+            pub fn sigmoid(self) -> Self {
+                self.eval(|x| 1.0 / (1.0 + exp(-x)))
+            }
+
+            /// The square root, NaN below zero.
+            pub fn sqrt(self) -> Self {
+                self.eval(|x| sqrt(x))
+            }
+
+            /// 1 / sqrt(self): +∞ at ±0 (as C23's `rsqrt`), zero at +∞ and NaN
+            /// below zero.
+            pub fn rsqrt(self) -> Self {
+                self.eval(|x| 1.0 / fabs(sqrt(x)))
+            }
+        }
+
+        impl PartialEq for $name {
+            #[inline]
+            fn eq(&self, o: &Self) -> bool {
+                self.to_f32() == o.to_f32()
+            }
+        }
+
+        impl PartialOrd for $name {
+            #[inline]
+            fn partial_cmp(&self, o: &Self) -> Option<Ordering> {
+                self.to_f32().partial_cmp(&o.to_f32())
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, concat!($tag, "({:#06x} ≈ {:e})"), self.0, self.to_f32())
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+
+            #[inline]
+            fn neg(self) -> Self {
+                Self(self.0 ^ SIGN)
+            }
+        }
+
+        impl From<$name> for f32 {
+            #[inline]
+            fn from(x: $name) -> f32 {
+                x.to_f32()
+            }
+        }
+
+        impl From<$name> for f64 {
+            #[inline]
+            fn from(x: $name) -> f64 {
+                x.to_f64()
+            }
+        }
+    };
+}
+
+half_type!(
+    /// An IEEE 754 binary16 number, from 2^-24 to 65504 with 11 bits of
+    /// precision.
+    F16,
+    5,
+    10,
+    0x7bff,
+    "F16"
+);
+
+half_type!(
+    /// A bfloat16 number, the range of an `f32` with 8 bits of precision.
+    BF16,
+    8,
+    7,
+    0x7f7f,
+    "BF16"
+);
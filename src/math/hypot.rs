@@ -3,7 +3,7 @@
 //! Scales inputs to avoid overflow/underflow, orders by magnitude, then computes
 //! sqrt(x^2+y^2) with guarded squaring. This follows fdlibm-style stable formulas.
 
-use super::{fma_available, fma_internal, sqrt};
+use super::{fma_available, fma_internal, sqrt, with_nearest};
 
 const SCALE: f64 = f64::from_bits(0x1a70_0000_0000_0000); // 2^-600
 const LARGE_VAL: f64 = f64::from_bits(0x5fe0_0000_0000_0000); // 2^511
@@ -39,6 +39,11 @@ fn kernel(ax: f64, ay: f64) -> f64 {
 
 #[inline]
 pub fn hypot(x: f64, y: f64) -> f64 {
+    with_nearest((x, y), |(x, y)| hypot_nearest(x, y))
+}
+
+#[inline]
+fn hypot_nearest(x: f64, y: f64) -> f64 {
     let mut ax = x.abs();
     let mut ay = y.abs();
 
@@ -0,0 +1,676 @@
+//! Interval arithmetic on the directed-rounding functions.
+//!
+//! An `Interval` is a closed, possibly unbounded set of reals [lo, hi] or the
+//! empty set, with the set-based semantics of IEEE 1788: an operation returns
+//! an enclosure of the values it takes at the points of its operands that lie
+//! in its domain, and the empty interval when there are none. So
+//! `sqrt([-4, 4])` is [0, 2], `ln([0, 1])` is [-∞, 0], `ln([-2, -1])` is
+//! empty and `[1, 2] / [-1, 1]` is the entire line.
+//!
+//! Endpoints are rounded outward: `+` and `-` from the exact error of the
+//! nearest sum, `×` from the exact product, `÷` from the sign of the exact
+//! remainder, and the functions through their `_rd`/`_ru` variants, so
+//! every result contains the exact image. Monotone functions take their
+//! endpoints' images; `sin`, `cos` and `tan` locate the multiples of π/2 in
+//! the interval by Payne-Hanek reduction of its endpoints, so an extremum or
+//! pole is never missed however close it lies to an endpoint; `lgamma` uses
+//! its minimum on the positive axis and, below zero, the reflection formula
+//! together with convexity between poles.
+
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+use super::mp::{Mp, Round, reduce_pio2};
+use super::two_sum;
+use super::{
+    acos_rd, acos_ru, acosh_rd, acosh_ru, asin_rd, asin_ru, asinh_rd, asinh_ru, atan_rd, atan_ru,
+    atan2_rd, atan2_ru, atanh_rd, atanh_ru, cbrt_rd, cbrt_ru, cos_rd, cos_ru, cosh_rd, cosh_ru,
+    erf_rd, erf_ru, erfc_rd, erfc_ru, exp_rd, exp_ru, exp2_rd, exp2_ru, exp10_rd, exp10_ru,
+    expm1_rd, expm1_ru, floor, hypot_rd, hypot_ru, lgamma_rd, lgamma_ru, ln_rd, ln_ru, log1p_rd,
+    log1p_ru, log2_rd, log2_ru, log10_rd, log10_ru, pow_rd, pow_ru, sin_rd, sin_ru, sinh_rd,
+    sinh_ru, sqrt_rd, sqrt_ru, tan_rd, tan_ru, tanh_rd, tanh_ru,
+};
+
+// Doubles enclosing π, π/2, 2π and ln π.
+const PI_LO: f64 = f64::from_bits(0x400921fb54442d18);
+const PI_HI: f64 = f64::from_bits(0x400921fb54442d19);
+const PI_2_LO: f64 = f64::from_bits(0x3ff921fb54442d18);
+const PI_2_HI: f64 = f64::from_bits(0x3ff921fb54442d19);
+const TWO_PI_LO: f64 = f64::from_bits(0x401921fb54442d18);
+const LN_PI_LO: f64 = f64::from_bits(0x3ff250d048e7a1bd);
+const LN_PI_HI: f64 = f64::from_bits(0x3ff250d048e7a1be);
+// lgamma decreases up to its minimum at 1.4616321449683623..., which lies
+// strictly between these doubles, and increases after it.
+const LGAMMA_ARGMIN_LO: f64 = f64::from_bits(0x3ff762d86356be3f);
+const LGAMMA_ARGMIN_HI: f64 = f64::from_bits(0x3ff762d86356be40);
+// A lower bound on the minimum, -0.1214862905358496080955...
+const LGAMMA_MIN_LO: f64 = f64::from_bits(0xbfbf19b9bcc38a42);
+
+/// A closed interval of reals [`lo`, `hi`], possibly unbounded, or the empty
+/// set.
+///
+/// The empty set is [+∞, -∞]; every other interval has `lo <= hi`,
+/// `lo < +∞` and `hi > -∞`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Interval {
+    /// Lower bound.
+    pub lo: f64,
+    /// Upper bound.
+    pub hi: f64,
+}
+
+impl Interval {
+    /// The empty set.
+    pub const EMPTY: Self = Self {
+        lo: f64::INFINITY,
+        hi: f64::NEG_INFINITY,
+    };
+
+    /// The whole real line [-∞, +∞].
+    pub const ENTIRE: Self = Self {
+        lo: f64::NEG_INFINITY,
+        hi: f64::INFINITY,
+    };
+
+    /// [`lo`, `hi`], or the empty set unless `lo <= hi`, `lo < +∞` and
+    /// `hi > -∞`.
+    #[inline]
+    pub fn new(lo: f64, hi: f64) -> Self {
+        if lo <= hi && lo < f64::INFINITY && hi > f64::NEG_INFINITY {
+            Self { lo, hi }
+        } else {
+            Self::EMPTY
+        }
+    }
+
+    /// [`x`, `x`], or the empty set for an infinite or NaN `x`.
+    #[inline]
+    pub fn point(x: f64) -> Self {
+        Self::new(x, x)
+    }
+
+    #[inline]
+    pub fn is_empty(self) -> bool {
+        self.lo > self.hi
+    }
+
+    #[inline]
+    pub fn is_entire(self) -> bool {
+        self.lo == f64::NEG_INFINITY && self.hi == f64::INFINITY
+    }
+
+    /// Whether `x` lies in the interval.
+    #[inline]
+    pub fn contains(self, x: f64) -> bool {
+        self.lo <= x && x <= self.hi
+    }
+
+    /// The smallest interval containing both.
+    pub fn hull(self, o: Self) -> Self {
+        if self.is_empty() {
+            return o;
+        }
+        if o.is_empty() {
+            return self;
+        }
+        Self::new(self.lo.min(o.lo), self.hi.max(o.hi))
+    }
+
+    pub fn intersection(self, o: Self) -> Self {
+        if self.is_empty() || o.is_empty() {
+            return Self::EMPTY;
+        }
+        Self::new(self.lo.max(o.lo), self.hi.min(o.hi))
+    }
+
+    /// {|x| : x in self}.
+    pub fn abs(self) -> Self {
+        if self.is_empty() {
+            return self;
+        }
+        Self::new(mig(self), mag(self))
+    }
+
+    // The image under a nondecreasing function.
+    fn increasing(self, rd: fn(f64) -> f64, ru: fn(f64) -> f64) -> Self {
+        if self.is_empty() {
+            return self;
+        }
+        Self::new(rd(self.lo), ru(self.hi))
+    }
+
+    // The image under a nonincreasing function.
+    fn decreasing(self, rd: fn(f64) -> f64, ru: fn(f64) -> f64) -> Self {
+        if self.is_empty() {
+            return self;
+        }
+        Self::new(rd(self.hi), ru(self.lo))
+    }
+
+    pub fn sqrt(self) -> Self {
+        self.intersection(Self::new(0.0, f64::INFINITY))
+            .increasing(sqrt_rd, sqrt_ru)
+    }
+
+    pub fn cbrt(self) -> Self {
+        self.increasing(cbrt_rd, cbrt_ru)
+    }
+
+    /// `x^y` for `x >= 0`, with `0^y` defined for `y > 0` only.
+    pub fn pow(self, y: Self) -> Self {
+        let x = self.intersection(Self::new(0.0, f64::INFINITY));
+        if x.is_empty() || y.is_empty() {
+            return Self::EMPTY;
+        }
+        // +0, as pow(-0, y) can be negative.
+        let (xl, xh) = (x.lo + 0.0, x.hi + 0.0);
+        if xh == 0.0 {
+            return if y.hi > 0.0 {
+                Self::point(0.0)
+            } else {
+                Self::EMPTY
+            };
+        }
+        // x^y is monotone in each argument, so the extremes are at corners;
+        // pow's limits at 0 and ∞ are those of the nearby points in domain.
+        let corners = [(xl, y.lo), (xl, y.hi), (xh, y.lo), (xh, y.hi)];
+        let lo = corners
+            .iter()
+            .map(|&(a, b)| pow_rd(a, b))
+            .fold(f64::INFINITY, f64::min);
+        let hi = corners
+            .iter()
+            .map(|&(a, b)| pow_ru(a, b))
+            .fold(f64::NEG_INFINITY, f64::max);
+        Self::new(lo, hi)
+    }
+
+    /// √(x² + y²).
+    pub fn hypot(self, y: Self) -> Self {
+        if self.is_empty() || y.is_empty() {
+            return Self::EMPTY;
+        }
+        Self::new(hypot_rd(mig(self), mig(y)), hypot_ru(mag(self), mag(y)))
+    }
+
+    pub fn exp(self) -> Self {
+        self.increasing(exp_rd, exp_ru)
+    }
+
+    pub fn exp2(self) -> Self {
+        self.increasing(exp2_rd, exp2_ru)
+    }
+
+    pub fn exp10(self) -> Self {
+        self.increasing(exp10_rd, exp10_ru)
+    }
+
+    pub fn expm1(self) -> Self {
+        self.increasing(expm1_rd, expm1_ru)
+    }
+
+    // A logarithm over (lower, +∞].
+    fn log(self, lower: f64, rd: fn(f64) -> f64, ru: fn(f64) -> f64) -> Self {
+        let x = self.intersection(Self::new(lower, f64::INFINITY));
+        if x.hi == lower {
+            return Self::EMPTY;
+        }
+        x.increasing(rd, ru)
+    }
+
+    /// The natural logarithm.
+    pub fn ln(self) -> Self {
+        self.log(0.0, ln_rd, ln_ru)
+    }
+
+    pub fn log2(self) -> Self {
+        self.log(0.0, log2_rd, log2_ru)
+    }
+
+    pub fn log10(self) -> Self {
+        self.log(0.0, log10_rd, log10_ru)
+    }
+
+    pub fn log1p(self) -> Self {
+        self.log(-1.0, log1p_rd, log1p_ru)
+    }
+
+    // The first multiple of π/2 in a finite interval narrower than 2π, as
+    // its index mod 4, and how many there are; None for wider intervals.
+    fn quadrant_crossings(self) -> Option<(u32, u32)> {
+        if !self.lo.is_finite()
+            || !self.hi.is_finite()
+            || add_round(self.hi, -self.lo, true) >= TWO_PI_LO
+        {
+            return None;
+        }
+        let (_, first) = quadrants(self.lo);
+        let (last, _) = quadrants(self.hi);
+        // The count is known mod 4: none below π/2 wide, four above 3π/2.
+        let mut n = (last + 5 - first) % 4;
+        if n == 0 && self.hi - self.lo > PI_LO {
+            n = 4;
+        }
+        Some((first, n))
+    }
+
+    // sin or cos from the endpoints, or ±1 where sin(jπ/2 + phase) peaks.
+    fn periodic(self, phase: u32, rd: fn(f64) -> f64, ru: fn(f64) -> f64) -> Self {
+        if self.is_empty() {
+            return self;
+        }
+        let Some((first, n)) = self.quadrant_crossings() else {
+            return Self::new(-1.0, 1.0);
+        };
+        let crosses = |q: u32| (0..n).any(|i| (first + i + phase) % 4 == q);
+        let lo = if crosses(3) {
+            -1.0
+        } else {
+            rd(self.lo).min(rd(self.hi))
+        };
+        let hi = if crosses(1) {
+            1.0
+        } else {
+            ru(self.lo).max(ru(self.hi))
+        };
+        Self::new(lo, hi)
+    }
+
+    pub fn sin(self) -> Self {
+        self.periodic(0, sin_rd, sin_ru)
+    }
+
+    pub fn cos(self) -> Self {
+        // cos(jπ/2) = sin((j + 1)π/2).
+        self.periodic(1, cos_rd, cos_ru)
+    }
+
+    /// The entire line if the interval reaches a pole.
+    pub fn tan(self) -> Self {
+        if self.is_empty() {
+            return self;
+        }
+        match self.quadrant_crossings() {
+            Some((first, n)) if (0..n).all(|i| (first + i) % 2 == 0) => {
+                Self::new(tan_rd(self.lo), tan_ru(self.hi))
+            }
+            _ => Self::ENTIRE,
+        }
+    }
+
+    pub fn asin(self) -> Self {
+        self.intersection(Self::new(-1.0, 1.0))
+            .increasing(asin_rd, asin_ru)
+    }
+
+    pub fn acos(self) -> Self {
+        self.intersection(Self::new(-1.0, 1.0))
+            .decreasing(acos_rd, acos_ru)
+    }
+
+    pub fn atan(self) -> Self {
+        self.increasing(atan_rd, atan_ru)
+    }
+
+    /// The angle of (x, y) for `self` = y, in [-π, π], with the branch cut
+    /// along the negative x axis, where the angle is π. The origin is not in
+    /// the domain.
+    pub fn atan2(self, x: Self) -> Self {
+        if self.is_empty() || x.is_empty() {
+            return Self::EMPTY;
+        }
+        // +0 on the axes, as the angle of (x, -0) is -π.
+        let (yl, yh, xl, xh) = (self.lo + 0.0, self.hi + 0.0, x.lo + 0.0, x.hi + 0.0);
+        if xl <= 0.0 && 0.0 <= xh && yl <= 0.0 && 0.0 <= yh {
+            // The box holds the origin: the angles are the directions from
+            // it into the box.
+            if xl == 0.0 && xh == 0.0 && yl == 0.0 && yh == 0.0 {
+                return Self::EMPTY;
+            }
+            if xl < 0.0 {
+                if yl < 0.0 {
+                    return Self::new(-PI_HI, PI_HI);
+                }
+                let lo = if xh > 0.0 {
+                    0.0
+                } else if yh > 0.0 {
+                    PI_2_LO
+                } else {
+                    PI_LO
+                };
+                return Self::new(lo, PI_HI);
+            }
+            let lo = if yl < 0.0 {
+                -PI_2_HI
+            } else if xh > 0.0 {
+                0.0
+            } else {
+                PI_2_LO
+            };
+            let hi = if yh > 0.0 {
+                PI_2_HI
+            } else if xh > 0.0 {
+                0.0
+            } else {
+                -PI_2_LO
+            };
+            return Self::new(lo, hi);
+        }
+        if xh < 0.0 && yl < 0.0 && 0.0 <= yh {
+            // Across the branch cut.
+            return Self::new(-PI_HI, PI_HI);
+        }
+        // Elsewhere the extreme angles of the box are at its corners.
+        let corners = [(yl, xl), (yl, xh), (yh, xl), (yh, xh)];
+        let lo = corners
+            .iter()
+            .map(|&(b, a)| atan2_rd(b, a))
+            .fold(f64::INFINITY, f64::min);
+        let hi = corners
+            .iter()
+            .map(|&(b, a)| atan2_ru(b, a))
+            .fold(f64::NEG_INFINITY, f64::max);
+        Self::new(lo, hi)
+    }
+
+    pub fn sinh(self) -> Self {
+        self.increasing(sinh_rd, sinh_ru)
+    }
+
+    pub fn cosh(self) -> Self {
+        if self.is_empty() {
+            return self;
+        }
+        Self::new(cosh_rd(mig(self)), cosh_ru(mag(self)))
+    }
+
+    pub fn tanh(self) -> Self {
+        self.increasing(tanh_rd, tanh_ru)
+    }
+
+    pub fn asinh(self) -> Self {
+        self.increasing(asinh_rd, asinh_ru)
+    }
+
+    pub fn acosh(self) -> Self {
+        self.intersection(Self::new(1.0, f64::INFINITY))
+            .increasing(acosh_rd, acosh_ru)
+    }
+
+    pub fn atanh(self) -> Self {
+        let x = self.intersection(Self::new(-1.0, 1.0));
+        if x.hi == -1.0 || x.lo == 1.0 {
+            return Self::EMPTY;
+        }
+        x.increasing(atanh_rd, atanh_ru)
+    }
+
+    pub fn erf(self) -> Self {
+        self.increasing(erf_rd, erf_ru)
+    }
+
+    pub fn erfc(self) -> Self {
+        self.decreasing(erfc_rd, erfc_ru)
+    }
+
+    /// ln|Γ(x)|, whose domain excludes the poles at 0, -1, -2, ...
+    pub fn lgamma(self) -> Self {
+        let pos = self.intersection(Self::new(0.0, f64::INFINITY));
+        let neg = self.intersection(Self::new(f64::NEG_INFINITY, 0.0));
+        pos.lgamma_positive().hull(neg.lgamma_negative())
+    }
+
+    fn lgamma_positive(self) -> Self {
+        if self.is_empty() {
+            return self;
+        }
+        let (a, b) = (self.lo, self.hi);
+        if b <= LGAMMA_ARGMIN_LO {
+            // lgamma(0) = +∞ makes [0, 0] empty.
+            return Self::new(lgamma_rd(b), lgamma_ru(a));
+        }
+        if a >= LGAMMA_ARGMIN_HI {
+            return Self::new(lgamma_rd(a), lgamma_ru(b));
+        }
+        Self::new(LGAMMA_MIN_LO, lgamma_ru(a).max(lgamma_ru(b)))
+    }
+
+    fn lgamma_negative(self) -> Self {
+        if self.is_empty() || (self.lo == self.hi && self.lo == floor(self.lo)) {
+            return Self::EMPTY;
+        }
+        // lgamma(x) = ln π - ln|sin πx| - lgamma(1 - x), with 1 - x >= 1.
+        let pi = Self::new(PI_LO, PI_HI);
+        let ln_pi = Self::new(LN_PI_LO, LN_PI_HI);
+        let sin = (pi * self).sin().abs().ln();
+        let reflected = ln_pi - sin - (Self::point(1.0) - self).lgamma_positive();
+        if floor(self.hi) < self.lo {
+            // No pole inside: ln|Γ| is convex between poles, as its second
+            // derivative Σ 1/(x + k)² is positive, so it peaks at an end.
+            let hi = lgamma_ru(self.lo).max(lgamma_ru(self.hi));
+            return reflected.intersection(Self::new(f64::NEG_INFINITY, hi));
+        }
+        Self::new(reflected.lo, f64::INFINITY)
+    }
+}
+
+impl From<f64> for Interval {
+    #[inline]
+    fn from(x: f64) -> Self {
+        Self::point(x)
+    }
+}
+
+// The smallest and largest |x| in a non-empty interval.
+fn mig(x: Interval) -> f64 {
+    if x.lo > 0.0 {
+        x.lo
+    } else if x.hi < 0.0 {
+        -x.hi
+    } else {
+        0.0
+    }
+}
+
+fn mag(x: Interval) -> f64 {
+    x.lo.abs().max(x.hi.abs())
+}
+
+// ±MAX or ±∞ for a result that overflowed, rounded up or down.
+fn overflowed(neg: bool, up: bool) -> f64 {
+    match (neg, up) {
+        (false, true) => f64::INFINITY,
+        (false, false) => f64::MAX,
+        (true, true) => -f64::MAX,
+        (true, false) => f64::NEG_INFINITY,
+    }
+}
+
+// a + b rounded up or down: the exact error of the nearest sum gives the side.
+fn add_round(a: f64, b: f64, up: bool) -> f64 {
+    let (s, e) = two_sum(a, b);
+    if s.is_infinite() {
+        if a.is_infinite() || b.is_infinite() {
+            return s;
+        }
+        return overflowed(s < 0.0, up);
+    }
+    if up && e > 0.0 {
+        s.next_up()
+    } else if !up && e < 0.0 {
+        s.next_down()
+    } else {
+        s
+    }
+}
+
+// a × b rounded up or down, with 0 × ∞ = 0; the product of two doubles is
+// exact in 128 bits.
+fn mul_round(a: f64, b: f64, up: bool) -> f64 {
+    if a == 0.0 || b == 0.0 {
+        return 0.0;
+    }
+    if a.is_infinite() || b.is_infinite() {
+        return a * b;
+    }
+    let mode = if up { Round::Up } else { Round::Down };
+    Mp::<2>::from_f64(a).mul(Mp::from_f64(b)).to_f64(mode)
+}
+
+// a / b rounded up or down, for b != 0 and not both infinite.
+fn div_round(a: f64, b: f64, up: bool) -> f64 {
+    let q = a / b;
+    if a == 0.0 || a.is_infinite() || b.is_infinite() {
+        return q;
+    }
+    if q.is_infinite() {
+        return overflowed(q < 0.0, up);
+    }
+    // q > a / b exactly when the remainder q b - a, exact in 128 bits, has
+    // the sign of b.
+    let r = Mp::<2>::from_f64(q)
+        .mul(Mp::from_f64(b))
+        .sub(Mp::from_f64(a));
+    let above = !r.is_zero() && r.is_neg() == (b < 0.0);
+    let below = !r.is_zero() && !above;
+    if up && below {
+        q.next_up()
+    } else if !up && above {
+        q.next_down()
+    } else {
+        q
+    }
+}
+
+// (⌊x / (π/2)⌋, ⌈x / (π/2)⌉) mod 4 for a finite x; no nonzero double is a
+// multiple of π/2, and the reduced argument keeps its sign however small.
+fn quadrants(x: f64) -> (u32, u32) {
+    if x == 0.0 {
+        return (0, 0);
+    }
+    let (k, r) = reduce_pio2::<2>(x);
+    if r.is_neg() {
+        ((k + 3) % 4, k)
+    } else {
+        (k, (k + 1) % 4)
+    }
+}
+
+impl Neg for Interval {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        if self.is_empty() {
+            return self;
+        }
+        Self::new(-self.hi, -self.lo)
+    }
+}
+
+impl Add for Interval {
+    type Output = Self;
+
+    fn add(self, o: Self) -> Self {
+        if self.is_empty() || o.is_empty() {
+            return Self::EMPTY;
+        }
+        Self::new(
+            add_round(self.lo, o.lo, false),
+            add_round(self.hi, o.hi, true),
+        )
+    }
+}
+
+impl Sub for Interval {
+    type Output = Self;
+
+    fn sub(self, o: Self) -> Self {
+        self + -o
+    }
+}
+
+impl Mul for Interval {
+    type Output = Self;
+
+    fn mul(self, o: Self) -> Self {
+        if self.is_empty() || o.is_empty() {
+            return Self::EMPTY;
+        }
+        let corners = [
+            (self.lo, o.lo),
+            (self.lo, o.hi),
+            (self.hi, o.lo),
+            (self.hi, o.hi),
+        ];
+        let lo = corners
+            .iter()
+            .map(|&(a, b)| mul_round(a, b, false))
+            .fold(f64::INFINITY, f64::min);
+        let hi = corners
+            .iter()
+            .map(|&(a, b)| mul_round(a, b, true))
+            .fold(f64::NEG_INFINITY, f64::max);
+        Self::new(lo, hi)
+    }
+}
+
+impl Div for Interval {
+    type Output = Self;
+
+    /// The hull of {x / y : y != 0}: entire when `o` holds 0 inside, one
+    /// half-line when it ends at 0, and empty for `o` = [0, 0].
+    fn div(self, o: Self) -> Self {
+        let (a, b, c, d) = (self.lo, self.hi, o.lo, o.hi);
+        if self.is_empty() || o.is_empty() || (c == 0.0 && d == 0.0) {
+            return Self::EMPTY;
+        }
+        let inf = f64::INFINITY;
+        if c > 0.0 {
+            if a >= 0.0 {
+                Self::new(div_round(a, d, false), div_round(b, c, true))
+            } else if b <= 0.0 {
+                Self::new(div_round(a, c, false), div_round(b, d, true))
+            } else {
+                Self::new(div_round(a, c, false), div_round(b, c, true))
+            }
+        } else if d < 0.0 {
+            if a >= 0.0 {
+                Self::new(div_round(b, d, false), div_round(a, c, true))
+            } else if b <= 0.0 {
+                Self::new(div_round(b, c, false), div_round(a, d, true))
+            } else {
+                Self::new(div_round(b, d, false), div_round(a, d, true))
+            }
+        } else if a == 0.0 && b == 0.0 {
+            Self::point(0.0)
+        } else if c == 0.0 {
+            // o = [0, d], d > 0.
+            if b < 0.0 {
+                Self::new(-inf, div_round(b, d, true))
+            } else if a > 0.0 {
+                Self::new(div_round(a, d, false), inf)
+            } else if a == 0.0 {
+                Self::new(0.0, inf)
+            } else if b == 0.0 {
+                Self::new(-inf, 0.0)
+            } else {
+                Self::ENTIRE
+            }
+        } else if d == 0.0 {
+            // o = [c, 0], c < 0.
+            if b < 0.0 {
+                Self::new(div_round(b, c, false), inf)
+            } else if a > 0.0 {
+                Self::new(-inf, div_round(a, c, true))
+            } else if a == 0.0 {
+                Self::new(-inf, 0.0)
+            } else if b == 0.0 {
+                Self::new(0.0, inf)
+            } else {
+                Self::ENTIRE
+            }
+        } else {
+            Self::ENTIRE
+        }
+    }
+}
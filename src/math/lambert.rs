@@ -0,0 +1,190 @@
+//! Lambert W function, principal branch W0(x) and lower branch W-1(x).
+//!
+//! Near the branch point -1/e both branches come from the series in
+//! p = ±sqrt(2 (e x + 1)), with x + 1/e formed against a double-double 1/e so
+//! p keeps full relative accuracy. Elsewhere an asymptotic or logarithmic
+//! initial guess is refined with Halley's method on g(w) = w + ln|w| - ln|x|,
+//! followed by one Newton step whose residual is evaluated in double-double.
+
+use super::double::twosum;
+use super::fasttwosum;
+use super::log::{ln, ln_dd};
+use super::log1p::log1p;
+use super::sqrt::sqrt;
+use super::with_nearest;
+
+// 1/e and e as double-doubles.
+const INV_E_HI: f64 = f64::from_bits(0x3fd7_8b56_362c_ef38);
+const INV_E_LO: f64 = f64::from_bits(0xbc6c_a8a4_270f_adf5);
+const E_HI: f64 = f64::from_bits(0x4005_bf0a_8b14_5769);
+
+// W(x) = sum mu_k p^k around the branch point, mu_0 = -1 and mu_1 = 1;
+// MU holds mu_2..mu_11. With |p| < 2^-6 the truncation is below 2^-70.
+const MU: [f64; 10] = [
+    f64::from_bits(0xbfd5_5555_5555_5555),
+    f64::from_bits(0x3fc3_8e38_e38e_38e4),
+    f64::from_bits(0xbfb4_629b_7f0d_462a),
+    f64::from_bits(0x3fa6_c901_e573_ac90),
+    f64::from_bits(0xbf9a_9bbc_b24f_e29f),
+    f64::from_bits(0x3f90_02c9_8983_bc43),
+    f64::from_bits(0xbf83_b205_65de_449c),
+    f64::from_bits(0x3f78_a2b4_a926_30e7),
+    f64::from_bits(0xbf6f_38df_0d49_1306),
+    f64::from_bits(0x3f63_fee4_64a7_d9c5),
+];
+// Below this |p| the branch-point series is the result.
+const BRANCH_SERIES: f64 = 0.015_625;
+// Below this |p| the branch-point series seeds the iteration.
+const BRANCH_GUESS: f64 = 0.5;
+// Below this |x|, W0(x) = x - x^2 + 3/2 x^3 - ... to working precision.
+const W0_SERIES: f64 = f64::from_bits(0x3f50_0000_0000_0000); // 2^-10
+// Halley stops once the step is below this fraction of w (cubic
+// convergence puts the remaining error far below the final correction).
+const HALLEY_TOL: f64 = f64::from_bits(0x3e10_0000_0000_0000); // 2^-30
+
+// sqrt(2 (e x + 1)) for x >= -1/e, or NaN below the branch point.
+#[inline(always)]
+fn branch_p(x: f64) -> f64 {
+    // x + 1/e is exact in its leading part near the branch point.
+    let mut dl = 0.0;
+    let dh = twosum(x, INV_E_HI, &mut dl);
+    let d = dh + (dl + INV_E_LO);
+    if d < 0.0 {
+        return f64::NAN;
+    }
+    sqrt(2.0 * E_HI * d)
+}
+
+// -1 + p + sum_{k>=2} mu_k p^k.
+#[inline(always)]
+fn branch_series(p: f64) -> f64 {
+    let mut s = MU[MU.len() - 1];
+    for &c in MU.iter().rev().skip(1) {
+        s = s * p + c;
+    }
+    let mut l = 0.0;
+    let h = fasttwosum(-1.0, p, &mut l);
+    h + (l + p * p * s)
+}
+
+// x - x^2 + 3/2 x^3 - 8/3 x^4 + ... for |x| <= 2^-10.
+#[inline(always)]
+fn w0_small(x: f64) -> f64 {
+    let p = -1.0
+        + x * (1.5
+            + x * (-8.0 / 3.0
+                + x * (125.0 / 24.0
+                    + x * (-10.8 + x * (16807.0 / 720.0 + x * (-16384.0 / 315.0))))));
+    x + x * x * p
+}
+
+// Refine w with Halley's method on g(w) = w + ln|w| - ln|x|, then apply one
+// Newton step with g evaluated in double-double; g' = (1 + w) / w.
+#[inline(always)]
+fn refine(x: f64, mut w: f64) -> f64 {
+    let lx = ln(x.abs());
+    for _ in 0..8 {
+        let g = w + (ln(w.abs()) - lx);
+        let gp = 1.0 + 1.0 / w;
+        let gpp = -1.0 / (w * w);
+        let dw = g / (gp - 0.5 * g * gpp / gp);
+        w -= dw;
+        if dw.abs() <= HALLEY_TOL * w.abs() {
+            break;
+        }
+    }
+    let (wh, wl) = ln_dd(w.abs());
+    let (xh, xl) = ln_dd(x.abs());
+    let mut e = 0.0;
+    let s = twosum(w, wh, &mut e);
+    let g = (s - xh) + (e + (wl - xl));
+    w - g * w / (1.0 + w)
+}
+
+/// Principal branch W0(x) of the Lambert W function, the solution w >= -1 of
+/// w e^w = x.
+///
+/// Defined for x >= -1/e (W0(-1/e) = -1); smaller arguments return NaN.
+#[inline]
+pub fn lambert_w0(x: f64) -> f64 {
+    with_nearest(x, lambert_w0_nearest)
+}
+
+#[inline]
+fn lambert_w0_nearest(x: f64) -> f64 {
+    if x.is_nan() {
+        return x;
+    }
+    if x == f64::INFINITY {
+        return x;
+    }
+    if x.abs() <= W0_SERIES {
+        return w0_small(x);
+    }
+    if x == -INV_E_HI {
+        return -1.0;
+    }
+    if x < 0.0 {
+        let p = branch_p(x);
+        if p.is_nan() {
+            return p;
+        }
+        if p < BRANCH_SERIES {
+            return branch_series(p);
+        }
+        if p < BRANCH_GUESS {
+            return refine(x, branch_series(p));
+        }
+    }
+    let w = if x < 3.0 {
+        // Winitzki's approximation.
+        let l = log1p(x);
+        l * (1.0 - log1p(l) / (2.0 + l))
+    } else {
+        let l1 = ln(x);
+        let l2 = ln(l1);
+        l1 - l2 + l2 / l1
+    };
+    refine(x, w)
+}
+
+/// Lower branch W-1(x) of the Lambert W function, the solution w <= -1 of
+/// w e^w = x.
+///
+/// Defined for -1/e <= x < 0 (W-1(-1/e) = -1, W-1(0-) = -inf); other
+/// arguments return NaN and a zero of either sign returns -inf.
+#[inline]
+pub fn lambert_wm1(x: f64) -> f64 {
+    with_nearest(x, lambert_wm1_nearest)
+}
+
+#[inline]
+fn lambert_wm1_nearest(x: f64) -> f64 {
+    if x.is_nan() {
+        return x;
+    }
+    if x == 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if x > 0.0 {
+        return f64::NAN;
+    }
+    if x == -INV_E_HI {
+        return -1.0;
+    }
+    let p = branch_p(x);
+    if p.is_nan() {
+        return p;
+    }
+    if p < BRANCH_SERIES {
+        return branch_series(-p);
+    }
+    let w = if p < BRANCH_GUESS {
+        branch_series(-p)
+    } else {
+        let l1 = ln(-x);
+        let l2 = ln(-l1);
+        l1 - l2 + l2 / l1
+    };
+    refine(x, w)
+}
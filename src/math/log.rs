@@ -4,7 +4,7 @@
 //! (5-bit index) and a minimax polynomial in the reduced variable. FMA is used
 //! where available for error-compensated evaluation; constants from glibc/core-math.
 
-use super::{LN2_HI, LN2_LO, f64_from_bits, f64_to_bits};
+use super::{LN2_HI, LN2_LO, f64_from_bits, f64_to_bits, with_nearest};
 
 // ========= glibc-derived log tables (N=128) =========
 
@@ -616,10 +616,10 @@ pub(crate) fn ln_dd(x: f64) -> (f64, f64) {
     let invc = unsafe { f64_from_bits(*LOG_INVC_U64.get_unchecked(i)) };
     let logc = unsafe { f64_from_bits(*LOG_LOGC_U64.get_unchecked(i)) };
     let z = f64_from_bits(iz);
-    #[cfg(target_feature = "fma")]
+    #[cfg(all(target_feature = "fma", not(feature = "soft-fma")))]
     let r = super::fma_internal(z, invc, -1.0);
 
-    #[cfg(not(target_feature = "fma"))]
+    #[cfg(not(all(target_feature = "fma", not(feature = "soft-fma"))))]
     let r = (z
         - unsafe { f64_from_bits(*LOG_CHI_U64.get_unchecked(i)) }
         - unsafe { f64_from_bits(*LOG_CLO_U64.get_unchecked(i)) })
@@ -637,13 +637,275 @@ pub(crate) fn ln_dd(x: f64) -> (f64, f64) {
 
 #[inline(always)]
 pub fn ln(x: f64) -> f64 {
+    with_nearest(x, ln_nearest)
+}
+
+#[inline(always)]
+fn ln_nearest(x: f64) -> f64 {
     let (hi, lo) = ln_dd(x);
     hi + lo
 }
 
+/// ln(x) in each lane of `x`, bit-identical to [`ln`] lane by lane.
+///
+/// The table path, the path near 1 and exact powers of two are all
+/// evaluated in vector registers and blended; zeros, subnormals, negative
+/// inputs, infinities and NaNs are handed to [`ln`].
+///
+/// # Safety
+///
+/// The CPU must support AVX2 and FMA.
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[target_feature(enable = "avx2,fma")]
+pub unsafe fn ln_avx2(x: core::arch::x86_64::__m256d) -> core::arch::x86_64::__m256d {
+    with_nearest(x, |x| unsafe { ln_avx2_nearest(x) })
+}
+
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[target_feature(enable = "avx2,fma")]
+unsafe fn ln_avx2_nearest(x: core::arch::x86_64::__m256d) -> core::arch::x86_64::__m256d {
+    use super::arch::avx2::{
+        fixup1, gather_u64, sign_extend_12, small_i64_to_f64, splat, splat_u64,
+    };
+    use core::arch::x86_64::*;
+
+    let ix = _mm256_castpd_si256(x);
+    let tmp = _mm256_sub_epi64(ix, splat_u64(OFF));
+    let i = _mm256_and_si256(
+        _mm256_srli_epi64::<{ 52 - LOG_TABLE_BITS as i32 }>(tmp),
+        splat_u64(N - 1),
+    );
+    let k = sign_extend_12(_mm256_srli_epi64::<52>(tmp));
+    let iz = _mm256_sub_epi64(ix, _mm256_and_si256(tmp, splat_u64(0xfff_u64 << 52)));
+    let invc = _mm256_castsi256_pd(unsafe { gather_u64(&LOG_INVC_U64, i) });
+    let logc = _mm256_castsi256_pd(unsafe { gather_u64(&LOG_LOGC_U64, i) });
+    let z = _mm256_castsi256_pd(iz);
+    #[cfg(target_feature = "fma")]
+    let r = _mm256_fmadd_pd(z, invc, splat(-1.0));
+    #[cfg(not(target_feature = "fma"))]
+    let r = {
+        let chi = _mm256_castsi256_pd(unsafe { gather_u64(&LOG_CHI_U64, i) });
+        let clo = _mm256_castsi256_pd(unsafe { gather_u64(&LOG_CLO_U64, i) });
+        _mm256_mul_pd(_mm256_sub_pd(_mm256_sub_pd(z, chi), clo), invc)
+    };
+    let kd = small_i64_to_f64(k);
+
+    let w = _mm256_add_pd(_mm256_mul_pd(kd, splat(LN2_HI)), logc);
+    let hi = _mm256_add_pd(w, r);
+    let lo = _mm256_add_pd(
+        _mm256_add_pd(_mm256_sub_pd(w, hi), r),
+        _mm256_mul_pd(kd, splat(LN2_LO)),
+    );
+    #[cfg(target_feature = "fma")]
+    let p = {
+        let p = _mm256_fmadd_pd(splat(LOG_A4), r, splat(LOG_A3));
+        let p = _mm256_fmadd_pd(p, r, splat(LOG_A2));
+        let p = _mm256_fmadd_pd(p, r, splat(LOG_A1));
+        _mm256_fmadd_pd(p, r, splat(LOG_A0))
+    };
+    #[cfg(not(target_feature = "fma"))]
+    let p = {
+        let p = _mm256_add_pd(splat(LOG_A3), _mm256_mul_pd(r, splat(LOG_A4)));
+        let p = _mm256_add_pd(splat(LOG_A2), _mm256_mul_pd(r, p));
+        let p = _mm256_add_pd(splat(LOG_A1), _mm256_mul_pd(r, p));
+        _mm256_add_pd(splat(LOG_A0), _mm256_mul_pd(r, p))
+    };
+    let poly = _mm256_mul_pd(_mm256_mul_pd(r, r), p);
+    let y = _mm256_add_pd(hi, _mm256_add_pd(lo, poly));
+
+    // Exact powers of two: k ln 2 split as in ln_dd.
+    let mant = _mm256_and_si256(ix, splat_u64(0x000f_ffff_ffff_ffff));
+    let pow2 = _mm256_castsi256_pd(_mm256_cmpeq_epi64(mant, _mm256_setzero_si256()));
+    let e = _mm256_sub_epi64(_mm256_srli_epi64::<52>(ix), splat_u64(1023));
+    let ed = small_i64_to_f64(e);
+    let y2 = _mm256_add_pd(
+        _mm256_mul_pd(ed, splat(LN2_HI)),
+        _mm256_mul_pd(ed, splat(LN2_LO)),
+    );
+    let y = _mm256_blendv_pd(y, y2, pow2);
+
+    // 1 - 2^-4 <= x < 1 + 0x1.09p-4.
+    let near = _mm256_and_pd(
+        _mm256_cmp_pd::<_CMP_GE_OQ>(x, splat(f64::from_bits(0x3fee000000000000))),
+        _mm256_cmp_pd::<_CMP_LT_OQ>(x, splat(f64::from_bits(0x3ff1090000000000))),
+    );
+    let r = _mm256_sub_pd(x, splat(1.0));
+    let r2 = _mm256_mul_pd(r, r);
+    let r3 = _mm256_mul_pd(r, r2);
+    // ((c0 + r c1) + r2 c2) + rest, summed left to right as in ln_dd.
+    let sum3 = |c0: f64, c1: f64, c2: f64, rest: __m256d| {
+        let t = _mm256_add_pd(splat(c0), _mm256_mul_pd(r, splat(c1)));
+        _mm256_add_pd(_mm256_add_pd(t, _mm256_mul_pd(r2, splat(c2))), rest)
+    };
+    let t = sum3(LOG_B7, LOG_B8, LOG_B9, _mm256_mul_pd(r3, splat(LOG_B10)));
+    let t = sum3(LOG_B4, LOG_B5, LOG_B6, _mm256_mul_pd(r3, t));
+    let t = sum3(LOG_B1, LOG_B2, LOG_B3, _mm256_mul_pd(r3, t));
+    let yn = _mm256_mul_pd(r3, t);
+    let w = _mm256_mul_pd(r, splat(f64::from_bits(0x4190000000000000))); // 2^27
+    let rhi = _mm256_sub_pd(_mm256_add_pd(r, w), w);
+    let rlo = _mm256_sub_pd(r, rhi);
+    let w = _mm256_mul_pd(_mm256_mul_pd(rhi, rhi), splat(LOG_B0));
+    let hi = _mm256_add_pd(r, w);
+    let lo = _mm256_add_pd(_mm256_sub_pd(r, hi), w);
+    let lo = _mm256_add_pd(
+        lo,
+        _mm256_mul_pd(_mm256_mul_pd(splat(LOG_B0), rlo), _mm256_add_pd(rhi, r)),
+    );
+    let yn = _mm256_add_pd(hi, _mm256_add_pd(lo, yn));
+    // ln(1) is +0 whatever the rounding of the expression above.
+    let one = _mm256_cmp_pd::<_CMP_EQ_OQ>(x, splat(1.0));
+    let yn = _mm256_andnot_pd(one, yn);
+    let y = _mm256_blendv_pd(y, yn, near);
+
+    // Positive normal inputs only.
+    let normal = _mm256_and_pd(
+        _mm256_cmp_pd::<_CMP_GE_OQ>(x, splat(f64::MIN_POSITIVE)),
+        _mm256_cmp_pd::<_CMP_LT_OQ>(x, splat(f64::INFINITY)),
+    );
+    let slow = _mm256_xor_pd(normal, _mm256_castsi256_pd(_mm256_set1_epi64x(-1)));
+    fixup1(y, slow, x, ln)
+}
+
+/// ln(x) in each lane of `x`, bit-identical to [`ln`] lane by lane.
+///
+/// Entirely branch-free: subnormals are rescaled into the table path, and
+/// the values for zeros, negative inputs, infinities and NaNs are blended in
+/// under AVX-512 masks.
+///
+/// # Safety
+///
+/// The CPU must support AVX-512F.
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[target_feature(enable = "avx512f")]
+pub unsafe fn ln_avx512(x: core::arch::x86_64::__m512d) -> core::arch::x86_64::__m512d {
+    with_nearest(x, |x| unsafe { ln_avx512_nearest(x) })
+}
+
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[target_feature(enable = "avx512f")]
+unsafe fn ln_avx512_nearest(x: core::arch::x86_64::__m512d) -> core::arch::x86_64::__m512d {
+    use super::arch::avx512::{gather_u64, sign_extend_12, small_i64_to_f64, splat, splat_u64};
+    use core::arch::x86_64::*;
+
+    // log_special: subnormals scaled by 2^52 with the exponent adjusted.
+    let ix = _mm512_castpd_si512(x);
+    let sub = _mm512_cmp_pd_mask::<_CMP_LT_OQ>(x, splat(f64::MIN_POSITIVE));
+    let scaled = _mm512_sub_epi64(
+        _mm512_castpd_si512(_mm512_mul_pd(
+            x,
+            splat(f64::from_bits(0x4330_0000_0000_0000)),
+        )),
+        splat_u64(52u64 << 52),
+    );
+    let ix = _mm512_mask_blend_epi64(sub, ix, scaled);
+
+    let tmp = _mm512_sub_epi64(ix, splat_u64(OFF));
+    let i = _mm512_and_si512(
+        _mm512_srli_epi64::<{ 52 - LOG_TABLE_BITS }>(tmp),
+        splat_u64(N - 1),
+    );
+    let k = sign_extend_12(_mm512_srli_epi64::<52>(tmp));
+    let iz = _mm512_sub_epi64(ix, _mm512_and_si512(tmp, splat_u64(0xfff_u64 << 52)));
+    let invc = _mm512_castsi512_pd(unsafe { gather_u64(&LOG_INVC_U64, i) });
+    let logc = _mm512_castsi512_pd(unsafe { gather_u64(&LOG_LOGC_U64, i) });
+    let z = _mm512_castsi512_pd(iz);
+    #[cfg(target_feature = "fma")]
+    let r = _mm512_fmadd_pd(z, invc, splat(-1.0));
+    #[cfg(not(target_feature = "fma"))]
+    let r = {
+        let chi = _mm512_castsi512_pd(unsafe { gather_u64(&LOG_CHI_U64, i) });
+        let clo = _mm512_castsi512_pd(unsafe { gather_u64(&LOG_CLO_U64, i) });
+        _mm512_mul_pd(_mm512_sub_pd(_mm512_sub_pd(z, chi), clo), invc)
+    };
+    let kd = small_i64_to_f64(k);
+
+    let w = _mm512_add_pd(_mm512_mul_pd(kd, splat(LN2_HI)), logc);
+    let hi = _mm512_add_pd(w, r);
+    let lo = _mm512_add_pd(
+        _mm512_add_pd(_mm512_sub_pd(w, hi), r),
+        _mm512_mul_pd(kd, splat(LN2_LO)),
+    );
+    #[cfg(target_feature = "fma")]
+    let p = {
+        let p = _mm512_fmadd_pd(splat(LOG_A4), r, splat(LOG_A3));
+        let p = _mm512_fmadd_pd(p, r, splat(LOG_A2));
+        let p = _mm512_fmadd_pd(p, r, splat(LOG_A1));
+        _mm512_fmadd_pd(p, r, splat(LOG_A0))
+    };
+    #[cfg(not(target_feature = "fma"))]
+    let p = {
+        let p = _mm512_add_pd(splat(LOG_A3), _mm512_mul_pd(r, splat(LOG_A4)));
+        let p = _mm512_add_pd(splat(LOG_A2), _mm512_mul_pd(r, p));
+        let p = _mm512_add_pd(splat(LOG_A1), _mm512_mul_pd(r, p));
+        _mm512_add_pd(splat(LOG_A0), _mm512_mul_pd(r, p))
+    };
+    let poly = _mm512_mul_pd(_mm512_mul_pd(r, r), p);
+    let y = _mm512_add_pd(hi, _mm512_add_pd(lo, poly));
+
+    // Exact powers of two (normal inputs only): k ln 2 split as in ln_dd.
+    let ix = _mm512_castpd_si512(x);
+    let mant = _mm512_and_si512(ix, splat_u64(0x000f_ffff_ffff_ffff));
+    let pow2 = _mm512_cmpeq_epi64_mask(mant, _mm512_setzero_si512()) & !sub;
+    let e = _mm512_sub_epi64(_mm512_srli_epi64::<52>(ix), splat_u64(1023));
+    let ed = small_i64_to_f64(e);
+    let y2 = _mm512_add_pd(
+        _mm512_mul_pd(ed, splat(LN2_HI)),
+        _mm512_mul_pd(ed, splat(LN2_LO)),
+    );
+    let y = _mm512_mask_blend_pd(pow2, y, y2);
+
+    // 1 - 2^-4 <= x < 1 + 0x1.09p-4.
+    let near = _mm512_cmp_pd_mask::<_CMP_GE_OQ>(x, splat(f64::from_bits(0x3fee000000000000)))
+        & _mm512_cmp_pd_mask::<_CMP_LT_OQ>(x, splat(f64::from_bits(0x3ff1090000000000)));
+    let r = _mm512_sub_pd(x, splat(1.0));
+    let r2 = _mm512_mul_pd(r, r);
+    let r3 = _mm512_mul_pd(r, r2);
+    // ((c0 + r c1) + r2 c2) + rest, summed left to right as in ln_dd.
+    let sum3 = |c0: f64, c1: f64, c2: f64, rest: __m512d| {
+        let t = _mm512_add_pd(splat(c0), _mm512_mul_pd(r, splat(c1)));
+        _mm512_add_pd(_mm512_add_pd(t, _mm512_mul_pd(r2, splat(c2))), rest)
+    };
+    let t = sum3(LOG_B7, LOG_B8, LOG_B9, _mm512_mul_pd(r3, splat(LOG_B10)));
+    let t = sum3(LOG_B4, LOG_B5, LOG_B6, _mm512_mul_pd(r3, t));
+    let t = sum3(LOG_B1, LOG_B2, LOG_B3, _mm512_mul_pd(r3, t));
+    let yn = _mm512_mul_pd(r3, t);
+    let w = _mm512_mul_pd(r, splat(f64::from_bits(0x4190000000000000))); // 2^27
+    let rhi = _mm512_sub_pd(_mm512_add_pd(r, w), w);
+    let rlo = _mm512_sub_pd(r, rhi);
+    let w = _mm512_mul_pd(_mm512_mul_pd(rhi, rhi), splat(LOG_B0));
+    let hi = _mm512_add_pd(r, w);
+    let lo = _mm512_add_pd(_mm512_sub_pd(r, hi), w);
+    let lo = _mm512_add_pd(
+        lo,
+        _mm512_mul_pd(_mm512_mul_pd(splat(LOG_B0), rlo), _mm512_add_pd(rhi, r)),
+    );
+    let yn = _mm512_add_pd(hi, _mm512_add_pd(lo, yn));
+    let y = _mm512_mask_blend_pd(near, y, yn);
+    // ln(1) is +0 whatever the rounding of the expression above.
+    let y = _mm512_mask_blend_pd(
+        _mm512_cmp_pd_mask::<_CMP_EQ_OQ>(x, splat(1.0)),
+        y,
+        _mm512_setzero_pd(),
+    );
+
+    // log_special, lowest priority first.
+    let not_pos = _mm512_cmp_pd_mask::<_CMP_NGT_UQ>(x, _mm512_setzero_pd());
+    let y = _mm512_mask_blend_pd(not_pos, y, splat(f64::NAN));
+    let y = _mm512_mask_blend_pd(
+        _mm512_cmp_pd_mask::<_CMP_EQ_OQ>(x, splat(f64::INFINITY)),
+        y,
+        splat(f64::INFINITY),
+    );
+    _mm512_mask_blend_pd(
+        _mm512_cmp_pd_mask::<_CMP_EQ_OQ>(x, _mm512_setzero_pd()),
+        y,
+        splat(f64::NEG_INFINITY),
+    )
+}
+
 #[inline(always)]
 fn eval_poly(r: f64, r2: f64) -> f64 {
-    #[cfg(target_feature = "fma")]
+    #[cfg(all(target_feature = "fma", not(feature = "soft-fma")))]
     {
         let p = super::fma_internal(LOG_A4, r, LOG_A3);
         let p = super::fma_internal(p, r, LOG_A2);
@@ -651,7 +913,7 @@ fn eval_poly(r: f64, r2: f64) -> f64 {
         let p = super::fma_internal(p, r, LOG_A0);
         r2 * p
     }
-    #[cfg(not(target_feature = "fma"))]
+    #[cfg(not(all(target_feature = "fma", not(feature = "soft-fma"))))]
     {
         let p = LOG_A0 + r * (LOG_A1 + r * (LOG_A2 + r * (LOG_A3 + r * LOG_A4)));
         r2 * p
@@ -4,7 +4,7 @@
 //! log10(x) = y*log10_2hi + (y*log10_2lo + ivln10*ln(mantissa)),
 //! using high/low splits for ≤1 ULP accuracy.
 
-use super::{TWO54, fma_internal, log::ln, log::ln_dd, two_sum};
+use super::{TWO54, fma_internal, log::ln, log::ln_dd, two_sum, with_nearest};
 
 const IVLN10: f64 = f64::from_bits(0x3fdb_cb7b_1526_e50e);
 const IVLN10_HI: f64 = f64::from_bits(0x3fdb_cb7b_1800_0000);
@@ -14,6 +14,11 @@ const LOG10_2_LO: f64 = f64::from_bits(0x3d59_fef3_11f1_2b36);
 
 #[inline]
 pub fn log10(x: f64) -> f64 {
+    with_nearest(x, log10_nearest)
+}
+
+#[inline]
+fn log10_nearest(x: f64) -> f64 {
     let mut ux = x.to_bits();
     let ax = ux & 0x7fff_ffff_ffff_ffff;
     if ax == 0 {
@@ -4,7 +4,7 @@
 //! log(1+x) with compensated argument reduction. Coefficients are derived from
 //! glibc/fdlibm minimax fits.
 
-use super::{LN2_HI, LN2_LO, TWO54, hi_word, lo_word, with_hi_lo};
+use super::{LN2_HI, LN2_LO, TWO54, hi_word, lo_word, with_hi_lo, with_nearest};
 const LP: [f64; 8] = [
     0.0,
     6.666_666_666_666_735_130e-01,
@@ -18,6 +18,11 @@ const LP: [f64; 8] = [
 
 #[inline]
 pub fn log1p(x: f64) -> f64 {
+    with_nearest(x, log1p_nearest)
+}
+
+#[inline]
+fn log1p_nearest(x: f64) -> f64 {
     let hx = hi_word(x) as i32;
     let ax = (hx & 0x7fff_ffff) as u32;
 
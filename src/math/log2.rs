@@ -4,10 +4,16 @@
 //! rounding error; preserves special-case handling per IEEE-754.
 
 use super::ln;
+use super::with_nearest;
 use core::f64::consts::LOG2_E;
 
 #[inline]
 pub fn log2(x: f64) -> f64 {
+    with_nearest(x, log2_nearest)
+}
+
+#[inline]
+fn log2_nearest(x: f64) -> f64 {
     if x.is_nan() {
         return f64::NAN;
     }
@@ -23,3 +29,24 @@ pub fn log2(x: f64) -> f64 {
 
     ln(x) * LOG2_E
 }
+
+/// log2(x) in each lane of `x`, bit-identical to [`log2`] lane by lane.
+///
+/// This is [`ln_avx2`](super::log::ln_avx2) scaled by log2(e); the special
+/// inputs that `log2` returns early give the same values through `ln`.
+///
+/// # Safety
+///
+/// The CPU must support AVX2 and FMA.
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[target_feature(enable = "avx2,fma")]
+pub unsafe fn log2_avx2(x: core::arch::x86_64::__m256d) -> core::arch::x86_64::__m256d {
+    with_nearest(x, |x| unsafe { log2_avx2_nearest(x) })
+}
+
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[target_feature(enable = "avx2,fma")]
+unsafe fn log2_avx2_nearest(x: core::arch::x86_64::__m256d) -> core::arch::x86_64::__m256d {
+    use core::arch::x86_64::{_mm256_mul_pd, _mm256_set1_pd};
+    _mm256_mul_pd(unsafe { super::log::ln_avx2(x) }, _mm256_set1_pd(LOG2_E))
+}
@@ -2,8 +2,9 @@
 //!
 //! Algorithms are drawn from fdlibm/glibc/core-math with table-driven and
 //! bit-level implementations designed for no_std. This module provides
-//! compile-time FMA selection and shared bit-manipulation helpers used across
-//! the math routines for tight error control.
+//! FMA selection (at compile time, or at run time with `runtime-dispatch`)
+//! and shared bit-manipulation helpers used across the math routines for
+//! tight error control.
 
 #![allow(non_camel_case_types)]
 #![allow(clippy::excessive_precision)]
@@ -19,38 +20,61 @@ mod atan;
 mod atan2;
 mod atanh;
 mod atanh_data;
+mod batch;
 mod cbrt;
+pub mod checked;
 mod classify;
+mod complex;
+mod complex_special;
+mod complex_trig;
 mod copysign;
 mod cos;
 mod cosh;
+mod cr;
+#[cfg(all(
+    any(target_arch = "x86_64", target_arch = "x86"),
+    feature = "runtime-dispatch"
+))]
+mod dispatch;
+mod double;
+mod elliptic;
 mod erf;
 mod erf_data;
 mod erfc_data;
 mod exp;
 mod exp10;
 mod exp2;
+mod expint;
 mod expm1;
+mod f128;
 mod fdim;
+mod flags;
 mod fma;
 mod fmax;
 mod fmin;
 mod fmod;
+mod fresnel;
 mod gamma;
+mod half;
 mod hypot;
 mod ilogb;
+mod interval;
+mod lambert;
 mod log;
 mod log10;
 mod log1p;
 mod log2;
 mod logb;
 mod modf;
+mod mp;
 mod nextafter;
 mod pow;
 mod remainder;
 mod remquo;
 mod rounding;
 mod scaling;
+mod sici;
+mod simd;
 mod sin;
 mod sincos_tab;
 mod sinh;
@@ -60,6 +84,7 @@ mod tanh;
 mod trig;
 mod utan_tables;
 mod utils;
+mod zeta;
 
 pub use acos::acos;
 pub use acosh::acosh;
@@ -68,49 +93,113 @@ pub use asinh::asinh;
 pub use atan::atan;
 pub use atan2::atan2;
 pub use atanh::atanh;
+pub use batch::*;
 pub use cbrt::cbrt;
 pub use classify::{
     FP_INFINITE, FP_NAN, FP_NORMAL, FP_SUBNORMAL, FP_ZERO, fpclassify, isfinite, isinf, isnan,
     signbit,
 };
+pub use complex::{Complex64, cabs, carg, cexp, clog, cpow, cproj, csqrt};
+pub use complex_special::{cerf, cerfc, cgamma, clgamma, faddeeva_w};
+pub use complex_trig::{
+    cacos, cacosh, casin, casinh, catan, catanh, ccos, ccosh, csin, csinh, ctan, ctanh,
+};
 pub use copysign::{copysign, fabs};
 pub use cos::cos;
 pub use cosh::cosh;
+pub use cr::{
+    acos_rd, acos_ru, acos_rz, acosh_rd, acosh_ru, acosh_rz, asin_rd, asin_ru, asin_rz, asinh_rd,
+    asinh_ru, asinh_rz, atan_dd, atan_rd, atan_ru, atan_rz, atan2_rd, atan2_ru, atan2_rz, atanh_rd,
+    atanh_ru, atanh_rz, cbrt_rd, cbrt_ru, cbrt_rz, cos_dd, cos_rd, cos_ru, cos_rz, cosh_rd,
+    cosh_ru, cosh_rz, cr_acos, cr_acosh, cr_asin, cr_asinh, cr_atan, cr_atan2, cr_atanh, cr_cbrt,
+    cr_cos, cr_cosh, cr_exp, cr_exp2, cr_exp10, cr_expm1, cr_hypot, cr_ln, cr_log1p, cr_log2,
+    cr_log10, cr_pow, cr_sin, cr_sinh, cr_tan, cr_tanh, erf_rd, erf_ru, erf_rz, erfc_rd, erfc_ru,
+    erfc_rz, exp_dd, exp_rd, exp_ru, exp_rz, exp2_rd, exp2_ru, exp2_rz, exp10_rd, exp10_ru,
+    exp10_rz, expm1_rd, expm1_ru, expm1_rz, hypot_rd, hypot_ru, hypot_rz, lgamma_rd, lgamma_ru,
+    lgamma_rz, ln_dd, ln_rd, ln_ru, ln_rz, log1p_rd, log1p_ru, log1p_rz, log2_dd, log2_rd, log2_ru,
+    log2_rz, log10_rd, log10_ru, log10_rz, pow_dd, pow_rd, pow_ru, pow_rz, sin_dd, sin_rd, sin_ru,
+    sin_rz, sinh_rd, sinh_ru, sinh_rz, sqrt_dd, sqrt_rd, sqrt_ru, sqrt_rz, tan_dd, tan_rd, tan_ru,
+    tan_rz, tanh_rd, tanh_ru, tanh_rz,
+};
+pub use double::DoubleF64;
+pub(crate) use double::{fasttwosum, two_sum};
+pub use elliptic::{
+    ellipe, ellipeinc, ellipk, ellipkinc, ellippi, ellippiinc, elliprc, elliprd, elliprf, elliprj,
+};
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+pub use erf::erf_avx512;
 pub use erf::{erf, erfc};
 pub use exp::exp;
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+pub use exp::{exp_avx2, exp_avx512};
 pub use exp2::exp2;
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+pub use exp2::exp2_avx2;
 pub use exp10::exp10;
+pub use expint::{expint_e1, expint_ei, expint_en};
 pub use expm1::expm1;
+pub use f128::F128;
 pub use fdim::fdim;
+pub use flags::{
+    FpExceptions, acos_with_flags, acosh_with_flags, asin_with_flags, asinh_with_flags,
+    atan_with_flags, atan2_with_flags, atanh_with_flags, cbrt_with_flags, cos_with_flags,
+    cosh_with_flags, erf_with_flags, erfc_with_flags, exp_with_flags, exp2_with_flags,
+    exp10_with_flags, expm1_with_flags, hypot_with_flags, lgamma_with_flags, ln_with_flags,
+    log1p_with_flags, log2_with_flags, log10_with_flags, pow_with_flags, sin_with_flags,
+    sinh_with_flags, sqrt_with_flags, tan_with_flags, tanh_with_flags, tgamma_with_flags,
+};
 pub use fma::fma;
 pub use fmax::fmax;
 pub use fmin::fmin;
 pub use fmod::fmod;
+pub use fresnel::{fresnel_c, fresnel_s};
 pub use gamma::{lgamma, tgamma};
+pub use half::{BF16, F16};
 pub use hypot::hypot;
 pub use ilogb::ilogb;
+pub use interval::Interval;
+pub use lambert::{lambert_w0, lambert_wm1};
 pub use log::ln;
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+pub use log::{ln_avx2, ln_avx512};
 pub use log1p::log1p;
 pub use log2::log2;
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+pub use log2::log2_avx2;
 pub use log10::log10;
 pub use logb::logb;
 pub use modf::modf;
 pub use nextafter::nextafter;
 pub use pow::pow;
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+pub use pow::{pow_avx2, pow_avx512};
 pub use remainder::remainder;
 pub use remquo::remquo;
-pub use rounding::{ceil, floor, llrint, llround, lrint, lround, nearbyint, rint, round, trunc};
+pub use rounding::{
+    RoundingMode, ceil, fegetround, fesetround, floor, llrint, llrint_mode, llround, lrint,
+    lrint_mode, lround, nearbyint, rint, rint_mode, round, trunc,
+};
 pub use scaling::{frexp, ldexp, scalbln, scalbn_public as scalbn};
+pub use sici::{ci, si};
+pub use simd::{F64x2, F64x4, F64x8};
 pub use sin::sin;
 pub use sinh::sinh;
 pub use sqrt::sqrt;
 pub use tan::tan;
 pub use tanh::tanh;
 pub use trig::sincos;
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+pub use trig::{cos_avx2, cos_avx512, sin_avx2, sin_avx512, sincos_avx2};
 pub(crate) use utils::{
-    LN2_HI, LN2_LO, PIO2_HI, PIO2_LO, SPLIT, TWO54, asdouble, fasttwosum, roundeven_finite, two_sum,
+    LN2_HI, LN2_LO, PIO2_HI, PIO2_LO, SPLIT, TWO54, asdouble, roundeven_finite, with_nearest,
 };
+pub use zeta::{hurwitz_zeta, zeta};
 
+// Hardware FMA is assumed on x86, x86_64 and aarch64 unless `soft-fma` is
+// on. `deterministic` implies `soft-fma`: every function then takes its
+// FMA-free path and `fma_internal` is built from plain IEEE operations, so
+// the results do not depend on the CPU, and are the same bits on targets
+// whose f64 operations round once to binary64 (not x87).
 const HAS_FMA: bool = !cfg!(feature = "soft-fma")
     && (cfg!(target_arch = "aarch64") || cfg!(any(target_arch = "x86_64", target_arch = "x86")));
 
@@ -128,19 +217,38 @@ fn f64_to_bits(x: f64) -> u64 {
     x.to_bits()
 }
 
+// Set by the determinism test to run a `soft-fma` build on the hardware
+// instruction, which must leave every result bit unchanged.
+#[cfg(all(test, feature = "soft-fma", target_arch = "x86_64"))]
+std::thread_local! {
+    static HW_FMA: core::cell::Cell<bool> = const { core::cell::Cell::new(false) };
+}
+
+// a b + c with a single rounding, from plain IEEE operations: the same bits
+// as a hardware FMA. With a and b normal and their product well inside the
+// exponent range, Dekker's product is exact and Boldo and Melquiond's
+// emulation finishes it, adding the two error terms rounded to odd so that
+// the final rounding to nearest sees the sticky bit. The rest goes to
+// `fma_exact`.
 #[inline(always)]
 fn fma_soft(a: f64, b: f64, c: f64) -> f64 {
-    if !a.is_finite() || !b.is_finite() || !c.is_finite() {
-        return a * b + c;
+    #[cfg(all(test, feature = "soft-fma", target_arch = "x86_64"))]
+    if HW_FMA.with(core::cell::Cell::get) {
+        // SAFETY: only set once FMA has been detected.
+        return unsafe { fma_hw(a, b, c) };
     }
-    let p = a * b;
-    if !p.is_finite() {
-        return p + c;
-    }
-    let max = f64::MAX / SPLIT;
-    if a.abs() > max || b.abs() > max {
-        return p + c;
+    let ea = (f64_to_bits(a) >> 52) & 0x7ff;
+    let eb = (f64_to_bits(b) >> 52) & 0x7ff;
+    let ec = (f64_to_bits(c) >> 52) & 0x7ff;
+    // 2^-1022 <= |a|, |b| < 2^995, 2^-968 <= |a b| < 2^1022, |c| < 2^1020.
+    if ea.wrapping_sub(1) >= 2017
+        || eb.wrapping_sub(1) >= 2017
+        || !(1078..3067).contains(&(ea + eb))
+        || ec >= 2043
+    {
+        return fma_exact(a, b, c);
     }
+    let p = a * b;
     let a_split = a * SPLIT;
     let a_hi = a_split - (a_split - a);
     let a_lo = a - a_hi;
@@ -149,15 +257,102 @@ fn fma_soft(a: f64, b: f64, c: f64) -> f64 {
     let b_lo = b - b_hi;
     let err = ((a_hi * b_hi - p) + a_hi * b_lo + a_lo * b_hi) + a_lo * b_lo;
     let s = p + c;
-    let err2 = (p - s) + c;
-    let t = err + err2;
-    s + t
+    let bp = s - c;
+    let s_err = (c - (s - bp)) + (p - bp);
+    // v = s_err + err rounded to odd: rounded to nearest, then moved to the
+    // odd neighbour on the side of the exact sum when inexact.
+    let v = s_err + err;
+    let vp = v - err;
+    let v_err = (err - (v - vp)) + (s_err - vp);
+    let vb = f64_to_bits(v);
+    let v = if v_err != 0.0 && vb & 1 == 0 {
+        f64_from_bits(if (v_err > 0.0) == (v > 0.0) {
+            vb + 1
+        } else {
+            vb - 1
+        })
+    } else {
+        v
+    };
+    s + v
+}
+
+// a b + c for any operands, in integer arithmetic: the exact sum of the
+// 106-bit product and c, with the bits shifted out kept as a sticky bit,
+// rounded once to nearest.
+#[inline(never)]
+fn fma_exact(a: f64, b: f64, c: f64) -> f64 {
+    if !a.is_finite() || !b.is_finite() || c.is_nan() || a == 0.0 || b == 0.0 {
+        return a * b + c;
+    }
+    // The exact product is finite, even where a b overflows.
+    if c.is_infinite() {
+        return c;
+    }
+    if c == 0.0 {
+        return a * b;
+    }
+    // |x| = m 2^e with bit 52 of m set.
+    fn unpack(x: f64) -> (u64, i32) {
+        let b = f64_to_bits(x) & !(1 << 63);
+        match b >> 52 {
+            0 => {
+                let s = b.leading_zeros() - 11;
+                (b << s, -1074 - s as i32)
+            }
+            e => (b & ((1 << 52) - 1) | 1 << 52, e as i32 - 1075),
+        }
+    }
+    let (ma, ea) = unpack(a);
+    let (mb, eb) = unpack(b);
+    let (mc, ec) = unpack(c);
+    let neg_p = (a < 0.0) != (b < 0.0);
+    let neg_c = c < 0.0;
+    // Both terms with their leading bit at 125.
+    let pm = ma as u128 * mb as u128;
+    let sh = pm.leading_zeros() - 2;
+    let (pm, pe) = (pm << sh, ea + eb - sh as i32);
+    let (cm, ce) = ((mc as u128) << 73, ec - 73);
+    let ((bm, be, neg), (sm, se)) = if (pe, pm) >= (ce, cm) {
+        ((pm, pe, neg_p), (cm, ce))
+    } else {
+        ((cm, ce, neg_c), (pm, pe))
+    };
+    let d = (be - se) as u32;
+    let sm = if d == 0 {
+        sm
+    } else if d < 128 {
+        sm >> d | (sm << (128 - d) != 0) as u128
+    } else {
+        1
+    };
+    let r = if neg_p == neg_c { bm + sm } else { bm - sm };
+    if r == 0 {
+        return 0.0;
+    }
+    let sign = (neg as u64) << 63;
+    // Round r 2^be to 53 bits, or to a multiple of 2^-1074 below the normals.
+    let lead = (127 - r.leading_zeros() as i32 + be).max(-1022);
+    let s = lead - 52 - be;
+    let q = if s <= 0 {
+        (r << -s) as u64
+    } else if s < 128 {
+        let (q, rest, half) = (r >> s, r & ((1 << s) - 1), 1u128 << (s - 1));
+        (q + (rest > half || rest == half && q & 1 == 1) as u128) as u64
+    } else {
+        0
+    };
+    // A carry out of the fraction bumps the exponent, as does a subnormal
+    // q reaching 2^52.
+    let bits = (((lead + 1022) as u64) << 52) + q;
+    f64_from_bits(sign | bits.min(0x7ff0_0000_0000_0000))
 }
 
 #[inline(always)]
 fn fma_internal(a: f64, b: f64, c: f64) -> f64 {
-    if HAS_FMA {
-        // Safety: compiled with FMA target feature or on aarch64.
+    if fma_available() {
+        // Safety: compiled with FMA target feature, detected at run time, or
+        // on aarch64.
         unsafe { fma_hw(a, b, c) }
     } else {
         fma_soft(a, b, c)
@@ -166,6 +361,15 @@ fn fma_internal(a: f64, b: f64, c: f64) -> f64 {
 
 #[inline(always)]
 fn fma_available() -> bool {
+    #[cfg(all(
+        any(target_arch = "x86_64", target_arch = "x86"),
+        feature = "runtime-dispatch"
+    ))]
+    return dispatch::has_fma();
+    #[cfg(not(all(
+        any(target_arch = "x86_64", target_arch = "x86"),
+        feature = "runtime-dispatch"
+    )))]
     HAS_FMA
 }
 
@@ -278,6 +482,76 @@ pub(crate) fn floor_f64(x: f64) -> f64 {
 mod tests {
     use super::*;
 
+    // Under `deterministic` every function runs its FMA-free path and the
+    // only fused operation left is `fma_soft`, which rounds once. Swapping in
+    // the hardware instruction must therefore give the same bits, which is
+    // what makes an FMA and a non-FMA target agree. NaNs compare as NaNs:
+    // their sign is not pinned.
+    #[cfg(all(feature = "deterministic", target_arch = "x86_64"))]
+    #[test]
+    fn deterministic_results_do_not_depend_on_fma() {
+        use std::vec::Vec;
+        if !std::is_x86_feature_detected!("fma") {
+            return;
+        }
+        let mut s = 0x9e37_79b9_7f4a_7c15u64;
+        let mut next = move || {
+            s ^= s << 13;
+            s ^= s >> 7;
+            s ^= s << 17;
+            s
+        };
+        // Half raw bit patterns, half moderate values across 2^-30..2^34.
+        let xs: Vec<f64> = (0..1 << 14)
+            .map(|i| {
+                let r = next();
+                if i % 2 == 0 {
+                    f64::from_bits(r)
+                } else {
+                    let m = (r >> 11) as f64 / (1u64 << 53) as f64 * 40.0 - 20.0;
+                    m * f64::from_bits(((r & 0x3f) + 993) << 52)
+                }
+            })
+            .collect();
+        let mut ys = xs.clone();
+        ys.rotate_left(7);
+        let run = |hw: bool| {
+            HW_FMA.with(|c| c.set(hw));
+            let mut out = Vec::new();
+            macro_rules! eval {
+                (unary: $($f:ident),*; binary: $($g:ident),*) => {
+                    $(out.extend(xs.iter().map(|&x| (stringify!($f), x, 0.0, $f(x)))));*;
+                    $(out.extend(
+                        xs.iter().zip(&ys).map(|(&x, &y)| (stringify!($g), x, y, $g(x, y))),
+                    ));*;
+                };
+            }
+            eval!(
+                unary: acos, acosh, asin, asinh, atan, atanh, cbrt, ci, cos, cosh, ellipe, ellipk,
+                erf, erfc, exp, exp10, exp2, expint_e1, expint_ei, expm1, fresnel_c, fresnel_s,
+                lambert_w0, lambert_wm1, lgamma, ln, log10, log1p, log2, si, sin, sinh, sqrt,
+                tan, tanh, tgamma, zeta;
+                binary: atan2, ellipeinc, ellipkinc, ellippi, elliprc, fmod, hurwitz_zeta, hypot,
+                pow, remainder
+            );
+            out.extend(
+                xs.iter()
+                    .zip(&ys)
+                    .map(|(&x, &y)| ("fma", x, y, fma(x, y, x))),
+            );
+            HW_FMA.with(|c| c.set(false));
+            out
+        };
+        let soft = run(false);
+        let hw = run(true);
+        for (&(name, x, y, a), &(_, _, _, b)) in soft.iter().zip(&hw) {
+            assert!(
+                a.to_bits() == b.to_bits() || (a.is_nan() && b.is_nan()),
+                "{name}({x:e}, {y:e}): {a:e} without FMA, {b:e} with"
+            );
+        }
+    }
+
     #[test]
     fn test_floor() {
         let values = [
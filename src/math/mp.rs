@@ -0,0 +1,1010 @@
+//! Fixed-precision multi-word binary floating point for accurate phases.
+//!
+//! `Mp<L>` holds a sign, an exponent and an `L`-limb (64·L-bit) mantissa,
+//! value = 0.man × 2^exp with the top mantissa bit set. Every operation
+//! truncates, so each contributes less than one unit in the last limb; the
+//! elementary functions below keep their total relative error well under
+//! 2^-(64·L-32), the margin the correctly rounded functions test against.
+//! Exponents are `i32`, so no intermediate of a double-precision evaluation
+//! can overflow or underflow.
+
+use core::cmp::Ordering;
+
+// Scratch buffers are sized for the largest supported precision.
+const MAXL: usize = 6;
+
+/// Rounding direction for [`Mp::to_f64`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Round {
+    Nearest,
+    Down,
+    Up,
+    Zero,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Mp<const L: usize> {
+    neg: bool,
+    exp: i32,
+    // Little-endian limbs; man[L - 1] holds the most significant bits.
+    man: [u64; L],
+}
+
+// ln 2, ln 10 and pi/2 as big-endian mantissa words, 0.w × 2^exp.
+const LN2: (i32, [u64; 6]) = (
+    0,
+    [
+        0xb17217f7d1cf79ab,
+        0xc9e3b39803f2f6af,
+        0x40f343267298b62d,
+        0x8a0d175b8baafa2b,
+        0xe7b876206debac98,
+        0x559552fb4afa1b10,
+    ],
+);
+const LN10: (i32, [u64; 6]) = (
+    2,
+    [
+        0x935d8dddaaa8ac16,
+        0xea56d62b82d30a28,
+        0xe28fecf9da5df90e,
+        0x83c61e8201f02d72,
+        0x962f02d7b1a8105c,
+        0xcc70cbc02c5f0d68,
+    ],
+);
+const PI_2: (i32, [u64; 6]) = (
+    1,
+    [
+        0xc90fdaa22168c234,
+        0xc4c6628b80dc1cd1,
+        0x29024e088a67cc74,
+        0x020bbea63b139b22,
+        0x514a08798e3404dd,
+        0xef9519b3cd3a431b,
+    ],
+);
+
+// The first 17152 fraction bits of 2/pi, most significant first, for the
+// Payne-Hanek reduction of arguments up to 2^16384.
+const TWO_OVER_PI: [u64; 268] = [
+    0xa2f9836e4e441529,
+    0xfc2757d1f534ddc0,
+    0xdb6295993c439041,
+    0xfe5163abdebbc561,
+    0xb7246e3a424dd2e0,
+    0x06492eea09d1921c,
+    0xfe1deb1cb129a73e,
+    0xe88235f52ebb4484,
+    0xe99c7026b45f7e41,
+    0x3991d639835339f4,
+    0x9c845f8bbdf9283b,
+    0x1ff897ffde05980f,
+    0xef2f118b5a0a6d1f,
+    0x6d367ecf27cb09b7,
+    0x4f463f669e5fea2d,
+    0x7527bac7ebe5f17b,
+    0x3d0739f78a5292ea,
+    0x6bfb5fb11f8d5d08,
+    0x56033046fc7b6bab,
+    0xf0cfbc209af4361d,
+    0xa9e391615ee61b08,
+    0x6599855f14a06840,
+    0x8dffd8804d732731,
+    0x06061556ca73a8c9,
+    0x60e27bc08c6b47c4,
+    0x19c367cddce8092a,
+    0x8359c4768b961ca6,
+    0xddaf44d15719053e,
+    0xa5ff07053f7e33e8,
+    0x32c2de4f98327dbb,
+    0xc33d26ef6b1e5ef8,
+    0x9f3a1f35caf27f1d,
+    0x87f121907c7c246a,
+    0xfa6ed5772d30433b,
+    0x15c614b59d19c3c2,
+    0xc4ad414d2c5d000c,
+    0x467d862d71e39ac6,
+    0x9b0062337cd2b497,
+    0xa7b4d55537f63ed7,
+    0x1810a3fc764d2a9d,
+    0x64abd770f87c6357,
+    0xb07ae715175649c0,
+    0xd9d63b3884a7cb23,
+    0x24778ad623545ab9,
+    0x1f001b0af1dfce19,
+    0xff319f6a1e666157,
+    0x9947fbacd87f7eb7,
+    0x652289e83260bfe6,
+    0xcdc4ef09366cd43f,
+    0x5dd7de16de3b5892,
+    0x9bde2822d2e88628,
+    0x4d58e232cac616e3,
+    0x08cb7de050c017a7,
+    0x1df35be01834132e,
+    0x6212830148835b8e,
+    0xf57fb0adf2e91e43,
+    0x4a48d36710d8ddaa,
+    0x425faece616aa428,
+    0x0ab499d3f2a6067f,
+    0x775c83c2a3883c61,
+    0x78738a5a8cafbdd7,
+    0x6f63a62dcbbff4ef,
+    0x818d67c12645ca55,
+    0x36d9cad2a8288d61,
+    0xc277c9121426049b,
+    0x4612c459c444c5c8,
+    0x91b24df31700ad43,
+    0xd4e5492910d5fdfc,
+    0xbe00cc941eeece70,
+    0xf53e1380f1ecc3e7,
+    0xb328f8c79405933e,
+    0x71c1b3092ef3450b,
+    0x9c12887b20ab9fb5,
+    0x2ec292472f327b6d,
+    0x550c90a7721fe76b,
+    0x96cb314a1679e279,
+    0x4189dff49794e884,
+    0xe6e29731996bed88,
+    0x365f5f0efdbbb49a,
+    0x486ca46742727132,
+    0x5d8db8159f09e5bc,
+    0x25318d3974f71c05,
+    0x30010c0d68084b58,
+    0xee2c90aa4702e774,
+    0x24d6bda67df77248,
+    0x6eef169fa6948ef6,
+    0x91b45153d1f20acf,
+    0x3398207e4bf56863,
+    0xb25f3edd035d407f,
+    0x8985295255c06437,
+    0x10d86d324832754c,
+    0x5bd4714e6e5445c1,
+    0x090b69f52ad56614,
+    0x9d072750045ddb3b,
+    0xb4c576ea17f9877d,
+    0x6b49ba271d296996,
+    0xacccc65414ad6ae2,
+    0x9089d98850722cbe,
+    0xa4049407777030f3,
+    0x27fc00a871ea49c2,
+    0x663de06483dd9797,
+    0x3fa3fd94438c860d,
+    0xde41319d39928c70,
+    0xdde7b7173bdf082b,
+    0x3715a0805c93805a,
+    0x921110d8e80faf80,
+    0x6c4bffdb0f903876,
+    0x185915a562bbcb61,
+    0xb989c7bd401004f2,
+    0xd2277549f6b6ebbb,
+    0x22dbaa140a2f2689,
+    0x768364333b091a94,
+    0x0eaa3a51c2a31dae,
+    0xedaf12265c4dc26d,
+    0x9c7a2d9756c0833f,
+    0x03f6f0098c402b99,
+    0x316d07b43915200c,
+    0x5bc3d8c492f54bad,
+    0xc6a5ca4ecd37a736,
+    0xa9e69492ab6842dd,
+    0xde6319ef8c76528b,
+    0x6837dbfcaba1ae31,
+    0x15dfa1ae00dafb0c,
+    0x664d64b705ed3065,
+    0x29bf56573aff47b9,
+    0xf96af3be75df9328,
+    0x3080abf68c6615cb,
+    0x040622fa1de4d9a4,
+    0xb33d8f1b5709cd36,
+    0xe9424ea4be13b523,
+    0x331aaaf0a8654fa5,
+    0xc1d20f3f0bcd785b,
+    0x76f923048b7b7217,
+    0x8953a6c6e26e6f00,
+    0xebef584a9bb7dac4,
+    0xba66aacfcf761d02,
+    0xd12df1b1c1998c77,
+    0xadc3da4886a05df7,
+    0xf480c62ff0ac9aec,
+    0xddbc5c3f6dded01f,
+    0xc790b6db2a3a25a3,
+    0x9aaf009353ad0457,
+    0xb6b42d297e804ba7,
+    0x07da0eaa76a1597b,
+    0x2a12162db7dcfde5,
+    0xfafedb89fdbe896c,
+    0x76e4fca90670803e,
+    0x156e85ff87fd073e,
+    0x2833676186182aea,
+    0xbd4dafe7b36e6d8f,
+    0x3967955bbf3148d7,
+    0x8416df30432dc735,
+    0x6125ce70c9b8cb30,
+    0xfd6cbfa200a4e46c,
+    0x05a0dd5a476f21d2,
+    0x1262845cb9496170,
+    0xe0566b0152993755,
+    0x50b7d51ec4f1335f,
+    0x6e13e4305da92e85,
+    0xc3b21d3632a1a4b7,
+    0x08d4b1ea21f716e4,
+    0x698f77ff2780030c,
+    0x2d408da0cd4f99a5,
+    0x20d3a2b30a5d2f42,
+    0xf9b4cbda11d0be7d,
+    0xc1db9bbd17ab81a2,
+    0xca5c6a0817552e55,
+    0x0027f0147f8607e1,
+    0x640b148d4196debe,
+    0x872afddab6256b34,
+    0x897bfef3059ebfb9,
+    0x4f6a68a82a4a5ac4,
+    0x4fbcf82d985ad795,
+    0xc7f48d4d0da63a20,
+    0x5f57a4b13f149538,
+    0x800120cc86dd71b6,
+    0xdec9f560bf11654d,
+    0x6b0701acb08cd0c0,
+    0xb24855510efb1ec3,
+    0x72953b06a33540c0,
+    0x7bdc06cc45e0fa29,
+    0x4ec8cad641f3e8de,
+    0x647cd8649b31bed9,
+    0xc397a4d45877c5e3,
+    0x6913daf03c3aba46,
+    0x18465f7555f5bdd2,
+    0xc6926e5d2eaced44,
+    0x0e423e1c87c461e9,
+    0xfd29f3d6e7ca7c22,
+    0x35916fc5e0088dd7,
+    0xffe26a6ec6fdb0c1,
+    0x0893745d7cb2ad6b,
+    0x9d6ecd7b723e6a11,
+    0xc6a9cff7df7329ba,
+    0xc9b55100b70db2e2,
+    0x24ba74607de58ad8,
+    0x742c150d0c188194,
+    0x667e162901767a9f,
+    0xbefdfdef4556367e,
+    0xd913d9ecb9ba8bfc,
+    0x97c427a831c36ef1,
+    0x36c59456a8d8b5a8,
+    0xb40ecccf2d891234,
+    0x576f89562ce3ce99,
+    0xb920d6aa5e6b9c2a,
+    0x3ecc5f114a0bfdfb,
+    0xf4e16d3b8e2c86e2,
+    0x84d4e9a9b4fcd1ee,
+    0xefc9352e61392f44,
+    0x2138c8d91b0afc81,
+    0x6a4afbd81c2f84b4,
+    0x538c994ecc2254dc,
+    0x552ad6c6c096190b,
+    0xb8701a649569605a,
+    0x26ee523f0f117f11,
+    0xb5f4f5cbfc2dbc34,
+    0xeebc34cc5de8605e,
+    0xdd9b8e67ef3392b8,
+    0x17c99b5861bc57e1,
+    0xc68351103ed84871,
+    0xdddd1c2da118af46,
+    0x2c21d7f359987ad9,
+    0xc0549efa864ffc06,
+    0x56ae79e536228922,
+    0xad38dc9367aae855,
+    0x3826829be7caa40d,
+    0x51b133990ed7a948,
+    0x0569f0b265a7887f,
+    0x974c8836d1f9b392,
+    0x214a827b21cf98dc,
+    0x9f405547dc3a74e1,
+    0x42eb67df9dfe5fd4,
+    0x5ea4677b7aacbaa2,
+    0xf65523882b55ba41,
+    0x086e59862a218347,
+    0x39e6e389d49ee540,
+    0xfb49e956ffca0f1c,
+    0x8a59c52bfa94c5c1,
+    0xd3cfc50fae5adb86,
+    0xc5476243853b8621,
+    0x94792c8761107b4c,
+    0x2a1a2c8012bf4390,
+    0x2688893c78e4c4a8,
+    0x7bdbe5c23ac4eaf4,
+    0x268a67f7bf920d2b,
+    0xa365b1933d0b7cbd,
+    0xdc51a463dd27dde1,
+    0x6919949a9529a828,
+    0xce68b4ed09209f44,
+    0xca984e638270237c,
+    0x7e32b90f8ef5a7e7,
+    0x561408f1212a9db5,
+    0x4d7e6f5119a5abf9,
+    0xb5d6df8261dd9602,
+    0x36169f3ac4a1a283,
+    0x6ded727a8d39a9b8,
+    0x825c326b5b2746ed,
+    0x34007700d255f4fc,
+    0x4d59018071e0e13f,
+    0x89b295f364a8f1ae,
+    0xa74b38fc4ceab2bb,
+    0x47270babc3a734ba,
+    0x6052dd34f8563aeb,
+    0x7e8a31bb365895b7,
+    0x47f7a994c3aad392,
+    0x251e7f3ed8974ebb,
+    0xa94fd8ae01e661b4,
+    0x393d8ea523aa3306,
+];
+
+// ---- little-endian limb helpers ----
+
+fn leading_zeros(a: &[u64]) -> u32 {
+    let mut n = 0;
+    for &w in a.iter().rev() {
+        if w != 0 {
+            return n + w.leading_zeros();
+        }
+        n += 64;
+    }
+    n
+}
+
+fn shl_bits(a: &mut [u64], n: u32) {
+    let (words, bits) = ((n / 64) as usize, n % 64);
+    for i in (0..a.len()).rev() {
+        let hi = if i >= words { a[i - words] } else { 0 };
+        let lo = if i > words { a[i - words - 1] } else { 0 };
+        a[i] = if bits == 0 {
+            hi
+        } else {
+            (hi << bits) | (lo >> (64 - bits))
+        };
+    }
+}
+
+fn shr_bits(a: &mut [u64], n: u32) {
+    let (words, bits) = ((n / 64) as usize, n % 64);
+    let len = a.len();
+    for i in 0..len {
+        let lo = if i + words < len { a[i + words] } else { 0 };
+        let hi = if i + words + 1 < len {
+            a[i + words + 1]
+        } else {
+            0
+        };
+        a[i] = if bits == 0 {
+            lo
+        } else {
+            (lo >> bits) | (hi << (64 - bits))
+        };
+    }
+}
+
+fn add_limbs(a: &mut [u64], b: &[u64]) -> bool {
+    let mut carry = false;
+    for (x, &y) in a.iter_mut().zip(b) {
+        let (s, c1) = x.overflowing_add(y);
+        let (s, c2) = s.overflowing_add(carry as u64);
+        *x = s;
+        carry = c1 || c2;
+    }
+    carry
+}
+
+fn sub_limbs(a: &mut [u64], b: &[u64]) {
+    let mut borrow = false;
+    for (x, &y) in a.iter_mut().zip(b) {
+        let (s, b1) = x.overflowing_sub(y);
+        let (s, b2) = s.overflowing_sub(borrow as u64);
+        *x = s;
+        borrow = b1 || b2;
+    }
+}
+
+fn bit(a: &[u64], i: u32) -> bool {
+    (a[(i / 64) as usize] >> (i % 64)) & 1 != 0
+}
+
+impl<const L: usize> Mp<L> {
+    pub(crate) const ZERO: Self = Self {
+        neg: false,
+        exp: 0,
+        man: [0; L],
+    };
+
+    pub(crate) fn one() -> Self {
+        let mut man = [0; L];
+        man[L - 1] = 1 << 63;
+        Self {
+            neg: false,
+            exp: 1,
+            man,
+        }
+    }
+
+    // Normalizes a little-endian magnitude of any width into an `Mp`, the
+    // value being `a` × 2^(exp - 64 * a.len()).
+    fn from_limbs(neg: bool, exp: i32, a: &mut [u64]) -> Self {
+        let lz = leading_zeros(a);
+        if lz == 64 * a.len() as u32 {
+            return Self::ZERO;
+        }
+        shl_bits(a, lz);
+        let mut man = [0; L];
+        let n = a.len().min(L);
+        man[L - n..].copy_from_slice(&a[a.len() - n..]);
+        Self {
+            neg,
+            exp: exp - lz as i32,
+            man,
+        }
+    }
+
+    fn from_words(c: &(i32, [u64; 6])) -> Self {
+        let mut man = [0; L];
+        for (i, m) in man.iter_mut().rev().enumerate() {
+            *m = c.1[i];
+        }
+        Self {
+            neg: false,
+            exp: c.0,
+            man,
+        }
+    }
+
+    pub(crate) fn ln2() -> Self {
+        Self::from_words(&LN2)
+    }
+
+    pub(crate) fn ln10() -> Self {
+        Self::from_words(&LN10)
+    }
+
+    pub(crate) fn pi_2() -> Self {
+        Self::from_words(&PI_2)
+    }
+
+    /// `x` exactly; `x` must be finite.
+    pub(crate) fn from_f64(x: f64) -> Self {
+        let b = x.to_bits();
+        let e = ((b >> 52) & 0x7ff) as i32;
+        let f = b & ((1 << 52) - 1);
+        let (m, e) = if e == 0 {
+            (f, -1074)
+        } else {
+            (f | (1 << 52), e - 1075)
+        };
+        Self::from_limbs(x.is_sign_negative(), e + 64, &mut [m])
+    }
+
+    /// `±m × 2^e` exactly (`L >= 2`).
+    pub(crate) fn from_u128(neg: bool, m: u128, e: i32) -> Self {
+        Self::from_limbs(neg, e + 128, &mut [m as u64, (m >> 64) as u64])
+    }
+
+    /// `(neg, m, e, sticky)` with `|self| = (m + δ) × 2^e`: `m` holds the
+    /// top 128 mantissa bits and `δ` in [0, 1) is nonzero iff `sticky`.
+    pub(crate) fn to_u128(self) -> (bool, u128, i32, bool) {
+        let m = ((self.man[L - 1] as u128) << 64) | self.man[L - 2] as u128;
+        let sticky = self.man[..L - 2].iter().any(|&w| w != 0);
+        (self.neg, m, self.exp - 128, sticky)
+    }
+
+    pub(crate) fn from_i64(n: i64) -> Self {
+        Self::from_limbs(n < 0, 64, &mut [n.unsigned_abs()])
+    }
+
+    pub(crate) fn is_zero(&self) -> bool {
+        self.man[L - 1] == 0
+    }
+
+    pub(crate) fn is_neg(&self) -> bool {
+        self.neg && !self.is_zero()
+    }
+
+    /// The exponent: `|self|` lies in [2^(e-1), 2^e).
+    pub(crate) fn exponent(&self) -> i32 {
+        self.exp
+    }
+
+    pub(crate) fn neg(self) -> Self {
+        Self {
+            neg: !self.neg,
+            ..self
+        }
+    }
+
+    pub(crate) fn abs(self) -> Self {
+        Self { neg: false, ..self }
+    }
+
+    /// `self` × 2^k.
+    pub(crate) fn ldexp(self, k: i32) -> Self {
+        if self.is_zero() {
+            return self;
+        }
+        Self {
+            exp: self.exp + k,
+            ..self
+        }
+    }
+
+    fn cmp_abs(&self, o: &Self) -> Ordering {
+        match (self.is_zero(), o.is_zero()) {
+            (true, true) => return Ordering::Equal,
+            (true, false) => return Ordering::Less,
+            (false, true) => return Ordering::Greater,
+            _ => {}
+        }
+        self.exp
+            .cmp(&o.exp)
+            .then_with(|| self.man.iter().rev().cmp(o.man.iter().rev()))
+    }
+
+    pub(crate) fn add(self, o: Self) -> Self {
+        const { assert!(L < MAXL) };
+        if o.is_zero() {
+            return self;
+        }
+        if self.is_zero() {
+            return o;
+        }
+        let (a, b) = if self.cmp_abs(&o) == Ordering::Less {
+            (o, self)
+        } else {
+            (self, o)
+        };
+        // One guard limb below the mantissa keeps cancellation exact.
+        let d = (a.exp - b.exp) as u32;
+        let mut x = [0u64; MAXL + 1];
+        let mut y = [0u64; MAXL + 1];
+        x[1..=L].copy_from_slice(&a.man);
+        if d < 64 * (L as u32 + 1) {
+            y[1..=L].copy_from_slice(&b.man);
+            shr_bits(&mut y[..=L], d);
+        }
+        if a.neg == b.neg {
+            let mut exp = a.exp;
+            if add_limbs(&mut x[..=L], &y[..=L]) {
+                shr_bits(&mut x[..=L], 1);
+                x[L] |= 1 << 63;
+                exp += 1;
+            }
+            Self::from_limbs(a.neg, exp, &mut x[..=L])
+        } else {
+            sub_limbs(&mut x[..=L], &y[..=L]);
+            Self::from_limbs(a.neg, a.exp, &mut x[..=L])
+        }
+    }
+
+    pub(crate) fn sub(self, o: Self) -> Self {
+        self.add(o.neg())
+    }
+
+    pub(crate) fn mul(self, o: Self) -> Self {
+        if self.is_zero() || o.is_zero() {
+            return Self::ZERO;
+        }
+        let mut p = [0u64; 2 * MAXL];
+        for i in 0..L {
+            let mut carry = 0u128;
+            for j in 0..L {
+                let t = self.man[i] as u128 * o.man[j] as u128 + p[i + j] as u128 + carry;
+                p[i + j] = t as u64;
+                carry = t >> 64;
+            }
+            p[i + L] = carry as u64;
+        }
+        Self::from_limbs(self.neg != o.neg, self.exp + o.exp, &mut p[..2 * L])
+    }
+
+    pub(crate) fn sqr(self) -> Self {
+        self.mul(self)
+    }
+
+    pub(crate) fn mul_u64(self, n: u64) -> Self {
+        if self.is_zero() || n == 0 {
+            return Self::ZERO;
+        }
+        let mut p = [0u64; MAXL + 1];
+        let mut carry = 0u128;
+        for (w, &m) in p.iter_mut().zip(&self.man) {
+            let t = m as u128 * n as u128 + carry;
+            *w = t as u64;
+            carry = t >> 64;
+        }
+        p[L] = carry as u64;
+        Self::from_limbs(self.neg, self.exp + 64, &mut p[..=L])
+    }
+
+    pub(crate) fn div_u64(self, n: u64) -> Self {
+        if self.is_zero() {
+            return self;
+        }
+        // floor(man × 2^64 / n), one extra limb of quotient.
+        let mut q = [0u64; MAXL + 1];
+        let mut r = 0u128;
+        for i in (0..=L).rev() {
+            let cur = (r << 64) | if i > 0 { self.man[i - 1] as u128 } else { 0 };
+            q[i] = (cur / n as u128) as u64;
+            r = cur % n as u128;
+        }
+        Self::from_limbs(self.neg, self.exp, &mut q[..=L])
+    }
+
+    // Newton steps double the 50 correct bits of an f64 seed until they
+    // cover the mantissa.
+    fn newton_steps() -> u32 {
+        let mut bits = 50;
+        let mut n = 0;
+        while bits < 64 * L as u32 + 8 {
+            bits *= 2;
+            n += 1;
+        }
+        n
+    }
+
+    pub(crate) fn recip(self) -> Self {
+        let m = Self {
+            neg: false,
+            exp: 0,
+            ..self
+        };
+        let mut r = Self::from_f64(1.0 / m.to_f64(Round::Nearest));
+        let one = Self::one();
+        for _ in 0..Self::newton_steps() {
+            let e = one.sub(m.mul(r));
+            r = r.add(r.mul(e));
+        }
+        Self {
+            neg: self.neg,
+            ..r.ldexp(-self.exp)
+        }
+    }
+
+    pub(crate) fn div(self, o: Self) -> Self {
+        self.mul(o.recip())
+    }
+
+    /// The square root of a non-negative value.
+    pub(crate) fn sqrt(self) -> Self {
+        if self.is_zero() {
+            return self;
+        }
+        // self = m × 2^(2k) with m in [1/4, 1).
+        let k = self.exp.div_euclid(2);
+        let m = Self {
+            neg: false,
+            exp: self.exp - 2 * k,
+            ..self
+        };
+        let mut r = Self::from_f64(1.0 / super::sqrt(m.to_f64(Round::Nearest)));
+        let one = Self::one();
+        for _ in 0..Self::newton_steps() {
+            let e = one.sub(m.mul(r.sqr()));
+            r = r.add(r.mul(e).ldexp(-1));
+        }
+        m.mul(r).ldexp(k)
+    }
+
+    /// The cube root of a nonzero value, by Newton steps
+    /// y -> (2y + self / y²) / 3 from the double cube root.
+    pub(crate) fn cbrt(self) -> Self {
+        // self = m × 2^(3k) with m in [1/8, 1), so the seed cannot overflow.
+        let k = self.exp.div_euclid(3);
+        let m = Self {
+            exp: self.exp - 3 * k,
+            ..self
+        };
+        let mut y = Self::from_f64(super::cbrt(m.to_f64(Round::Nearest)));
+        for _ in 0..Self::newton_steps() {
+            y = y.ldexp(1).add(m.div(y.sqr())).div_u64(3);
+        }
+        y.ldexp(k)
+    }
+
+    /// `self` rounded to a double in direction `mode`, with overflow to
+    /// infinity or the largest finite value and gradual underflow.
+    pub(crate) fn to_f64(self, mode: Round) -> f64 {
+        if self.is_zero() {
+            return if self.neg { -0.0 } else { 0.0 };
+        }
+        let away = |inexact: bool| match mode {
+            Round::Nearest | Round::Zero => false,
+            Round::Up => inexact && !self.neg,
+            Round::Down => inexact && self.neg,
+        };
+        let sign = if self.neg { -1.0 } else { 1.0 };
+        let overflow = || {
+            if mode == Round::Nearest || away(true) {
+                sign * f64::INFINITY
+            } else {
+                sign * f64::MAX
+            }
+        };
+        // |self| in [2^e2, 2^(e2 + 1)); keep the bits down to 2^-1074.
+        let e2 = self.exp - 1;
+        if e2 > 1023 {
+            return overflow();
+        }
+        let kept = if e2 >= -1022 { 53 } else { e2 + 1075 };
+        let top = 64 * L as u32;
+        let (mut q, half, sticky) = if kept >= 1 {
+            let kept = kept as u32;
+            let q = self.man[L - 1] >> (64 - kept);
+            let half = bit(&self.man, top - kept - 1);
+            let mut rest = self.man;
+            rest[L - 1] &= (u64::MAX >> kept) >> 1;
+            (q, half, rest.iter().any(|&w| w != 0))
+        } else if kept == 0 {
+            let mut rest = self.man;
+            rest[L - 1] &= !(1 << 63);
+            (0, true, rest.iter().any(|&w| w != 0))
+        } else {
+            (0, false, true)
+        };
+        let inc = match mode {
+            Round::Nearest => half && (sticky || q & 1 != 0),
+            _ => away(half || sticky),
+        };
+        q += inc as u64;
+        let bits = if kept >= 53 {
+            let mut e2 = e2;
+            if q == 1 << 53 {
+                q >>= 1;
+                e2 += 1;
+                if e2 > 1023 {
+                    return overflow();
+                }
+            }
+            (((e2 + 1023) as u64) << 52) | (q & ((1 << 52) - 1))
+        } else {
+            // Subnormal: q counts units of 2^-1074 (2^52 of them is the
+            // smallest normal, whose encoding is the same).
+            q
+        };
+        sign * f64::from_bits(bits)
+    }
+
+    /// `self` rounded in direction `mode` if every value within a relative
+    /// `2^-err_bits` of it rounds the same way.
+    pub(crate) fn round_checked(self, err_bits: u32, mode: Round) -> Option<f64> {
+        let eps = Self {
+            neg: false,
+            exp: self.exp - err_bits as i32,
+            ..self
+        };
+        let lo = self.sub(eps).to_f64(mode);
+        let hi = self.add(eps).to_f64(mode);
+        (lo.to_bits() == hi.to_bits()).then_some(lo)
+    }
+
+    /// Rounds a value whose exact counterpart is known to be a rounding
+    /// boundary: a midpoint, which rounds to even, for [`Round::Nearest`],
+    /// else a double, which is returned as is.
+    pub(crate) fn round_exact(self, mode: Round) -> f64 {
+        if mode != Round::Nearest {
+            return self.to_f64(Round::Nearest);
+        }
+        let (lo, hi) = (self.to_f64(Round::Down), self.to_f64(Round::Up));
+        if lo.to_bits() & 1 == 0 { lo } else { hi }
+    }
+
+    // ---- elementary functions ----
+
+    /// e^r - 1 for |r| <= 1/2: the Taylor series at r × 2^-10, then ten
+    /// doublings e -> e(e + 2), which keep the relative error of e.
+    fn expm1_reduced(r: Self) -> Self {
+        const HALVINGS: i32 = 10;
+        let r = r.ldexp(-HALVINGS);
+        let mut sum = r;
+        let mut term = r;
+        let mut n = 2;
+        while !term.is_zero() && term.exp > sum.exp - 64 * L as i32 - 4 {
+            term = term.mul(r).div_u64(n);
+            sum = sum.add(term);
+            n += 1;
+        }
+        let two = Self::from_i64(2);
+        for _ in 0..HALVINGS {
+            sum = sum.mul(sum.add(two));
+        }
+        sum
+    }
+
+    // (k, r) with self = k ln 2 + r, |r| <= ln 2 / 2 + tiny.
+    fn reduce_ln2(self) -> (i32, Self) {
+        let k =
+            super::roundeven_finite(self.to_f64(Round::Nearest) / core::f64::consts::LN_2) as i32;
+        (k, self.sub(Self::ln2().mul(Self::from_i64(k as i64))))
+    }
+
+    /// e^self, for |self| below about 2^11.
+    pub(crate) fn exp(self) -> Self {
+        let (k, r) = self.reduce_ln2();
+        Self::one().add(Self::expm1_reduced(r)).ldexp(k)
+    }
+
+    /// e^self - 1, for |self| below about 2^11.
+    pub(crate) fn expm1(self) -> Self {
+        if self.exp <= -1 {
+            return Self::expm1_reduced(self);
+        }
+        self.exp().sub(Self::one())
+    }
+
+    // Σ s^(2n+1) / (2n+1), the series of atanh(s), for |s| <= 0.18.
+    fn atanh_series(s: Self) -> Self {
+        let s2 = s.sqr();
+        let mut p = s;
+        let mut sum = s;
+        let mut n = 3;
+        loop {
+            p = p.mul(s2);
+            let term = p.div_u64(n);
+            if term.is_zero() || term.exp <= sum.exp - 64 * L as i32 - 4 {
+                return sum;
+            }
+            sum = sum.add(term);
+            n += 2;
+        }
+    }
+
+    /// ln(self), for positive self: self = m × 2^e with m in [1/√2, √2),
+    /// ln m = 2 atanh((m - 1)/(m + 1)).
+    pub(crate) fn ln(self) -> Self {
+        let mut e = self.exp;
+        let mut m = Self { exp: 0, ..self };
+        // 0xb504... is the top of 1/√2.
+        if m.man[L - 1] < 0xb504_f333_f9de_6484 {
+            m = m.ldexp(1);
+            e -= 1;
+        }
+        let one = Self::one();
+        let s = m.sub(one).div(m.add(one));
+        let lnm = Self::atanh_series(s).ldexp(1);
+        if e == 0 {
+            return lnm;
+        }
+        Self::ln2().mul(Self::from_i64(e as i64)).add(lnm)
+    }
+
+    /// ln(1 + self), for self > -1, accurate relative to the result for
+    /// small self.
+    pub(crate) fn ln1p(self) -> Self {
+        if self.exp <= -4 {
+            let s = self.div(self.add(Self::from_i64(2)));
+            return Self::atanh_series(s).ldexp(1);
+        }
+        self.add(Self::one()).ln()
+    }
+
+    // Σ (-1)^n r^(2n+i) / (2n+i)! for i = 1 (sin) or 0 (cos), |r| <= π/4.
+    fn sin_cos_series(r: Self, i: u64) -> Self {
+        let r2 = r.sqr();
+        let mut term = if i == 1 { r } else { Self::one() };
+        let mut sum = term;
+        let mut n = i + 1;
+        loop {
+            term = term.mul(r2).div_u64(n * (n + 1)).neg();
+            if term.is_zero() || term.exp <= sum.exp - 64 * L as i32 - 4 {
+                return sum;
+            }
+            sum = sum.add(term);
+            n += 2;
+        }
+    }
+
+    /// sin r for |r| <= π/4.
+    pub(crate) fn sin_reduced(r: Self) -> Self {
+        Self::sin_cos_series(r, 1)
+    }
+
+    /// cos r for |r| <= π/4.
+    pub(crate) fn cos_reduced(r: Self) -> Self {
+        Self::sin_cos_series(r, 0)
+    }
+
+    /// atan(self): for |x| > 1 through π/2 - atan(1/x), then three
+    /// half-angle steps x -> x / (1 + √(1 + x²)) before the series.
+    pub(crate) fn atan(self) -> Self {
+        if self.is_zero() {
+            return self;
+        }
+        let one = Self::one();
+        if self.exp >= 1 && self.cmp_abs(&one) == Ordering::Greater {
+            let a = Self::pi_2().sub(self.abs().recip().atan());
+            return if self.neg { a.neg() } else { a };
+        }
+        const STEPS: i32 = 3;
+        let mut a = self;
+        for _ in 0..STEPS {
+            a = a.div(one.add(one.add(a.sqr()).sqrt()));
+        }
+        let a2 = a.sqr();
+        let mut p = a;
+        let mut sum = a;
+        let mut n = 3;
+        loop {
+            p = p.mul(a2).neg();
+            let term = p.div_u64(n);
+            if term.is_zero() || term.exp <= sum.exp - 64 * L as i32 - 4 {
+                return sum.ldexp(STEPS);
+            }
+            sum = sum.add(term);
+            n += 2;
+        }
+    }
+}
+
+/// Payne-Hanek reduction of a finite, nonzero `x`: `(k, r)` with
+/// `x = k π/2 + r`, `k` taken mod 4 and `|r| <= π/4`.
+pub(crate) fn reduce_pio2<const L: usize>(x: f64) -> (u32, Mp<L>) {
+    if x.abs() <= core::f64::consts::FRAC_PI_4 {
+        return (0, Mp::from_f64(x));
+    }
+    let b = x.abs().to_bits();
+    let m = (b & ((1 << 52) - 1)) | (1 << 52);
+    let e = ((b >> 52) & 0x7ff) as i32 - 1075;
+    reduce_pio2_parts(x < 0.0, m as u128, e, 7)
+}
+
+/// [`reduce_pio2`] of `±m × 2^e` above π/4, for mantissas of up to 128
+/// bits, keeping `64 w` bits of 2/π below the integer part (`w <= 9`).
+pub(crate) fn reduce_pio2_parts<const L: usize>(
+    neg: bool,
+    m: u128,
+    e: i32,
+    w: usize,
+) -> (u32, Mp<L>) {
+    const MAXW: usize = 9;
+    let n = 64 * w as u32;
+    // x 2/π = m × Σ b_i 2^(e - i): bits b_i with i <= e - 2 contribute
+    // multiples of 4. Take the n bits from b_i0 as an integer.
+    let i0 = (e - 1).max(1) as u32;
+    let (word, shift) = (((i0 - 1) / 64) as usize, (i0 - 1) % 64);
+    let mut win = [0u64; MAXW];
+    for (j, v) in win[..w].iter_mut().rev().enumerate() {
+        let hi = TWO_OVER_PI[word + j];
+        let lo = TWO_OVER_PI[word + j + 1];
+        *v = if shift == 0 {
+            hi
+        } else {
+            (hi << shift) | (lo >> (64 - shift))
+        };
+    }
+    // p = m × win, scaled by 2^sh with sh = e - i0 - n + 1.
+    let mut p = [0u64; MAXW + 2];
+    for (i, mi) in [m as u64, (m >> 64) as u64].into_iter().enumerate() {
+        let mut carry = 0u128;
+        for j in 0..w {
+            let t = win[j] as u128 * mi as u128 + p[i + j] as u128 + carry;
+            p[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        p[i + w] = carry as u64;
+    }
+    let p = &mut p[..w + 2];
+    let int_pos = (i0 + n - 1) as i32 - e; // bit index of 2^0
+    let int_pos = int_pos as u32;
+    let mut k = ((bit(p, int_pos + 1) as u32) << 1) | bit(p, int_pos) as u32;
+    let half = bit(p, int_pos - 1);
+    // The fraction below 2^0, then the nearest-integer remainder.
+    for i in int_pos..64 * (w as u32 + 2) {
+        p[(i / 64) as usize] &= !(1 << (i % 64));
+    }
+    let mut rneg = false;
+    if half {
+        k = (k + 1) & 3;
+        let mut one = [0u64; MAXW + 2];
+        one[(int_pos / 64) as usize] = 1 << (int_pos % 64);
+        sub_limbs(&mut one[..w + 2], p);
+        p.copy_from_slice(&one[..w + 2]);
+        rneg = true;
+    }
+    let f = Mp::<L>::from_limbs(rneg, 64 * (w as i32 + 2) - int_pos as i32, p);
+    let r = f.mul(Mp::pi_2());
+    if neg { ((4 - k) & 3, r.neg()) } else { (k, r) }
+}
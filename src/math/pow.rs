@@ -4,7 +4,9 @@
 //! to exp(y*log(x)) for the general case. Uses ln/exp cores with split constants
 //! for accuracy.
 
-use super::{LN2_HI, LN2_LO, f64_from_bits, f64_to_bits, fma_internal, ln};
+use super::{
+    DoubleF64, LN2_HI, LN2_LO, f64_from_bits, f64_to_bits, fma_internal, ln, with_nearest,
+};
 
 const POW_LOG_TABLE_BITS: u32 = 7;
 const POW_LOG_N: u64 = 1u64 << POW_LOG_TABLE_BITS;
@@ -450,37 +452,25 @@ fn classify_integer(x: f64) -> (bool, bool) {
 }
 
 #[inline]
-fn dd_mul(a_hi: f64, a_lo: f64, b_hi: f64, b_lo: f64) -> (f64, f64) {
-    let p = a_hi * b_hi;
-    if !p.is_finite() || !a_hi.is_finite() || !b_hi.is_finite() {
-        return (p, 0.0);
-    }
-    let e = fma_internal(a_hi, b_hi, -p) + (a_hi * b_lo + a_lo * b_hi) + (a_lo * b_lo);
-    let hi = p + e;
-    let lo = (p - hi) + e;
-    (hi, lo)
-}
-
-#[inline]
-fn dd_recip(a_hi: f64, a_lo: f64) -> f64 {
-    if a_hi == 0.0 {
-        if a_lo == 0.0 {
+fn dd_recip(a: DoubleF64) -> f64 {
+    if a.hi == 0.0 {
+        if a.lo == 0.0 {
             return f64::INFINITY;
         }
-        return 1.0 / a_lo;
+        return 1.0 / a.lo;
     }
     // Newton refinement of reciprocal using double-double product.
-    let mut r = 1.0 / a_hi;
-    // For very small a_hi, the naive reciprocal can overflow to inf and the
+    let mut r = 1.0 / a.hi;
+    // For very small a.hi, the naive reciprocal can overflow to inf and the
     // Newton step would turn into inf + (-inf) = NaN. In that regime the true
-    // reciprocal overflows too (a_lo is only a tiny correction), so return +inf.
+    // reciprocal overflows too (a.lo is only a tiny correction), so return +inf.
     if !r.is_finite() {
         return f64::INFINITY;
     }
     for _ in 0..2 {
-        let (p_hi, p_lo) = dd_mul(a_hi, a_lo, r, 0.0);
-        let mut err = fma_internal(-p_hi, 1.0, 1.0);
-        err -= p_lo;
+        let p = a * DoubleF64::from(r);
+        let mut err = fma_internal(-p.hi, 1.0, 1.0);
+        err -= p.lo;
         r = r + r * err;
     }
     r
@@ -494,27 +484,21 @@ fn powi(base: f64, mut exp: i64) -> f64 {
     if neg {
         exp = -exp;
     }
-    let mut acc_hi = 1.0;
-    let mut acc_lo = 0.0;
-    let mut b_hi = base;
-    let mut b_lo = 0.0;
+    let mut acc = DoubleF64::ONE;
+    let mut b = DoubleF64::from(base);
     let mut e = exp as u64;
     while e != 0 {
         if (e & 1) != 0 {
-            let (hi, lo) = dd_mul(acc_hi, acc_lo, b_hi, b_lo);
-            acc_hi = hi;
-            acc_lo = lo;
+            acc = acc * b;
         }
-        let (hi, lo) = dd_mul(b_hi, b_lo, b_hi, b_lo);
-        b_hi = hi;
-        b_lo = lo;
+        b = b * b;
         e >>= 1;
     }
     if !neg {
-        return acc_hi + acc_lo;
+        return acc.to_f64();
     }
 
-    dd_recip(acc_hi, acc_lo)
+    dd_recip(acc)
 }
 
 #[inline]
@@ -657,7 +641,10 @@ unsafe fn pow_exp_fma(x: f64, y: f64) -> f64 {
 }
 
 #[inline]
+#[allow(unreachable_code)]
 fn pow_exp(x: f64, y: f64) -> f64 {
+    #[cfg(all(target_arch = "x86_64", feature = "runtime-dispatch"))]
+    return super::dispatch::select!(pow_exp_fma, pow_exp_generic; fn(x: f64, y: f64) -> f64)(x, y);
     #[cfg(target_arch = "x86_64")]
     if super::fma_available() {
         // SAFETY: guarded by compile-time FMA availability.
@@ -666,6 +653,269 @@ fn pow_exp(x: f64, y: f64) -> f64 {
     pow_exp_generic(x, y)
 }
 
+/// x^y in each lane, bit-identical to [`pow`] lane by lane.
+///
+/// Lanes with a positive normal `x` other than 1, a finite nonzero `y` and a
+/// result in the normal range follow the scalar log/exp path with gathered
+/// table entries; every other lane (negative or subnormal bases, zeros,
+/// infinities, NaNs, overflow and underflow) is handed to [`pow`].
+///
+/// # Safety
+///
+/// The CPU must support AVX2 and FMA.
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[target_feature(enable = "avx2,fma")]
+pub unsafe fn pow_avx2(
+    x: core::arch::x86_64::__m256d,
+    y: core::arch::x86_64::__m256d,
+) -> core::arch::x86_64::__m256d {
+    with_nearest((x, y), |(x, y)| unsafe { pow_avx2_nearest(x, y) })
+}
+
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[target_feature(enable = "avx2,fma")]
+unsafe fn pow_avx2_nearest(
+    x: core::arch::x86_64::__m256d,
+    y: core::arch::x86_64::__m256d,
+) -> core::arch::x86_64::__m256d {
+    use super::arch::avx2::{
+        fixup2, gather_u64, sign_extend_12, small_i64_to_f64, splat, splat_u64,
+    };
+    use core::arch::x86_64::*;
+
+    let a = |i: usize| splat(POW_LOG_A[i]);
+    let lookup = |table: &[u64; 128], i: __m256i| {
+        // i is masked to the table size.
+        _mm256_castsi256_pd(unsafe { gather_u64(table, i) })
+    };
+
+    // log_inline_fma
+    let ix = _mm256_castpd_si256(x);
+    let tmp = _mm256_sub_epi64(ix, splat_u64(POW_LOG_OFF));
+    let i = _mm256_and_si256(
+        _mm256_srli_epi64::<{ 52 - POW_LOG_TABLE_BITS as i32 }>(tmp),
+        splat_u64(POW_LOG_N - 1),
+    );
+    let kd = small_i64_to_f64(sign_extend_12(_mm256_srli_epi64::<52>(tmp)));
+    let iz = _mm256_sub_epi64(ix, _mm256_and_si256(tmp, splat_u64(0x0fffu64 << 52)));
+    let z = _mm256_castsi256_pd(iz);
+
+    let invc = lookup(&POW_LOG_INVC, i);
+    let logc = lookup(&POW_LOG_LOGC, i);
+    let logctail = lookup(&POW_LOG_LOGCTAIL, i);
+
+    let r = _mm256_fmadd_pd(z, invc, splat(-1.0));
+    let t1 = _mm256_add_pd(_mm256_mul_pd(kd, splat(LN2_HI)), logc);
+    let t2 = _mm256_add_pd(t1, r);
+    let lo1 = _mm256_add_pd(_mm256_mul_pd(kd, splat(LN2_LO)), logctail);
+    let lo2 = _mm256_add_pd(_mm256_sub_pd(t1, t2), r);
+
+    let ar = _mm256_mul_pd(a(0), r);
+    let ar2 = _mm256_mul_pd(r, ar);
+    let ar3 = _mm256_mul_pd(r, ar2);
+    let hi = _mm256_add_pd(t2, ar2);
+    let lo3 = _mm256_fmsub_pd(ar, r, ar2);
+    let lo4 = _mm256_add_pd(_mm256_sub_pd(t2, hi), ar2);
+
+    let q = _mm256_add_pd(a(5), _mm256_mul_pd(r, a(6)));
+    let q = _mm256_add_pd(
+        _mm256_add_pd(a(3), _mm256_mul_pd(r, a(4))),
+        _mm256_mul_pd(ar2, q),
+    );
+    let q = _mm256_add_pd(
+        _mm256_add_pd(a(1), _mm256_mul_pd(r, a(2))),
+        _mm256_mul_pd(ar2, q),
+    );
+    let p = _mm256_mul_pd(ar3, q);
+    let lo = _mm256_add_pd(
+        _mm256_add_pd(_mm256_add_pd(_mm256_add_pd(lo1, lo2), lo3), lo4),
+        p,
+    );
+    let lhi = _mm256_add_pd(hi, lo);
+    let llo = _mm256_add_pd(_mm256_sub_pd(hi, lhi), lo);
+
+    // mul_log_fma
+    let ehi = _mm256_mul_pd(y, lhi);
+    let elo = _mm256_add_pd(_mm256_mul_pd(y, llo), _mm256_fmsub_pd(y, lhi, ehi));
+
+    let (v, fast) = super::exp::exp_with_tail_avx2(ehi, elo);
+    let ay = _mm256_andnot_pd(splat(-0.0), y);
+    let fast = _mm256_and_pd(
+        fast,
+        _mm256_and_pd(
+            _mm256_and_pd(
+                _mm256_cmp_pd::<_CMP_GE_OQ>(x, splat(f64::MIN_POSITIVE)),
+                _mm256_cmp_pd::<_CMP_LT_OQ>(x, splat(f64::INFINITY)),
+            ),
+            _mm256_and_pd(
+                _mm256_cmp_pd::<_CMP_NEQ_OQ>(x, splat(1.0)),
+                _mm256_and_pd(
+                    _mm256_cmp_pd::<_CMP_GT_OQ>(ay, splat(0.0)),
+                    _mm256_cmp_pd::<_CMP_LT_OQ>(ay, splat(f64::INFINITY)),
+                ),
+            ),
+        ),
+    );
+    let slow = _mm256_xor_pd(fast, _mm256_castsi256_pd(splat_u64(!0)));
+    fixup2(v, slow, x, y, pow)
+}
+
+/// x^y in each lane, bit-identical to [`pow`] lane by lane.
+///
+/// The log/exp path runs on |x| in every lane, subnormal bases and results
+/// near the overflow and underflow thresholds included. The C99 special
+/// cases (zeros, ones, infinities, NaNs, negative bases with integer or
+/// non-integer `y`) are blended in under AVX-512 masks. Only negative bases
+/// with an integer exponent of magnitude at most 64, which the scalar code
+/// evaluates by repeated squaring, are handed to [`pow`].
+///
+/// # Safety
+///
+/// The CPU must support AVX-512F.
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[target_feature(enable = "avx512f")]
+pub unsafe fn pow_avx512(
+    x: core::arch::x86_64::__m512d,
+    y: core::arch::x86_64::__m512d,
+) -> core::arch::x86_64::__m512d {
+    with_nearest((x, y), |(x, y)| unsafe { pow_avx512_nearest(x, y) })
+}
+
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[target_feature(enable = "avx512f")]
+unsafe fn pow_avx512_nearest(
+    x: core::arch::x86_64::__m512d,
+    y: core::arch::x86_64::__m512d,
+) -> core::arch::x86_64::__m512d {
+    use super::arch::avx512::{
+        abs, fixup2, gather_u64, sign_extend_12, small_i64_to_f64, splat, splat_u64,
+    };
+    use core::arch::x86_64::*;
+
+    let a = |i: usize| splat(POW_LOG_A[i]);
+    let lookup = |table: &[u64; 128], i: __m512i| {
+        // i is masked to the table size.
+        _mm512_castsi512_pd(unsafe { gather_u64(table, i) })
+    };
+    let eq = |a: __m512d, b: f64| _mm512_cmp_pd_mask::<_CMP_EQ_OQ>(a, splat(b));
+    let lt = |a: __m512d, b: f64| _mm512_cmp_pd_mask::<_CMP_LT_OQ>(a, splat(b));
+    let gt = |a: __m512d, b: f64| _mm512_cmp_pd_mask::<_CMP_GT_OQ>(a, splat(b));
+
+    // pow_exp_fma on |x|, with subnormals scaled by 2^52.
+    let ax = abs(x);
+    let sub = lt(ax, f64::MIN_POSITIVE);
+    let scaled = _mm512_sub_epi64(
+        _mm512_castpd_si512(abs(_mm512_mul_pd(
+            x,
+            splat(f64::from_bits(0x4330_0000_0000_0000)),
+        ))),
+        splat_u64(52u64 << 52),
+    );
+    let ix = _mm512_mask_blend_epi64(sub, _mm512_castpd_si512(ax), scaled);
+
+    // log_inline_fma
+    let tmp = _mm512_sub_epi64(ix, splat_u64(POW_LOG_OFF));
+    let i = _mm512_and_si512(
+        _mm512_srli_epi64::<{ 52 - POW_LOG_TABLE_BITS }>(tmp),
+        splat_u64(POW_LOG_N - 1),
+    );
+    let kd = small_i64_to_f64(sign_extend_12(_mm512_srli_epi64::<52>(tmp)));
+    let iz = _mm512_sub_epi64(ix, _mm512_and_si512(tmp, splat_u64(0x0fffu64 << 52)));
+    let z = _mm512_castsi512_pd(iz);
+
+    let invc = lookup(&POW_LOG_INVC, i);
+    let logc = lookup(&POW_LOG_LOGC, i);
+    let logctail = lookup(&POW_LOG_LOGCTAIL, i);
+
+    let r = _mm512_fmadd_pd(z, invc, splat(-1.0));
+    let t1 = _mm512_add_pd(_mm512_mul_pd(kd, splat(LN2_HI)), logc);
+    let t2 = _mm512_add_pd(t1, r);
+    let lo1 = _mm512_add_pd(_mm512_mul_pd(kd, splat(LN2_LO)), logctail);
+    let lo2 = _mm512_add_pd(_mm512_sub_pd(t1, t2), r);
+
+    let ar = _mm512_mul_pd(a(0), r);
+    let ar2 = _mm512_mul_pd(r, ar);
+    let ar3 = _mm512_mul_pd(r, ar2);
+    let hi = _mm512_add_pd(t2, ar2);
+    let lo3 = _mm512_fmsub_pd(ar, r, ar2);
+    let lo4 = _mm512_add_pd(_mm512_sub_pd(t2, hi), ar2);
+
+    let q = _mm512_add_pd(a(5), _mm512_mul_pd(r, a(6)));
+    let q = _mm512_add_pd(
+        _mm512_add_pd(a(3), _mm512_mul_pd(r, a(4))),
+        _mm512_mul_pd(ar2, q),
+    );
+    let q = _mm512_add_pd(
+        _mm512_add_pd(a(1), _mm512_mul_pd(r, a(2))),
+        _mm512_mul_pd(ar2, q),
+    );
+    let p = _mm512_mul_pd(ar3, q);
+    let lo = _mm512_add_pd(
+        _mm512_add_pd(_mm512_add_pd(_mm512_add_pd(lo1, lo2), lo3), lo4),
+        p,
+    );
+    let lhi = _mm512_add_pd(hi, lo);
+    let llo = _mm512_add_pd(_mm512_sub_pd(hi, lhi), lo);
+
+    // mul_log_fma
+    let ehi = _mm512_mul_pd(y, lhi);
+    let elo = _mm512_add_pd(_mm512_mul_pd(y, llo), _mm512_fmsub_pd(y, lhi, ehi));
+    let v = super::exp::exp_with_tail_avx512(ehi, elo);
+
+    // classify_integer(y) for finite y.
+    let uy = _mm512_castpd_si512(abs(y));
+    let e = _mm512_srli_epi64::<52>(uy);
+    let frac_bits = _mm512_sub_epi64(splat_u64(1075), e);
+    let frac = _mm512_sub_epi64(_mm512_sllv_epi64(splat_u64(1), frac_bits), splat_u64(1));
+    let in_range =
+        _mm512_cmpge_epu64_mask(e, splat_u64(1023)) & _mm512_cmple_epu64_mask(e, splat_u64(1075));
+    let y_int = (in_range & _mm512_testn_epi64_mask(uy, frac))
+        | _mm512_cmpgt_epu64_mask(e, splat_u64(1075))
+        | eq(y, 0.0);
+    let y_odd =
+        in_range & y_int & _mm512_test_epi64_mask(_mm512_srlv_epi64(uy, frac_bits), splat_u64(1));
+    let ay = abs(y);
+    let y_finite = lt(ay, f64::INFINITY);
+    let y_int = y_int & y_finite;
+    let y_odd = y_odd & y_finite;
+    let y_pos = gt(y, 0.0);
+
+    let x_neg = _mm512_test_epi64_mask(_mm512_castpd_si512(x), splat_u64(SIGN_BIT));
+    let signed = |v: __m512d, m: __mmask8| {
+        _mm512_mask_blend_pd(
+            m,
+            v,
+            _mm512_castsi512_pd(_mm512_or_si512(_mm512_castpd_si512(v), splat_u64(SIGN_BIT))),
+        )
+    };
+    let nan = splat(f64::NAN);
+    let inf = splat(f64::INFINITY);
+    let zero = _mm512_setzero_pd();
+
+    // Negative bases: odd integers keep the sign, non-integers give NaN.
+    let v = signed(v, x_neg & y_odd);
+    let v = _mm512_mask_blend_pd(x_neg & !y_int, v, nan);
+    // x = +-inf
+    let odd_neg = x_neg & y_odd;
+    let xinf = _mm512_mask_blend_pd(y_pos, zero, inf);
+    let v = _mm512_mask_blend_pd(eq(ax, f64::INFINITY), v, signed(xinf, odd_neg));
+    // x = +-0
+    let xzero = _mm512_mask_blend_pd(y_pos, inf, zero);
+    let v = _mm512_mask_blend_pd(eq(x, 0.0), v, signed(xzero, odd_neg));
+    // y = +-inf
+    let grow = gt(ax, 1.0) ^ lt(y, 0.0);
+    let yinf = _mm512_mask_blend_pd(grow, zero, inf);
+    let yinf = _mm512_mask_blend_pd(eq(ax, 1.0), yinf, splat(1.0));
+    let v = _mm512_mask_blend_pd(eq(ay, f64::INFINITY), v, yinf);
+    let v = _mm512_mask_blend_pd(_mm512_cmp_pd_mask::<_CMP_UNORD_Q>(x, y), v, nan);
+    let one = eq(x, 1.0) | eq(y, 0.0);
+    let v = _mm512_mask_blend_pd(one, v, splat(1.0));
+
+    let small_int = x_neg & y_int & !gt(ay, POWI_EXP_CUTOFF as f64);
+    let slow = small_int & lt(ax, f64::INFINITY) & !eq(x, 0.0);
+    fixup2(v, slow, x, y, pow)
+}
+
 #[inline]
 fn apply_sign(x: f64, neg: bool) -> f64 {
     if neg {
@@ -677,6 +927,11 @@ fn apply_sign(x: f64, neg: bool) -> f64 {
 
 #[inline]
 pub fn pow(x: f64, y: f64) -> f64 {
+    with_nearest((x, y), |(x, y)| pow_nearest(x, y))
+}
+
+#[inline]
+fn pow_nearest(x: f64, y: f64) -> f64 {
     if y == 0.0 {
         return 1.0;
     }
@@ -689,7 +944,8 @@ pub fn pow(x: f64, y: f64) -> f64 {
     if y.is_infinite() {
         let ax = x.abs();
         if ax == 1.0 {
-            return f64::NAN;
+            // (-1)^±inf = 1 (C99 F.9.4.4).
+            return 1.0;
         }
         if ax > 1.0 {
             return if y.is_sign_positive() {
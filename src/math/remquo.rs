@@ -3,7 +3,7 @@
 //! Computes IEEE remainder and low bits of the quotient. Uses exponent alignment
 //! and subtractive reduction with correct tie handling, mirroring glibc behavior.
 
-use super::{f64_from_bits, fma_internal, fmod, rint};
+use super::{f64_from_bits, fma_internal, fmod, roundeven_finite};
 
 const EXP_MASK: u64 = 0x7ff0_0000_0000_0000u64;
 const SIGN_MASK: u64 = 0x8000_0000_0000_0000u64;
@@ -96,7 +96,7 @@ pub fn remquo(x: f64, y: f64) -> (f64, i32) {
         let ay_f = f64_from_bits(ay);
         let t = x / y;
         if t.is_finite() && t.abs() < 4_503_599_627_370_496.0 {
-            let n = rint(t);
+            let n = roundeven_finite(t);
             let mut n_i = n as i64;
             let mut r = fma_internal(-n, y, x);
             let ayh = 0.5 * ay_f;
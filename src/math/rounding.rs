@@ -2,13 +2,32 @@
 //!
 //! Uses the classic 2^52 "add-sub" trick and bit masks for fast rounding
 //! with correct ties-to-even behavior. Integer forms clamp and handle NaN/Inf
-//! per glibc semantics.
+//! per glibc semantics, which differ between x86 and ARM; the `deterministic`
+//! feature uses the x86 results everywhere.
+//!
+//! Like C's, `rint`, `nearbyint`, `lrint` and `llrint` round in the current
+//! dynamic rounding mode (see [`fesetround`]); the `_mode` variants take the
+//! mode as an argument and work from the bits, so they ignore it.
 
 use super::{f64_from_bits, f64_to_bits, floor_f64};
 
 const SIGN_MASK: u64 = 0x8000_0000_0000_0000u64;
 const TOINT: f64 = 4503599627370496.0; // 2^52
 
+/// An IEEE-754 rounding direction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum RoundingMode {
+    /// To nearest, ties to even (the default).
+    #[default]
+    ToNearest,
+    /// Toward -∞.
+    Downward,
+    /// Toward +∞.
+    Upward,
+    /// Toward zero.
+    TowardZero,
+}
+
 #[inline(always)]
 fn copysign(x: f64, y: f64) -> f64 {
     f64_from_bits((f64_to_bits(x) & !SIGN_MASK) | (f64_to_bits(y) & SIGN_MASK))
@@ -94,9 +113,31 @@ pub fn nearbyint(x: f64) -> f64 {
     rint(x)
 }
 
+/// `x` rounded to an integer in direction `mode`, whatever the current
+/// rounding mode.
+#[inline]
+pub fn rint_mode(x: f64, mode: RoundingMode) -> f64 {
+    if !x.is_finite() || x.abs() >= TOINT {
+        return x;
+    }
+    // t keeps the sign of x when it is zero, and x - t and t ± 1 are exact.
+    let t = trunc_bits(x);
+    let frac = (x - t).abs();
+    let away = match mode {
+        RoundingMode::ToNearest => frac > 0.5 || (frac == 0.5 && (t.abs() as u64) & 1 == 1),
+        RoundingMode::Downward => frac != 0.0 && x < 0.0,
+        RoundingMode::Upward => frac != 0.0 && x > 0.0,
+        RoundingMode::TowardZero => false,
+    };
+    if away { t + copysign(1.0, x) } else { t }
+}
+
 #[inline(always)]
 fn clamp_i64(x: f64) -> i64 {
-    #[cfg(any(target_arch = "aarch64", target_arch = "arm"))]
+    #[cfg(all(
+        any(target_arch = "aarch64", target_arch = "arm"),
+        not(feature = "deterministic")
+    ))]
     {
         if x.is_nan() {
             return 0;
@@ -116,7 +157,10 @@ fn clamp_i64(x: f64) -> i64 {
         }
         x as i64
     }
-    #[cfg(not(any(target_arch = "aarch64", target_arch = "arm")))]
+    #[cfg(not(all(
+        any(target_arch = "aarch64", target_arch = "arm"),
+        not(feature = "deterministic")
+    )))]
     {
         if !x.is_finite() {
             return i64::MIN;
@@ -139,9 +183,22 @@ pub fn llrint(x: f64) -> i64 {
     clamp_i64(rint(x))
 }
 
+#[inline]
+pub fn lrint_mode(x: f64, mode: RoundingMode) -> i64 {
+    clamp_i64(rint_mode(x, mode))
+}
+
+#[inline]
+pub fn llrint_mode(x: f64, mode: RoundingMode) -> i64 {
+    clamp_i64(rint_mode(x, mode))
+}
+
 #[inline(always)]
 pub fn lround(x: f64) -> i64 {
-    #[cfg(any(target_arch = "aarch64", target_arch = "arm"))]
+    #[cfg(all(
+        any(target_arch = "aarch64", target_arch = "arm"),
+        not(feature = "deterministic")
+    ))]
     {
         if x.is_nan() {
             return 0;
@@ -154,7 +211,10 @@ pub fn lround(x: f64) -> i64 {
             };
         }
     }
-    #[cfg(not(any(target_arch = "aarch64", target_arch = "arm")))]
+    #[cfg(not(all(
+        any(target_arch = "aarch64", target_arch = "arm"),
+        not(feature = "deterministic")
+    )))]
     {
         if !x.is_finite() {
             return i64::MIN;
@@ -163,7 +223,10 @@ pub fn lround(x: f64) -> i64 {
     let ax = x.abs();
     if ax >= TOINT {
         return if ax > i64::MAX as f64 {
-            #[cfg(any(target_arch = "aarch64", target_arch = "arm"))]
+            #[cfg(all(
+                any(target_arch = "aarch64", target_arch = "arm"),
+                not(feature = "deterministic")
+            ))]
             {
                 if x.is_sign_negative() {
                     i64::MIN
@@ -171,7 +234,10 @@ pub fn lround(x: f64) -> i64 {
                     i64::MAX
                 }
             }
-            #[cfg(not(any(target_arch = "aarch64", target_arch = "arm")))]
+            #[cfg(not(all(
+                any(target_arch = "aarch64", target_arch = "arm"),
+                not(feature = "deterministic")
+            )))]
             {
                 i64::MIN
             }
@@ -181,7 +247,10 @@ pub fn lround(x: f64) -> i64 {
     }
     let y = x + copysign(0.5, x);
     if y > i64::MAX as f64 || y < i64::MIN as f64 {
-        #[cfg(any(target_arch = "aarch64", target_arch = "arm"))]
+        #[cfg(all(
+            any(target_arch = "aarch64", target_arch = "arm"),
+            not(feature = "deterministic")
+        ))]
         {
             if y.is_sign_negative() {
                 i64::MIN
@@ -189,7 +258,10 @@ pub fn lround(x: f64) -> i64 {
                 i64::MAX
             }
         }
-        #[cfg(not(any(target_arch = "aarch64", target_arch = "arm")))]
+        #[cfg(not(all(
+            any(target_arch = "aarch64", target_arch = "arm"),
+            not(feature = "deterministic")
+        )))]
         {
             i64::MIN
         }
@@ -202,3 +274,42 @@ pub fn lround(x: f64) -> i64 {
 pub fn llround(x: f64) -> i64 {
     lround(x)
 }
+
+/// The current dynamic rounding mode: MXCSR on x86_64, FPCR on aarch64, and
+/// always [`RoundingMode::ToNearest`] elsewhere.
+#[inline]
+pub fn fegetround() -> RoundingMode {
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    {
+        super::arch::get_rounding_mode()
+    }
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    {
+        RoundingMode::ToNearest
+    }
+}
+
+/// Installs `mode` as the current thread's rounding mode, returning whether
+/// the target supports it. Only [`RoundingMode::ToNearest`] is supported
+/// outside x86_64 and aarch64. The internal helpers of the crate round their
+/// reductions independently of the mode.
+///
+/// # Safety
+///
+/// Rust and LLVM assume round-to-nearest: constant folding and other
+/// optimizations may compute an expression in that mode even while another
+/// is installed, and code elsewhere may depend on it. Restore
+/// [`RoundingMode::ToNearest`] before returning to code that is not prepared
+/// for other modes.
+#[inline]
+pub unsafe fn fesetround(mode: RoundingMode) -> bool {
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    {
+        unsafe { super::arch::set_rounding_mode(mode) };
+        true
+    }
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    {
+        mode == RoundingMode::ToNearest
+    }
+}
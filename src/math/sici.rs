@@ -0,0 +1,235 @@
+//! Sine and cosine integrals Si(x) and Ci(x).
+//!
+//! Up to |x| = 8 the power series are summed in double-double; the partial
+//! sums lose at most 7 bits to cancellation. Larger arguments use the
+//! auxiliary functions f and g of DLMF 6.2(ii),
+//!   Si(x) = pi/2 - f(x) cos x - g(x) sin x,  Ci(x) = f(x) sin x - g(x) cos x,
+//! with g - i f = e^(ix) E1(ix) taken from the continued fraction of E1, the
+//! convergent form of f ~ 1/x (1 - 2!/x^2 + ...), g ~ 1/x^2 (1 - 3!/x^2 + ...).
+//! Past 2^32 two terms of those expansions suffice. The phase is reduced with
+//! `branred` and sin, cos of the reduced argument are summed in
+//! double-double.
+
+use super::double::{add_dd, divdd, divddd, muldd_acc, norm_dd};
+use super::fma_internal;
+use super::gamma::as_logd_accurate;
+use super::trig::branred;
+use super::with_nearest;
+
+// pi/2 and Euler's constant as double-doubles.
+const FRAC_PI_2_HI: f64 = f64::from_bits(0x3ff9_21fb_5444_2d18);
+const FRAC_PI_2_LO: f64 = f64::from_bits(0x3c91_a626_3314_5c07);
+const EULER_HI: f64 = f64::from_bits(0x3fe2_788c_fc6f_b619);
+const EULER_LO: f64 = f64::from_bits(0xbc56_cb90_701f_bfab);
+
+// Up to this |x| the power series is summed directly.
+const SERIES_MAX: f64 = 8.0;
+// From this x on, f and g are two terms of their asymptotic expansions.
+const ASYMPTOTIC_MIN: f64 = 4_294_967_296.0; // 2^32
+// Below this |x|, Si(x) = x to working precision.
+const SI_TINY: f64 = f64::from_bits(0x3e50_0000_0000_0000); // 2^-26
+// Series summation stops once a term drops below this fraction of the sum.
+const SERIES_EPS: f64 = f64::from_bits(0x3970_0000_0000_0000); // 2^-104
+// Terms of the Taylor series of sin and cos on |a| <= pi/4.
+const TAYLOR_TERMS: usize = 14;
+
+// Si(x) = sum_k (-1)^k x^(2k+1) / ((2k+1) (2k+1)!) for 0 < x <= 8.
+fn si_series(x: f64, l: &mut f64) -> f64 {
+    let qh = x * x;
+    let ql = fma_internal(x, x, -qh);
+    let (mut ph, mut pl) = (x, 0.0);
+    let (mut sh, mut sl) = (x, 0.0);
+    let mut k = 1.0;
+    loop {
+        ph = muldd_acc(ph, pl, -qh, -ql, &mut pl);
+        ph = divddd(ph, pl, (2.0 * k) * (2.0 * k + 1.0), &mut pl);
+        let mut tl = 0.0;
+        let th = divddd(ph, pl, 2.0 * k + 1.0, &mut tl);
+        sh = add_dd(sh, sl, th, tl, &mut sl);
+        if th.abs() <= SERIES_EPS * sh.abs() {
+            break;
+        }
+        k += 1.0;
+    }
+    *l = sl;
+    sh
+}
+
+// Ci(x) = gamma + ln x + sum_{k>=1} (-1)^k x^(2k) / ((2k) (2k)!) for
+// 0 < x <= 8.
+fn ci_series(x: f64, l: &mut f64) -> f64 {
+    let qh = x * x;
+    let ql = fma_internal(x, x, -qh);
+    let (mut ph, mut pl) = (1.0, 0.0);
+    let (mut sh, mut sl) = (0.0, 0.0);
+    let mut k = 1.0;
+    loop {
+        ph = muldd_acc(ph, pl, -qh, -ql, &mut pl);
+        ph = divddd(ph, pl, (2.0 * k - 1.0) * (2.0 * k), &mut pl);
+        let mut tl = 0.0;
+        let th = divddd(ph, pl, 2.0 * k, &mut tl);
+        sh = add_dd(sh, sl, th, tl, &mut sl);
+        if th.abs() <= SERIES_EPS * sh.abs() {
+            break;
+        }
+        k += 1.0;
+    }
+    // ln x carries a third word so that Ci keeps its relative accuracy next
+    // to its zeros, where gamma + ln x cancels against the series.
+    let (mut ll, mut ll2) = (0.0, 0.0);
+    let lh = as_logd_accurate(x, &mut ll, &mut ll2);
+    let mut el = 0.0;
+    let eh = add_dd(EULER_HI, EULER_LO, lh, ll, &mut el);
+    let h = add_dd(eh, el, sh, sl, l);
+    *l += ll2;
+    h
+}
+
+// Auxiliary functions (f, g) as double-doubles for x > 8.
+//
+// g - i f = 1 / (1 + ix - 1 / (3 + ix - 4 / (5 + ix - 9 / ...))), evaluated
+// backwards from a depth that keeps the truncation error below 2^-70.
+fn aux_fg(x: f64) -> ((f64, f64), (f64, f64)) {
+    if x >= ASYMPTOTIC_MIN {
+        // f = 1/x (1 - 2/x^2), g = 1/x^2; the next terms are below 2^-120.
+        let mut fl = 0.0;
+        let fh = divdd(1.0, 0.0, x, 0.0, &mut fl);
+        let fl = fl - fh * (2.0 / (x * x));
+        return ((fh, fl), (fh / x, 0.0));
+    }
+    let n = (360.0 / x) as u32 + 5;
+    let (mut rh, mut rl) = ((2 * n + 1) as f64, 0.0);
+    let (mut ih, mut il) = (x, 0.0);
+    for k in (1..=n).rev() {
+        // t = (2k - 1) + ix - k^2 / t
+        let mut nl = 0.0;
+        let nh = norm_dd(rh, rl, ih, il, &mut nl);
+        let mut wl = 0.0;
+        let wh = divdd((k * k) as f64, 0.0, nh, nl, &mut wl);
+        let mut al = 0.0;
+        let ah = muldd_acc(wh, wl, rh, rl, &mut al);
+        rh = add_dd((2 * k - 1) as f64, 0.0, -ah, -al, &mut rl);
+        let ah = muldd_acc(wh, wl, ih, il, &mut al);
+        ih = add_dd(x, 0.0, ah, al, &mut il);
+    }
+    // 1/t = (re t - i im t) / |t|^2
+    let mut nl = 0.0;
+    let nh = norm_dd(rh, rl, ih, il, &mut nl);
+    let (mut fl, mut gl) = (0.0, 0.0);
+    let fh = divdd(ih, il, nh, nl, &mut fl);
+    let gh = divdd(rh, rl, nh, nl, &mut gl);
+    ((fh, fl), (gh, gl))
+}
+
+// (sin a, cos a) as double-doubles for |a| <= pi/4, from the Taylor series
+// in nested double-double form.
+pub(crate) fn sincos_taylor_dd(ah: f64, al: f64) -> ((f64, f64), (f64, f64)) {
+    let mut ql = 0.0;
+    let qh = muldd_acc(ah, al, ah, al, &mut ql);
+    // sin a = a (1 - a^2/(2*3) (1 - a^2/(4*5) (1 - ...)))
+    // cos a = 1 - a^2/(1*2) (1 - a^2/(3*4) (1 - ...))
+    let (mut sh, mut sl) = (1.0, 0.0);
+    let (mut ch, mut cl) = (1.0, 0.0);
+    for k in (1..=TAYLOR_TERMS).rev() {
+        let k = k as f64;
+        let mut tl = 0.0;
+        let th = muldd_acc(qh, ql, sh, sl, &mut tl);
+        let th = divddd(th, tl, (2.0 * k) * (2.0 * k + 1.0), &mut tl);
+        sh = add_dd(1.0, 0.0, -th, -tl, &mut sl);
+        let th = muldd_acc(qh, ql, ch, cl, &mut tl);
+        let th = divddd(th, tl, (2.0 * k - 1.0) * (2.0 * k), &mut tl);
+        ch = add_dd(1.0, 0.0, -th, -tl, &mut cl);
+    }
+    sh = muldd_acc(ah, al, sh, sl, &mut sl);
+    ((sh, sl), (ch, cl))
+}
+
+// (sin x, cos x) as double-doubles: x = n pi/2 + a with `branred`, in
+// round-to-nearest, then `sincos_taylor_dd` on a.
+pub(crate) fn sincos_dd(x: f64) -> ((f64, f64), (f64, f64)) {
+    let (n, ah, al) = with_nearest(x, branred);
+    let ((sh, sl), (ch, cl)) = sincos_taylor_dd(ah, al);
+    match n & 3 {
+        0 => ((sh, sl), (ch, cl)),
+        1 => ((ch, cl), (-sh, -sl)),
+        2 => ((-sh, -sl), (-ch, -cl)),
+        _ => ((-ch, -cl), (sh, sl)),
+    }
+}
+
+// a b + c d as a double-double.
+#[inline(always)]
+fn dot_dd(a: (f64, f64), b: (f64, f64), c: (f64, f64), d: (f64, f64), l: &mut f64) -> f64 {
+    let (mut pl, mut ql) = (0.0, 0.0);
+    let ph = muldd_acc(a.0, a.1, b.0, b.1, &mut pl);
+    let qh = muldd_acc(c.0, c.1, d.0, d.1, &mut ql);
+    add_dd(ph, pl, qh, ql, l)
+}
+
+/// Sine integral Si(x) = ∫_0^x sin(t)/t dt.
+///
+/// Odd, with Si(±inf) = ±pi/2.
+#[inline]
+pub fn si(x: f64) -> f64 {
+    with_nearest(x, si_nearest)
+}
+
+#[inline]
+fn si_nearest(x: f64) -> f64 {
+    if x.is_nan() {
+        return x;
+    }
+    let ax = x.abs();
+    if ax < SI_TINY {
+        return x;
+    }
+    let r = if ax == f64::INFINITY {
+        FRAC_PI_2_HI
+    } else if ax <= SERIES_MAX {
+        let mut l = 0.0;
+        let h = si_series(ax, &mut l);
+        h + l
+    } else {
+        let (f, g) = aux_fg(ax);
+        let (s, c) = sincos_dd(ax);
+        let mut tl = 0.0;
+        let th = dot_dd(f, c, g, s, &mut tl);
+        let mut l = 0.0;
+        let h = add_dd(FRAC_PI_2_HI, FRAC_PI_2_LO, -th, -tl, &mut l);
+        h + l
+    };
+    if x < 0.0 { -r } else { r }
+}
+
+/// Cosine integral Ci(x) = gamma + ln x + ∫_0^x (cos(t) - 1)/t dt.
+///
+/// Ci(0) = -inf and Ci(+inf) = 0; negative arguments return NaN.
+#[inline]
+pub fn ci(x: f64) -> f64 {
+    with_nearest(x, ci_nearest)
+}
+
+#[inline]
+fn ci_nearest(x: f64) -> f64 {
+    if x.is_nan() {
+        return x;
+    }
+    if x < 0.0 {
+        return f64::NAN;
+    }
+    if x == 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if x == f64::INFINITY {
+        return 0.0;
+    }
+    let mut l = 0.0;
+    let h = if x <= SERIES_MAX {
+        ci_series(x, &mut l)
+    } else {
+        let (f, g) = aux_fg(x);
+        let (s, c) = sincos_dd(x);
+        dot_dd(f, s, (-g.0, -g.1), c, &mut l)
+    };
+    h + l
+}
@@ -0,0 +1,563 @@
+//! Portable SIMD lane types `F64x2`, `F64x4` and `F64x8`.
+//!
+//! Each type is an aligned array of lanes with a method for every real
+//! function of the crate, applied lane by lane. The per-lane contract is the
+//! same for every width and code path: lane `i` of `v.f()` is bit-identical
+//! to `f(v.0[i])`, so each method has exactly the accuracy of its scalar
+//! function and results never depend on the width or the target.
+//!
+//! Without target support the methods loop over the lanes. On x86_64 the
+//! arithmetic operators and `sqrt` use SSE2 for `F64x2`, AVX for `F64x4` and
+//! AVX-512F for `F64x8` when the build enables them. `F64x4` runs the
+//! AVX2+FMA kernels (`exp`, `exp2`, `ln`, `log2`, `sin`, `cos`, `sincos`,
+//! `pow`) when AVX2 and FMA are enabled; `F64x8` runs the AVX-512 kernels
+//! (`exp`, `ln`, `sin`, `cos`, `pow`, `erf`) when AVX-512F is enabled and
+//! otherwise works on two `F64x4` halves.
+
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+use super::{
+    acos, acosh, asin, asinh, atan, atan2, atanh, cbrt, ceil, ci, copysign, cos, cosh, ellipe,
+    ellipeinc, ellipk, ellipkinc, ellippi, ellippiinc, elliprc, elliprd, elliprf, elliprj, erf,
+    erfc, exp, exp2, exp10, expint_e1, expint_ei, expint_en, expm1, fabs, fdim, floor, fma, fmax,
+    fmin, fmod, fpclassify, fresnel_c, fresnel_s, frexp, hurwitz_zeta, hypot, ilogb, isfinite,
+    isinf, isnan, lambert_w0, lambert_wm1, ldexp, lgamma, llrint, llround, ln, log1p, log2, log10,
+    logb, lrint, lround, modf, nearbyint, nextafter, pow, remainder, remquo, rint, round, scalbln,
+    scalbn, si, signbit, sin, sincos, sinh, tan, tanh, tgamma, trunc, zeta,
+};
+
+macro_rules! lane_type {
+    ($(#[$attr:meta])* $ty:ident, $n:literal, $align:literal) => {
+        $(#[$attr])*
+        #[repr(C, align($align))]
+        #[derive(Clone, Copy, Debug, Default, PartialEq)]
+        pub struct $ty(pub [f64; $n]);
+
+        impl $ty {
+            /// Number of lanes.
+            pub const LANES: usize = $n;
+
+            /// All lanes set to `x`.
+            #[inline]
+            pub const fn splat(x: f64) -> Self {
+                Self([x; $n])
+            }
+
+            /// The lanes of `a`, in order.
+            #[inline]
+            pub const fn from_array(a: [f64; $n]) -> Self {
+                Self(a)
+            }
+
+            /// The lanes as an array.
+            #[inline]
+            pub const fn to_array(self) -> [f64; $n] {
+                self.0
+            }
+
+            /// The first `LANES` elements of `s`.
+            ///
+            /// # Panics
+            ///
+            /// If `s` is shorter than `LANES`.
+            #[inline]
+            pub fn from_slice(s: &[f64]) -> Self {
+                let mut a = [0.0; $n];
+                a.copy_from_slice(&s[..$n]);
+                Self(a)
+            }
+
+            /// Writes the lanes to the first `LANES` elements of `s`.
+            ///
+            /// # Panics
+            ///
+            /// If `s` is shorter than `LANES`.
+            #[inline]
+            pub fn write_to_slice(self, s: &mut [f64]) {
+                s[..$n].copy_from_slice(&self.0);
+            }
+
+            #[inline(always)]
+            fn map(self, f: impl Fn(f64) -> f64) -> Self {
+                Self(self.0.map(f))
+            }
+
+            #[inline(always)]
+            fn map_to<T>(self, f: impl Fn(f64) -> T) -> [T; $n] {
+                self.0.map(f)
+            }
+
+            #[inline(always)]
+            fn zip(self, o: Self, f: impl Fn(f64, f64) -> f64) -> Self {
+                Self(core::array::from_fn(|i| f(self.0[i], o.0[i])))
+            }
+
+            #[inline(always)]
+            fn zip3(self, b: Self, c: Self, f: impl Fn(f64, f64, f64) -> f64) -> Self {
+                Self(core::array::from_fn(|i| f(self.0[i], b.0[i], c.0[i])))
+            }
+        }
+
+        impl From<[f64; $n]> for $ty {
+            #[inline]
+            fn from(a: [f64; $n]) -> Self {
+                Self(a)
+            }
+        }
+
+        impl From<$ty> for [f64; $n] {
+            #[inline]
+            fn from(v: $ty) -> Self {
+                v.0
+            }
+        }
+
+        impl Neg for $ty {
+            type Output = Self;
+
+            #[inline]
+            fn neg(self) -> Self {
+                self.map(|x| -x)
+            }
+        }
+    };
+}
+
+lane_type!(
+    /// Two `f64` lanes, an SSE2 register on x86_64.
+    F64x2, 2, 16
+);
+lane_type!(
+    /// Four `f64` lanes, an AVX register on x86_64.
+    F64x4, 4, 32
+);
+lane_type!(
+    /// Eight `f64` lanes, an AVX-512 register on x86_64.
+    F64x8, 8, 64
+);
+
+impl F64x8 {
+    /// The low and high four lanes.
+    #[inline]
+    pub fn halves(self) -> (F64x4, F64x4) {
+        let [a, b, c, d, e, f, g, h] = self.0;
+        (F64x4([a, b, c, d]), F64x4([e, f, g, h]))
+    }
+
+    /// `lo` in the low four lanes and `hi` in the high four.
+    #[inline]
+    pub fn from_halves(lo: F64x4, hi: F64x4) -> Self {
+        let ([a, b, c, d], [e, f, g, h]) = (lo.0, hi.0);
+        Self([a, b, c, d, e, f, g, h])
+    }
+}
+
+// Arithmetic and sqrt: one vector instruction where the build enables the
+// register width, a lane loop otherwise. sqrt blends in the scalar results
+// for NaNs, negative inputs and -inf, which differ from the instruction's.
+macro_rules! arith {
+    ($ty:ident, $cfg:meta, $v:ident, $load:ident, $store:ident,
+     $add:ident, $sub:ident, $mul:ident, $div:ident, $sqrt:ident) => {
+        impl $ty {
+            #[cfg($cfg)]
+            #[inline(always)]
+            fn to_vec(self) -> core::arch::x86_64::$v {
+                // SAFETY: the array holds exactly one vector.
+                unsafe { core::arch::x86_64::$load(self.0.as_ptr()) }
+            }
+
+            #[cfg($cfg)]
+            #[inline(always)]
+            fn from_vec(v: core::arch::x86_64::$v) -> Self {
+                let mut out = Self::default();
+                // SAFETY: the array holds exactly one vector.
+                unsafe { core::arch::x86_64::$store(out.0.as_mut_ptr(), v) };
+                out
+            }
+
+            /// [`sqrt`](crate::sqrt) of each lane.
+            #[inline]
+            pub fn sqrt(self) -> Self {
+                #[cfg($cfg)]
+                {
+                    // SAFETY: the build enables the instruction's target feature.
+                    let r = Self::from_vec(unsafe { core::arch::x86_64::$sqrt(self.to_vec()) });
+                    Self(core::array::from_fn(|i| {
+                        let x = self.0[i];
+                        if x.is_nan() || x < 0.0 {
+                            f64::NAN
+                        } else {
+                            r.0[i]
+                        }
+                    }))
+                }
+                #[cfg(not($cfg))]
+                self.map(super::sqrt)
+            }
+        }
+
+        arith!(@op $ty, $cfg, Add, add, $add, +);
+        arith!(@op $ty, $cfg, Sub, sub, $sub, -);
+        arith!(@op $ty, $cfg, Mul, mul, $mul, *);
+        arith!(@op $ty, $cfg, Div, div, $div, /);
+    };
+    (@op $ty:ident, $cfg:meta, $trait:ident, $method:ident, $intrinsic:ident, $op:tt) => {
+        impl $trait for $ty {
+            type Output = Self;
+
+            #[inline]
+            fn $method(self, o: Self) -> Self {
+                #[cfg($cfg)]
+                // SAFETY: the build enables the instruction's target feature.
+                return Self::from_vec(unsafe {
+                    core::arch::x86_64::$intrinsic(self.to_vec(), o.to_vec())
+                });
+                #[cfg(not($cfg))]
+                self.zip(o, |a, b| a $op b)
+            }
+        }
+    };
+}
+
+arith!(
+    F64x2,
+    all(target_arch = "x86_64", target_feature = "sse2"),
+    __m128d,
+    _mm_loadu_pd,
+    _mm_storeu_pd,
+    _mm_add_pd,
+    _mm_sub_pd,
+    _mm_mul_pd,
+    _mm_div_pd,
+    _mm_sqrt_pd
+);
+arith!(
+    F64x4,
+    all(target_arch = "x86_64", target_feature = "avx"),
+    __m256d,
+    _mm256_loadu_pd,
+    _mm256_storeu_pd,
+    _mm256_add_pd,
+    _mm256_sub_pd,
+    _mm256_mul_pd,
+    _mm256_div_pd,
+    _mm256_sqrt_pd
+);
+arith!(
+    F64x8,
+    all(target_arch = "x86_64", target_feature = "avx512f"),
+    __m512d,
+    _mm512_loadu_pd,
+    _mm512_storeu_pd,
+    _mm512_add_pd,
+    _mm512_sub_pd,
+    _mm512_mul_pd,
+    _mm512_div_pd,
+    _mm512_sqrt_pd
+);
+
+// How a method without a kernel is evaluated: a scalar loop, or the F64x4
+// method on each half.
+macro_rules! fallback {
+    (map, $self:ident, $f:ident) => {
+        $self.map($f)
+    };
+    (map, $self:ident, $f:ident, $o:ident) => {
+        $self.zip($o, $f)
+    };
+    (halves, $self:ident, $f:ident) => {{
+        let (lo, hi) = $self.halves();
+        Self::from_halves(lo.$f(), hi.$f())
+    }};
+    (halves, $self:ident, $f:ident, $o:ident) => {{
+        let ((lo, hi), (olo, ohi)) = ($self.halves(), $o.halves());
+        Self::from_halves(lo.$f(olo), hi.$f(ohi))
+    }};
+}
+
+macro_rules! unary_methods {
+    ($ty:ident, $fallback:ident, $kcfg:meta; $($f:ident $([$k:ident])?,)*) => {
+        impl $ty {$(
+            #[doc = concat!("[`", stringify!($f), "`](crate::", stringify!($f), ") of each lane.")]
+            #[inline]
+            #[allow(unreachable_code)]
+            pub fn $f(self) -> Self {
+                $(
+                    #[cfg($kcfg)]
+                    // SAFETY: the build enables the kernel's target features.
+                    return Self::from_vec(unsafe { super::$k(self.to_vec()) });
+                )?
+                fallback!($fallback, self, $f)
+            }
+        )*}
+    };
+}
+
+macro_rules! binary_methods {
+    ($ty:ident, $fallback:ident, $kcfg:meta; $($f:ident($a:ident) $([$k:ident])?,)*) => {
+        impl $ty {$(
+            #[doc = concat!(
+                "[`", stringify!($f), "`](crate::", stringify!($f), ")`(self, ",
+                stringify!($a), ")` in each lane."
+            )]
+            #[inline]
+            #[allow(unreachable_code)]
+            pub fn $f(self, $a: Self) -> Self {
+                $(
+                    #[cfg($kcfg)]
+                    // SAFETY: the build enables the kernel's target features.
+                    return Self::from_vec(unsafe { super::$k(self.to_vec(), $a.to_vec()) });
+                )?
+                fallback!($fallback, self, $f, $a)
+            }
+        )*}
+    };
+}
+
+// The functions with other signatures, identical for every width.
+macro_rules! other_methods {
+    ($ty:ident, $n:literal) => {
+        impl $ty {
+            /// [`fma`](crate::fma)`(self, b, c)` in each lane.
+            #[inline]
+            pub fn fma(self, b: Self, c: Self) -> Self {
+                self.zip3(b, c, fma)
+            }
+
+            /// [`elliprf`](crate::elliprf)`(self, y, z)` in each lane.
+            #[inline]
+            pub fn elliprf(self, y: Self, z: Self) -> Self {
+                self.zip3(y, z, elliprf)
+            }
+
+            /// [`elliprd`](crate::elliprd)`(self, y, z)` in each lane.
+            #[inline]
+            pub fn elliprd(self, y: Self, z: Self) -> Self {
+                self.zip3(y, z, elliprd)
+            }
+
+            /// [`elliprj`](crate::elliprj)`(self, y, z, p)` in each lane.
+            #[inline]
+            pub fn elliprj(self, y: Self, z: Self, p: Self) -> Self {
+                Self(core::array::from_fn(|i| {
+                    elliprj(self.0[i], y.0[i], z.0[i], p.0[i])
+                }))
+            }
+
+            /// [`ellippiinc`](crate::ellippiinc)`(self, phi, m)` in each lane.
+            #[inline]
+            pub fn ellippiinc(self, phi: Self, m: Self) -> Self {
+                self.zip3(phi, m, ellippiinc)
+            }
+
+            /// [`expint_en`](crate::expint_en)`(n, self)` in each lane.
+            #[inline]
+            pub fn expint_en(self, n: i32) -> Self {
+                self.map(|x| expint_en(n, x))
+            }
+
+            /// [`ldexp`](crate::ldexp)`(self, n)` in each lane.
+            #[inline]
+            pub fn ldexp(self, n: [i32; $n]) -> Self {
+                Self(core::array::from_fn(|i| ldexp(self.0[i], n[i])))
+            }
+
+            /// [`scalbn`](crate::scalbn)`(self, n)` in each lane.
+            #[inline]
+            pub fn scalbn(self, n: [i32; $n]) -> Self {
+                Self(core::array::from_fn(|i| scalbn(self.0[i], n[i])))
+            }
+
+            /// [`scalbln`](crate::scalbln)`(self, n)` in each lane.
+            #[inline]
+            pub fn scalbln(self, n: [i64; $n]) -> Self {
+                Self(core::array::from_fn(|i| scalbln(self.0[i], n[i])))
+            }
+
+            /// [`frexp`](crate::frexp) of each lane: the fractions and the
+            /// exponents.
+            #[inline]
+            pub fn frexp(self) -> (Self, [i32; $n]) {
+                let r = self.map_to(frexp);
+                (Self(r.map(|(m, _)| m)), r.map(|(_, e)| e))
+            }
+
+            /// [`modf`](crate::modf) of each lane: the fractional and the
+            /// integral parts.
+            #[inline]
+            pub fn modf(self) -> (Self, Self) {
+                let r = self.map_to(modf);
+                (Self(r.map(|(f, _)| f)), Self(r.map(|(_, i)| i)))
+            }
+
+            /// [`remquo`](crate::remquo)`(self, y)` in each lane: the
+            /// remainders and the quotient bits.
+            #[inline]
+            pub fn remquo(self, y: Self) -> (Self, [i32; $n]) {
+                let r: [(f64, i32); $n] = core::array::from_fn(|i| remquo(self.0[i], y.0[i]));
+                (Self(r.map(|(v, _)| v)), r.map(|(_, q)| q))
+            }
+
+            /// [`ilogb`](crate::ilogb) of each lane.
+            #[inline]
+            pub fn ilogb(self) -> [i32; $n] {
+                self.map_to(ilogb)
+            }
+
+            /// [`lrint`](crate::lrint) of each lane.
+            #[inline]
+            pub fn lrint(self) -> [i64; $n] {
+                self.map_to(lrint)
+            }
+
+            /// [`llrint`](crate::llrint) of each lane.
+            #[inline]
+            pub fn llrint(self) -> [i64; $n] {
+                self.map_to(llrint)
+            }
+
+            /// [`lround`](crate::lround) of each lane.
+            #[inline]
+            pub fn lround(self) -> [i64; $n] {
+                self.map_to(lround)
+            }
+
+            /// [`llround`](crate::llround) of each lane.
+            #[inline]
+            pub fn llround(self) -> [i64; $n] {
+                self.map_to(llround)
+            }
+
+            /// [`fpclassify`](crate::fpclassify) of each lane.
+            #[inline]
+            pub fn fpclassify(self) -> [i32; $n] {
+                self.map_to(fpclassify)
+            }
+
+            /// [`isfinite`](crate::isfinite) of each lane.
+            #[inline]
+            pub fn isfinite(self) -> [bool; $n] {
+                self.map_to(isfinite)
+            }
+
+            /// [`isinf`](crate::isinf) of each lane.
+            #[inline]
+            pub fn isinf(self) -> [bool; $n] {
+                self.map_to(isinf)
+            }
+
+            /// [`isnan`](crate::isnan) of each lane.
+            #[inline]
+            pub fn isnan(self) -> [bool; $n] {
+                self.map_to(isnan)
+            }
+
+            /// [`signbit`](crate::signbit) of each lane.
+            #[inline]
+            pub fn signbit(self) -> [bool; $n] {
+                self.map_to(signbit)
+            }
+        }
+    };
+}
+
+other_methods!(F64x2, 2);
+other_methods!(F64x4, 4);
+other_methods!(F64x8, 8);
+
+impl F64x2 {
+    /// [`sincos`](crate::sincos) of each lane: the sines and the cosines.
+    #[inline]
+    pub fn sincos(self) -> (Self, Self) {
+        let r = self.map_to(sincos);
+        (Self(r.map(|(s, _)| s)), Self(r.map(|(_, c)| c)))
+    }
+}
+
+impl F64x4 {
+    /// [`sincos`](crate::sincos) of each lane: the sines and the cosines.
+    #[inline]
+    pub fn sincos(self) -> (Self, Self) {
+        #[cfg(all(
+            target_arch = "x86_64",
+            target_feature = "avx2",
+            target_feature = "fma",
+            not(feature = "soft-fma")
+        ))]
+        {
+            // SAFETY: the build enables the kernel's target features.
+            let (s, c) = unsafe { super::sincos_avx2(self.to_vec()) };
+            (Self::from_vec(s), Self::from_vec(c))
+        }
+        #[cfg(not(all(
+            target_arch = "x86_64",
+            target_feature = "avx2",
+            target_feature = "fma",
+            not(feature = "soft-fma")
+        )))]
+        {
+            let r = self.map_to(sincos);
+            (Self(r.map(|(s, _)| s)), Self(r.map(|(_, c)| c)))
+        }
+    }
+}
+
+impl F64x8 {
+    /// [`sincos`](crate::sincos) of each lane: the sines and the cosines.
+    #[inline]
+    pub fn sincos(self) -> (Self, Self) {
+        let (lo, hi) = self.halves();
+        let ((slo, clo), (shi, chi)) = (lo.sincos(), hi.sincos());
+        (Self::from_halves(slo, shi), Self::from_halves(clo, chi))
+    }
+}
+
+macro_rules! all_methods {
+    ($ty:ident, $fallback:ident, $kcfg:meta,
+     $($f:ident $([$k:ident])?),* ; $($g:ident($a:ident) $([$gk:ident])?),*) => {
+        unary_methods! {
+            $ty, $fallback, $kcfg;
+            acos, acosh, asin, asinh, atan, atanh, cbrt, ceil, ci, cosh, ellipe, ellipk, erfc,
+            exp10, expint_e1, expint_ei, expm1, fabs, floor, fresnel_c, fresnel_s, lambert_w0,
+            lambert_wm1, lgamma, log10, log1p, logb, nearbyint, rint, round, si, sinh, tan,
+            tanh, tgamma, trunc, zeta,
+            $($f $([$k])?,)*
+        }
+        binary_methods! {
+            $ty, $fallback, $kcfg;
+            atan2(x), copysign(y), ellipeinc(m), ellipkinc(m), ellippi(m), elliprc(y),
+            fdim(y), fmax(y), fmin(y), fmod(y), hurwitz_zeta(q), hypot(y), nextafter(y),
+            remainder(y),
+            $($g($a) $([$gk])?,)*
+        }
+    };
+}
+
+all_methods!(
+    F64x2, map, any(),
+    cos, erf, exp, exp2, ln, log2, sin;
+    pow(y)
+);
+all_methods!(
+    F64x4, map,
+    all(
+        target_arch = "x86_64",
+        target_feature = "avx2",
+        target_feature = "fma",
+        not(feature = "soft-fma")
+    ),
+    cos [cos_avx2], erf, exp [exp_avx2], exp2 [exp2_avx2], ln [ln_avx2], log2 [log2_avx2],
+    sin [sin_avx2];
+    pow(y) [pow_avx2]
+);
+all_methods!(
+    F64x8, halves,
+    all(
+        target_arch = "x86_64",
+        target_feature = "avx512f",
+        not(feature = "soft-fma")
+    ),
+    cos [cos_avx512], erf [erf_avx512], exp [exp_avx512], exp2, ln [ln_avx512], log2,
+    sin [sin_avx512];
+    pow(y) [pow_avx512]
+);
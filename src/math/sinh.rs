@@ -4,7 +4,7 @@
 //! and handles overflow for large inputs. Coefficients and thresholds mirror
 //! fdlibm/glibc strategies.
 
-use super::{exp, expm1, two_sum};
+use super::{exp, expm1, two_sum, with_nearest};
 
 const TINY: f64 = 3.725_290_298_461_914e-09; // 2^-28
 const EXP_HI: f64 = 709.782_712_893_384;
@@ -20,6 +20,11 @@ const EXP_MASK: u64 = 0x7ff0_0000_0000_0000u64;
 
 #[inline(always)]
 pub fn sinh(x: f64) -> f64 {
+    with_nearest(x, sinh_nearest)
+}
+
+#[inline(always)]
+fn sinh_nearest(x: f64) -> f64 {
     let ux = x.to_bits();
     let ax_bits = ux & !SIGN_MASK;
     if ax_bits >= EXP_MASK {
@@ -94,8 +94,8 @@ pub fn sqrt(x: f64) -> f64 {
     if x == 0.0 {
         return x;
     }
-    if x.is_infinite() {
-        return f64::INFINITY;
+    if x == f64::INFINITY {
+        return x;
     }
     if x < 0.0 {
         return f64::NAN;
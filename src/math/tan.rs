@@ -2,11 +2,12 @@
 //!
 //! Performs argument reduction to [-pi/4, pi/4] and evaluates an odd rational
 //! polynomial. Quadrant selection and reciprocal identities handle |x|>pi/4.
-//! Constants and tables follow fdlibm/glibc conventions.
+//! Constants and tables follow fdlibm/glibc conventions. As in glibc, the
+//! evaluation runs in round-to-nearest whatever the caller's mode.
 
-use super::lo_word;
 use super::trig::branred;
 use super::utan_tables::*;
+use super::{lo_word, with_nearest};
 
 const CN: f64 = 134217729.0; // 1 + 2^27
 const SIGN_MASK: u64 = 0x8000_0000_0000_0000u64;
@@ -227,6 +228,14 @@ fn tan_generic(x: f64) -> f64 {
 
 #[inline(always)]
 pub fn tan(x: f64) -> f64 {
+    with_nearest(x, tan_dispatch)
+}
+
+#[inline(always)]
+#[allow(unreachable_code)]
+fn tan_dispatch(x: f64) -> f64 {
+    #[cfg(all(target_arch = "x86_64", feature = "runtime-dispatch"))]
+    return super::dispatch::select!(tan_fma, tan_generic; fn(x: f64) -> f64)(x);
     #[cfg(target_arch = "x86_64")]
     if super::fma_available() {
         // SAFETY: guarded by compile-time FMA availability.
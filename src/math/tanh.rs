@@ -3,7 +3,7 @@
 //! Uses expm1(2|x|) to compute tanh with reduced cancellation for |x|<1, and
 //! saturates to ±1 for large inputs.
 
-use super::{exp, expm1, fma_internal};
+use super::{exp, expm1, fma_internal, with_nearest};
 
 const TINY: f64 = 2.775_557_561_562_891_4e-17; // 2^-55
 const SERIES_BOUND: f64 = 0.3;
@@ -50,6 +50,11 @@ fn tanh_series(x: f64) -> f64 {
 
 #[inline(always)]
 pub fn tanh(x: f64) -> f64 {
+    with_nearest(x, tanh_nearest)
+}
+
+#[inline(always)]
+fn tanh_nearest(x: f64) -> f64 {
     let ux = x.to_bits();
     let ax_bits = ux & !SIGN_MASK;
     if ax_bits >= EXP_MASK {
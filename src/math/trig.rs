@@ -3,6 +3,10 @@
 //! Implements rem_pio2 with fast paths for small/medium inputs and Payne–Hanek
 //! reduction for huge arguments using 2/pi tables. Kernel sin/cos polynomials
 //! (odd/even) are evaluated on |x|≤pi/4; FMA is used where available.
+//!
+//! The table index and reductions are add-sub tricks that assume
+//! round-to-nearest, so like glibc's, the entry points install it for the
+//! duration of the call.
 
 #![allow(
     clippy::collapsible_if,
@@ -12,7 +16,7 @@
 )]
 
 use super::sincos_tab::SINCOS_TAB;
-use super::{floor_f64, hi_word, lo_word, scalbn_internal, with_hi_lo};
+use super::{floor_f64, hi_word, lo_word, scalbn_internal, with_hi_lo, with_nearest};
 
 // ========= fdlibm/glibc-grade sin/cos =========
 //
@@ -280,17 +284,15 @@ fn kernel_rem_pio2(x: &[f64; 3], y: &mut [f64; 2], e0: i32, nx: i32, prec: i32)
     }
     let mut q0 = e0 - 24 * (jv + 1);
 
-    let mut j = jv - jx;
     let m = jx + jk;
     let jx_us = jx as usize;
 
-    for i in 0..=(m as usize) {
+    for (i, j) in (0..=(m as usize)).zip(jv - jx..) {
         f[i] = if j < 0 {
             KR_ZERO
         } else {
             TWO_OVER_PI[j as usize] as f64
         };
-        j += 1;
     }
 
     for i in 0..=(jk as usize) {
@@ -487,9 +489,9 @@ fn rem_pio2(x: f64) -> (i32, f64, f64) {
     // medium: |x| <= 2^19*(pi/2)
     if ix <= 0x4139_21fbu32 {
         let t = x.abs();
-        #[cfg(target_feature = "fma")]
+        #[cfg(all(target_feature = "fma", not(feature = "soft-fma")))]
         let n = (super::fma_internal(t, INVPIO2, HALF)) as i32;
-        #[cfg(not(target_feature = "fma"))]
+        #[cfg(not(all(target_feature = "fma", not(feature = "soft-fma"))))]
         let n = (t * INVPIO2 + HALF) as i32;
         let fn_ = n as f64;
 
@@ -559,9 +561,9 @@ fn rem_pio2(x: f64) -> (i32, f64, f64) {
     let mut yy = [0.0f64; 2];
     let n = kernel_rem_pio2(&tx, &mut yy, e0, nx as i32, 2);
     if hx < 0 {
-        (-(n as i32), -yy[0], -yy[1])
+        (-n, -yy[0], -yy[1])
     } else {
-        (n as i32, yy[0], yy[1])
+        (n, yy[0], yy[1])
     }
 }
 
@@ -921,6 +923,14 @@ unsafe fn sin_fma(x: f64) -> f64 {
 
 #[inline(always)]
 pub(super) fn sin(x: f64) -> f64 {
+    with_nearest(x, sin_dispatch)
+}
+
+#[inline(always)]
+#[allow(unreachable_code)]
+fn sin_dispatch(x: f64) -> f64 {
+    #[cfg(all(target_arch = "x86_64", feature = "runtime-dispatch"))]
+    return super::dispatch::select!(sin_fma, sin_generic; fn(x: f64) -> f64)(x);
     #[cfg(target_arch = "x86_64")]
     unsafe {
         if super::fma_available() {
@@ -985,6 +995,14 @@ unsafe fn cos_fma(x: f64) -> f64 {
 
 #[inline(always)]
 pub(super) fn cos(x: f64) -> f64 {
+    with_nearest(x, cos_dispatch)
+}
+
+#[inline(always)]
+#[allow(unreachable_code)]
+fn cos_dispatch(x: f64) -> f64 {
+    #[cfg(all(target_arch = "x86_64", feature = "runtime-dispatch"))]
+    return super::dispatch::select!(cos_fma, cos_generic; fn(x: f64) -> f64)(x);
     #[cfg(target_arch = "x86_64")]
     unsafe {
         if super::fma_available() {
@@ -1067,6 +1085,14 @@ unsafe fn sincos_fma(x: f64) -> (f64, f64) {
 
 #[inline(always)]
 pub fn sincos(x: f64) -> (f64, f64) {
+    with_nearest(x, sincos_dispatch)
+}
+
+#[inline(always)]
+#[allow(unreachable_code)]
+fn sincos_dispatch(x: f64) -> (f64, f64) {
+    #[cfg(all(target_arch = "x86_64", feature = "runtime-dispatch"))]
+    return super::dispatch::select!(sincos_fma, sincos_generic; fn(x: f64) -> (f64, f64))(x);
     #[cfg(target_arch = "x86_64")]
     unsafe {
         if super::fma_available() {
@@ -1076,6 +1102,542 @@ pub fn sincos(x: f64) -> (f64, f64) {
     sincos_generic(x)
 }
 
+// ---- AVX2+FMA 4-lane kernels mirroring the FMA scalar paths ----
+
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+mod avx2 {
+    use super::*;
+    use crate::math::arch::avx2::{fixup1, gather_f64, splat, splat_u64};
+    use core::arch::x86_64::*;
+
+    #[inline]
+    #[target_feature(enable = "avx2,fma")]
+    fn sign_of(x: __m256d) -> __m256d {
+        _mm256_and_pd(x, _mm256_castsi256_pd(splat_u64(SIGN_BIT)))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2,fma")]
+    fn lookup(u: __m256d) -> (__m256d, __m256d, __m256d, __m256d) {
+        let idx = _mm256_slli_epi64::<2>(_mm256_and_si256(
+            _mm256_castpd_si256(u),
+            splat_u64(0xffff_ffff),
+        ));
+        // Callers keep |x| below 0.86, so idx + 3 stays inside the table.
+        unsafe {
+            (
+                gather_f64(&SINCOS_TAB, idx),
+                gather_f64(&SINCOS_TAB[1..], idx),
+                gather_f64(&SINCOS_TAB[2..], idx),
+                gather_f64(&SINCOS_TAB[3..], idx),
+            )
+        }
+    }
+
+    // do_sin_fma lane by lane.
+    #[inline]
+    #[target_feature(enable = "avx2,fma")]
+    fn do_sin(x: __m256d, dx: __m256d) -> __m256d {
+        let sign = sign_of(x);
+        let absx = _mm256_xor_pd(x, sign);
+        let dx = _mm256_xor_pd(dx, sign);
+
+        let xx = _mm256_mul_pd(absx, absx);
+        let poly = _mm256_fmadd_pd(splat(IBM_S5), xx, splat(IBM_S4));
+        let poly = _mm256_fmadd_pd(poly, xx, splat(IBM_S3));
+        let poly = _mm256_fmadd_pd(poly, xx, splat(IBM_S2));
+        let poly = _mm256_fmadd_pd(poly, xx, splat(IBM_S1));
+        let t = _mm256_fmadd_pd(poly, absx, _mm256_mul_pd(splat(-0.5), dx));
+        let taylor = _mm256_add_pd(absx, _mm256_fmadd_pd(t, xx, dx));
+
+        let u = _mm256_add_pd(splat(IBM_BIG), absx);
+        let x = _mm256_sub_pd(absx, _mm256_sub_pd(u, splat(IBM_BIG)));
+        let xx = _mm256_mul_pd(x, x);
+        let t = _mm256_fmadd_pd(xx, splat(IBM_SN5), splat(IBM_SN3));
+        let s = _mm256_add_pd(x, _mm256_fmadd_pd(_mm256_mul_pd(x, xx), t, dx));
+        let t = _mm256_fmadd_pd(xx, splat(IBM_CS6), splat(IBM_CS4));
+        let t = _mm256_fmadd_pd(xx, t, splat(IBM_CS2));
+        let c = _mm256_fmadd_pd(x, dx, _mm256_mul_pd(xx, t));
+        let (sn, ssn, cs, ccs) = lookup(u);
+        let neg_sn = _mm256_xor_pd(sn, sign_of(splat(-0.0)));
+        let cor = _mm256_fmadd_pd(
+            cs,
+            s,
+            _mm256_fmadd_pd(neg_sn, c, _mm256_fmadd_pd(s, ccs, ssn)),
+        );
+        let table = _mm256_add_pd(sn, cor);
+
+        let small = _mm256_cmp_pd::<_CMP_LT_OQ>(absx, splat(0.126));
+        _mm256_xor_pd(_mm256_blendv_pd(table, taylor, small), sign)
+    }
+
+    // do_cos_fma lane by lane.
+    #[inline]
+    #[target_feature(enable = "avx2,fma")]
+    fn do_cos(x: __m256d, dx: __m256d) -> __m256d {
+        let neg = _mm256_cmp_pd::<_CMP_LT_OQ>(x, _mm256_setzero_pd());
+        let dx = _mm256_xor_pd(dx, _mm256_and_pd(neg, sign_of(splat(-0.0))));
+        let absx = _mm256_andnot_pd(sign_of(splat(-0.0)), x);
+        let u = _mm256_add_pd(splat(IBM_BIG), absx);
+        let x = _mm256_add_pd(_mm256_sub_pd(absx, _mm256_sub_pd(u, splat(IBM_BIG))), dx);
+
+        let xx = _mm256_mul_pd(x, x);
+        let t = _mm256_fmadd_pd(xx, splat(IBM_SN5), splat(IBM_SN3));
+        let s = _mm256_fmadd_pd(_mm256_mul_pd(x, xx), t, x);
+        let t = _mm256_fmadd_pd(xx, splat(IBM_CS6), splat(IBM_CS4));
+        let t = _mm256_fmadd_pd(xx, t, splat(IBM_CS2));
+        let c = _mm256_mul_pd(xx, t);
+
+        let (sn, ssn, cs, ccs) = lookup(u);
+        let m = sign_of(splat(-0.0));
+        let cor = _mm256_fmadd_pd(
+            _mm256_xor_pd(sn, m),
+            s,
+            _mm256_fmadd_pd(
+                _mm256_xor_pd(cs, m),
+                c,
+                _mm256_fmadd_pd(_mm256_xor_pd(s, m), ssn, ccs),
+            ),
+        );
+        _mm256_add_pd(cs, cor)
+    }
+
+    // do_sincos_fma: cos for odd n, negated when n & 2.
+    #[inline]
+    #[target_feature(enable = "avx2,fma")]
+    fn do_sincos(a: __m256d, da: __m256d, n: __m256i) -> __m256d {
+        let odd = _mm256_castsi256_pd(_mm256_cmpeq_epi64(
+            _mm256_and_si256(n, splat_u64(1)),
+            splat_u64(1),
+        ));
+        let v = _mm256_blendv_pd(do_sin(a, da), do_cos(a, da), odd);
+        let flip = _mm256_slli_epi64::<62>(_mm256_and_si256(n, splat_u64(2)));
+        _mm256_xor_pd(v, _mm256_castsi256_pd(flip))
+    }
+
+    // reduce_sincos lane by lane.
+    #[inline]
+    #[target_feature(enable = "avx2,fma")]
+    fn reduce(x: __m256d) -> (__m256i, __m256d, __m256d) {
+        let t = _mm256_add_pd(_mm256_mul_pd(x, splat(IBM_HPINV)), splat(IBM_TOINT));
+        let xn = _mm256_sub_pd(t, splat(IBM_TOINT));
+        let n = _mm256_and_si256(_mm256_castpd_si256(t), splat_u64(3));
+        let y = _mm256_sub_pd(
+            _mm256_sub_pd(x, _mm256_mul_pd(xn, splat(IBM_MP1))),
+            _mm256_mul_pd(xn, splat(IBM_MP2)),
+        );
+        let t1 = _mm256_mul_pd(xn, splat(IBM_PP3));
+        let t2 = _mm256_sub_pd(y, t1);
+        let db = _mm256_sub_pd(_mm256_sub_pd(y, t2), t1);
+        let t1 = _mm256_mul_pd(xn, splat(IBM_PP4));
+        let b = _mm256_sub_pd(t2, t1);
+        let db = _mm256_add_pd(db, _mm256_sub_pd(_mm256_sub_pd(t2, b), t1));
+        (n, b, db)
+    }
+
+    // The |x| < 2^-26 (or 2^-27) shortcut, the three polynomial ranges and
+    // the lanes left to the scalar code (Payne-Hanek, infinities, NaN).
+    struct Ranges {
+        x: __m256d,
+        below: [__m256d; 3],
+        slow: __m256d,
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2,fma")]
+    fn ranges(x: __m256d, tiny: u64) -> Ranges {
+        let k = _mm256_srli_epi64::<32>(_mm256_andnot_si256(
+            splat_u64(SIGN_BIT),
+            _mm256_castpd_si256(x),
+        ));
+        let lt = |t: u64| _mm256_castsi256_pd(_mm256_cmpgt_epi64(splat_u64(t), k));
+        let slow = _mm256_xor_pd(lt(0x4199_21fb), _mm256_castsi256_pd(splat_u64(!0)));
+        Ranges {
+            // Slow lanes are zeroed so that no table index leaves its bounds.
+            x: _mm256_andnot_pd(slow, x),
+            below: [lt(tiny), lt(0x3feb_6000), lt(0x4003_68fd)],
+            slow,
+        }
+    }
+
+    // sin_fma on the non-tiny, non-slow lanes.
+    #[inline]
+    #[target_feature(enable = "avx2,fma")]
+    fn sin_lanes(r: &Ranges) -> __m256d {
+        let x = r.x;
+        let (n, a, da) = reduce(x);
+        // pi/2 - |x| with cos, the sign of x restored afterwards.
+        let t = _mm256_sub_pd(splat(IBM_HP0), _mm256_andnot_pd(sign_of(splat(-0.0)), x));
+        let a = _mm256_blendv_pd(a, t, r.below[2]);
+        let da = _mm256_blendv_pd(da, splat(IBM_HP1), r.below[2]);
+        let n = _mm256_blendv_epi8(n, splat_u64(1), _mm256_castpd_si256(r.below[2]));
+        let a = _mm256_blendv_pd(a, x, r.below[1]);
+        let da = _mm256_blendv_pd(da, _mm256_setzero_pd(), r.below[1]);
+        let n = _mm256_blendv_epi8(n, splat_u64(0), _mm256_castpd_si256(r.below[1]));
+        let v = do_sincos(a, da, n);
+        let mid = _mm256_andnot_pd(r.below[1], r.below[2]);
+        _mm256_xor_pd(v, _mm256_and_pd(mid, sign_of(x)))
+    }
+
+    // cos_fma on the non-tiny, non-slow lanes.
+    #[inline]
+    #[target_feature(enable = "avx2,fma")]
+    fn cos_lanes(r: &Ranges) -> __m256d {
+        let x = r.x;
+        let (n, a, da) = reduce(x);
+        let n = _mm256_add_epi64(n, splat_u64(1));
+        // sin of pi/2 - |x| as a double-double.
+        let y = _mm256_sub_pd(splat(IBM_HP0), _mm256_andnot_pd(sign_of(splat(-0.0)), x));
+        let ay = _mm256_add_pd(y, splat(IBM_HP1));
+        let day = _mm256_add_pd(_mm256_sub_pd(y, ay), splat(IBM_HP1));
+        let a = _mm256_blendv_pd(a, ay, r.below[2]);
+        let da = _mm256_blendv_pd(da, day, r.below[2]);
+        let n = _mm256_blendv_epi8(n, splat_u64(0), _mm256_castpd_si256(r.below[2]));
+        let a = _mm256_blendv_pd(a, x, r.below[1]);
+        let da = _mm256_blendv_pd(da, _mm256_setzero_pd(), r.below[1]);
+        let n = _mm256_blendv_epi8(n, splat_u64(1), _mm256_castpd_si256(r.below[1]));
+        do_sincos(a, da, n)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2,fma")]
+    pub(super) fn sin(x: __m256d) -> __m256d {
+        let r = ranges(x, 0x3e50_0000);
+        let v = _mm256_blendv_pd(sin_lanes(&r), x, r.below[0]);
+        fixup1(v, r.slow, x, super::sin)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2,fma")]
+    pub(super) fn cos(x: __m256d) -> __m256d {
+        let r = ranges(x, 0x3e40_0000);
+        let v = _mm256_blendv_pd(cos_lanes(&r), splat(1.0), r.below[0]);
+        fixup1(v, r.slow, x, super::cos)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2,fma")]
+    pub(super) fn sincos(x: __m256d) -> (__m256d, __m256d) {
+        let r = ranges(x, 0x3e50_0000);
+        let s = _mm256_blendv_pd(sin_lanes(&r), x, r.below[0]);
+        let c = _mm256_blendv_pd(cos_lanes(&r), splat(1.0), r.below[0]);
+        (
+            fixup1(s, r.slow, x, |x| super::sincos(x).0),
+            fixup1(c, r.slow, x, |x| super::sincos(x).1),
+        )
+    }
+}
+
+/// sin(x) in each lane of `x`, bit-identical to [`sin`](super::sin) lane by
+/// lane.
+///
+/// The three polynomial ranges of the scalar code are evaluated together and
+/// blended; lanes needing Payne-Hanek reduction (|x| >= 2^26 or so),
+/// infinities and NaNs are handed to the scalar function.
+///
+/// # Safety
+///
+/// The CPU must support AVX2 and FMA.
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[target_feature(enable = "avx2,fma")]
+pub unsafe fn sin_avx2(x: core::arch::x86_64::__m256d) -> core::arch::x86_64::__m256d {
+    with_nearest(x, |x| unsafe { sin_avx2_nearest(x) })
+}
+
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[target_feature(enable = "avx2,fma")]
+unsafe fn sin_avx2_nearest(x: core::arch::x86_64::__m256d) -> core::arch::x86_64::__m256d {
+    avx2::sin(x)
+}
+
+/// cos(x) in each lane of `x`, bit-identical to [`cos`](super::cos) lane by
+/// lane; see [`sin_avx2`] for the lanes handed to the scalar function.
+///
+/// # Safety
+///
+/// The CPU must support AVX2 and FMA.
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[target_feature(enable = "avx2,fma")]
+pub unsafe fn cos_avx2(x: core::arch::x86_64::__m256d) -> core::arch::x86_64::__m256d {
+    with_nearest(x, |x| unsafe { cos_avx2_nearest(x) })
+}
+
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[target_feature(enable = "avx2,fma")]
+unsafe fn cos_avx2_nearest(x: core::arch::x86_64::__m256d) -> core::arch::x86_64::__m256d {
+    avx2::cos(x)
+}
+
+/// (sin x, cos x) in each lane of `x`, bit-identical to [`sincos`] lane by
+/// lane; see [`sin_avx2`] for the lanes handed to the scalar function.
+///
+/// # Safety
+///
+/// The CPU must support AVX2 and FMA.
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[target_feature(enable = "avx2,fma")]
+pub unsafe fn sincos_avx2(
+    x: core::arch::x86_64::__m256d,
+) -> (core::arch::x86_64::__m256d, core::arch::x86_64::__m256d) {
+    with_nearest(x, |x| unsafe { sincos_avx2_nearest(x) })
+}
+
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[target_feature(enable = "avx2,fma")]
+unsafe fn sincos_avx2_nearest(
+    x: core::arch::x86_64::__m256d,
+) -> (core::arch::x86_64::__m256d, core::arch::x86_64::__m256d) {
+    avx2::sincos(x)
+}
+
+// ---- AVX-512F 8-lane kernels: the same lanes as above under mask registers ----
+
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+mod avx512 {
+    use super::*;
+    use crate::math::arch::avx512::{abs, fixup1, gather_f64, neg, sign, splat, splat_u64, xor};
+    use core::arch::x86_64::*;
+
+    #[inline]
+    #[target_feature(enable = "avx512f")]
+    fn lookup(u: __m512d) -> (__m512d, __m512d, __m512d, __m512d) {
+        let idx = _mm512_slli_epi64::<2>(_mm512_and_si512(
+            _mm512_castpd_si512(u),
+            splat_u64(0xffff_ffff),
+        ));
+        // Callers keep |x| below 0.86, so idx + 3 stays inside the table.
+        unsafe {
+            (
+                gather_f64(&SINCOS_TAB, idx),
+                gather_f64(&SINCOS_TAB[1..], idx),
+                gather_f64(&SINCOS_TAB[2..], idx),
+                gather_f64(&SINCOS_TAB[3..], idx),
+            )
+        }
+    }
+
+    // do_sin_fma lane by lane.
+    #[inline]
+    #[target_feature(enable = "avx512f")]
+    fn do_sin(x: __m512d, dx: __m512d) -> __m512d {
+        let sx = sign(x);
+        let absx = abs(x);
+        let dx = xor(dx, sx);
+
+        let xx = _mm512_mul_pd(absx, absx);
+        let poly = _mm512_fmadd_pd(splat(IBM_S5), xx, splat(IBM_S4));
+        let poly = _mm512_fmadd_pd(poly, xx, splat(IBM_S3));
+        let poly = _mm512_fmadd_pd(poly, xx, splat(IBM_S2));
+        let poly = _mm512_fmadd_pd(poly, xx, splat(IBM_S1));
+        let t = _mm512_fmadd_pd(poly, absx, _mm512_mul_pd(splat(-0.5), dx));
+        let taylor = _mm512_add_pd(absx, _mm512_fmadd_pd(t, xx, dx));
+
+        let u = _mm512_add_pd(splat(IBM_BIG), absx);
+        let x = _mm512_sub_pd(absx, _mm512_sub_pd(u, splat(IBM_BIG)));
+        let xx = _mm512_mul_pd(x, x);
+        let t = _mm512_fmadd_pd(xx, splat(IBM_SN5), splat(IBM_SN3));
+        let s = _mm512_add_pd(x, _mm512_fmadd_pd(_mm512_mul_pd(x, xx), t, dx));
+        let t = _mm512_fmadd_pd(xx, splat(IBM_CS6), splat(IBM_CS4));
+        let t = _mm512_fmadd_pd(xx, t, splat(IBM_CS2));
+        let c = _mm512_fmadd_pd(x, dx, _mm512_mul_pd(xx, t));
+        let (sn, ssn, cs, ccs) = lookup(u);
+        let cor = _mm512_fmadd_pd(
+            cs,
+            s,
+            _mm512_fmadd_pd(neg(sn), c, _mm512_fmadd_pd(s, ccs, ssn)),
+        );
+        let table = _mm512_add_pd(sn, cor);
+
+        let small = _mm512_cmp_pd_mask::<_CMP_LT_OQ>(absx, splat(0.126));
+        xor(_mm512_mask_blend_pd(small, table, taylor), sx)
+    }
+
+    // do_cos_fma lane by lane.
+    #[inline]
+    #[target_feature(enable = "avx512f")]
+    fn do_cos(x: __m512d, dx: __m512d) -> __m512d {
+        let negative = _mm512_cmp_pd_mask::<_CMP_LT_OQ>(x, _mm512_setzero_pd());
+        let dx = _mm512_mask_blend_pd(negative, dx, neg(dx));
+        let absx = abs(x);
+        let u = _mm512_add_pd(splat(IBM_BIG), absx);
+        let x = _mm512_add_pd(_mm512_sub_pd(absx, _mm512_sub_pd(u, splat(IBM_BIG))), dx);
+
+        let xx = _mm512_mul_pd(x, x);
+        let t = _mm512_fmadd_pd(xx, splat(IBM_SN5), splat(IBM_SN3));
+        let s = _mm512_fmadd_pd(_mm512_mul_pd(x, xx), t, x);
+        let t = _mm512_fmadd_pd(xx, splat(IBM_CS6), splat(IBM_CS4));
+        let t = _mm512_fmadd_pd(xx, t, splat(IBM_CS2));
+        let c = _mm512_mul_pd(xx, t);
+
+        let (sn, ssn, cs, ccs) = lookup(u);
+        let cor = _mm512_fmadd_pd(
+            neg(sn),
+            s,
+            _mm512_fmadd_pd(neg(cs), c, _mm512_fmadd_pd(neg(s), ssn, ccs)),
+        );
+        _mm512_add_pd(cs, cor)
+    }
+
+    // do_sincos_fma: cos for odd n, negated when n & 2.
+    #[inline]
+    #[target_feature(enable = "avx512f")]
+    fn do_sincos(a: __m512d, da: __m512d, n: __m512i) -> __m512d {
+        let odd = _mm512_test_epi64_mask(n, splat_u64(1));
+        let v = _mm512_mask_blend_pd(odd, do_sin(a, da), do_cos(a, da));
+        xor(
+            v,
+            _mm512_slli_epi64::<62>(_mm512_and_si512(n, splat_u64(2))),
+        )
+    }
+
+    // reduce_sincos lane by lane.
+    #[inline]
+    #[target_feature(enable = "avx512f")]
+    fn reduce(x: __m512d) -> (__m512i, __m512d, __m512d) {
+        let t = _mm512_add_pd(_mm512_mul_pd(x, splat(IBM_HPINV)), splat(IBM_TOINT));
+        let xn = _mm512_sub_pd(t, splat(IBM_TOINT));
+        let n = _mm512_and_si512(_mm512_castpd_si512(t), splat_u64(3));
+        let y = _mm512_sub_pd(
+            _mm512_sub_pd(x, _mm512_mul_pd(xn, splat(IBM_MP1))),
+            _mm512_mul_pd(xn, splat(IBM_MP2)),
+        );
+        let t1 = _mm512_mul_pd(xn, splat(IBM_PP3));
+        let t2 = _mm512_sub_pd(y, t1);
+        let db = _mm512_sub_pd(_mm512_sub_pd(y, t2), t1);
+        let t1 = _mm512_mul_pd(xn, splat(IBM_PP4));
+        let b = _mm512_sub_pd(t2, t1);
+        let db = _mm512_add_pd(db, _mm512_sub_pd(_mm512_sub_pd(t2, b), t1));
+        (n, b, db)
+    }
+
+    // The tiny shortcut, the three polynomial ranges, the finite lanes that
+    // need Payne-Hanek reduction and the infinities and NaNs.
+    struct Ranges {
+        x: __m512d,
+        below: [__mmask8; 3],
+        huge: __mmask8,
+        nonfinite: __mmask8,
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx512f")]
+    fn ranges(x: __m512d, tiny: u64) -> Ranges {
+        let k = _mm512_srli_epi64::<32>(_mm512_castpd_si512(abs(x)));
+        let lt = |t: u64| _mm512_cmplt_epu64_mask(k, splat_u64(t));
+        let slow = !lt(0x4199_21fb);
+        let nonfinite = !lt(0x7ff0_0000);
+        Ranges {
+            // Slow lanes are zeroed so that no table index leaves its bounds.
+            x: _mm512_mask_blend_pd(slow, x, _mm512_setzero_pd()),
+            below: [lt(tiny), lt(0x3feb_6000), lt(0x4003_68fd)],
+            huge: slow & !nonfinite,
+            nonfinite,
+        }
+    }
+
+    // sin_fma on the polynomial-range lanes.
+    #[inline]
+    #[target_feature(enable = "avx512f")]
+    fn sin_lanes(r: &Ranges) -> __m512d {
+        let x = r.x;
+        let (n, a, da) = reduce(x);
+        // pi/2 - |x| with cos, the sign of x restored afterwards.
+        let t = _mm512_sub_pd(splat(IBM_HP0), abs(x));
+        let a = _mm512_mask_blend_pd(r.below[2], a, t);
+        let da = _mm512_mask_blend_pd(r.below[2], da, splat(IBM_HP1));
+        let n = _mm512_mask_blend_epi64(r.below[2], n, splat_u64(1));
+        let a = _mm512_mask_blend_pd(r.below[1], a, x);
+        let da = _mm512_mask_blend_pd(r.below[1], da, _mm512_setzero_pd());
+        let n = _mm512_mask_blend_epi64(r.below[1], n, splat_u64(0));
+        let v = do_sincos(a, da, n);
+        let mid = r.below[2] & !r.below[1];
+        _mm512_mask_blend_pd(mid, v, xor(v, sign(x)))
+    }
+
+    // cos_fma on the polynomial-range lanes.
+    #[inline]
+    #[target_feature(enable = "avx512f")]
+    fn cos_lanes(r: &Ranges) -> __m512d {
+        let x = r.x;
+        let (n, a, da) = reduce(x);
+        let n = _mm512_add_epi64(n, splat_u64(1));
+        // sin of pi/2 - |x| as a double-double.
+        let y = _mm512_sub_pd(splat(IBM_HP0), abs(x));
+        let ay = _mm512_add_pd(y, splat(IBM_HP1));
+        let day = _mm512_add_pd(_mm512_sub_pd(y, ay), splat(IBM_HP1));
+        let a = _mm512_mask_blend_pd(r.below[2], a, ay);
+        let da = _mm512_mask_blend_pd(r.below[2], da, day);
+        let n = _mm512_mask_blend_epi64(r.below[2], n, splat_u64(0));
+        let a = _mm512_mask_blend_pd(r.below[1], a, x);
+        let da = _mm512_mask_blend_pd(r.below[1], da, _mm512_setzero_pd());
+        let n = _mm512_mask_blend_epi64(r.below[1], n, splat_u64(1));
+        do_sincos(a, da, n)
+    }
+
+    // The NaN of x * 0 on infinities and NaNs.
+    #[inline]
+    #[target_feature(enable = "avx512f")]
+    fn nonfinite(v: __m512d, r: &Ranges, x: __m512d) -> __m512d {
+        _mm512_mask_blend_pd(r.nonfinite, v, _mm512_mul_pd(x, _mm512_setzero_pd()))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx512f")]
+    pub(super) fn sin(x: __m512d) -> __m512d {
+        let r = ranges(x, 0x3e50_0000);
+        let v = _mm512_mask_blend_pd(r.below[0], sin_lanes(&r), x);
+        fixup1(nonfinite(v, &r, x), r.huge, x, super::sin)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx512f")]
+    pub(super) fn cos(x: __m512d) -> __m512d {
+        let r = ranges(x, 0x3e40_0000);
+        let v = _mm512_mask_blend_pd(r.below[0], cos_lanes(&r), splat(1.0));
+        fixup1(nonfinite(v, &r, x), r.huge, x, super::cos)
+    }
+}
+
+/// sin(x) in each lane of `x`, bit-identical to [`sin`](super::sin) lane by
+/// lane.
+///
+/// As [`sin_avx2`], with the range selection, infinities and NaNs handled
+/// under AVX-512 masks; only finite lanes needing Payne-Hanek reduction
+/// (|x| >= 2^26 or so) are handed to the scalar function.
+///
+/// # Safety
+///
+/// The CPU must support AVX-512F.
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[target_feature(enable = "avx512f")]
+pub unsafe fn sin_avx512(x: core::arch::x86_64::__m512d) -> core::arch::x86_64::__m512d {
+    with_nearest(x, |x| unsafe { sin_avx512_nearest(x) })
+}
+
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[target_feature(enable = "avx512f")]
+unsafe fn sin_avx512_nearest(x: core::arch::x86_64::__m512d) -> core::arch::x86_64::__m512d {
+    avx512::sin(x)
+}
+
+/// cos(x) in each lane of `x`, bit-identical to [`cos`](super::cos) lane by
+/// lane; see [`sin_avx512`] for the lanes handed to the scalar function.
+///
+/// # Safety
+///
+/// The CPU must support AVX-512F.
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[target_feature(enable = "avx512f")]
+pub unsafe fn cos_avx512(x: core::arch::x86_64::__m512d) -> core::arch::x86_64::__m512d {
+    with_nearest(x, |x| unsafe { cos_avx512_nearest(x) })
+}
+
+#[cfg(all(target_arch = "x86_64", not(feature = "soft-fma")))]
+#[target_feature(enable = "avx512f")]
+unsafe fn cos_avx512_nearest(x: core::arch::x86_64::__m512d) -> core::arch::x86_64::__m512d {
+    avx512::cos(x)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
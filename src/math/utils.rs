@@ -1,7 +1,5 @@
 //! Shared math helpers and constants.
 
-use super::rint;
-
 pub(crate) const PIO2_HI: f64 = f64::from_bits(0x3ff9_21fb_5444_2d18);
 pub(crate) const PIO2_LO: f64 = f64::from_bits(0x3c91_a626_3314_5c07);
 
@@ -13,25 +11,53 @@ pub(crate) const LN2_LO: f64 = f64::from_bits(0x3dea_39ef_3579_3c76);
 pub(crate) const SPLIT: f64 = 134_217_729.0; // 2^27 + 1
 pub(crate) const TWO54: f64 = f64::from_bits(0x4350_0000_0000_0000);
 
+// Round to nearest, ties to even, independently of the dynamic rounding
+// mode, so argument reductions give the same quotient in every mode.
 #[inline(always)]
-pub(crate) fn two_sum(a: f64, b: f64) -> (f64, f64) {
-    let s = a + b;
-    let bb = s - a;
-    let err = (a - (s - bb)) + (b - bb);
-    (s, err)
-}
-
-#[inline(always)]
-pub(crate) fn fasttwosum(x: f64, y: f64, e: &mut f64) -> f64 {
-    let s = x + y;
-    let z = s - x;
-    *e = y - z;
-    s
+pub(crate) fn roundeven_finite(x: f64) -> f64 {
+    #[cfg(any(
+        all(target_arch = "x86_64", target_feature = "sse4.1"),
+        target_arch = "aarch64"
+    ))]
+    {
+        super::arch::roundeven_hw(x)
+    }
+    #[cfg(not(any(
+        all(target_arch = "x86_64", target_feature = "sse4.1"),
+        target_arch = "aarch64"
+    )))]
+    {
+        super::rint_mode(x, super::RoundingMode::ToNearest)
+    }
 }
 
+// glibc's SET_RESTORE_ROUND (FE_TONEAREST): runs `f` on `a` in
+// round-to-nearest and then restores the caller's mode, keeping the
+// exceptions `f` raised. The functions are written and their error bounds
+// proved for round-to-nearest: table indices, reductions and exact error
+// terms come from add-sub tricks and two-sums that assume it. In
+// round-to-nearest this only reads the control register, but that read and
+// the branch around it add several nanoseconds to the shortest functions
+// (tanh about 23 ns with LTO), so it is opt-in through the `rounding-modes`
+// feature.
 #[inline(always)]
-pub(crate) fn roundeven_finite(x: f64) -> f64 {
-    rint(x)
+pub(crate) fn with_nearest<A, R>(a: A, f: impl FnOnce(A) -> R) -> R {
+    #[cfg(all(
+        feature = "rounding-modes",
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    ))]
+    {
+        use super::arch::{ROUNDING_BITS, fp_control, set_fp_control};
+        let mode = fp_control() & ROUNDING_BITS;
+        if mode != 0 {
+            let mut a = a;
+            unsafe { set_fp_control(fp_control() & !ROUNDING_BITS, &mut a) };
+            let mut r = f(a);
+            unsafe { set_fp_control(fp_control() | mode, &mut r) };
+            return r;
+        }
+    }
+    f(a)
 }
 
 #[inline(always)]
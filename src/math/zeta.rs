@@ -0,0 +1,418 @@
+//! Riemann zeta(s) and Hurwitz zeta(s, q) implementation.
+//!
+//! For s > 1 the Hurwitz sum is evaluated with Euler-Maclaurin summation in
+//! double-double, scaled by q^s so the leading term is exact. Around the pole
+//! zeta(1 + t) = 1/t + sum (-1)^n gamma_n t^n / n! (Stieltjes constants), and
+//! negative s go through the reflection formula
+//!   zeta(s) = 2 (2 pi)^(s-1) sin(pi s / 2) Gamma(1 - s) zeta(1 - s)
+//! assembled in log space so the single exp at the end does the rounding.
+
+use core::f64::consts::PI;
+
+use super::double::{adddd, divdd, divddd, muldd_acc, twosum};
+use super::exp::exp_with_tail;
+use super::gamma::{as_lgamma_accurate_dd, sinpi_parts};
+use super::log::ln_dd;
+use super::{ceil, fasttwosum, floor, fma_internal, roundeven_finite, with_nearest};
+
+const LN_2PI_HI: f64 = f64::from_bits(0x3ffd_67f1_c864_beb5);
+const LN_2PI_LO: f64 = f64::from_bits(0xbc96_5b5a_1b7f_f5df);
+// zeta'(0) = -ln(2 pi) / 2
+const HALF_LN_2PI: f64 = f64::from_bits(0x3fed_67f1_c864_beb5);
+
+// zeta(1 + t) - 1/t = sum_n a_n t^n with a_n = (-1)^n gamma_n / n!.
+// a0 and a1 are double-doubles; a2..a22 keep the truncation below 2^-70 for
+// |t| <= 1.
+const STIELTJES_A0: [f64; 2] = [
+    f64::from_bits(0x3fe2_788c_fc6f_b619),
+    f64::from_bits(0xbc56_cb90_701f_bfab),
+];
+const STIELTJES_A1: [f64; 2] = [
+    f64::from_bits(0x3fb2_a40f_2afb_a4a2),
+    f64::from_bits(0xbc4a_4c5c_a5ee_7f03),
+];
+const STIELTJES_A: [f64; 21] = [
+    f64::from_bits(0xbf73_d88a_87ff_7c46),
+    f64::from_bits(0xbf36_6eef_f1c6_6798),
+    f64::from_bits(0x3f19_6634_bafa_2d70),
+    f64::from_bits(0xbedb_ba8a_963f_5f9f),
+    f64::from_bits(0xbe96_4142_2d05_f35a),
+    f64::from_bits(0x3e7c_157f_24b8_c4d7),
+    f64::from_bits(0xbe42_c123_2e41_d1c0),
+    f64::from_bits(0x3dda_0dbe_bb4b_9cca),
+    f64::from_bits(0x3dcf_1b85_59cc_5ebc),
+    f64::from_bits(0xbd9d_c4de_0681_961d),
+    f64::from_bits(0x3d58_92d6_3957_437c),
+    f64::from_bits(0x3cf3_dcdf_9f9c_733a),
+    f64::from_bits(0xbce5_9d87_c0a1_bda4),
+    f64::from_bits(0x3caf_3d84_4062_d89b),
+    f64::from_bits(0xbc66_020e_ef2c_491d),
+    f64::from_bits(0xbbf5_cdf8_7d6a_3d25),
+    f64::from_bits(0x3bec_56d7_3080_8b10),
+    f64::from_bits(0xbbb3_8ce6_8d37_b2e4),
+    f64::from_bits(0x3b6c_f756_bd1e_4dbf),
+    f64::from_bits(0xbb03_c514_b86d_dc60),
+    f64::from_bits(0xbae2_a402_80e0_7e8e),
+];
+
+// B_2j / (2j)! for j = 1..16 (Euler-Maclaurin correction terms).
+const EM_B: [f64; 16] = [
+    f64::from_bits(0x3fb5_5555_5555_5555),
+    f64::from_bits(0xbf56_c16c_16c1_6c17),
+    f64::from_bits(0x3f01_566a_bc01_1567),
+    f64::from_bits(0xbeab_bd77_9334_ef0b),
+    f64::from_bits(0x3e56_6a8f_2bf7_0ebe),
+    f64::from_bits(0xbe02_2805_d644_267f),
+    f64::from_bits(0x3dad_6db2_c4e0_9162),
+    f64::from_bits(0xbd57_da4e_1f79_955c),
+    f64::from_bits(0x3d03_5587_1d65_2e9e),
+    f64::from_bits(0xbcaf_57d9_68ca_acf1),
+    f64::from_bits(0x3c59_67e1_f09c_376f),
+    f64::from_bits(0xbc04_97d9_033a_2b5c),
+    f64::from_bits(0x3bb0_b132_d7c6_ad06),
+    f64::from_bits(0xbb5b_0f72_d59f_1c16),
+    f64::from_bits(0x3b05_ef2d_a4cc_a26d),
+    f64::from_bits(0xbab1_c77d_f96d_e38b),
+];
+
+// Terms below this fraction of the running sum are dropped.
+const SUM_EPS: f64 = f64::from_bits(0x3b90_0000_0000_0000); // 2^-70
+// Below this |x|, ln(1 + x) uses its Taylor series instead of ln_dd.
+const LOG1P_SERIES: f64 = 0.0625;
+// Below this |s|, zeta(s) = -1/2 + zeta'(0) s to working precision.
+const ZETA_TINY: f64 = f64::from_bits(0x3c30_0000_0000_0000); // 2^-60
+
+// ln(1 + x) for |x| < 1/16, where x = xh + xl, as a double-double.
+#[inline(always)]
+fn log1p_small(xh: f64, xl: f64, l: &mut f64) -> f64 {
+    // x - x^2/2 in double-double; the remaining terms are below 2^-5 x and
+    // only need double precision.
+    let mut r = 0.0;
+    let mut p = -xh * xh;
+    for k in 3..18 {
+        p *= -xh;
+        r += p / k as f64;
+    }
+    let x2 = xh * xh;
+    let x2l = fma_internal(xh, xh, -x2);
+    let mut e = 0.0;
+    let h = adddd(xh, xl, -0.5 * x2, r - 0.5 * x2l - xh * xl, &mut e);
+    fasttwosum(h, e, l)
+}
+
+// ln(x) for a positive double-double x.
+#[inline(always)]
+fn ln_dd2(xh: f64, xl: f64, l: &mut f64) -> f64 {
+    let (h, lo) = ln_dd(xh);
+    fasttwosum(h, lo + xl / xh, l)
+}
+
+// ln((k + q) / q) for k >= 0, q > 0, as a double-double.
+#[inline(always)]
+fn ln_ratio(k: f64, q: f64, lqh: f64, lql: f64, l: &mut f64) -> f64 {
+    let mut rl = 0.0;
+    let rh = divddd(k, 0.0, q, &mut rl);
+    if rh < LOG1P_SERIES {
+        return log1p_small(rh, rl, l);
+    }
+    let mut bl = 0.0;
+    let bh = twosum(k, q, &mut bl);
+    let mut ll = 0.0;
+    let lh = ln_dd2(bh, bl, &mut ll);
+    let mut e = 0.0;
+    let h = adddd(lh, ll, -lqh, -lql, &mut e);
+    fasttwosum(h, e, l)
+}
+
+// ((k + q) / q)^-s for s = sh + sl, as exp(eh + el).
+#[inline(always)]
+fn ratio_pow_arg(k: f64, q: f64, lqh: f64, lql: f64, sh: f64, sl: f64, el: &mut f64) -> f64 {
+    let mut ll = 0.0;
+    let lh = ln_ratio(k, q, lqh, lql, &mut ll);
+    muldd_acc(lh, ll, -sh, -sl, el)
+}
+
+// ((k + q) / q)^-s for s = sh + sl.
+#[inline(always)]
+fn ratio_pow(k: f64, q: f64, lqh: f64, lql: f64, sh: f64, sl: f64) -> f64 {
+    let mut el = 0.0;
+    let eh = ratio_pow_arg(k, q, lqh, lql, sh, sl, &mut el);
+    exp_with_tail(eh, el)
+}
+
+// ((k + q) / q)^-s as a double-double: the rounded exp is corrected by the
+// residual of its logarithm, r = E - ln(exp(E)), via exp(E) = y (1 + r).
+#[inline(always)]
+fn ratio_pow_dd(k: f64, q: f64, lqh: f64, lql: f64, sh: f64, sl: f64, l: &mut f64) -> f64 {
+    let mut el = 0.0;
+    let eh = ratio_pow_arg(k, q, lqh, lql, sh, sl, &mut el);
+    let y = exp_with_tail(eh, el);
+    if y < f64::MIN_POSITIVE {
+        *l = 0.0;
+        return y;
+    }
+    let (yh, yl) = ln_dd(y);
+    let r = (eh - yh) + (el - yl);
+    fasttwosum(y, y * r, l)
+}
+
+// q^s zeta(s, q) for s = sh + sl > 1 and q > 0, as a double-double.
+//
+// Euler-Maclaurin with sixteen Bernoulli terms:
+//   zeta(s, q) = sum_{k<N} (k + q)^-s + M^(1-s) / (s - 1) + M^-s / 2
+//              + sum_j B_2j / (2j)! s (s + 1) ... (s + 2j - 2) M^(-s-2j+1)
+// with M = N + q >= 3/4 (s + 32), which keeps the remainder below 2^-68.
+// Summation stops early once the terms (and the tail they bound) vanish.
+fn hurwitz_scaled(sh: f64, sl: f64, q: f64, l: &mut f64) -> f64 {
+    let (lqh, lql) = ln_dd(q);
+    let m_min = 0.75 * (sh + 32.0);
+    let n = if q + 1.0 >= m_min {
+        1.0
+    } else {
+        ceil(m_min - q)
+    };
+    let mut s1l = 0.0;
+    let s1h = twosum(sh, -1.0, &mut s1l);
+    s1l += sl;
+
+    let mut acc_h = 1.0;
+    let mut acc_l = 0.0;
+    let mut k = 1.0;
+    while k < n {
+        let t = ratio_pow(k, q, lqh, lql, sh, sl);
+        let mut e = 0.0;
+        acc_h = adddd(acc_h, acc_l, t, 0.0, &mut e);
+        acc_h = fasttwosum(acc_h, e, &mut acc_l);
+        // The remaining terms and tail sum to at most t (1 + (k + q) / (s - 1)).
+        if t * (1.0 + (k + q) / s1h) <= SUM_EPS * acc_h {
+            *l = acc_l;
+            return acc_h;
+        }
+        k += 1.0;
+    }
+
+    // Tail: (M / q)^-s (M / (s - 1) + 1/2 + sum_j c_j (s)_(2j-1) M^(1-2j)).
+    let mut ml = 0.0;
+    let mh = twosum(n, q, &mut ml);
+    let mut corr = 0.0;
+    let inv_m2 = 1.0 / (mh * mh);
+    let mut poch = sh / mh;
+    for (j, &b) in EM_B.iter().enumerate() {
+        let term = b * poch;
+        corr += term;
+        if term.abs() <= SUM_EPS * corr.abs() {
+            break;
+        }
+        let a = sh + (2 * j + 1) as f64;
+        poch *= a * (a + 1.0) * inv_m2;
+    }
+    let mut bl = 0.0;
+    let bh = divdd(mh, ml, s1h, s1l, &mut bl);
+    let mut e = 0.0;
+    let bh = adddd(bh, bl, 0.5, corr, &mut e);
+    let mut scl = 0.0;
+    let sch = ratio_pow_dd(n, q, lqh, lql, sh, sl, &mut scl);
+    let mut tl = 0.0;
+    let th = muldd_acc(bh, e, sch, scl, &mut tl);
+    let mut e = 0.0;
+    acc_h = adddd(acc_h, acc_l, th, tl, &mut e);
+    fasttwosum(acc_h, e, l)
+}
+
+// zeta(1 + t) for |t| <= 1 with t = th + tl, as a double-double.
+#[inline(always)]
+fn zeta_near_one(th: f64, tl: f64, l: &mut f64) -> f64 {
+    let mut p = STIELTJES_A[STIELTJES_A.len() - 1];
+    for &c in STIELTJES_A.iter().rev().skip(1) {
+        p = fma_internal(p, th, c);
+    }
+    // a0 + a1 t + t^2 p
+    let mh = STIELTJES_A1[0] * th;
+    let ml = fma_internal(STIELTJES_A1[0], th, -mh) + STIELTJES_A1[1] * th + STIELTJES_A1[0] * tl;
+    let mut e = 0.0;
+    let rh = adddd(
+        STIELTJES_A0[0],
+        STIELTJES_A0[1],
+        mh,
+        ml + th * th * p,
+        &mut e,
+    );
+    let mut il = 0.0;
+    let ih = divdd(1.0, 0.0, th, tl, &mut il);
+    let mut e2 = 0.0;
+    let rh = adddd(ih, il, rh, e, &mut e2);
+    fasttwosum(rh, e2, l)
+}
+
+// sin(pi x) / pi as a double-double, accurate relative to the result near
+// the integers (where sinpi_parts loses low bits of x).
+#[inline(always)]
+fn sinpi_dd(x: f64, l: &mut f64) -> f64 {
+    let n = roundeven_finite(x);
+    let r = x - n;
+    let odd = (n * 0.5) != floor(n * 0.5);
+    let ar = r.abs();
+    let (mut h, mut lo);
+    if ar < 0.25 {
+        // r (1 + c), c = -(pi r)^2 / 3! + (pi r)^4 / 5! - ...
+        let z = (PI * r) * (PI * r);
+        let mut c = 0.0;
+        let mut t = 1.0;
+        for k in 1..12 {
+            t *= -z / ((2 * k) * (2 * k + 1)) as f64;
+            c += t;
+        }
+        let ch = r * c;
+        let cl = fma_internal(r, c, -ch);
+        let mut e = 0.0;
+        h = fasttwosum(r, ch, &mut e);
+        lo = e + cl;
+    } else {
+        lo = 0.0;
+        h = sinpi_parts(ar, &mut lo);
+        if r < 0.0 {
+            h = -h;
+            lo = -lo;
+        }
+    }
+    if odd {
+        h = -h;
+        lo = -lo;
+    }
+    *l = lo;
+    h
+}
+
+// zeta(s) for s < 0 from the reflection formula, with 1 - s = uh + ul:
+//   ln|zeta(s)| = s ln(2 pi) + ln|sin(pi s / 2) / pi| + ln(-s) + lgamma(-s)
+//               + ln(zeta(1 - s))
+// using Gamma(1 - s) = -s Gamma(-s) so every argument is exact.
+fn zeta_reflect(s: f64) -> f64 {
+    let mut sinl = 0.0;
+    let mut sinh = sinpi_dd(0.5 * s, &mut sinl);
+    let negative = sinh < 0.0;
+    if negative {
+        sinh = -sinh;
+        sinl = -sinl;
+    }
+
+    let mut zl = 0.0;
+    let mut ul = 0.0;
+    let uh = twosum(1.0, -s, &mut ul);
+    let mut lzl = 0.0;
+    let lzh = if uh <= 2.0 {
+        let zh = zeta_near_one(-s, 0.0, &mut zl);
+        ln_dd2(zh, zl, &mut lzl)
+    } else {
+        let zh = hurwitz_scaled(uh, ul, 1.0, &mut zl);
+        ln_dd2(zh, zl, &mut lzl)
+    };
+
+    let mut acc_l = 0.0;
+    let mut acc_h = muldd_acc(LN_2PI_HI, LN_2PI_LO, s, 0.0, &mut acc_l);
+    let (gh, gl) = as_lgamma_accurate_dd(-s);
+    let (nh, nl) = ln_dd(-s);
+    let mut sl = 0.0;
+    let sh = ln_dd2(sinh, sinl, &mut sl);
+    for (h, l) in [(sh, sl), (nh, nl), (gh, gl), (lzh, lzl)] {
+        let mut e = 0.0;
+        acc_h = adddd(acc_h, acc_l, h, l, &mut e);
+        acc_h = fasttwosum(acc_h, e, &mut acc_l);
+    }
+    let r = exp_with_tail(acc_h, acc_l);
+    if negative { -r } else { r }
+}
+
+/// Riemann zeta function zeta(s) for real s.
+///
+/// Has a pole at s = 1 (returns +inf), trivial zeros at the negative even
+/// integers and zeta(0) = -1/2. Negative s use the reflection formula.
+#[inline]
+pub fn zeta(s: f64) -> f64 {
+    with_nearest(s, zeta_nearest)
+}
+
+#[inline]
+fn zeta_nearest(s: f64) -> f64 {
+    if s.is_nan() {
+        return s;
+    }
+    if s == 1.0 {
+        return f64::INFINITY;
+    }
+    if s == f64::INFINITY {
+        return 1.0;
+    }
+    if s == f64::NEG_INFINITY {
+        return f64::NAN;
+    }
+    if s.abs() < ZETA_TINY {
+        return fma_internal(-HALF_LN_2PI, s, -0.5);
+    }
+    if s >= 2.0 {
+        let mut l = 0.0;
+        let h = hurwitz_scaled(s, 0.0, 1.0, &mut l);
+        return h + l;
+    }
+    if s >= 0.0 {
+        let mut tl = 0.0;
+        let th = twosum(s, -1.0, &mut tl);
+        let mut l = 0.0;
+        let h = zeta_near_one(th, tl, &mut l);
+        return h + l;
+    }
+    if 0.5 * s == floor(0.5 * s) {
+        return 0.0;
+    }
+    zeta_reflect(s)
+}
+
+/// Hurwitz zeta function zeta(s, q) = sum_{k>=0} (k + q)^-s.
+///
+/// Defined for s > 1 and q > 0; zeta(1, q) = +inf and other arguments return
+/// NaN. hurwitz_zeta(s, 1) equals zeta(s).
+#[inline]
+pub fn hurwitz_zeta(s: f64, q: f64) -> f64 {
+    with_nearest((s, q), |(s, q)| hurwitz_zeta_nearest(s, q))
+}
+
+#[inline]
+fn hurwitz_zeta_nearest(s: f64, q: f64) -> f64 {
+    if s.is_nan() || q.is_nan() {
+        return s + q;
+    }
+    if s < 1.0 || q <= 0.0 {
+        return f64::NAN;
+    }
+    if s == 1.0 {
+        return f64::INFINITY;
+    }
+    if q == f64::INFINITY {
+        return 0.0;
+    }
+    if s == f64::INFINITY {
+        return if q < 1.0 {
+            f64::INFINITY
+        } else if q == 1.0 {
+            1.0
+        } else {
+            0.0
+        };
+    }
+    if q == 1.0 {
+        return zeta(s);
+    }
+    // zeta(s, q) = exp(-s ln(q) + ln(q^s zeta(s, q)))
+    let mut zl = 0.0;
+    let zh = hurwitz_scaled(s, 0.0, q, &mut zl);
+    let mut ll = 0.0;
+    let lh = ln_dd2(zh, zl, &mut ll);
+    let (qh, ql) = ln_dd(q);
+    let mut pl = 0.0;
+    let ph = muldd_acc(qh, ql, -s, 0.0, &mut pl);
+    let mut e = 0.0;
+    let h = adddd(lh, ll, ph, pl, &mut e);
+    let mut l = 0.0;
+    let h = fasttwosum(h, e, &mut l);
+    exp_with_tail(h, l)
+}